@@ -0,0 +1,78 @@
+use bevy_mesh_text_3d::{Parameters, bench, embedded_font_system};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+const FONT_SIZE: f32 = 14.0;
+// Straight segments, simple curves, a hole and a dense outline
+const GLYPHS: &[&str] = &["H", "o", "B", "@"];
+const EXTRUSION_DEPTHS: &[f32] = &[0.5, 2.5, 10.0];
+const SENTENCE: &str = "The quick brown fox jumps over the lazy dog";
+
+fn bench_layout(c: &mut Criterion) {
+    let mut font_system = embedded_font_system();
+    let params = Parameters {
+        extrusion_depth: 2.5,
+        font_size: FONT_SIZE,
+        line_height: 16.0,
+        max_width: Some(200.0),
+        ..Default::default()
+    };
+
+    c.bench_function("layout/sentence", |b| {
+        b.iter(|| bench::layout(&mut font_system, black_box(SENTENCE), &params))
+    });
+}
+
+fn bench_outline_extraction(c: &mut Criterion) {
+    let mut font_system = embedded_font_system();
+    let mut group = c.benchmark_group("outline_extraction");
+    for text in GLYPHS {
+        let glyph = bench::shape_glyphs(&mut font_system, text, FONT_SIZE).remove(0);
+        group.bench_with_input(BenchmarkId::from_parameter(text), &glyph, |b, glyph| {
+            b.iter(|| bench::extract_outline(&mut font_system, black_box(glyph)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_tessellation(c: &mut Criterion) {
+    let mut font_system = embedded_font_system();
+    let mut group = c.benchmark_group("tessellation");
+    for text in GLYPHS {
+        let glyph = bench::shape_glyphs(&mut font_system, text, FONT_SIZE).remove(0);
+        for depth in EXTRUSION_DEPTHS {
+            group.bench_with_input(
+                BenchmarkId::new(*text, depth),
+                &(glyph.clone(), *depth),
+                |b, (glyph, depth)| {
+                    b.iter(|| bench::tessellate(&mut font_system, black_box(glyph), *depth))
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_mesh_building(c: &mut Criterion) {
+    let mut font_system = embedded_font_system();
+    let mut group = c.benchmark_group("mesh_building");
+    for text in GLYPHS {
+        let glyph = bench::shape_glyphs(&mut font_system, text, FONT_SIZE).remove(0);
+        let geometry = bench::tessellate(&mut font_system, &glyph, 2.5).unwrap();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(text),
+            &geometry,
+            |b, geometry| b.iter(|| bench::build_mesh(black_box(geometry.clone()))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_layout,
+    bench_outline_extraction,
+    bench_tessellation,
+    bench_mesh_building
+);
+criterion_main!(benches);
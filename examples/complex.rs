@@ -128,13 +128,11 @@ fn spawn_text(
 
     let blue_material = materials.add(StandardMaterial {
         base_color: Color::Srgba(Srgba::BLUE),
-        cull_mode: None,
         ..default()
     });
 
     let red_material = materials.add(StandardMaterial {
         base_color: Color::Srgba(Srgba::RED),
-        cull_mode: None,
         ..default()
     });
 
@@ -153,16 +151,13 @@ fn spawn_text(
             extrusion_depth: 3.0,
             font_size: default_metrics.font_size,
             line_height: default_metrics.line_height,
-            alignment: None,
-            max_width: None,
-            max_height: None,
+            ..default()
         },
         &mut meshes,
     )
     .unwrap();
 
-    let mut idx = 1;
-    for mesh in meshes {
+    for (idx, mesh) in (1..).zip(meshes) {
         // Calculate the final spawn transform first, including any horizontal adjustments
         let spawn_transform = mesh.transform.with_translation(Vec3::new(
             -200.0 + mesh.transform.translation.x, // Apply horizontal shift
@@ -189,6 +184,5 @@ fn spawn_text(
             // Add the MovingText component, initialized with the desired offset and center
             MovingText::new(desired_initial_y_offset, initial_y_center_for_movement),
         ));
-        idx += 1;
     }
 }
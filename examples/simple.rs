@@ -7,9 +7,7 @@ use bevy::{
         settings::{RenderCreation, WgpuFeatures, WgpuSettings},
     },
 };
-use cosmic_text::Attrs;
-
-use bevy_mesh_text_3d::{InputText, MeshTextPlugin, Parameters, Settings, generate_meshes};
+use bevy_mesh_text_3d::{MeshTextPlugin, Parameters, Settings, TextSpawnDesc, spawn_text_3d};
 
 const CAMERA_VIEWPORT_HEIGHT: f32 = 950.0;
 // This factor controls the overall size of text in the world
@@ -92,39 +90,22 @@ fn spawn_text(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    let meshes = generate_meshes(
-        InputText::Simple {
-            text: "Hello, World!".to_string(),
-            material: materials.add(StandardMaterial {
-                base_color: Color::WHITE,
-                cull_mode: None,
-                ..default()
-            }),
-            attrs: Attrs::new(),
-        },
+    let text = spawn_text_3d(
+        &mut commands,
         &mut fonts,
-        Parameters {
-            extrusion_depth: 2.5,
-            font_size: 14.0,
-            line_height: 16.0,
-            alignment: None,
-            max_width: None,
-            max_height: None,
-        },
         &mut meshes,
+        &mut materials,
+        TextSpawnDesc {
+            params: Parameters {
+                extrusion_depth: 2.5,
+                font_size: 14.0,
+                line_height: 16.0,
+                ..default()
+            },
+            transform: Transform::from_xyz(-200.0, 0.0, 0.0),
+            ..TextSpawnDesc::new("Hello, World!")
+        },
     )
     .unwrap();
-
-    for mesh in meshes {
-        commands.spawn((
-            Mesh3d(mesh.mesh),
-            MeshMaterial3d(mesh.material),
-            mesh.transform.with_translation(Vec3::new(
-                -200.0 + mesh.transform.translation.x,
-                mesh.transform.translation.y,
-                mesh.transform.translation.z,
-            )),
-            RotatingText::default(),
-        ));
-    }
+    commands.entity(text).insert(RotatingText::default());
 }
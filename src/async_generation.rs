@@ -0,0 +1,417 @@
+//! Mesh generation on the async compute pool.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, poll_once};
+use cosmic_text::{Font, LayoutGlyph};
+
+use crate::bounds::insert_glyph_bounds;
+use crate::extrude_glyph::{ExtrudedGlyphGeometry, ExtrusionOptions, UvMode};
+use crate::glyph_cache::GlyphCacheKey;
+use crate::glyph_store::BakedTextMesh;
+use crate::mesh_text_plugin::{add_back_side, glyph_entry, layout_text};
+use crate::text_glyphs::{BakedGlyph, BlockUv, TextGlyphs, glyph_cache_key, tessellate_font_glyph};
+use crate::{InputText, LayoutGrid, MeshGlyph, MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// Progress and cancellation shared between a background generation and its owner.
+#[derive(Debug, Default)]
+pub(crate) struct GenerationState {
+    cancelled: AtomicBool,
+    done: AtomicUsize,
+    total: usize,
+}
+
+impl GenerationState {
+    /// Fails with [`MeshTextError::Cancelled`] once the owner gave up on the result. Checked
+    /// between glyphs.
+    pub(crate) fn check(&self) -> Result<(), MeshTextError> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            Err(MeshTextError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Counts one more finished glyph.
+    pub(crate) fn advance(&self) {
+        self.done.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// A task on the async compute pool that stops at its next glyph once cancelled or dropped.
+pub(crate) struct TrackedTask<T> {
+    task: Task<Result<T, MeshTextError>>,
+    state: Arc<GenerationState>,
+}
+
+impl<T: Send + 'static> TrackedTask<T> {
+    /// Runs `generate` over `total` glyphs in the background.
+    pub(crate) fn spawn(
+        total: usize,
+        generate: impl FnOnce(&GenerationState) -> Result<T, MeshTextError> + Send + 'static,
+    ) -> Self {
+        let state = Arc::new(GenerationState { total, ..default() });
+        let shared = state.clone();
+        let task = AsyncComputeTaskPool::get().spawn(async move { generate(&shared) });
+        Self { task, state }
+    }
+
+    /// The result once the task is done. Cancelled tasks fail with
+    /// [`MeshTextError::Cancelled`], even if they finished first.
+    pub(crate) fn poll(&mut self) -> Option<Result<T, MeshTextError>> {
+        let result = block_on(poll_once(&mut self.task))?;
+        Some(self.state.check().and(result))
+    }
+}
+
+impl<T> TrackedTask<T> {
+    pub(crate) fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Fraction of the glyphs generated so far.
+    pub(crate) fn progress(&self) -> f32 {
+        match self.state.total {
+            0 => 1.0,
+            total => self.state.done.load(Ordering::Relaxed) as f32 / total as f32,
+        }
+    }
+}
+
+impl<T> Drop for TrackedTask<T> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// A glyph to tessellate in the background, as it is baked.
+struct GlyphJob {
+    glyph: LayoutGlyph,
+    options: ExtrusionOptions,
+    line_y: f32,
+    font: Option<Arc<Font>>,
+    /// Placement of [`UvMode::TextBlock`] UVs. These glyphs are not cached.
+    block: Option<BlockUv>,
+    /// Mesh loaded from the glyph store, which needs no tessellation
+    stored: Option<BakedTextMesh>,
+}
+
+/// Where the mesh of a pending glyph comes from.
+enum GlyphSource {
+    Cached(Handle<Mesh>, f32, f32),
+    Job(usize),
+}
+
+struct PendingGlyph<M: Asset> {
+    glyph: LayoutGlyph,
+    line_y: f32,
+    source: GlyphSource,
+    /// Size of the glyph relative to its mesh, in the plane of the text
+    mesh_scale: f32,
+    material: Handle<M>,
+    /// Z offset of the glyph by [`Parameters::stagger`]
+    stagger: f32,
+}
+
+type JobResults = Vec<Option<(ExtrudedGlyphGeometry, f32, f32)>>;
+
+/// A text being generated on the async compute pool by [`generate_meshes_async`].
+///
+/// Dropping the handle cancels the generation, so keeping it as a component of the text
+/// entity aborts the tessellation when the entity is despawned. The plugin spawns the glyphs
+/// of `StandardMaterial` texts as children of their entity when they're done; add
+/// [`finish_text_generation`] for other materials yourself, or [`poll`](Self::poll) the
+/// handle directly.
+#[derive(Component)]
+#[require(Transform, Visibility)]
+pub struct TextGenerationHandle<M: Asset = StandardMaterial> {
+    task: Option<TrackedTask<JobResults>>,
+    glyphs: Vec<PendingGlyph<M>>,
+    /// Cache key of every job, `None` for glyphs that are not cached
+    keys: Vec<Option<GlyphCacheKey>>,
+    scale: Vec3,
+    /// Z of the front faces, see [`Parameters::front_z`]
+    front_z: f32,
+    grid: Option<LayoutGrid>,
+    /// Depth of the turned copy of a [`Parameters::double_sided`] text
+    back_depth: Option<f32>,
+}
+
+impl<M: Asset> TextGenerationHandle<M> {
+    /// Stops the generation at its next glyph. [`poll`](Self::poll) then returns
+    /// [`MeshTextError::Cancelled`] without adding any mesh.
+    pub fn cancel(&self) {
+        if let Some(task) = &self.task {
+            task.cancel();
+        }
+    }
+
+    /// Fraction of the glyphs tessellated so far, from 0 to 1. Cached glyphs don't count.
+    pub fn progress(&self) -> f32 {
+        self.task.as_ref().map_or(1.0, TrackedTask::progress)
+    }
+
+    /// Whether the result is ready to be polled, or was already taken.
+    pub fn is_finished(&self) -> bool {
+        self.task
+            .as_ref()
+            .is_none_or(|tracked| tracked.task.is_finished())
+    }
+
+    /// Takes the glyph meshes once the generation is done. The new meshes go into the glyph
+    /// cache of `fonts`, which should be the context the text was laid out with.
+    ///
+    /// Returns `None` while the generation runs and after the result was taken.
+    pub fn poll(
+        &mut self,
+        fonts: &mut Settings,
+        meshes: &mut Assets<Mesh>,
+    ) -> Option<Result<Vec<MeshTextEntry<M>>, MeshTextError>> {
+        let results = match self.task.as_mut()?.poll()? {
+            Ok(results) => results,
+            Err(e) => {
+                self.task = None;
+                return Some(Err(e));
+            }
+        };
+        self.task = None;
+
+        let generated: Vec<_> = results
+            .into_iter()
+            .zip(&self.keys)
+            .map(|(result, key)| {
+                let (geometry, center_x, center_y) = result?;
+                let mesh = match key {
+                    Some(key) => {
+                        let baked = BakedTextMesh {
+                            options: key.options(),
+                            geometry,
+                            center_x_layout: center_x,
+                            center_y_layout: center_y,
+                        };
+                        let Settings {
+                            font_system,
+                            glyph_cache,
+                            ..
+                        } = &mut *fonts;
+                        glyph_cache.insert_baked(font_system, key.clone(), baked, meshes)
+                    }
+                    None => meshes.add(geometry),
+                };
+                Some((mesh, center_x, center_y))
+            })
+            .collect();
+
+        let mut entries: Vec<_> = std::mem::take(&mut self.glyphs)
+            .into_iter()
+            .filter_map(|pending| {
+                let (mesh, center_x, center_y) = match pending.source {
+                    GlyphSource::Cached(mesh, center_x, center_y) => (mesh, center_x, center_y),
+                    GlyphSource::Job(job) => generated[job].clone()?,
+                };
+                let glyph = &pending.glyph;
+                let mesh_glyph = MeshGlyph {
+                    glyph_id: glyph.glyph_id,
+                    font_id: Some(glyph.font_id),
+                    x: glyph.x,
+                    y: glyph.y,
+                    x_offset: glyph.x_offset,
+                    y_offset: glyph.y_offset,
+                    line_y: pending.line_y,
+                    glyph_center_x_layout: center_x * pending.mesh_scale,
+                    glyph_center_y_layout: center_y * pending.mesh_scale,
+                    height: glyph.font_size,
+                    mesh_scale: Vec3::new(pending.mesh_scale, pending.mesh_scale, 1.0),
+                    mesh,
+                    material: pending.material,
+                };
+                let front_z = self.front_z + pending.stagger;
+                Some(glyph_entry(mesh_glyph, self.scale, front_z, self.grid))
+            })
+            .collect();
+        if let Some(depth) = self.back_depth {
+            add_back_side(&mut entries, depth);
+        }
+        Some(Ok(entries))
+    }
+}
+
+/// Like [`crate::generate_meshes`], but tessellates the glyphs on the async compute pool.
+///
+/// The text is laid out right away, and glyphs already in the glyph cache are reused. The
+/// others are tessellated in the background; poll the returned handle for the result.
+pub fn generate_meshes_async<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+) -> Result<TextGenerationHandle<M>, MeshTextError> {
+    let (tx, materials) = layout_text(text, &mut fonts.font_system, &mut params)?;
+    Ok(generate_glyphs_async(&tx, &materials, fonts, &params))
+}
+
+/// Starts generating the glyphs of laid out text on the async compute pool. `materials` must
+/// not be empty.
+pub(crate) fn generate_glyphs_async<M: Asset>(
+    tx: &TextGlyphs,
+    materials: &[Handle<M>],
+    fonts: &mut Settings,
+    params: &Parameters,
+) -> TextGenerationHandle<M> {
+    let options = params.extrusion_options();
+    let policy = params.missing_glyph_policy;
+    let width = tx
+        .layout_runs()
+        .fold(0.0, |width: f32, run| width.max(run.line_w));
+
+    let mut jobs = Vec::new();
+    let mut keys = Vec::new();
+    let mut job_by_key: HashMap<GlyphCacheKey, usize> = HashMap::new();
+    let mut glyphs = Vec::new();
+    for (line, run) in tx.layout_runs().enumerate() {
+        let block = (options.uv_mode == UvMode::TextBlock).then_some(BlockUv {
+            width,
+            line_top: run.line_top,
+            line_height: run.line_height,
+        });
+        for (column, glyph) in run.glyphs.iter().enumerate() {
+            let baked = BakedGlyph::new(glyph, &options);
+            let Some(key) = glyph_cache_key(&baked.glyph, policy, &baked.options) else {
+                continue;
+            };
+            let cached = block
+                .is_none()
+                .then(|| fonts.glyph_cache.get(&key))
+                .flatten();
+            let source = match cached {
+                Some(cached) => GlyphSource::Cached(
+                    cached.mesh.clone(),
+                    cached.center_x_layout,
+                    cached.center_y_layout,
+                ),
+                None => {
+                    let shared = block.is_none().then(|| job_by_key.get(&key)).flatten();
+                    GlyphSource::Job(match shared {
+                        Some(&job) => job,
+                        None => {
+                            let stored = block
+                                .is_none()
+                                .then(|| {
+                                    let Settings {
+                                        font_system,
+                                        glyph_cache,
+                                        ..
+                                    } = &mut *fonts;
+                                    glyph_cache.load_stored(font_system, &key)
+                                })
+                                .flatten();
+                            if block.is_none() {
+                                job_by_key.insert(key.clone(), jobs.len());
+                            }
+                            keys.push(block.is_none().then_some(key));
+                            jobs.push(GlyphJob {
+                                glyph: baked.glyph.clone(),
+                                options: baked.options,
+                                line_y: run.line_y,
+                                font: fonts.font_system.get_font(glyph.font_id),
+                                block,
+                                stored,
+                            });
+                            jobs.len() - 1
+                        }
+                    })
+                }
+            };
+            glyphs.push(PendingGlyph {
+                glyph: glyph.clone(),
+                line_y: run.line_y,
+                source,
+                mesh_scale: baked.scale,
+                material: materials
+                    .get(glyph.metadata)
+                    .unwrap_or_else(|| &materials[0])
+                    .clone(),
+                stagger: params
+                    .stagger
+                    .map_or(0.0, |stagger| stagger.offset(line, column)),
+            });
+        }
+    }
+
+    let task = TrackedTask::spawn(jobs.len(), move |state| {
+        let mut results = Vec::with_capacity(jobs.len());
+        for job in jobs {
+            state.check()?;
+            if let Some(stored) = job.stored {
+                let BakedTextMesh {
+                    geometry,
+                    center_x_layout,
+                    center_y_layout,
+                    ..
+                } = stored;
+                results.push(Some((geometry, center_x_layout, center_y_layout)));
+                state.advance();
+                continue;
+            }
+            let data = job.font.as_deref().map(Font::data);
+            let result = match tessellate_font_glyph(data, &job.glyph, policy, &job.options) {
+                Ok(result) => result,
+                Err(e) => {
+                    error!("Failed to tessalate glyph {:?}", e);
+                    None
+                }
+            };
+            results.push(result.map(|(mut geometry, center_x, center_y)| {
+                if let Some(block) = &job.block {
+                    block.place_uvs(&mut geometry, &job.glyph, job.line_y, center_x, center_y);
+                }
+                (geometry, center_x, center_y)
+            }));
+            state.advance();
+        }
+        Ok(results)
+    });
+
+    let scale = params.world_scale(fonts.text_scale_factor);
+    TextGenerationHandle {
+        task: Some(task),
+        glyphs,
+        keys,
+        scale,
+        front_z: params.front_z(scale),
+        grid: params.grid,
+        back_depth: params
+            .double_sided
+            .then_some(params.layout_depth() * scale.z),
+    }
+}
+
+/// Spawns the glyphs of finished [`TextGenerationHandle`]s as children of their entity and
+/// removes the handle.
+pub fn finish_text_generation<M: Material>(
+    mut commands: Commands,
+    mut texts: Query<(Entity, &mut TextGenerationHandle<M>)>,
+    mut settings: ResMut<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (entity, mut handle) in &mut texts {
+        let Some(result) = handle.poll(&mut settings, &mut meshes) else {
+            continue;
+        };
+        commands.entity(entity).remove::<TextGenerationHandle<M>>();
+        match result {
+            Ok(entries) => {
+                commands.entity(entity).with_children(|parent| {
+                    for entry in entries {
+                        let mut glyph = parent.spawn(entry.transform);
+                        insert_glyph_bounds(&mut glyph, &entry.mesh, &meshes);
+                        glyph.insert((Mesh3d(entry.mesh), MeshMaterial3d(entry.material)));
+                    }
+                });
+            }
+            Err(e) => warn!("Failed to generate text: {e}"),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! The individual stages of the mesh generation pipeline, exposed so they can be
+//! measured in isolation. Only available with the `bench` feature.
+
+use bevy::render::mesh::Mesh;
+use cosmic_text::ttf_parser::{Face, GlyphId};
+use cosmic_text::{Attrs, Buffer, FontSystem, LayoutGlyph, Metrics, Shaping};
+use lyon::path::Path;
+
+use crate::Parameters;
+use crate::command_encoder::LyonCommandEncoder;
+use crate::extrude_glyph::{ExtrudedGlyphGeometry, ExtrusionOptions, tessalate_glyph};
+use crate::text_glyphs::TextGlyphs;
+
+/// Shapes `text` on a single unbounded line and returns its layout glyphs.
+pub fn shape_glyphs(font_system: &mut FontSystem, text: &str, font_size: f32) -> Vec<LayoutGlyph> {
+    let mut buffer = Buffer::new(font_system, Metrics::new(font_size, font_size));
+    buffer.set_size(font_system, None, None);
+    buffer.set_text(font_system, text, &Attrs::new(), Shaping::Advanced);
+    buffer.shape_until_scroll(font_system, false);
+    buffer
+        .layout_runs()
+        .flat_map(|run| run.glyphs.iter().cloned())
+        .collect()
+}
+
+/// Shapes and measures `text` the same way `generate_meshes` does, without tessellating.
+pub fn layout(font_system: &mut FontSystem, text: &str, params: &Parameters) -> (f32, f32) {
+    let attrs = Attrs::new();
+    let mut tx = TextGlyphs::new(
+        Metrics::new(params.font_size, params.line_height),
+        [(text, attrs.clone())],
+        &attrs,
+        font_system,
+        params.alignment,
+    );
+    tx.measure(params.max_width, params.max_height, font_system)
+}
+
+/// Extracts the flattened outline of a glyph.
+pub fn extract_outline(font_system: &mut FontSystem, glyph: &LayoutGlyph) -> Option<Path> {
+    font_system
+        .db()
+        .with_face_data(glyph.font_id, |file, _| {
+            let face = Face::parse(file, 0).ok()?;
+            let mut builder = LyonCommandEncoder::new();
+            face.outline_glyph(GlyphId(glyph.glyph_id), &mut builder)?;
+            Some(builder.build_path())
+        })
+        .flatten()
+}
+
+/// Tessellates the front, back and side faces of a glyph.
+pub fn tessellate(
+    font_system: &mut FontSystem,
+    glyph: &LayoutGlyph,
+    extrusion_depth: f32,
+) -> Option<ExtrudedGlyphGeometry> {
+    font_system
+        .db()
+        .with_face_data(glyph.font_id, |file, _| {
+            let face = Face::parse(file, 0).ok()?;
+            let bb = face.glyph_bounding_box(GlyphId(glyph.glyph_id))?;
+            tessalate_glyph(glyph, bb, face, &ExtrusionOptions::new(extrusion_depth))
+                .ok()
+                .map(|(geometry, _, _)| geometry)
+        })
+        .flatten()
+}
+
+/// Converts tessellated geometry into a Bevy mesh.
+pub fn build_mesh(geometry: ExtrudedGlyphGeometry) -> Mesh {
+    geometry.into()
+}
@@ -0,0 +1,50 @@
+//! Bounds of glyph entities, inserted as they are spawned so frustum culling and distance
+//! checks are right from the start.
+
+use bevy::prelude::*;
+use bevy::render::mesh::{MeshAabb, VertexAttributeValues};
+use bevy::render::primitives::Aabb;
+
+/// Bounding sphere of the mesh of a glyph entity, in the space of the entity.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct GlyphBoundingSphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl GlyphBoundingSphere {
+    /// Center and radius of the sphere in world space, for an entity at `transform`. The
+    /// radius grows with the largest scale of the transform.
+    pub fn to_world(&self, transform: &GlobalTransform) -> (Vec3, f32) {
+        let scale = transform.compute_transform().scale.abs().max_element();
+        (transform.transform_point(self.center), self.radius * scale)
+    }
+}
+
+/// The `Aabb` and bounding sphere of `mesh`, `None` without positions. The sphere is
+/// centered on the box and just reaches the farthest vertex.
+pub fn glyph_bounds(mesh: &Mesh) -> Option<(Aabb, GlyphBoundingSphere)> {
+    let aabb = mesh.compute_aabb()?;
+    let center = Vec3::from(aabb.center);
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+    let radius = positions
+        .iter()
+        .map(|&p| center.distance(Vec3::from(p)))
+        .fold(0.0, f32::max);
+    Some((aabb, GlyphBoundingSphere { center, radius }))
+}
+
+/// Inserts the bounds of `mesh` on `entity`, if the mesh is loaded.
+pub(crate) fn insert_glyph_bounds(
+    entity: &mut EntityCommands,
+    mesh: &Handle<Mesh>,
+    meshes: &Assets<Mesh>,
+) {
+    if let Some(bounds) = meshes.get(mesh).and_then(glyph_bounds) {
+        entity.insert(bounds);
+    }
+}
@@ -0,0 +1,159 @@
+//! Text clipped against a mask shape before it is extruded, e.g. cut by a circle or partially
+//! erased.
+
+use bevy::prelude::*;
+use cosmic_text::ttf_parser::Face;
+use cosmic_text::{FontSystem, LayoutGlyph};
+
+use crate::contour::{
+    ClipOperation, Contour, clip_contours, contours_from_path, normalize_orientation,
+};
+use crate::extrude_glyph::{extrude_contours, glyph_path};
+use crate::mesh_text_plugin::{add_back_side, glyph_entry, layout_text};
+use crate::text_glyphs::mesh_glyph;
+use crate::{
+    FillRule, InputText, MeshTextEntry, MeshTextError, MissingGlyphPolicy, Parameters, Settings,
+};
+
+/// Bounds of `contours`, `None` without points.
+pub(crate) fn bounds(contours: &[Contour]) -> Option<Rect> {
+    contours
+        .iter()
+        .flat_map(|contour| &contour.points)
+        .map(|&p| Rect::from_corners(p, p))
+        .reduce(|a, b| a.union(b))
+}
+
+/// The oriented outline of a laid out glyph in layout units, Y up and placed like
+/// `generate_meshes` places the glyph. `None` for glyphs without an outline, including the
+/// stand-ins for missing glyphs other than `.notdef`.
+pub(crate) fn glyph_outline(
+    font_system: &mut FontSystem,
+    glyph: &LayoutGlyph,
+    line_y: f32,
+    params: &Parameters,
+) -> Option<Vec<Contour>> {
+    if glyph.glyph_id == 0 && params.missing_glyph_policy != MissingGlyphPolicy::Notdef {
+        return None;
+    }
+    let font = font_system.get_font(glyph.font_id)?;
+    let face = Face::parse(font.data(), 0).ok()?;
+    let path = glyph_path(
+        &face,
+        glyph.glyph_id,
+        &params.tessellation,
+        params.synthetic_style,
+    )
+    .ok()?;
+
+    let origin = Vec2::new(
+        glyph.x + glyph.x_offset,
+        -(line_y + glyph.y + glyph.y_offset),
+    );
+    let units = glyph.font_size / face.units_per_em() as f32;
+    let contours = contours_from_path(&path)
+        .into_iter()
+        .map(|contour| Contour {
+            points: contour.points.iter().map(|&p| origin + p * units).collect(),
+            ..contour
+        })
+        .collect();
+    Some(normalize_orientation(
+        contours,
+        params.tessellation.fill_rule,
+    ))
+}
+
+/// Generates the glyph meshes of `text` like [`crate::generate_meshes`], with the outlines
+/// clipped against `mask` before they are extruded.
+///
+/// The mask is given in the plane of the text and in the space of the glyph transforms, like
+/// [`crate::text_footprints`], and may wind either way; its holes are found by containment.
+/// Clipped glyphs get meshes of their own, centered on what is left of them. Glyphs the mask
+/// doesn't touch keep their shared meshes with [`ClipOperation::Difference`] and are dropped
+/// with [`ClipOperation::Intersection`], as are glyphs without an outline.
+pub fn generate_clipped_meshes<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    mask: &[Contour],
+    operation: ClipOperation,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    let (tx, materials) = layout_text(text, &mut fonts.font_system, &mut params)?;
+    let Settings {
+        font_system,
+        text_scale_factor,
+        glyph_cache,
+    } = fonts;
+    let scale = params.world_scale(*text_scale_factor);
+    let front_z = params.front_z(scale);
+    let options = params.extrusion_options();
+
+    let mask = normalize_orientation(
+        mask.iter()
+            .map(|contour| Contour {
+                points: contour
+                    .points
+                    .iter()
+                    .map(|&p| p / scale.truncate())
+                    .collect(),
+                closed: contour.closed,
+            })
+            .collect(),
+        FillRule::EvenOdd,
+    );
+    let mask_bounds = bounds(&mask);
+
+    let mut entries = Vec::new();
+    for run in tx.layout_runs() {
+        for glyph in run.glyphs {
+            let outline =
+                glyph_outline(font_system, glyph, run.line_y, &params).filter(|contours| {
+                    bounds(contours)
+                        .zip(mask_bounds)
+                        .is_some_and(|(a, b)| !a.intersect(b).is_empty())
+                });
+            let Some(contours) = outline else {
+                if operation == ClipOperation::Difference {
+                    entries.extend(
+                        mesh_glyph(
+                            font_system,
+                            glyph_cache,
+                            &params,
+                            &options,
+                            meshes,
+                            &materials,
+                            glyph,
+                            run.line_y,
+                            None,
+                        )
+                        .map(|glyph| glyph_entry(glyph, scale, front_z, params.grid)),
+                    );
+                }
+                continue;
+            };
+
+            let clipped = clip_contours(&contours, &mask, operation)?;
+            let Some(clipped_bounds) = bounds(&clipped) else {
+                continue;
+            };
+            let material = materials
+                .get(glyph.metadata)
+                .unwrap_or_else(|| &materials[0])
+                .clone();
+            entries.push(MeshTextEntry {
+                mesh: meshes.add(extrude_contours(&clipped, &options)?),
+                material,
+                transform: Transform::from_translation(
+                    (clipped_bounds.center() * scale.truncate()).extend(front_z),
+                )
+                .with_scale(scale),
+            });
+        }
+    }
+    if params.double_sided {
+        add_back_side(&mut entries, params.layout_depth() * scale.z);
+    }
+    Ok(entries)
+}
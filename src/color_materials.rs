@@ -0,0 +1,92 @@
+//! Materials for text colored with `Attrs::color`, so colored rich text needs no material per
+//! color.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::InputText;
+
+/// Colored copies of text materials, one per material and color.
+///
+/// The plugin adds it as a resource. The copies stay alive until [`clear`](Self::clear) is
+/// called, so texts regenerated with the same colors reuse them.
+#[derive(Resource, Debug, Default)]
+pub struct ColorMaterials {
+    materials: HashMap<(AssetId<StandardMaterial>, u32), Handle<StandardMaterial>>,
+}
+
+impl ColorMaterials {
+    /// A copy of `base` with the base color `color`, created the first time. Materials that
+    /// are not loaded are copied from the default `StandardMaterial`.
+    pub fn get_or_create(
+        &mut self,
+        base: &Handle<StandardMaterial>,
+        color: cosmic_text::Color,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Handle<StandardMaterial> {
+        self.materials
+            .entry((base.id(), color.0))
+            .or_insert_with(|| {
+                let [r, g, b, a] = color.as_rgba();
+                materials.add(StandardMaterial {
+                    base_color: Color::srgba_u8(r, g, b, a),
+                    ..materials.get(base).cloned().unwrap_or_default()
+                })
+            })
+            .clone()
+    }
+
+    /// Replaces the material of every span of `text` whose attributes have a color with a copy
+    /// of that material in the color. Spans with the same material and color share the copy;
+    /// spans without a color keep their material.
+    pub fn apply<'a>(
+        &mut self,
+        text: InputText<'a, StandardMaterial>,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> InputText<'a, StandardMaterial> {
+        match text {
+            InputText::Simple {
+                text,
+                material,
+                attrs,
+            } => InputText::Simple {
+                text,
+                material: match attrs.color_opt {
+                    Some(color) => self.get_or_create(&material, color, materials),
+                    None => material,
+                },
+                attrs,
+            },
+            InputText::Rich {
+                words,
+                materials: span_materials,
+                attrs,
+            } => InputText::Rich {
+                materials: span_materials
+                    .into_iter()
+                    .zip(&attrs)
+                    .map(|(material, attrs)| match attrs.color_opt {
+                        Some(color) => self.get_or_create(&material, color, materials),
+                        None => material,
+                    })
+                    .collect(),
+                words,
+                attrs,
+            },
+        }
+    }
+
+    /// Number of colored copies.
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.materials.clear();
+    }
+}
@@ -1,23 +1,42 @@
 use cosmic_text::ttf_parser::OutlineBuilder;
-use lyon::math::Point;
+use lyon::math::{Point, Transform};
 use lyon::path::Path;
 
-pub(crate) struct LyonCommandEncoder {
+/// Builds flattened lyon paths from font outlines.
+pub struct LyonCommandEncoder {
     builder: lyon::path::builder::WithSvg<lyon::path::builder::Flattened<lyon::path::BuilderImpl>>,
+    /// Applied to every point of the outline as it's built
+    transform: Transform,
 }
 
 impl LyonCommandEncoder {
     pub fn new() -> Self {
         // maximum distance between a curve and its approximation.
-        let tolerance = 0.05;
+        Self::with_tolerance(0.05)
+    }
+
+    /// Flattens curves to within `tolerance` font units.
+    pub fn with_tolerance(tolerance: f32) -> Self {
+        Self::with_transform(tolerance, Transform::identity())
+    }
+
+    /// Flattens curves to within `tolerance` font units after moving their points by
+    /// `transform`, e.g. a shear for a slanted outline. Curves stay curves under an affine
+    /// transform, so they're flattened as precisely as without one.
+    pub fn with_transform(tolerance: f32, transform: Transform) -> Self {
         Self {
             builder: Path::builder().with_svg().flattened(tolerance),
+            transform,
         }
     }
 
     pub fn build_path(self) -> Path {
         self.builder.build()
     }
+
+    fn point(&self, x: f32, y: f32) -> Point {
+        self.transform.transform_point(Point::new(x, y))
+    }
 }
 
 impl Default for LyonCommandEncoder {
@@ -28,21 +47,23 @@ impl Default for LyonCommandEncoder {
 
 impl OutlineBuilder for LyonCommandEncoder {
     fn move_to(&mut self, x: f32, y: f32) {
-        self.builder.move_to(Point::new(x, y));
+        let to = self.point(x, y);
+        self.builder.move_to(to);
     }
 
     fn line_to(&mut self, x: f32, y: f32) {
-        self.builder.line_to(Point::new(x, y));
+        let to = self.point(x, y);
+        self.builder.line_to(to);
     }
 
     fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
-        self.builder
-            .quadratic_bezier_to(Point::new(x1, y1), Point::new(x, y));
+        let (ctrl, to) = (self.point(x1, y1), self.point(x, y));
+        self.builder.quadratic_bezier_to(ctrl, to);
     }
 
     fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
-        self.builder
-            .cubic_bezier_to(Point::new(x1, y1), Point::new(x2, y2), Point::new(x, y));
+        let (ctrl1, ctrl2, to) = (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
     }
 
     fn close(&mut self) {
@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+use cosmic_text::{Attrs, Metrics};
+
+use crate::contour::Contour;
+use crate::extrude_glyph::extrude_contours;
+use crate::mesh_text_plugin::glyph_entry;
+use crate::text_glyphs::{TextGlyphs, mesh_glyph};
+use crate::{MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// Character standing in for icons during layout. It never breaks a line.
+const PLACEHOLDER: &str = "\u{a0}";
+
+/// A shape laid out inline with text like a glyph, e.g. a logo from
+/// `svg::parse_svg_path`.
+#[derive(Debug, Clone, Default)]
+pub struct InlineIcon {
+    pub contours: Vec<Contour>,
+    /// Height of the icon in layout units. The contours are scaled to it, keeping their
+    /// proportions, and the icon sits on the baseline.
+    pub height: f32,
+    /// Space before and after the icon, in layout units
+    pub margin: f32,
+}
+
+impl InlineIcon {
+    pub fn new(contours: Vec<Contour>, height: f32) -> Self {
+        Self {
+            contours,
+            height,
+            margin: 0.0,
+        }
+    }
+
+    /// The contours scaled to `height`, and their width.
+    fn scaled(&self) -> (Vec<Contour>, f32) {
+        let points = self.contours.iter().flat_map(|contour| &contour.points);
+        let min = points.clone().fold(Vec2::MAX, |min, p| min.min(*p));
+        let max = points.fold(Vec2::MIN, |max, p| max.max(*p));
+        let size = (max - min).max(Vec2::ZERO);
+        let scale = if size.y > 0.0 {
+            self.height / size.y
+        } else {
+            0.0
+        };
+        let contours = self
+            .contours
+            .iter()
+            .map(|contour| Contour {
+                points: contour.points.iter().map(|p| *p * scale).collect(),
+                closed: contour.closed,
+            })
+            .collect();
+        (contours, size.x * scale)
+    }
+
+    /// Horizontal space taken by the icon.
+    pub fn advance(&self) -> f32 {
+        self.scaled().1 + 2.0 * self.margin
+    }
+}
+
+/// A part of a composite text: a run of text or an icon, each with its material.
+pub enum CompositePart<'a, M: Asset> {
+    Text {
+        text: String,
+        material: Handle<M>,
+        attrs: Attrs<'a>,
+    },
+    Icon {
+        icon: InlineIcon,
+        material: Handle<M>,
+    },
+}
+
+/// Generates the meshes of text with icons laid out inline, e.g. a play symbol followed by
+/// "PLAY", in one call.
+///
+/// Icons take part in the layout like glyphs with their [`InlineIcon::advance`], so they wrap
+/// and align with the text. Text glyphs come from the glyph cache; icon meshes are generated
+/// once per call.
+pub fn generate_composite_meshes<M: Asset>(
+    parts: Vec<CompositePart<M>>,
+    fonts: &mut Settings,
+    params: Parameters,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    if parts.is_empty() {
+        return Err(MeshTextError::InvalidInput);
+    }
+    let Settings {
+        font_system,
+        text_scale_factor,
+        glyph_cache,
+    } = fonts;
+    let scale = params.world_scale(*text_scale_factor);
+    let front_z = params.front_z(scale);
+
+    let default_attrs = parts
+        .iter()
+        .find_map(|part| match part {
+            CompositePart::Text { attrs, .. } => Some(attrs.clone()),
+            CompositePart::Icon { .. } => None,
+        })
+        .unwrap_or_else(Attrs::new);
+    let materials: Vec<Handle<M>> = parts
+        .iter()
+        .map(|part| match part {
+            CompositePart::Text { material, .. } | CompositePart::Icon { material, .. } => {
+                material.clone()
+            }
+        })
+        .collect();
+    // Scaled contours, size and margin of every icon part
+    let icons: Vec<Option<(Vec<Contour>, Vec2, f32)>> = parts
+        .iter()
+        .map(|part| match part {
+            CompositePart::Text { .. } => None,
+            CompositePart::Icon { icon, .. } => {
+                let (contours, width) = icon.scaled();
+                Some((contours, Vec2::new(width, icon.height), icon.margin))
+            }
+        })
+        .collect();
+
+    let metrics = Metrics {
+        font_size: params.font_size,
+        line_height: params.line_height,
+    };
+    // Letter spacing widens each placeholder to the advance of its icon, in ems
+    let mut spacing = vec![0.0; parts.len()];
+    let layout = |font_system: &mut _, spacing: &[f32]| {
+        let spans = parts.iter().enumerate().map(|(i, part)| match part {
+            CompositePart::Text { text, attrs, .. } => (text.as_str(), attrs.clone().metadata(i)),
+            CompositePart::Icon { .. } => (
+                PLACEHOLDER,
+                default_attrs.clone().metadata(i).letter_spacing(spacing[i]),
+            ),
+        });
+        let mut tx = TextGlyphs::new(
+            metrics,
+            spans,
+            &default_attrs,
+            font_system,
+            params.alignment,
+        );
+        tx.measure(params.max_width, params.max_height, font_system);
+        tx
+    };
+
+    // Measure the placeholders, then lay out again with the icons' advances
+    let measured = layout(font_system, &spacing);
+    for run in measured.layout_runs() {
+        for glyph in run.glyphs {
+            if let Some(Some((_, size, margin))) = icons.get(glyph.metadata) {
+                spacing[glyph.metadata] = (size.x + 2.0 * margin - glyph.w) / glyph.font_size;
+            }
+        }
+    }
+    let tx = layout(font_system, &spacing);
+
+    let options = params.extrusion_options();
+    let mut icon_meshes: Vec<Option<Handle<Mesh>>> = vec![None; parts.len()];
+    let mut entries = Vec::new();
+    for run in tx.layout_runs() {
+        for glyph in run.glyphs {
+            let Some(Some((contours, size, margin))) = icons.get(glyph.metadata) else {
+                entries.extend(
+                    mesh_glyph(
+                        font_system,
+                        glyph_cache,
+                        &params,
+                        &options,
+                        meshes,
+                        &materials,
+                        glyph,
+                        run.line_y,
+                        None,
+                    )
+                    .map(|glyph| glyph_entry(glyph, scale, front_z, params.grid)),
+                );
+                continue;
+            };
+
+            let mesh = match &icon_meshes[glyph.metadata] {
+                Some(mesh) => mesh.clone(),
+                None => {
+                    let mesh = meshes.add(extrude_contours(contours, &options)?);
+                    icon_meshes[glyph.metadata] = Some(mesh.clone());
+                    mesh
+                }
+            };
+            // Centered in its advance, standing on the baseline
+            let center = Vec2::new(
+                glyph.x + margin + size.x / 2.0,
+                -(run.line_y + glyph.y) + size.y / 2.0,
+            );
+            entries.push(MeshTextEntry {
+                mesh,
+                transform: Transform::from_translation((center * scale.truncate()).extend(front_z))
+                    .with_scale(scale),
+                material: materials[glyph.metadata].clone(),
+            });
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,565 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::math::Vec2;
+use lyon::geom::point;
+use lyon::path::iterator::PathIterator;
+use lyon::path::{Path, PathEvent};
+use lyon::tessellation::{
+    BuffersBuilder, FillOptions, FillRule, FillTessellator, FillVertex, VertexBuffers,
+};
+
+use crate::MeshTextError;
+
+/// Longest allowed miter, as a multiple of the offset distance. Sharper corners are clamped so
+/// thin spikes don't shoot out of the outline.
+const MITER_LIMIT: f32 = 4.0;
+
+/// A flattened outline contour.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Vec2>,
+    pub closed: bool,
+}
+
+impl Contour {
+    /// Shoelace area, positive for counter-clockwise contours in a Y-up space.
+    pub fn signed_area(&self) -> f32 {
+        let n = self.points.len();
+        (0..n)
+            .map(|i| self.points[i].perp_dot(self.points[(i + 1) % n]))
+            .sum::<f32>()
+            / 2.0
+    }
+
+    /// Whether `point` lies inside the contour, by the even-odd rule.
+    pub fn contains(&self, point: Vec2) -> bool {
+        let mut inside = false;
+        // Open contours are treated as closed
+        for (a, b) in self.edges().chain(closing_edge(self)) {
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Iterates the contour's edges, including the closing edge of closed contours.
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let n = self.points.len();
+        let count = if self.closed { n } else { n.saturating_sub(1) };
+        (0..count).map(move |i| (self.points[i], self.points[(i + 1) % n]))
+    }
+
+    /// Drops points between edges running in the same direction, within `tolerance` radians, so
+    /// straight runs of an outline become single edges.
+    pub fn merge_collinear(&self, tolerance: f32) -> Contour {
+        let mut points = self.points.clone();
+        let mut i = 0;
+        while i < points.len() && points.len() > 3 {
+            let n = points.len();
+            let (prev, next) = match (i, self.closed) {
+                (0, false) => {
+                    i += 1;
+                    continue;
+                }
+                (i, false) if i + 1 == n => break,
+                (i, _) => (points[(i + n - 1) % n], points[(i + 1) % n]),
+            };
+            let incoming = (points[i] - prev).normalize_or_zero();
+            let outgoing = (next - points[i]).normalize_or_zero();
+            let straight = incoming.dot(outgoing) > 0.0
+                && incoming.perp_dot(outgoing).abs() <= tolerance.sin();
+            if straight {
+                points.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        Contour {
+            points,
+            closed: self.closed,
+        }
+    }
+
+    /// Splits a self-intersecting closed contour into simple loops at its crossings. Offsetting
+    /// far enough makes parts of a contour cross over, e.g. when the legs of an "m" separate.
+    pub fn split_loops(&self) -> Vec<Contour> {
+        if !self.closed {
+            return vec![self.clone()];
+        }
+        let mut pending = vec![self.points.clone()];
+        let mut loops = Vec::new();
+        while let Some(points) = pending.pop() {
+            let Some((i, j, crossing)) = first_crossing(&points) else {
+                loops.push(Contour {
+                    points,
+                    closed: true,
+                });
+                continue;
+            };
+            let mut inner = vec![crossing];
+            inner.extend_from_slice(&points[i + 1..=j]);
+            let mut outer = vec![crossing];
+            outer.extend_from_slice(&points[j + 1..]);
+            outer.extend_from_slice(&points[..=i]);
+            for mut part in [inner, outer] {
+                // Crossings at a corner repeat it
+                part.dedup_by(|a, b| a.distance_squared(*b) < 1e-8);
+                if part.len() > 1 && part[0].distance_squared(part[part.len() - 1]) < 1e-8 {
+                    part.pop();
+                }
+                if part.len() > 2 {
+                    pending.push(part);
+                }
+            }
+        }
+        loops
+    }
+
+    /// Outline of a strip of `width` centered on an open contour, with square ends and wound
+    /// counter-clockwise. `None` for closed contours and contours without a length.
+    pub fn ribbon(&self, width: f32) -> Option<Contour> {
+        if self.closed || self.points.len() < 2 || width <= 0.0 {
+            return None;
+        }
+        let mut points = self.offset(-width / 2.0).points;
+        points.extend(self.offset(width / 2.0).points.into_iter().rev());
+        let mut ribbon = Contour {
+            points,
+            closed: true,
+        };
+        if ribbon.signed_area() < 0.0 {
+            ribbon.points.reverse();
+        }
+        Some(ribbon)
+    }
+
+    /// Average width of the contour: twice its area over its perimeter, e.g. the width of a
+    /// long thin strip, and 0 for a hairline that doubles back on itself.
+    pub fn mean_thickness(&self) -> f32 {
+        let perimeter: f32 = self
+            .edges()
+            .chain(closing_edge(self))
+            .map(|(a, b)| a.distance(b))
+            .sum();
+        if perimeter > 0.0 {
+            2.0 * self.signed_area().abs() / perimeter
+        } else {
+            0.0
+        }
+    }
+
+    /// Outline of a stroke `width` wide along every edge of the contour, with square ends,
+    /// wound counter-clockwise, e.g. to thicken a hairline. Strokes around holes come with
+    /// clockwise loops for them.
+    pub fn stroke(&self, width: f32) -> Result<Vec<Contour>, MeshTextError> {
+        let edges: Vec<Contour> = self
+            .edges()
+            .chain(closing_edge(self))
+            .filter(|(a, b)| a != b)
+            .map(|(a, b)| {
+                let along = (b - a).normalize() * width / 2.0;
+                let across = along.perp();
+                Contour {
+                    points: vec![
+                        a - along - across,
+                        b + along - across,
+                        b + along + across,
+                        a - along + across,
+                    ],
+                    closed: true,
+                }
+            })
+            .collect();
+        if edges.is_empty() {
+            return Ok(Vec::new());
+        }
+        resolve_overlaps(&edges)
+    }
+
+    /// Moves every point `distance` to the left of the direction of travel, using mitered
+    /// corners. Negative distances move to the right.
+    pub fn offset(&self, distance: f32) -> Contour {
+        let n = self.points.len();
+        if n < 2 {
+            return self.clone();
+        }
+
+        let left_normal = |a: Vec2, b: Vec2| (b - a).normalize_or_zero().perp();
+        let points = (0..n)
+            .map(|i| {
+                let p = self.points[i];
+                let prev = (self.closed || i > 0).then(|| self.points[(i + n - 1) % n]);
+                let next = (self.closed || i + 1 < n).then(|| self.points[(i + 1) % n]);
+
+                let normal = match (prev, next) {
+                    (Some(prev), Some(next)) => {
+                        let n1 = left_normal(prev, p);
+                        let n2 = left_normal(p, next);
+                        let bisector = (n1 + n2).normalize_or_zero();
+                        let cos_half = bisector.dot(n1);
+                        if bisector == Vec2::ZERO {
+                            // The outline doubles back on itself
+                            n1
+                        } else if cos_half < 1.0 / MITER_LIMIT {
+                            bisector * MITER_LIMIT
+                        } else {
+                            bisector / cos_half
+                        }
+                    }
+                    (None, Some(next)) => left_normal(p, next),
+                    (Some(prev), None) => left_normal(prev, p),
+                    (None, None) => Vec2::ZERO,
+                };
+                p + normal * distance
+            })
+            .collect();
+
+        Contour {
+            points,
+            closed: self.closed,
+        }
+    }
+}
+
+/// Finds two non-adjacent edges of a closed polygon crossing each other, returning the indices
+/// of their first points and the crossing.
+fn first_crossing(points: &[Vec2]) -> Option<(usize, usize, Vec2)> {
+    let n = points.len();
+    for i in 0..n {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        for j in i + 2..n {
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+            let (c, d) = (points[j], points[(j + 1) % n]);
+            let denominator = (b - a).perp_dot(d - c);
+            if denominator.abs() < f32::EPSILON {
+                continue;
+            }
+            let t = (c - a).perp_dot(d - c) / denominator;
+            let u = (c - a).perp_dot(b - a) / denominator;
+            // A corner touching the middle of another edge splits the contour as well, but
+            // two edges meeting at their ends don't
+            const EPSILON: f32 = 1e-5;
+            let inside = |x: f32| x > EPSILON && x < 1.0 - EPSILON;
+            let on = |x: f32| x > -EPSILON && x < 1.0 + EPSILON;
+            if (inside(t) && on(u)) || (on(t) && inside(u)) {
+                return Some((i, j, a + (b - a) * t));
+            }
+        }
+    }
+    None
+}
+
+/// Splits a path into its contours, flattening curves and dropping repeated points.
+pub fn contours_from_path(path: &Path) -> Vec<Contour> {
+    // Same tolerance as the outline encoder, in path units
+    const TOLERANCE: f32 = 0.05;
+
+    let mut contours = Vec::new();
+    let mut current = Contour::default();
+    for event in path.iter().flattened(TOLERANCE) {
+        match event {
+            PathEvent::Begin { at } => {
+                current = Contour::default();
+                current.points.push(Vec2::new(at.x, at.y));
+            }
+            PathEvent::Line { to, .. } => {
+                let to = Vec2::new(to.x, to.y);
+                if current.points.last() != Some(&to) {
+                    current.points.push(to);
+                }
+            }
+            PathEvent::End { close, .. } => {
+                let mut contour = std::mem::take(&mut current);
+                // Outlines usually repeat their first point to close the shape
+                if contour.points.len() > 1 && contour.points.first() == contour.points.last() {
+                    contour.points.pop();
+                }
+                contour.closed = close;
+                if contour.points.len() > 1 {
+                    contours.push(contour);
+                }
+            }
+            PathEvent::Quadratic { .. } | PathEvent::Cubic { .. } => {
+                unreachable!("flattened paths only contain lines")
+            }
+        }
+    }
+    contours
+}
+
+/// Repairs broken outlines, as produced by some CFF fonts: merges points closer than
+/// `tolerance`, closes open contours and drops contours without an area.
+pub fn repair_contours(contours: Vec<Contour>, tolerance: f32) -> Vec<Contour> {
+    let tolerance_squared = tolerance * tolerance;
+    contours
+        .into_iter()
+        .filter_map(|mut contour| {
+            contour
+                .points
+                .dedup_by(|a, b| a.distance_squared(*b) <= tolerance_squared);
+            // A contour that ends where it started was only missing its close command
+            while contour.points.len() > 1
+                && contour.points[0].distance_squared(contour.points[contour.points.len() - 1])
+                    <= tolerance_squared
+            {
+                contour.points.pop();
+            }
+            contour.closed = true;
+            (contour.points.len() > 2 && contour.signed_area().abs() > tolerance_squared)
+                .then_some(contour)
+        })
+        .collect()
+}
+
+/// Builds a path from contours, e.g. to tessellate offset contours.
+pub fn path_from_contours<'a>(contours: impl IntoIterator<Item = &'a Contour>) -> Path {
+    let mut builder = Path::builder();
+    for contour in contours {
+        let Some((first, rest)) = contour.points.split_first() else {
+            continue;
+        };
+        builder.begin(point(first.x, first.y));
+        for p in rest {
+            builder.line_to(point(p.x, p.y));
+        }
+        builder.end(contour.closed);
+    }
+    builder.build()
+}
+
+/// Replaces contours that overlap each other with the outline of the area they wind around
+/// counter-clockwise, e.g. inset contours of an outer contour and a hole that cross where a
+/// stroke is thinner than twice the inset.
+///
+/// The outline runs through the crossings, so faces filled from it and walls built along it
+/// share their edges. Expects oriented contours, see [`normalize_orientation`].
+pub fn resolve_overlaps(contours: &[Contour]) -> Result<Vec<Contour>, MeshTextError> {
+    outline_where(contours, |p| winding(contours, p) > 0)
+}
+
+/// Moves oriented contours `distance` outward, growing outer contours and shrinking holes, or
+/// the other way for negative distances.
+///
+/// Offsets that cross over themselves are split into loops, and loops winding against their
+/// contour are parts that collapsed, so they're dropped. Grown contours may overlap each
+/// other, see [`resolve_overlaps`]. Expects oriented contours, see [`normalize_orientation`].
+pub fn grow_contours(contours: &[Contour], distance: f32) -> Vec<Contour> {
+    // The filled area is left of every edge, so moving right grows it
+    contours
+        .iter()
+        .flat_map(|contour| {
+            let winding = contour.signed_area();
+            contour
+                .offset(-distance)
+                .split_loops()
+                .into_iter()
+                .filter(move |grown| grown.signed_area() * winding > 0.0)
+        })
+        .collect()
+}
+
+/// How a mask clips outlines, see [`clip_contours`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClipOperation {
+    /// Keep the parts of the outline inside the mask
+    #[default]
+    Intersection,
+    /// Cut the mask out of the outline
+    Difference,
+}
+
+/// Clips `contours` against `mask`, e.g. to cut text with a circle or erase part of a letter.
+///
+/// Like [`resolve_overlaps`], the result runs through the crossings of both outlines and
+/// expects oriented contours, see [`normalize_orientation`].
+pub fn clip_contours(
+    contours: &[Contour],
+    mask: &[Contour],
+    operation: ClipOperation,
+) -> Result<Vec<Contour>, MeshTextError> {
+    let both: Vec<Contour> = contours.iter().chain(mask).cloned().collect();
+    outline_where(&both, |p| {
+        let masked = winding(mask, p) > 0;
+        winding(contours, p) > 0
+            && match operation {
+                ClipOperation::Intersection => masked,
+                ClipOperation::Difference => !masked,
+            }
+    })
+}
+
+/// Winding number of oriented `contours` around `p`.
+fn winding(contours: &[Contour], p: Vec2) -> i32 {
+    contours
+        .iter()
+        .flat_map(|contour| contour.edges().chain(closing_edge(contour)))
+        .map(|(a, b)| {
+            let side = (b - a).perp_dot(p - a);
+            if a.y <= p.y && b.y > p.y && side > 0.0 {
+                1
+            } else if b.y <= p.y && a.y > p.y && side < 0.0 {
+                -1
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+/// The outline of the triangles of the arrangement of `contours` whose centroid is `inside`.
+/// The arrangement covers the area the contours wind around counter-clockwise.
+fn outline_where(
+    contours: &[Contour],
+    inside: impl Fn(Vec2) -> bool,
+) -> Result<Vec<Contour>, MeshTextError> {
+    let mut geometry: VertexBuffers<Vec2, u32> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path_from_contours(contours),
+            &FillOptions::default().with_fill_rule(FillRule::NonZero),
+            &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| {
+                Vec2::new(vertex.position().x, vertex.position().y)
+            }),
+        )
+        .map_err(|_| MeshTextError::TessellationFailed)?;
+
+    // The same position may come out as several vertices
+    let mut ids: HashMap<[u32; 2], u32> = HashMap::new();
+    let mut positions = Vec::new();
+    let mut id = |p: Vec2| {
+        *ids.entry([p.x.to_bits(), p.y.to_bits()])
+            .or_insert_with(|| {
+                positions.push(p);
+                positions.len() as u32 - 1
+            })
+    };
+    let mut edges = HashSet::new();
+    for tri in geometry.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| geometry.vertices[tri[k] as usize]);
+        let area = (b - a).perp_dot(c - a);
+        // Triangles never straddle a contour, so their centroid tells which side they are on
+        if area == 0.0 || !inside((a + b + c) / 3.0) {
+            continue;
+        }
+        let [a, b, c] = if area > 0.0 { [a, b, c] } else { [a, c, b] }.map(&mut id);
+        edges.extend([(a, b), (b, c), (c, a)]);
+    }
+
+    // Edges without a twin running the other way are on the outline, with the area to
+    // their left. Chain them into loops.
+    let mut next: HashMap<u32, Vec<u32>> = HashMap::new();
+    for &(a, b) in &edges {
+        if !edges.contains(&(b, a)) {
+            next.entry(a).or_default().push(b);
+        }
+    }
+    let mut starts: Vec<u32> = next.keys().copied().collect();
+    starts.sort_unstable();
+    let mut loops = Vec::new();
+    for start in starts {
+        while next.get(&start).is_some_and(|ends| !ends.is_empty()) {
+            let mut points = vec![positions[start as usize]];
+            let mut current = start;
+            while let Some(to) = next.get_mut(&current).and_then(Vec::pop) {
+                if to == start {
+                    break;
+                }
+                points.push(positions[to as usize]);
+                current = to;
+            }
+            if points.len() > 2 {
+                loops.push(Contour {
+                    points,
+                    closed: true,
+                });
+            }
+        }
+    }
+    Ok(loops)
+}
+
+/// The edge back to the start of an open contour, which fills treat as closed.
+fn closing_edge(contour: &Contour) -> Option<(Vec2, Vec2)> {
+    let n = contour.points.len();
+    (!contour.closed && n > 2).then(|| (contour.points[n - 1], contour.points[0]))
+}
+
+/// Orients contours the same way whatever the convention of the font: outer contours
+/// counter-clockwise and holes clockwise, so the filled area is always to the left.
+///
+/// With `EvenOdd` a contour is a hole when an odd number of other contours contain it, so
+/// contours wound against the convention are fixed as well. `NonZero` fills depend on the
+/// winding, so all contours are only flipped together, following [`fill_side`].
+pub fn normalize_orientation(mut contours: Vec<Contour>, fill_rule: FillRule) -> Vec<Contour> {
+    let flip_all = fill_side(&contours) < 0.0;
+    let holes: Vec<bool> = match fill_rule {
+        FillRule::EvenOdd => contours
+            .iter()
+            .enumerate()
+            .map(|(i, contour)| {
+                let Some(&inner) = contour.points.first() else {
+                    return false;
+                };
+                let depth = contours
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, other)| j != i && other.contains(inner))
+                    .count();
+                depth % 2 == 1
+            })
+            .collect(),
+        FillRule::NonZero => vec![false; contours.len()],
+    };
+    for (contour, hole) in contours.iter_mut().zip(holes) {
+        let area = contour.signed_area();
+        let reverse = match fill_rule {
+            FillRule::EvenOdd => (area < 0.0 && !hole) || (area > 0.0 && hole),
+            FillRule::NonZero => flip_all,
+        };
+        if reverse {
+            contour.points.reverse();
+        }
+    }
+    contours
+}
+
+/// The outer contours of oriented `contours` that no other outer contour contains, i.e. the
+/// silhouette of the outline with its holes and whatever lies in them filled.
+pub fn outermost_contours(contours: Vec<Contour>) -> Vec<Contour> {
+    let outer: Vec<Contour> = contours
+        .into_iter()
+        .filter(|contour| contour.signed_area() > 0.0)
+        .collect();
+    outer
+        .iter()
+        .enumerate()
+        .filter(|&(i, contour)| {
+            contour.points.first().is_some_and(|&inner| {
+                !outer
+                    .iter()
+                    .enumerate()
+                    .any(|(j, other)| j != i && other.contains(inner))
+            })
+        })
+        .map(|(_, contour)| contour.clone())
+        .collect()
+}
+
+/// Sign to apply to offsets so that positive distances move into the filled area.
+///
+/// TrueType outlines wind their outer contours clockwise and PostScript outlines wind them
+/// counter-clockwise; holes always wind the other way. The largest contour is an outer contour
+/// either way.
+pub fn fill_side(contours: &[Contour]) -> f32 {
+    let largest = contours
+        .iter()
+        .map(Contour::signed_area)
+        .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or_default();
+    if largest >= 0.0 { 1.0 } else { -1.0 }
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use cosmic_text::Attrs;
+
+use crate::entity_pool::{GlyphEntityPool, PooledText, clear_pooled_text, update_pooled_text};
+use crate::{InputText, Parameters, Settings, generate_meshes};
+
+/// A printed label and the entities showing it.
+#[derive(Debug)]
+struct DebugLabel {
+    root: Entity,
+    glyphs: PooledText,
+    remaining: Duration,
+}
+
+/// Immediate-mode world-space text for visual debugging, like `Gizmos` for text.
+///
+/// Call [`DebugText3d::print`] from any system to show a label for the current frame, or
+/// [`DebugText3d::print_for`] to keep it for a while. Labels are generated through the glyph
+/// cache and their entities come from the [`GlyphEntityPool`], so printing every frame doesn't
+/// regenerate geometry or churn entities.
+#[derive(Resource, Default)]
+pub struct DebugText3d {
+    /// Parameters used to generate the labels
+    pub parameters: Parameters,
+    /// Material of the labels. An unlit white material is created when unset.
+    pub material: Option<Handle<StandardMaterial>>,
+    queued: Vec<(Vec3, String, Duration)>,
+    labels: Vec<DebugLabel>,
+    idle: Vec<DebugLabel>,
+}
+
+impl DebugText3d {
+    /// Shows `text` at `position` for the current frame.
+    pub fn print(&mut self, position: Vec3, text: &str) {
+        self.print_for(position, text, Duration::ZERO);
+    }
+
+    /// Shows `text` at `position` until `duration` has passed.
+    pub fn print_for(&mut self, position: Vec3, text: &str, duration: Duration) {
+        self.queued.push((position, text.to_string(), duration));
+    }
+
+    /// Number of labels currently shown.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+/// Hides expired labels and spawns the ones printed since the last run.
+pub fn draw_debug_text(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut debug: ResMut<DebugText3d>,
+    mut pool: ResMut<GlyphEntityPool>,
+    mut settings: ResMut<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let DebugText3d {
+        parameters,
+        material,
+        queued,
+        labels,
+        idle,
+    } = &mut *debug;
+
+    let mut index = 0;
+    while index < labels.len() {
+        let label = &mut labels[index];
+        label.remaining = label.remaining.saturating_sub(time.delta());
+        if label.remaining.is_zero() {
+            let mut label = labels.swap_remove(index);
+            clear_pooled_text(&mut commands, &mut pool, &mut label.glyphs);
+            commands.entity(label.root).insert(Visibility::Hidden);
+            idle.push(label);
+        } else {
+            index += 1;
+        }
+    }
+
+    if queued.is_empty() {
+        return;
+    }
+    let material = material
+        .get_or_insert_with(|| {
+            materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                unlit: true,
+                ..default()
+            })
+        })
+        .clone();
+
+    for (position, text, duration) in queued.drain(..) {
+        let input = InputText::Simple {
+            text,
+            material: material.clone(),
+            attrs: Attrs::new(),
+        };
+        let entries = match generate_meshes(input, &mut settings, parameters.clone(), &mut meshes) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("Failed to generate debug text: {e}");
+                continue;
+            }
+        };
+
+        let mut label = idle.pop().unwrap_or_else(|| DebugLabel {
+            root: commands.spawn_empty().id(),
+            glyphs: PooledText::default(),
+            remaining: Duration::ZERO,
+        });
+        label.remaining = duration;
+        commands
+            .entity(label.root)
+            .insert((Transform::from_translation(position), Visibility::Visible));
+        update_pooled_text(
+            &mut commands,
+            &mut pool,
+            label.root,
+            &mut label.glyphs,
+            entries,
+            &meshes,
+        );
+        labels.push(label);
+    }
+}
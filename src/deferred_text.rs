@@ -0,0 +1,122 @@
+//! Text that is only generated once a camera can see it.
+
+use bevy::prelude::*;
+use bevy::render::primitives::{Aabb, Frustum};
+
+use crate::async_generation::generate_glyphs_async;
+use crate::mesh_text_plugin::layout_text;
+use crate::text_glyphs::TextGlyphs;
+use crate::{InputText, Parameters, Settings};
+
+enum DeferredState<M: Asset> {
+    Text(InputText<'static, M>),
+    /// Laid out, with the bounds of its lines unless it has no glyphs
+    LaidOut(TextGlyphs, Vec<Handle<M>>, Option<Aabb>),
+    /// Taken while the text is being laid out or generated
+    Empty,
+}
+
+/// Text whose meshes are generated once it enters the view of a camera, e.g. signs spread over
+/// a large level that would stall loading if all generated at once.
+///
+/// The text is laid out right away and gets an [`Aabb`] around its lines, in the space of its
+/// glyphs. As soon as that box intersects the frustum of an active camera, the component is
+/// replaced by a [`TextGenerationHandle`](crate::TextGenerationHandle), which tessellates the
+/// glyphs in the background and spawns them as children.
+#[derive(Component)]
+#[require(Transform, Visibility)]
+pub struct DeferredText<M: Asset = StandardMaterial> {
+    state: DeferredState<M>,
+    params: Parameters,
+}
+
+impl<M: Asset> DeferredText<M> {
+    pub fn new(text: InputText<'static, M>, params: Parameters) -> Self {
+        Self {
+            state: DeferredState::Text(text),
+            params,
+        }
+    }
+}
+
+/// Bounds of laid out text, in the space of its glyph transforms.
+fn text_bounds(tx: &TextGlyphs, params: &Parameters, scale: Vec3) -> Option<Aabb> {
+    let Rect { min, max } = tx.bounds()?;
+    let sides = if params.double_sided { 2.0 } else { 1.0 };
+    let depth = sides * params.layout_depth();
+    // Inflated caps bulge out of both faces
+    let bulge = params.inflate.map_or(0.0, |inflate| inflate.amount.abs());
+    let front = params.front_z(scale) * Vec3::Z;
+    // Staggered glyphs reach from the smallest to the largest offset
+    let (near, far) = params.stagger.map_or((0.0, 0.0), |stagger| {
+        tx.layout_runs()
+            .enumerate()
+            .flat_map(|(line, run)| (0..run.glyphs.len()).map(move |column| (line, column)))
+            .map(|(line, column)| stagger.offset(line, column))
+            .fold((0.0, 0.0), |(near, far), z| (z.min(near), z.max(far)))
+    });
+    Some(Aabb::from_min_max(
+        min.extend(-bulge) * scale + front + near * Vec3::Z,
+        max.extend(depth + bulge) * scale + front + far * Vec3::Z,
+    ))
+}
+
+/// Lays out new [`DeferredText`]s and starts generating those that became visible.
+///
+/// Runs after transform propagation and the frustum update, so the text and cameras are
+/// tested where they are shown this frame.
+pub fn update_deferred_text<M: Asset>(
+    mut commands: Commands,
+    mut texts: Query<(Entity, &mut DeferredText<M>, &GlobalTransform)>,
+    cameras: Query<(&Camera, &Frustum)>,
+    mut settings: ResMut<Settings>,
+) {
+    for (entity, mut deferred, transform) in &mut texts {
+        let deferred = &mut *deferred;
+        let state = std::mem::replace(&mut deferred.state, DeferredState::Empty);
+        deferred.state = match state {
+            DeferredState::Text(text) => {
+                match layout_text(text, &mut settings.font_system, &mut deferred.params) {
+                    Ok((tx, materials)) => {
+                        let scale = deferred.params.world_scale(settings.text_scale_factor);
+                        let bounds = text_bounds(&tx, &deferred.params, scale);
+                        if let Some(bounds) = bounds {
+                            commands.entity(entity).insert(bounds);
+                        }
+                        DeferredState::LaidOut(tx, materials, bounds)
+                    }
+                    Err(e) => {
+                        warn!("Failed to lay out deferred text: {e}");
+                        commands.entity(entity).remove::<DeferredText<M>>();
+                        continue;
+                    }
+                }
+            }
+            state => state,
+        };
+        let DeferredState::LaidOut(_, _, bounds) = &deferred.state else {
+            continue;
+        };
+
+        // Text without glyphs has nothing to wait for
+        let visible = bounds.is_none_or(|bounds| {
+            cameras.iter().any(|(camera, frustum)| {
+                camera.is_active
+                    && frustum.intersects_obb(&bounds, &transform.affine(), true, false)
+            })
+        });
+        if !visible {
+            continue;
+        }
+        let DeferredState::LaidOut(tx, materials, _) =
+            std::mem::replace(&mut deferred.state, DeferredState::Empty)
+        else {
+            continue;
+        };
+        let handle = generate_glyphs_async(&tx, &materials, &mut settings, &deferred.params);
+        commands
+            .entity(entity)
+            .remove::<DeferredText<M>>()
+            .insert(handle);
+    }
+}
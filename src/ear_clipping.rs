@@ -0,0 +1,226 @@
+//! Ear clipping of flattened outlines, an alternative to lyon's sweep line fill for outlines
+//! it fails on, e.g. the self-touching contours of some display fonts.
+//!
+//! Holes are bridged into the outer contour around them, and the resulting polygons are
+//! clipped one ear at a time. No vertices are added, so every triangle corner is a point of
+//! the contours. Where no ear is left, e.g. in a polygon that crosses itself, the vertex at
+//! hand is clipped anyway, so the fill degrades instead of failing.
+
+use bevy::math::Vec2;
+use lyon::tessellation::FillRule;
+
+use crate::contour::Contour;
+
+/// Triangulates the area `contours` fill by `fill_rule`. Returns three indices per triangle
+/// into the points of all contours in order, wound clockwise like lyon's fills in a Y-up
+/// space.
+///
+/// Contours are expected not to cross each other. Which side of a contour is filled is told
+/// by the winding of the other contours around its first point.
+pub fn ear_clip(contours: &[Contour], fill_rule: FillRule) -> Vec<u32> {
+    let points: Vec<Vec2> = contours
+        .iter()
+        .flat_map(|contour| contour.points.iter().copied())
+        .collect();
+    let mut starts = Vec::with_capacity(contours.len());
+    let mut start = 0;
+    for contour in contours {
+        starts.push(start);
+        start += contour.points.len() as u32;
+    }
+    let winding = |contour: &Contour| contour.signed_area().signum() as i32;
+    // Even-odd fills cancel out contours that come in pairs, e.g. a contour without a lip on
+    // both sides of the lip of a front inset
+    let cancelled: Vec<bool> = contours
+        .iter()
+        .enumerate()
+        .map(|(i, contour)| {
+            fill_rule == FillRule::EvenOdd
+                && contours
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, other)| j != i && other.points == contour.points)
+                    .count()
+                    % 2
+                    == 1
+        })
+        .collect();
+    let usable = |i: usize| {
+        let contour = &contours[i];
+        !cancelled[i] && contour.points.len() > 2 && contour.signed_area() != 0.0
+    };
+    let filled = |winding: i32| match fill_rule {
+        FillRule::EvenOdd => winding % 2 != 0,
+        FillRule::NonZero => winding != 0,
+    };
+
+    // A contour bounds the fill on one side when the winding number changes from filled to
+    // empty across it
+    let mut outers = Vec::new();
+    let mut holes = Vec::new();
+    for (i, contour) in contours.iter().enumerate() {
+        if !usable(i) {
+            continue;
+        }
+        let outside: i32 = contours
+            .iter()
+            .enumerate()
+            .filter(|&(j, other)| j != i && usable(j) && other.contains(contour.points[0]))
+            .map(|(_, other)| winding(other))
+            .sum();
+        match (filled(outside), filled(outside + winding(contour))) {
+            (false, true) => outers.push(i),
+            (true, false) => holes.push(i),
+            _ => {}
+        }
+    }
+
+    // Outer contours run counter-clockwise and holes clockwise
+    let ring = |i: usize, counter_clockwise: bool| -> Vec<u32> {
+        let mut ring: Vec<u32> = (starts[i]..starts[i] + contours[i].points.len() as u32).collect();
+        if (contours[i].signed_area() > 0.0) != counter_clockwise {
+            ring.reverse();
+        }
+        ring
+    };
+    let mut polygons: Vec<Vec<u32>> = outers.iter().map(|&i| ring(i, true)).collect();
+    let mut nested: Vec<Vec<Vec<u32>>> = vec![Vec::new(); outers.len()];
+    for &hole in &holes {
+        // A hole belongs to the smallest outer contour around it
+        let inner = contours[hole].points[0];
+        let outer = outers
+            .iter()
+            .enumerate()
+            .filter(|&(_, &outer)| contours[outer].contains(inner))
+            .min_by(|(_, a), (_, b)| {
+                let area = |i: usize| contours[i].signed_area().abs();
+                area(**a).total_cmp(&area(**b))
+            });
+        if let Some((k, _)) = outer {
+            nested[k].push(ring(hole, false));
+        }
+    }
+
+    let mut indices = Vec::new();
+    for (mut polygon, mut holes) in polygons.drain(..).zip(nested) {
+        // Bridging the rightmost holes first keeps the bridges of the others clear of them
+        let rightmost = |hole: &Vec<u32>| {
+            hole.iter()
+                .map(|&i| points[i as usize].x)
+                .fold(f32::MIN, f32::max)
+        };
+        holes.sort_by(|a, b| rightmost(b).total_cmp(&rightmost(a)));
+        for hole in &holes {
+            bridge(&mut polygon, hole, &points);
+        }
+        clip(polygon, &points, &mut indices);
+    }
+    indices
+}
+
+/// Joins `hole` into `polygon` along a bridge from its rightmost point to a point of the
+/// polygon visible from it, after Eberly's "Triangulation by Ear Clipping". The bridge is
+/// walked both ways, so the polygon stays a single loop. Holes no ray reaches are left out.
+fn bridge(polygon: &mut Vec<u32>, hole: &[u32], points: &[Vec2]) {
+    let at = |i: u32| points[i as usize];
+    let Some(m_index) = (0..hole.len()).max_by(|&a, &b| at(hole[a]).x.total_cmp(&at(hole[b]).x))
+    else {
+        return;
+    };
+    let m = at(hole[m_index]);
+
+    // The closest edge of the polygon a ray from M to the right crosses. Only edges running
+    // upwards face the ray, with the polygon counter-clockwise and its bridged holes clockwise.
+    let n = polygon.len();
+    let mut closest: Option<(f32, usize)> = None;
+    for i in 0..n {
+        let (a, b) = (at(polygon[i]), at(polygon[(i + 1) % n]));
+        if !(a.y <= m.y && m.y <= b.y && a.y < b.y) {
+            continue;
+        }
+        let x = a.x + (m.y - a.y) / (b.y - a.y) * (b.x - a.x);
+        if x >= m.x && closest.is_none_or(|(closest, _)| x < closest) {
+            closest = Some((x, i));
+        }
+    }
+    let Some((x, edge)) = closest else {
+        return;
+    };
+    let crossing = Vec2::new(x, m.y);
+    let (a, b) = (edge, (edge + 1) % n);
+    let mut visible = if at(polygon[a]).x > at(polygon[b]).x {
+        a
+    } else {
+        b
+    };
+    let candidate = at(polygon[visible]);
+    if candidate != crossing {
+        // Reflex points of the polygon inside the triangle of M, the crossing and the
+        // candidate may block the view, and the one closest in angle to the ray is visible
+        let mut best = f32::MAX;
+        for i in 0..n {
+            let p = at(polygon[i]);
+            let (prev, next) = (at(polygon[(i + n - 1) % n]), at(polygon[(i + 1) % n]));
+            let reflex = (p - prev).perp_dot(next - p) <= 0.0;
+            if !reflex || p == candidate || !in_triangle(p, m, crossing, candidate) {
+                continue;
+            }
+            let to = p - m;
+            let angle = to.y.abs() / to.length().max(f32::MIN_POSITIVE);
+            if angle < best || angle == best && to.length() < (at(polygon[visible]) - m).length() {
+                best = angle;
+                visible = i;
+            }
+        }
+    }
+
+    let mut joined = Vec::with_capacity(n + hole.len() + 2);
+    joined.extend_from_slice(&polygon[..=visible]);
+    joined.extend(hole[m_index..].iter().chain(&hole[..m_index]));
+    joined.push(hole[m_index]);
+    joined.extend_from_slice(&polygon[visible..]);
+    *polygon = joined;
+}
+
+/// Clips ears off the counter-clockwise `polygon` until a triangle is left, adding them to
+/// `indices`.
+fn clip(mut polygon: Vec<u32>, points: &[Vec2], indices: &mut Vec<u32>) {
+    let at = |i: u32| points[i as usize];
+    let mut i = 0;
+    let mut stalled = 0;
+    while polygon.len() > 3 {
+        let n = polygon.len();
+        let (a, b, c) = (polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]);
+        let (pa, pb, pc) = (at(a), at(b), at(c));
+        let convex = (pb - pa).perp_dot(pc - pb) > 0.0;
+        let ear = convex
+            && polygon.iter().all(|&other| {
+                let p = at(other);
+                p == pa || p == pb || p == pc || !in_triangle(p, pa, pb, pc)
+            });
+        // Without an ear in a whole turn the polygon is degenerate, and the vertex at hand
+        // goes, keeping its triangle unless it is flat or turned over
+        if ear || stalled >= n {
+            if convex {
+                indices.extend([a, c, b]);
+            }
+            polygon.remove(i);
+            stalled = 0;
+            i = i.checked_sub(1).unwrap_or(polygon.len() - 1) % polygon.len();
+        } else {
+            i = (i + 1) % n;
+            stalled += 1;
+        }
+    }
+    if let [a, b, c] = polygon[..]
+        && (at(b) - at(a)).perp_dot(at(c) - at(b)) > 0.0
+    {
+        indices.extend([a, c, b]);
+    }
+}
+
+/// Whether `p` lies inside or on the edges of the triangle `a`, `b`, `c`, wound either way.
+fn in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    let sides = [(a, b), (b, c), (c, a)].map(|(from, to)| (to - from).perp_dot(p - from));
+    sides.iter().all(|&side| side >= 0.0) || sides.iter().all(|&side| side <= 0.0)
+}
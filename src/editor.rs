@@ -0,0 +1,264 @@
+use bevy::prelude::*;
+use cosmic_text::{Action, Attrs, Buffer, Edit, Editor, FontSystem, Metrics, Shaping};
+
+use crate::entity_pool::{GlyphEntityPool, PooledText, clear_pooled_text, update_pooled_text};
+use crate::mesh_text_plugin::glyph_entry;
+use crate::text_glyphs::mesh_glyph;
+use crate::{Parameters, Settings};
+
+/// Width of the caret relative to the font size
+const CARET_WIDTH: f32 = 0.08;
+
+/// The entities showing one line of the buffer.
+#[derive(Debug)]
+struct EditorLine {
+    text: String,
+    root: Entity,
+    glyphs: PooledText,
+}
+
+/// Editable in-world text backed by a `cosmic_text::Editor`.
+///
+/// Edit the text with [`MeshTextEditor::action`] or [`MeshTextEditor::insert_str`].
+/// [`update_mesh_text_editors`] then regenerates the glyphs of the lines whose text changed,
+/// moves the other lines and places a caret mesh at the cursor. The plugin runs it for
+/// `StandardMaterial`; add it for other materials yourself.
+#[derive(Component)]
+#[require(Transform, Visibility)]
+pub struct MeshTextEditor<M: Material = StandardMaterial> {
+    editor: Editor<'static>,
+    params: Parameters,
+    material: Handle<M>,
+    /// Whether to show the caret
+    pub show_caret: bool,
+    lines: Vec<EditorLine>,
+    caret: Option<Entity>,
+}
+
+impl<M: Material> MeshTextEditor<M> {
+    /// Creates an editor containing `text`, laid out and extruded with `params`.
+    pub fn new(
+        font_system: &mut FontSystem,
+        text: &str,
+        attrs: &Attrs,
+        params: Parameters,
+        material: Handle<M>,
+    ) -> Self {
+        let mut buffer = Buffer::new_empty(Metrics::new(params.font_size, params.line_height));
+        buffer.set_size(font_system, params.max_width, params.max_height);
+        buffer.set_rich_text(
+            font_system,
+            [(text, attrs.clone())],
+            attrs,
+            Shaping::Advanced,
+            params.alignment,
+        );
+        Self {
+            editor: Editor::new(buffer),
+            params,
+            material,
+            show_caret: true,
+            lines: Vec::new(),
+            caret: None,
+        }
+    }
+
+    /// Performs an editing action such as moving the cursor or deleting a character.
+    pub fn action(&mut self, font_system: &mut FontSystem, action: Action) {
+        self.editor.action(font_system, action);
+    }
+
+    /// Inserts `text` at the cursor, replacing the selection.
+    pub fn insert_str(&mut self, text: &str) {
+        self.editor.insert_string(text, None);
+    }
+
+    /// The edited text, with lines joined by `\n`.
+    pub fn text(&self) -> String {
+        self.editor.with_buffer(|buffer| {
+            buffer
+                .lines
+                .iter()
+                .map(|line| line.text())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+
+    pub fn editor(&self) -> &Editor<'static> {
+        &self.editor
+    }
+
+    /// Mutable access to the wrapped editor, e.g. to set the cursor or the selection.
+    pub fn editor_mut(&mut self) -> &mut Editor<'static> {
+        &mut self.editor
+    }
+
+    /// Root entities of the buffer lines, in order.
+    pub fn lines(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.lines.iter().map(|line| line.root)
+    }
+
+    pub fn caret(&self) -> Option<Entity> {
+        self.caret
+    }
+
+    /// Updates the glyph entities below `entity` to the current text and cursor.
+    fn sync(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        pool: &mut GlyphEntityPool,
+        settings: &mut Settings,
+        meshes: &mut Assets<Mesh>,
+    ) {
+        let Settings {
+            font_system,
+            text_scale_factor,
+            glyph_cache,
+        } = settings;
+        let scale = self.params.world_scale(*text_scale_factor);
+        let front_z = self.params.front_z(scale);
+        let Self {
+            editor,
+            params,
+            material,
+            show_caret,
+            lines,
+            caret,
+        } = self;
+
+        editor.shape_as_needed(font_system, false);
+        let (texts, tops) = editor.with_buffer(|buffer| {
+            let texts: Vec<String> = buffer.lines.iter().map(|l| l.text().to_string()).collect();
+            let mut tops = vec![None; texts.len()];
+            for run in buffer.layout_runs() {
+                tops[run.line_i].get_or_insert(run.line_top);
+            }
+            (texts, tops)
+        });
+
+        // Lines before and after the edit keep their glyphs
+        let prefix = lines
+            .iter()
+            .zip(&texts)
+            .take_while(|(line, text)| line.text == **text)
+            .count();
+        let suffix = lines[prefix..]
+            .iter()
+            .rev()
+            .zip(texts[prefix..].iter().rev())
+            .take_while(|(line, text)| line.text == **text)
+            .count();
+        let tail = lines.split_off(lines.len() - suffix);
+        let mut stale = lines.split_off(prefix).into_iter();
+
+        let options = params.extrusion_options();
+        let materials = [material.clone()];
+        for (line_i, text) in texts
+            .iter()
+            .enumerate()
+            .take(texts.len() - suffix)
+            .skip(prefix)
+        {
+            let mut line = stale.next().unwrap_or_else(|| {
+                let root = commands
+                    .spawn((Transform::default(), Visibility::default()))
+                    .id();
+                commands.entity(entity).add_child(root);
+                EditorLine {
+                    text: String::new(),
+                    root,
+                    glyphs: PooledText::default(),
+                }
+            });
+            line.text.clone_from(text);
+
+            let top = tops[line_i].unwrap_or_default();
+            let mut entries = Vec::new();
+            editor.with_buffer(|buffer| {
+                for run in buffer.layout_runs().filter(|run| run.line_i == line_i) {
+                    for glyph in run.glyphs {
+                        entries.extend(
+                            mesh_glyph(
+                                font_system,
+                                glyph_cache,
+                                params,
+                                &options,
+                                meshes,
+                                &materials,
+                                glyph,
+                                run.line_y - top,
+                                None,
+                            )
+                            .map(|glyph| glyph_entry(glyph, scale, front_z, params.grid)),
+                        );
+                    }
+                }
+            });
+            update_pooled_text(commands, pool, line.root, &mut line.glyphs, entries, meshes);
+            lines.push(line);
+        }
+        for mut line in stale {
+            clear_pooled_text(commands, pool, &mut line.glyphs);
+            commands.entity(line.root).despawn();
+        }
+        lines.extend(tail);
+
+        for (line, top) in lines.iter().zip(&tops) {
+            let y = -top.unwrap_or_default() * scale.y;
+            commands
+                .entity(line.root)
+                .insert(Transform::from_xyz(0.0, y, 0.0));
+        }
+
+        let caret = *caret.get_or_insert_with(|| {
+            let mesh = meshes.add(Cuboid::new(
+                CARET_WIDTH * params.font_size,
+                params.line_height,
+                params.layout_depth(),
+            ));
+            let caret = commands
+                .spawn((Mesh3d(mesh), MeshMaterial3d(material.clone())))
+                .id();
+            commands.entity(entity).add_child(caret);
+            caret
+        });
+        match editor.cursor_position().filter(|_| *show_caret) {
+            Some((x, y)) => {
+                let center = Vec3::new(
+                    x as f32,
+                    -(y as f32 + params.line_height / 2.0),
+                    params.layout_depth() / 2.0,
+                );
+                commands.entity(caret).insert((
+                    Transform::from_translation(center * scale + front_z * Vec3::Z)
+                        .with_scale(scale),
+                    Visibility::Inherited,
+                ));
+            }
+            None => {
+                commands.entity(caret).insert(Visibility::Hidden);
+            }
+        }
+    }
+}
+
+/// Regenerates the changed lines and moves the caret of edited [`MeshTextEditor`]s.
+pub fn update_mesh_text_editors<M: Material>(
+    mut commands: Commands,
+    mut editors: Query<(Entity, &mut MeshTextEditor<M>), Changed<MeshTextEditor<M>>>,
+    mut pool: ResMut<GlyphEntityPool>,
+    mut settings: ResMut<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (entity, mut editor) in &mut editors {
+        editor.bypass_change_detection().sync(
+            &mut commands,
+            entity,
+            &mut pool,
+            &mut settings,
+            &mut meshes,
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+
+use crate::MeshTextEntry;
+use crate::bounds::insert_glyph_bounds;
+
+/// Hidden glyph entities kept around for reuse, so text that changes every frame doesn't
+/// spawn and despawn entities.
+///
+/// The plugin adds a pool of up to 1024 entities. Insert `GlyphEntityPool::new(max)` after
+/// the plugin to change the limit.
+#[derive(Resource, Debug, Clone)]
+pub struct GlyphEntityPool {
+    free: Vec<Entity>,
+    max: usize,
+}
+
+impl Default for GlyphEntityPool {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl GlyphEntityPool {
+    /// Creates a pool that keeps at most `max` unused entities.
+    pub fn new(max: usize) -> Self {
+        Self {
+            free: Vec::new(),
+            max,
+        }
+    }
+
+    /// Number of unused entities in the pool.
+    pub fn len(&self) -> usize {
+        self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.free.is_empty()
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Takes an entity from the pool, or spawns one if the pool is empty, and sets it up to
+    /// show `entry`, with the bounds of its mesh in `meshes`.
+    pub fn acquire<M: Material>(
+        &mut self,
+        commands: &mut Commands,
+        entry: MeshTextEntry<M>,
+        meshes: &Assets<Mesh>,
+    ) -> Entity {
+        let mesh = entry.mesh.clone();
+        let components = (
+            Mesh3d(entry.mesh),
+            MeshMaterial3d(entry.material),
+            entry.transform,
+            Visibility::Inherited,
+        );
+        let mut entity = match self.free.pop() {
+            Some(entity) => {
+                let mut entity = commands.entity(entity);
+                entity.insert(components);
+                entity
+            }
+            None => commands.spawn(components),
+        };
+        insert_glyph_bounds(&mut entity, &mesh, meshes);
+        entity.id()
+    }
+
+    /// Hides `entity` and returns it to the pool, or despawns it if the pool is full.
+    pub fn release(&mut self, commands: &mut Commands, entity: Entity) {
+        if self.free.len() < self.max {
+            commands
+                .entity(entity)
+                .remove::<ChildOf>()
+                .insert(Visibility::Hidden);
+            self.free.push(entity);
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// The glyph entities of a text whose glyphs come from a [`GlyphEntityPool`].
+#[derive(Component, Debug, Clone, Default)]
+pub struct PooledText {
+    glyphs: Vec<Entity>,
+}
+
+impl PooledText {
+    pub fn glyphs(&self) -> &[Entity] {
+        &self.glyphs
+    }
+}
+
+/// Replaces the glyphs of the pooled text on `root` with `entries`.
+///
+/// Existing glyph entities are updated in place, missing ones come from `pool` and leftover
+/// ones are returned to it. The glyphs get the bounds of their meshes in `meshes`.
+pub fn update_pooled_text<M: Material>(
+    commands: &mut Commands,
+    pool: &mut GlyphEntityPool,
+    root: Entity,
+    text: &mut PooledText,
+    entries: Vec<MeshTextEntry<M>>,
+    meshes: &Assets<Mesh>,
+) {
+    for removed in text.glyphs.drain(entries.len().min(text.glyphs.len())..) {
+        pool.release(commands, removed);
+    }
+
+    let kept = text.glyphs.len();
+    for (index, entry) in entries.into_iter().enumerate() {
+        if index < kept {
+            let mut glyph = commands.entity(text.glyphs[index]);
+            insert_glyph_bounds(&mut glyph, &entry.mesh, meshes);
+            glyph.insert((
+                Mesh3d(entry.mesh),
+                MeshMaterial3d(entry.material),
+                entry.transform,
+            ));
+        } else {
+            let glyph = pool.acquire(commands, entry, meshes);
+            commands.entity(root).add_child(glyph);
+            text.glyphs.push(glyph);
+        }
+    }
+}
+
+/// Returns all glyphs of the pooled text to `pool`.
+pub fn clear_pooled_text(
+    commands: &mut Commands,
+    pool: &mut GlyphEntityPool,
+    text: &mut PooledText,
+) {
+    for glyph in text.glyphs.drain(..) {
+        pool.release(commands, glyph);
+    }
+}
@@ -0,0 +1,84 @@
+//! Areas of the layout that lines flow around, e.g. a picture or a 3D object set into a
+//! page of text.
+
+use bevy::math::{Rect, Vec2};
+
+/// An area kept clear of glyphs, in layout units with Y down from the top left of the text,
+/// like the layout of cosmic-text. Glyphs sit at the negated Y in the text's space.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExclusionZone {
+    Rect(Rect),
+    /// A polygon, wound either way. Lines keep clear of all of its width across their
+    /// height, so the bays of concave polygons stay empty.
+    Polygon(Vec<Vec2>),
+}
+
+impl ExclusionZone {
+    /// Whether every coordinate of the zone is finite.
+    pub fn is_finite(&self) -> bool {
+        match self {
+            ExclusionZone::Rect(rect) => rect.min.is_finite() && rect.max.is_finite(),
+            ExclusionZone::Polygon(points) => points.iter().all(|point| point.is_finite()),
+        }
+    }
+
+    /// The left and right edges of the zone between the heights `top` and `bottom`, or `None`
+    /// if it doesn't reach between them.
+    pub fn span(&self, top: f32, bottom: f32) -> Option<(f32, f32)> {
+        match self {
+            ExclusionZone::Rect(rect) => {
+                (rect.min.y < bottom && rect.max.y > top).then_some((rect.min.x, rect.max.x))
+            }
+            ExclusionZone::Polygon(points) => {
+                // The polygon between the two heights reaches as far as its points in there
+                // and its edges where they cross them
+                let mut xs = Vec::new();
+                for (i, &a) in points.iter().enumerate() {
+                    let b = points[(i + 1) % points.len()];
+                    if a.y > top && a.y < bottom {
+                        xs.push(a.x);
+                    }
+                    for y in [top, bottom] {
+                        if (a.y - y) * (b.y - y) <= 0.0 && a.y != b.y {
+                            xs.push(a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x));
+                        }
+                    }
+                }
+                let min = xs.iter().copied().reduce(f32::min)?;
+                let max = xs.iter().copied().reduce(f32::max)?;
+                Some((min, max))
+            }
+        }
+    }
+}
+
+/// The stretches of a line from 0 to `width` between the heights `top` and `bottom` that no
+/// zone covers, left to right.
+pub(crate) fn free_segments(
+    zones: &[ExclusionZone],
+    top: f32,
+    bottom: f32,
+    width: f32,
+) -> Vec<(f32, f32)> {
+    let mut spans: Vec<(f32, f32)> = zones
+        .iter()
+        .filter_map(|zone| zone.span(top, bottom))
+        .collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut segments = Vec::new();
+    let mut start = 0.0;
+    for (left, right) in spans {
+        if left > start {
+            segments.push((start, left.min(width)));
+        }
+        start = start.max(right);
+        if start >= width {
+            break;
+        }
+    }
+    if start < width {
+        segments.push((start, width));
+    }
+    segments.retain(|(start, end)| end > start);
+    segments
+}
@@ -1,32 +1,685 @@
-use bevy::{asset::RenderAssetUsages, prelude::*, render::mesh::PrimitiveTopology};
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::mesh::{MeshVertexAttribute, PrimitiveTopology, VertexAttributeValues},
+    render::render_resource::VertexFormat,
+};
 use cosmic_text::ttf_parser::Rect;
 use cosmic_text::ttf_parser::{Face, GlyphId};
 use lyon::{
     geom::point,
-    path::PathEvent,
-    tessellation::{BuffersBuilder, FillOptions, FillTessellator, FillVertex, VertexBuffers},
+    path::{Event, Path},
+    tessellation::{
+        BuffersBuilder, FillOptions, FillRule, FillTessellator, FillVertex, VertexBuffers,
+    },
+};
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::FRAC_PI_2;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::command_encoder::LyonCommandEncoder;
+use crate::contour::{
+    Contour, contours_from_path, grow_contours, normalize_orientation, outermost_contours,
+    path_from_contours, repair_contours, resolve_overlaps,
 };
+use crate::ear_clipping::ear_clip;
+use crate::generation_report::GlyphIssue;
+use crate::lightmap::pack_charts;
+use crate::noise::vector_noise;
+use crate::{
+    BevelEdges, BevelWidth, FrontInset, FrontRim, InflateParameters, MeshTextError, RoundedBack,
+    SurfaceNoise, SyntheticStyle,
+};
+
+/// Largest angle, in radians, between edges merged by
+/// [`TessellationOptions::merge_coplanar_walls`]
+const COLLINEAR_TOLERANCE: f32 = 1e-3;
+
+/// Distance, relative to the em size, below which [`TessellationOptions::repair_outlines`]
+/// merges points
+const REPAIR_TOLERANCE: f32 = 1e-3;
+
+/// Distance, relative to the em size, within which an edge of the front inset lies on a
+/// contour left out by [`ExtrusionOptions::bevel_edges`]
+const ON_EDGE_TOLERANCE: f32 = 1e-4;
+
+/// How far [`ExtrusionOptions::front_rim`] sits behind the front face, relative to its width,
+/// so it never fights with the caps of neighbouring glyphs
+const RIM_SETBACK: f32 = 0.1;
+
+/// How often [`ExtrusionOptions::rounded_back`] halves its radius looking for rings that
+/// don't fold over, before leaving the back flat
+const ROUNDED_BACK_ATTEMPTS: usize = 6;
 
-use crate::MeshTextError;
+/// Vertices a cap may grow to while it is subdivided for [`ExtrusionOptions::inflate`]
+const INFLATE_MAX_CAP_VERTICES: usize = 16_384;
 
-#[derive(Debug, Clone)]
+/// Passes of diagonal flips improving the triangles of a subdivided cap
+const MAX_FLIP_PASSES: usize = 64;
+
+/// Wall cells per noise feature of [`ExtrusionOptions::surface_noise`], along and across
+const NOISE_CELLS_PER_FEATURE: f32 = 4.0;
+
+/// Wall cells a glyph may be split into for [`ExtrusionOptions::surface_noise`]. Denser
+/// noise gets coarser cells.
+const MAX_NOISE_CELLS: usize = 8_192;
+
+/// Smallest angle, in radians, between the faces on both sides of an edge kept by
+/// [`OutputTopology::Lines`]
+const WIREFRAME_CREASE_ANGLE: f32 = 0.5;
+
+/// Cells per unit of the grid that vertices are snapped to when matching the edges of
+/// [`OutputTopology::Lines`]
+const WIREFRAME_GRID: f32 = 1024.0;
+
+#[derive(Debug, Clone, Default)]
 pub struct ExtrudedGlyphGeometry {
     pub vertices: Vec<Vec3>,
     pub indices: Vec<u16>,
     pub normals: Vec<Vec3>,
     pub uvs: Vec<Vec2>, // Added UV coordinates for texture mapping
+    /// Non-overlapping lightmap coordinates, stored in `UV_1`
+    pub uv1: Option<Vec<Vec2>>,
+    /// Per-vertex distance into the edge, 0 on the outer walls and rim and 1 on the caps.
+    /// Stored in the vertex color alpha so shaders can tint the edge.
+    pub edge_mask: Option<Vec<f32>>,
+    /// Per-vertex linear RGB tint, white except on the front rim. Stored in the vertex color
+    /// together with the edge mask.
+    pub vertex_colors: Option<Vec<[f32; 3]>>,
+    /// Whether every vertex is skinned to joint 0, see [`crate::skeleton`].
+    pub skinned: bool,
+    /// Per-vertex depth as a fraction of the extrusion depth, 0 on the front face and 1 on the
+    /// back face. Stored in [`ATTRIBUTE_DEPTH_FRACTION`].
+    pub depth_fraction: Option<Vec<f32>>,
+    /// Per-vertex [`TextSurface`] id. Stored in [`ATTRIBUTE_SURFACE`].
+    pub surface: Option<Vec<f32>>,
+    /// What `indices` list: triangles, line segments or points
+    pub topology: OutputTopology,
+    /// Vertex ranges of the caps, the lip and the rim, whose UVs map the outline rather than
+    /// the walls, for [`UvMode::TextBlock`]. Not part of the mesh.
+    pub caps: Vec<std::ops::Range<usize>>,
+    /// What tessellating the glyph ran into, for the [`crate::GenerationReport`]. Not part of
+    /// the mesh.
+    pub issues: Vec<GlyphIssue>,
+}
+
+/// The surfaces of an extruded glyph, as written to [`ATTRIBUTE_SURFACE`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TextSurface {
+    /// The front face, or the recessed cap with a front inset
+    Front = 0,
+    /// The lip and its inner walls with a front inset
+    Edge = 1,
+    /// The walls along the outline
+    Side = 2,
+    /// The back face
+    Back = 3,
+}
+
+/// Vertex attribute holding the depth of a vertex as a fraction of the extrusion depth.
+pub const ATTRIBUTE_DEPTH_FRACTION: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_DepthFraction", 284_190_517, VertexFormat::Float32);
+
+/// Vertex attribute holding the [`TextSurface`] id of a vertex.
+pub const ATTRIBUTE_SURFACE: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_TextSurface", 284_190_518, VertexFormat::Float32);
+
+impl ExtrudedGlyphGeometry {
+    /// Whether every triangle winds counter-clockwise when seen from the side its normals point
+    /// to, so the mesh renders correctly with back-face culling.
+    pub fn winding_matches_normals(&self) -> bool {
+        if self.topology != OutputTopology::Triangles {
+            return true;
+        }
+        self.indices.chunks_exact(3).all(|tri| {
+            let [a, b, c] = [0, 1, 2].map(|k| self.vertices[tri[k] as usize]);
+            let normal = self.normals[tri[0] as usize];
+            // Degenerate triangles don't render either way
+            (b - a).cross(c - a).dot(normal) >= 0.0
+        })
+    }
+
+    /// Estimated size of the vertex attributes and indices once uploaded as a mesh.
+    pub fn estimated_byte_size(&self) -> usize {
+        self.vertices.len() * size_of::<Vec3>()
+            + self.normals.len() * size_of::<Vec3>()
+            + self.uvs.len() * size_of::<Vec2>()
+            + self
+                .uv1
+                .as_ref()
+                .map_or(0, |uv1| uv1.len() * size_of::<Vec2>())
+            + self
+                .depth_fraction
+                .as_ref()
+                .map_or(0, |depth_fraction| depth_fraction.len() * size_of::<f32>())
+            + self
+                .surface
+                .as_ref()
+                .map_or(0, |surface| surface.len() * size_of::<f32>())
+            + if self.edge_mask.is_some() || self.vertex_colors.is_some() {
+                self.vertices.len() * size_of::<[f32; 4]>()
+            } else {
+                0
+            }
+            + if self.skinned {
+                self.vertices.len() * (size_of::<[u16; 4]>() + size_of::<[f32; 4]>())
+            } else {
+                0
+            }
+            + self.indices.len() * size_of::<u16>()
+    }
+
+    /// Turns the triangles into the primitives of `topology`, keeping the vertices. Geometry
+    /// that isn't made of triangles is returned as it is.
+    pub fn with_topology(mut self, topology: OutputTopology) -> Self {
+        if self.topology != OutputTopology::Triangles || topology == OutputTopology::Triangles {
+            return self;
+        }
+        // Vertices are split between the caps and walls, so edges are matched by position,
+        // snapped to a grid absorbing rounding differences between the pieces
+        let key = |i: u16| {
+            (self.vertices[i as usize] * WIREFRAME_GRID)
+                .round()
+                .as_ivec3()
+                .to_array()
+        };
+        let mut edges: HashMap<_, (u16, u16, Vec<Vec3>)> = HashMap::new();
+        for tri in self.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|k| self.vertices[tri[k] as usize]);
+            let normal = (b - a).cross(c - a).normalize_or_zero();
+            if normal == Vec3::ZERO {
+                continue;
+            }
+            for (from, to) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+                let edge_key = if key(from) < key(to) {
+                    (key(from), key(to))
+                } else {
+                    (key(to), key(from))
+                };
+                edges
+                    .entry(edge_key)
+                    .or_insert_with(|| (from, to, Vec::new()))
+                    .2
+                    .push(normal);
+            }
+        }
+        let min_cos = WIREFRAME_CREASE_ANGLE.cos();
+        let mut lines: Vec<(u16, u16)> = edges
+            .into_values()
+            .filter(|(_, _, normals)| {
+                normals.len() == 1
+                    || normals
+                        .iter()
+                        .any(|normal| normal.dot(normals[0]) < min_cos)
+            })
+            .map(|(from, to, _)| (from, to))
+            .collect();
+        // Hash map order isn't stable, but meshes should be
+        lines.sort_unstable();
+
+        self.indices = match topology {
+            OutputTopology::Points => {
+                let mut seen = HashSet::new();
+                lines
+                    .iter()
+                    .flat_map(|&(from, to)| [from, to])
+                    .filter(|&i| seen.insert(key(i)))
+                    .collect()
+            }
+            _ => lines.iter().flat_map(|&(from, to)| [from, to]).collect(),
+        };
+        self.topology = topology;
+        self
+    }
+}
+
+/// How texture coordinates are laid out on the front and back caps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum UvMode {
+    /// Positions relative to the em square, squeezed into the middle of the texture
+    #[default]
+    Em,
+    /// 0..1 across the bounding box of each glyph's outline, with V running down from its
+    /// top, so a texture or decal covers every letter face the same way
+    GlyphBounds,
+    /// U runs along the laid out text, 0..1 across its widest line, and V down each line,
+    /// 0..1 across the line height, so a gradient flows across all letters. The UVs depend on
+    /// where a glyph sits, so these glyphs are not shared through the glyph cache.
+    ///
+    /// Glyphs tessellated on their own get layout units around the glyph center, Y down.
+    TextBlock,
+}
+
+/// Where the walls' texture U, running from 0 to 1 around each contour, wraps back to 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WallSeam {
+    /// Where the font starts the contour
+    #[default]
+    ContourStart,
+    /// At the lowest point of the contour, out of sight of a camera looking down at the text
+    Lowest,
+}
+
+/// The primitives glyph meshes are made of.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OutputTopology {
+    /// Solid triangles
+    #[default]
+    Triangles,
+    /// A `LineList` wireframe of the edges where the surface folds: the outlines of the front
+    /// and back and the corners of the walls, e.g. for holographic text
+    Lines,
+    /// A `PointList` of the corners of those edges
+    Points,
+}
+
+/// Options shaping the extruded geometry of every glyph.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExtrusionOptions {
+    pub extrusion_depth: f32,
+    pub front_inset: Option<FrontInset>,
+    /// Width of the lip of the front inset, in place of its width, resolved for each glyph
+    pub bevel_width: Option<BevelWidth>,
+    /// Contours the front inset runs along
+    pub bevel_edges: BevelEdges,
+    /// Width of the lip of the front inset along holes, in place of its width elsewhere
+    pub hole_bevel_width: Option<BevelWidth>,
+    /// Colored ring around the front face
+    pub front_rim: Option<FrontRim>,
+    /// Rounding of the back edges
+    pub rounded_back: Option<RoundedBack>,
+    /// Bulge of the caps
+    pub inflate: Option<InflateParameters>,
+    /// Noise roughening the walls
+    pub surface_noise: Option<SurfaceNoise>,
+    /// Bake the edge mask into the vertex colors
+    pub edge_mask: bool,
+    /// Add joint attributes that skin every vertex to joint 0
+    pub skinned: bool,
+    /// Add the depth fraction attribute
+    pub depth_attribute: bool,
+    /// Add the surface id attribute
+    pub surface_attribute: bool,
+    /// Texture coordinates of the caps
+    pub uv_mode: UvMode,
+    /// Add non-overlapping lightmap coordinates in `UV_1`
+    pub lightmap_uvs: bool,
+    /// Largest angle, in radians, between walls that are shaded as one smooth surface. Walls
+    /// meeting at a sharper corner keep a hard edge. Walls are flat when `None`, and so are the
+    /// walls of contours the front inset leaves out by [`Self::bevel_edges`], as they are split
+    /// where the cap meets the lip.
+    pub wall_smoothing_angle: Option<f32>,
+    /// Where the texture U of the walls wraps around each contour
+    pub wall_seam: WallSeam,
+    /// Texture repeats per unit of the mesh on the walls, U by the distance walked around each
+    /// contour and V by the depth. `None` maps 0..1 once around each contour and across the depth.
+    pub wall_uv_scale: Option<f32>,
+    /// Rows the walls are split into along the depth, at least one
+    pub depth_segments: u32,
+    /// Extrude only the outermost contours, filling the holes, e.g. for shadow casters
+    pub silhouette: bool,
+    /// How outlines are flattened and filled
+    pub tessellation: TessellationOptions,
+    /// Primitives of the generated meshes
+    pub topology: OutputTopology,
+    /// Slant and weight faked on the glyph outlines
+    pub synthetic_style: Option<SyntheticStyle>,
+}
+
+/// How glyph outlines are flattened and filled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TessellationOptions {
+    /// Rule deciding which parts of overlapping contours are filled. Fonts with overlapping
+    /// contours, common in variable and decorative fonts, need `NonZero`.
+    pub fill_rule: FillRule,
+    /// Maximum distance between a curve and its flattened approximation, in font units
+    pub tolerance: f32,
+    /// Retry with the other fill rule when tessellation fails, instead of skipping the glyph.
+    /// Fallbacks are logged as warnings.
+    pub fallback_chain: bool,
+    /// Repair outlines before extruding them: close open contours, merge points closer than a
+    /// thousandth of an em and drop contours without an area. Helps with broken CFF fonts.
+    pub repair_outlines: bool,
+    /// Merge consecutive outline edges running in the same direction, so straight runs get a
+    /// single wall quad. Cuts the triangle count of blocky fonts.
+    pub merge_coplanar_walls: bool,
+    /// What to do with contours that don't end where they start, e.g. in damaged or
+    /// decorative fonts. Open contours are logged as warnings.
+    pub open_contours: OpenContours,
+    /// Bake every font size into meshes of its own. By default, glyph meshes are tessellated
+    /// once at [`CANONICAL_FONT_SIZE`](crate::glyph_cache::CANONICAL_FONT_SIZE) and scaled to
+    /// each size by their transform, so all sizes share one mesh per glyph.
+    pub size_specific: bool,
+    /// How the front and back faces and the lip of a front inset are filled
+    pub cap_tessellator: CapTessellator,
+    /// What to do with hairline contours, e.g. decorative strokes of display fonts that have
+    /// no area and break insets. Hairlines are logged as warnings and handled before
+    /// [`Self::repair_outlines`], which drops them otherwise.
+    pub hairlines: HairlineContours,
+}
+
+/// How contours thinner on average than a threshold are extruded, see
+/// [`Contour::mean_thickness`]. Contours without an area are the thinnest.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum HairlineContours {
+    /// Extrude them like any other contour
+    #[default]
+    Keep,
+    /// Leave out contours thinner than `thickness`, in layout units
+    Drop { thickness: f32 },
+    /// Replace contours thinner than `thickness`, in layout units, with a stroke that wide
+    /// along them
+    Thicken { thickness: f32 },
+}
+
+/// Algorithm filling the front and back faces of the glyphs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CapTessellator {
+    /// Lyon's sweep line fill, which handles overlapping contours but gives up on some
+    /// degenerate outlines
+    #[default]
+    Sweep,
+    /// Ear clipping of the flattened contours, for outlines the sweep fails on, e.g. contours
+    /// touching themselves in obscure display fonts. Contours must not cross each other, and
+    /// the faces get long thin triangles.
+    EarClipping,
+}
+
+/// How open contours are extruded.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OpenContours {
+    /// Join the ends with a straight edge, so the contour is filled and walled like a closed one
+    #[default]
+    Close,
+    /// Extrude the contour as a strip of `width`, in layout units, centered on it with square
+    /// ends, e.g. for decorative strokes
+    Ribbon { width: f32 },
+}
+
+impl Default for TessellationOptions {
+    fn default() -> Self {
+        Self {
+            fill_rule: FillRule::EvenOdd,
+            tolerance: 0.05,
+            fallback_chain: false,
+            repair_outlines: false,
+            merge_coplanar_walls: false,
+            open_contours: OpenContours::default(),
+            size_specific: false,
+            cap_tessellator: CapTessellator::default(),
+            hairlines: HairlineContours::default(),
+        }
+    }
+}
+
+/// [`ExtrusionOptions`] bit for bit, from [`ExtrusionOptions::cache_key`], so equal keys make
+/// equal glyph meshes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ExtrusionOptionsKey {
+    words: Vec<u32>,
+}
+
+impl ExtrusionOptionsKey {
+    /// The key in little-endian bytes after its number of words, to store next to a glyph
+    /// mesh.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        std::iter::once(self.words.len() as u32)
+            .chain(self.words.iter().copied())
+            .flat_map(|word| word.to_le_bytes())
+            .collect()
+    }
+
+    /// Reads a key written by [`to_bytes`](Self::to_bytes), `None` if `bytes` is too short.
+    /// Returns the bytes after the key.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Option<(Self, &[u8])> {
+        let (len, rest) = bytes.split_at_checked(4)?;
+        let len = u32::from_le_bytes(len.try_into().ok()?) as usize;
+        let (head, rest) = rest.split_at_checked(len.checked_mul(4)?)?;
+        let words = head
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        Some((Self { words }, rest))
+    }
+
+    /// Pushes whether `words` are set, then the words or as many zeros.
+    fn push_option<const N: usize>(&mut self, words: Option<[u32; N]>) {
+        self.words.push(words.is_some() as u32);
+        self.words.extend(words.unwrap_or([0; N]));
+    }
+}
+
+impl ExtrusionOptions {
+    pub fn new(extrusion_depth: f32) -> Self {
+        Self {
+            extrusion_depth,
+            ..default()
+        }
+    }
+
+    /// The options bit for bit, keying cached glyph meshes. Options that don't change the
+    /// mesh, like the inset width when a bevel width replaces it, are left out. Every field is
+    /// named, so new ones can't be left out of the key unnoticed.
+    pub fn cache_key(&self) -> ExtrusionOptionsKey {
+        let ExtrusionOptions {
+            extrusion_depth,
+            front_inset,
+            bevel_width,
+            bevel_edges,
+            hole_bevel_width,
+            front_rim,
+            rounded_back,
+            inflate,
+            surface_noise,
+            edge_mask,
+            skinned,
+            depth_attribute,
+            surface_attribute,
+            uv_mode,
+            lightmap_uvs,
+            wall_smoothing_angle,
+            wall_seam,
+            wall_uv_scale,
+            depth_segments,
+            silhouette,
+            tessellation,
+            topology,
+            synthetic_style,
+        } = self;
+        let TessellationOptions {
+            fill_rule,
+            tolerance,
+            fallback_chain,
+            repair_outlines,
+            merge_coplanar_walls,
+            open_contours,
+            size_specific,
+            cap_tessellator,
+            hairlines,
+        } = tessellation;
+
+        let mut key = ExtrusionOptionsKey::default();
+        key.words.extend([
+            extrusion_depth.to_bits(),
+            *bevel_edges as u32,
+            *edge_mask as u32,
+            *skinned as u32,
+            *depth_attribute as u32,
+            *surface_attribute as u32,
+            *uv_mode as u32,
+            *lightmap_uvs as u32,
+            *wall_seam as u32,
+            (*depth_segments).max(1),
+            *silhouette as u32,
+            *topology as u32,
+        ]);
+        // The bevel width replaces the inset width, which then doesn't tell meshes apart
+        key.push_option(front_inset.map(|inset| {
+            let width = bevel_width.map_or(inset.width, |_| 0.0);
+            [width.to_bits(), inset.depth.to_bits()]
+        }));
+        for width in [bevel_width, hole_bevel_width] {
+            key.push_option(width.map(|width| match width {
+                BevelWidth::Em(width) => [0, width.to_bits()],
+                BevelWidth::WorldUnits(width) => [1, width.to_bits()],
+                BevelWidth::FontUnits(width) => [2, width.to_bits()],
+            }));
+        }
+        key.push_option(front_rim.map(|rim| {
+            let [r, g, b] = rim.color.map(f32::to_bits);
+            [rim.width.to_bits(), r, g, b]
+        }));
+        key.push_option(rounded_back.map(|rounded| {
+            [
+                rounded.radius.to_bits(),
+                rounded.depth.to_bits(),
+                rounded.segments,
+                rounded.profile_power.to_bits(),
+            ]
+        }));
+        key.push_option(
+            inflate.map(|inflate| [inflate.amount.to_bits(), inflate.falloff.to_bits()]),
+        );
+        key.push_option(surface_noise.map(|noise| {
+            let [x, y, z] = noise.axis_mask.map(f32::to_bits);
+            [
+                noise.seed,
+                noise.frequency.to_bits(),
+                noise.amplitude.to_bits(),
+                x,
+                y,
+                z,
+            ]
+        }));
+        key.push_option(wall_smoothing_angle.map(|angle| [angle.to_bits()]));
+        key.push_option(wall_uv_scale.map(|scale| [scale.to_bits()]));
+        key.push_option(
+            synthetic_style.map(|style| [style.skew.to_bits(), style.embolden.to_bits()]),
+        );
+        key.words.extend([
+            (*fill_rule == FillRule::NonZero) as u32,
+            tolerance.to_bits(),
+            *fallback_chain as u32,
+            *repair_outlines as u32,
+            *merge_coplanar_walls as u32,
+            *size_specific as u32,
+            *cap_tessellator as u32,
+        ]);
+        key.push_option(match open_contours {
+            OpenContours::Close => None,
+            OpenContours::Ribbon { width } => Some([width.to_bits()]),
+        });
+        key.push_option(match hairlines {
+            HairlineContours::Keep => None,
+            HairlineContours::Drop { thickness } => Some([0, thickness.to_bits()]),
+            HairlineContours::Thicken { thickness } => Some([1, thickness.to_bits()]),
+        });
+        key
+    }
+
+    /// Hash of [`cache_key`](Self::cache_key), e.g. to name stored glyph meshes. Different
+    /// options may share a hash, so it only keys caches that check the options too.
+    pub fn cache_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.cache_key().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Whether glyph meshes with these options can be tessellated at one font size and scaled
+    /// to the others. Options whose result depends on the size in all three dimensions at once,
+    /// like noise, the rim's setback and texture coordinates by distance, need meshes baked at
+    /// every size.
+    pub fn size_independent(&self) -> bool {
+        !self.tessellation.size_specific
+            && self.front_rim.is_none()
+            && self.surface_noise.is_none()
+            && self.wall_uv_scale.is_none()
+            && !self.lightmap_uvs
+            && self.uv_mode != UvMode::TextBlock
+    }
+
+    /// These options for glyphs drawn `factor` times as large in the plane of the text. Widths
+    /// in the plane are scaled and depths kept, so a mesh generated with them and scaled back
+    /// by `1 / factor` along X and Y matches the mesh generated with the original options.
+    pub fn scaled_in_plane(&self, factor: f32) -> Self {
+        let mut options = *self;
+        if let Some(inset) = &mut options.front_inset {
+            inset.width *= factor;
+        }
+        // Em and font units follow the glyph size on their own
+        for width in [&mut options.bevel_width, &mut options.hole_bevel_width] {
+            if let Some(BevelWidth::WorldUnits(width)) = width {
+                *width *= factor;
+            }
+        }
+        if let Some(rim) = &mut options.front_rim {
+            rim.width *= factor;
+        }
+        if let Some(rounded) = &mut options.rounded_back {
+            rounded.radius *= factor;
+        }
+        if let Some(inflate) = &mut options.inflate {
+            inflate.falloff *= factor;
+        }
+        if let HairlineContours::Drop { thickness } | HairlineContours::Thicken { thickness } =
+            &mut options.tessellation.hairlines
+        {
+            *thickness *= factor;
+        }
+        if let OpenContours::Ribbon { width } = &mut options.tessellation.open_contours {
+            *width *= factor;
+        }
+        options
+    }
 }
 
 impl From<ExtrudedGlyphGeometry> for Mesh {
     fn from(value: ExtrudedGlyphGeometry) -> Self {
-        Mesh::new(
-            PrimitiveTopology::TriangleList,
+        let topology = match value.topology {
+            OutputTopology::Triangles => PrimitiveTopology::TriangleList,
+            OutputTopology::Lines => PrimitiveTopology::LineList,
+            OutputTopology::Points => PrimitiveTopology::PointList,
+        };
+        let mut mesh = Mesh::new(
+            topology,
             RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
         )
         .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, value.vertices)
         .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, value.normals)
         .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, value.uvs)
-        .with_inserted_indices(bevy::render::mesh::Indices::U16(value.indices))
+        .with_inserted_indices(bevy::render::mesh::Indices::U16(value.indices));
+        if let Some(uv1) = value.uv1 {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_1, uv1);
+        }
+        if value.edge_mask.is_some() || value.vertex_colors.is_some() {
+            let colors: Vec<[f32; 4]> = (0..mesh.count_vertices())
+                .map(|i| {
+                    let [r, g, b] = value.vertex_colors.as_ref().map_or([1.0; 3], |c| c[i]);
+                    let mask = value.edge_mask.as_ref().map_or(1.0, |mask| mask[i]);
+                    [r, g, b, mask]
+                })
+                .collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        }
+        if let Some(depth_fraction) = value.depth_fraction {
+            mesh.insert_attribute(ATTRIBUTE_DEPTH_FRACTION, depth_fraction);
+        }
+        if let Some(surface) = value.surface {
+            mesh.insert_attribute(ATTRIBUTE_SURFACE, surface);
+        }
+        if value.skinned {
+            let vertex_count = mesh.count_vertices();
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_JOINT_INDEX,
+                VertexAttributeValues::Uint16x4(vec![[0; 4]; vertex_count]),
+            );
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_JOINT_WEIGHT,
+                vec![[1.0, 0.0, 0.0, 0.0]; vertex_count],
+            );
+        }
+        mesh
     }
 }
 
@@ -34,179 +687,1527 @@ pub fn tessalate_glyph(
     glyph_info: &cosmic_text::LayoutGlyph,
     bounding_box: Rect,
     face: Face,
-    extrusion_depth: f32,
+    options: &ExtrusionOptions,
 ) -> Result<(ExtrudedGlyphGeometry, f32, f32), MeshTextError> {
     let units_per_em = face.units_per_em();
     // Scale factor to convert font units to layout units (e.g., based on font_size)
     let scale_factor = glyph_info.font_size / units_per_em as f32;
 
-    let mut builder = crate::command_encoder::LyonCommandEncoder::new();
-    face.outline_glyph(GlyphId(glyph_info.glyph_id), &mut builder)
+    let path = glyph_path(
+        &face,
+        glyph_info.glyph_id,
+        &options.tessellation,
+        options.synthetic_style,
+    )?;
+
+    // Calculate the center of the glyph using the font units bounding box
+    // (font unit coordinates - these come directly from the font), slanted like the outline
+    let mut center = Vec2::new(
+        (bounding_box.x_min as f32 + bounding_box.x_max as f32) / 2.0,
+        (bounding_box.y_min as f32 + bounding_box.y_max as f32) / 2.0,
+    );
+    if let Some(style) = options.synthetic_style {
+        center.x += center.y * style.skew.tan();
+    }
+
+    extrude_path(&path, center, units_per_em as f32, scale_factor, options)
+}
+
+/// The outline of glyph `glyph_id` of `face` in font units, flattened within the tolerance of
+/// `tessellation`, with `style` applied: slanted as the outline is built and grown or thinned
+/// after. Fails for glyphs without an outline.
+pub(crate) fn glyph_path(
+    face: &Face,
+    glyph_id: u16,
+    tessellation: &TessellationOptions,
+    style: Option<SyntheticStyle>,
+) -> Result<Path, MeshTextError> {
+    let style = style.unwrap_or_default();
+    let mut builder =
+        LyonCommandEncoder::with_transform(tessellation.tolerance, style.skew_transform());
+    face.outline_glyph(GlyphId(glyph_id), &mut builder)
         .ok_or(MeshTextError::PathBuildingFailed)?;
     let path = builder.build_path();
+    if style.embolden == 0.0 {
+        return Ok(path);
+    }
+    let contours = normalize_orientation(contours_from_path(&path), tessellation.fill_rule);
+    let distance = style.embolden * face.units_per_em() as f32;
+    let styled = resolve_overlaps(&grow_contours(&contours, distance))?;
+    Ok(path_from_contours(&styled))
+}
 
-    // Calculate the center of the glyph using the font units bounding box
-    // (font unit coordinates - these come directly from the font)
-    let center_x = (bounding_box.x_min as f32 + bounding_box.x_max as f32) / 2.0;
-    let center_y = (bounding_box.y_min as f32 + bounding_box.y_max as f32) / 2.0;
+/// Generates a placeholder for a glyph missing from every font: a hollow box as wide as the
+/// glyph's advance, extruded like a regular glyph.
+pub fn placeholder_glyph(
+    glyph_info: &cosmic_text::LayoutGlyph,
+    options: &ExtrusionOptions,
+) -> Result<(ExtrudedGlyphGeometry, f32, f32), MeshTextError> {
+    // Build the box in a virtual 1000 units per em font
+    let units_per_em = 1000.0;
+    let scale_factor = glyph_info.font_size / units_per_em;
+
+    let advance = (glyph_info.w / scale_factor).clamp(200.0, 2000.0);
+    let (x_min, x_max) = (advance * 0.1, advance * 0.9);
+    let (y_min, y_max) = (0.0, 700.0);
+    let thickness = 80.0;
+
+    let mut builder = Path::builder();
+    for (x0, y0, x1, y1) in [
+        (x_min, y_min, x_max, y_max),
+        // The hole winds the opposite way so its side walls face into the hole
+        (
+            x_min + thickness,
+            y_max - thickness,
+            x_max - thickness,
+            y_min + thickness,
+        ),
+    ] {
+        builder.begin(point(x0, y0));
+        builder.line_to(point(x1, y0));
+        builder.line_to(point(x1, y1));
+        builder.line_to(point(x0, y1));
+        builder.end(true);
+    }
+    let path = builder.build();
+
+    let center = Vec2::new((x_min + x_max) / 2.0, (y_min + y_max) / 2.0);
+    extrude_path(&path, center, units_per_em, scale_factor, options)
+}
+
+/// Extrudes a rectangle of `size` layout units centered on the origin like a glyph, e.g. for
+/// underlines and dividers in the style of their text.
+pub fn extrude_rectangle(
+    size: Vec2,
+    options: &ExtrusionOptions,
+) -> Result<ExtrudedGlyphGeometry, MeshTextError> {
+    let half = size / 2.0;
+    let rectangle = Contour {
+        points: vec![
+            Vec2::new(-half.x, -half.y),
+            Vec2::new(half.x, -half.y),
+            Vec2::new(half.x, half.y),
+            Vec2::new(-half.x, half.y),
+        ],
+        closed: true,
+    };
+    extrude_contours(&[rectangle], options)
+}
+
+/// Extrudes arbitrary contours like a glyph, e.g. a logo or a shape from
+/// [`crate::svg::parse_svg_path`]. Coordinates are layout units, and the geometry is centered
+/// on the bounding box of the contours.
+pub fn extrude_contours(
+    contours: &[Contour],
+    options: &ExtrusionOptions,
+) -> Result<ExtrudedGlyphGeometry, MeshTextError> {
+    let points = contours.iter().flat_map(|contour| &contour.points);
+    let min = points.clone().fold(Vec2::MAX, |min, p| min.min(*p));
+    let max = points.fold(Vec2::MIN, |max, p| max.max(*p));
+    if min.cmpgt(max).any() {
+        return Err(MeshTextError::InvalidInput);
+    }
+
+    // The height stands in for the em size, which scales the repair tolerance and the UVs
+    let size = (max - min).max_element().max(f32::EPSILON);
+    extrude_path(
+        &path_from_contours(contours),
+        (min + max) / 2.0,
+        size,
+        1.0,
+        options,
+    )
+    .map(|(geometry, _, _)| geometry)
+}
+
+/// Extrudes a flattened outline given in font units around `center`. `scale_factor` converts
+/// font units to layout units.
+pub fn extrude_path(
+    path: &Path,
+    center: Vec2,
+    units_per_em: f32,
+    scale_factor: f32,
+    options: &ExtrusionOptions,
+) -> Result<(ExtrudedGlyphGeometry, f32, f32), MeshTextError> {
+    let (center_x, center_y) = (center.x, center.y);
+    let extrusion_depth = options.extrusion_depth;
 
     let mut final_positions: Vec<Vec3> = Vec::new();
     let mut final_indices: Vec<u16> = Vec::new();
     let mut final_normals: Vec<Vec3> = Vec::new();
     let mut final_uvs: Vec<Vec2> = Vec::new();
+    let mut final_mask: Vec<f32> = Vec::new();
+    let mut final_surface: Vec<f32> = Vec::new();
+    // Every cap and every wall strip is a lightmap chart with flat coordinates of its own
+    let mut charts: Vec<std::ops::Range<usize>> = Vec::new();
+    let mut lightmap: Vec<Vec2> = Vec::new();
+    let mut caps: Vec<std::ops::Range<usize>> = Vec::new();
 
     // Adjust front and back z positions
     let (front_z, back_z) = (0.0, extrusion_depth);
 
     let mut tessellator = FillTessellator::new();
 
-    // 1. Tessellate front face (z=front_z)
-    let mut front_geometry: VertexBuffers<Vec3, u16> = VertexBuffers::new();
-    tessellator
-        .tessellate_path(
-            &path,
-            &FillOptions::default(),
-            &mut BuffersBuilder::new(&mut front_geometry, |vertex: FillVertex| Vec3 {
-                // Subtract center to make rotation happen around the center of each glyph
-                x: (vertex.position().x - center_x) * scale_factor,
-                y: (vertex.position().y - center_y) * scale_factor,
-                z: front_z,
-            }),
+    // Every face is built from the contours, so caps and walls share their edges. Merging
+    // straight runs simplifies both.
+    let mut contours = contours_from_path(path);
+    let mut issues = Vec::new();
+    let open = contours.iter().filter(|contour| !contour.closed).count();
+    if open > 0 {
+        issues.push(GlyphIssue::OpenContours(open));
+        warn!(
+            "Outline has {open} open contours, extruding them with {:?}",
+            options.tessellation.open_contours
+        );
+        contours = contours
+            .into_iter()
+            .filter_map(|contour| match options.tessellation.open_contours {
+                _ if contour.closed => Some(contour),
+                OpenContours::Close => Some(Contour {
+                    closed: true,
+                    ..contour
+                }),
+                OpenContours::Ribbon { width } => contour.ribbon(width / scale_factor),
+            })
+            .collect();
+    }
+    if let HairlineContours::Drop { thickness } | HairlineContours::Thicken { thickness } =
+        options.tessellation.hairlines
+    {
+        let thickness = thickness / scale_factor;
+        let hairline = |contour: &Contour| contour.mean_thickness() < thickness;
+        let hairlines = contours.iter().filter(|contour| hairline(contour)).count();
+        if hairlines > 0 {
+            issues.push(GlyphIssue::HairlineContours(hairlines));
+            warn!(
+                "Outline has {hairlines} hairline contours, extruding them with {:?}",
+                options.tessellation.hairlines
+            );
+            let mut kept = Vec::with_capacity(contours.len());
+            for contour in contours {
+                match options.tessellation.hairlines {
+                    _ if !hairline(&contour) => kept.push(contour),
+                    HairlineContours::Thicken { .. } => kept.extend(contour.stroke(thickness)?),
+                    _ => {}
+                }
+            }
+            contours = kept;
+        }
+    }
+    if options.tessellation.repair_outlines {
+        contours = repair_contours(contours, units_per_em * REPAIR_TOLERANCE);
+    }
+    if options.tessellation.merge_coplanar_walls {
+        contours = contours
+            .iter()
+            .map(|contour| contour.merge_collinear(COLLINEAR_TOLERANCE))
+            .collect();
+    }
+    // Outer contours wind counter-clockwise and holes clockwise from here on, whatever the
+    // font's convention. The filled area is left of every edge, so side walls, which face
+    // right of their direction of travel, face outward.
+    let mut contours = normalize_orientation(contours, options.tessellation.fill_rule);
+    if options.silhouette {
+        contours = outermost_contours(contours);
+    }
+    let outline = path_from_contours(&contours);
+    let cap_uv = cap_uv_mapping(
+        &contours,
+        center,
+        units_per_em,
+        scale_factor,
+        options.uv_mode,
+    );
+
+    // Width of the lip along each contour, in layout units. Contours left out of the bevel
+    // get none.
+    let to_layout_units =
+        |width: BevelWidth| width.to_layout_units(units_per_em * scale_factor, units_per_em, 1.0);
+    let lip_widths = options.front_inset.map(|inset| {
+        let width = options.bevel_width.map_or(inset.width, to_layout_units);
+        (
+            width,
+            options.hole_bevel_width.map_or(width, to_layout_units),
         )
-        .map_err(|_| MeshTextError::TessellationFailed)?;
+    });
+    let lip_width = |contour: &Contour| {
+        let hole = contour.signed_area() < 0.0;
+        match lip_widths {
+            Some((width, hole_width)) if options.bevel_edges.includes(hole) => {
+                if hole {
+                    hole_width
+                } else {
+                    width
+                }
+            }
+            _ => 0.0,
+        }
+    };
 
-    let front_v_offset = final_positions.len() as u16;
-    for v_pos in &front_geometry.vertices {
-        final_positions.push(*v_pos);
-        final_normals.push(Vec3::NEG_Z); // Front face normal (0,0,-1)
+    // The inset contours run parallel to the outline, the lip width into the filled area
+    let widths: Vec<f32> = contours.iter().map(lip_width).collect();
+    let mut collapsed = 0;
+    let inset = options
+        .front_inset
+        .filter(|_| widths.iter().any(|&width| width > 0.0))
+        .map(|inset| {
+            // Offsets that make a contour cross over itself are split into separate loops.
+            // Loops winding against their contour are parts that collapsed, so they're dropped.
+            // Contours without a lip stay where they are, so the cap reaches them.
+            let loops: Vec<Contour> = contours
+                .iter()
+                .zip(&widths)
+                .flat_map(|(contour, &width)| {
+                    let winding = contour.signed_area();
+                    if width <= 0.0 {
+                        return vec![contour.clone()];
+                    }
+                    let loops: Vec<Contour> = contour
+                        .offset(width / scale_factor)
+                        .split_loops()
+                        .into_iter()
+                        .filter(|inset| inset.signed_area() * winding > 0.0)
+                        .collect();
+                    if loops.is_empty() {
+                        collapsed += 1;
+                    }
+                    loops
+                })
+                .collect();
+            // Loops of different contours overlap where a stroke is thinner than twice the
+            // inset. Their common outline keeps the lip, the cap and the walls in one piece.
+            let inset_contours = resolve_overlaps(&loops)?;
+            let depth = inset.depth.clamp(0.0, extrusion_depth);
+            Ok::<_, MeshTextError>(InsetFace {
+                lip: lip_path(&contours, &inset_contours),
+                cap: path_from_contours(&inset_contours),
+                contours: inset_contours,
+                depth,
+                z: front_z + depth,
+                widths: widths.clone(),
+            })
+        })
+        .transpose()?;
+    if collapsed > 0 {
+        issues.push(GlyphIssue::CollapsedBevel(collapsed));
+    }
+
+    // The rounded back closes in on a smaller back face ring by ring. The walls stop at the
+    // first ring.
+    let rings = match options.rounded_back {
+        Some(rounded) => rounded_back_rings(&contours, rounded, scale_factor, back_z)?,
+        None => Vec::new(),
+    };
+    let walls_back_z = rings.first().map_or(back_z, |ring| ring.z);
+    let back_outline = rings.last().map(|ring| path_from_contours(&ring.contours));
 
-        // Add basic UV mapping for front face based on normalized position
-        // Find bounding box of the glyph for UV normalization
-        let uv_x = (v_pos.x / (units_per_em as f32 * scale_factor) + 0.5) * 0.5 + 0.5;
-        let uv_y = (v_pos.y / (units_per_em as f32 * scale_factor) + 0.5) * 0.5 + 0.5;
-        final_uvs.push(Vec2::new(uv_x, uv_y));
+    // Caps only have vertices on their outline, so they are subdivided for the bulge to
+    // show, to an edge length a fraction of the falloff
+    let inflate = options
+        .inflate
+        .filter(|inflate| inflate.amount != 0.0 && inflate.falloff > 0.0);
+    let inflate_edge = inflate.map(|inflate| inflate.falloff / 2.0);
+
+    // 1. Tessellate front face (z=front_z). With an inset, the front face is a flat lip
+    // between the outline and the inset contours, around a cap recessed by the inset depth.
+    // Each face comes with the contours bounding it, where inflating leaves it in place
+    let front_faces = match &inset {
+        Some(inset) => vec![
+            (
+                &inset.lip,
+                front_z,
+                TextSurface::Edge,
+                vec![&contours, &inset.contours],
+            ),
+            (
+                &inset.cap,
+                inset.z,
+                TextSurface::Front,
+                vec![&inset.contours],
+            ),
+        ],
+        None => vec![(&outline, front_z, TextSurface::Front, vec![&contours])],
+    };
+    // The edges of `bounds` in layout units around the glyph center
+    let cap_edges = |bounds: &[&Vec<Contour>]| -> Vec<(Vec2, Vec2)> {
+        bounds
+            .iter()
+            .flat_map(|contours| contours.iter().flat_map(Contour::edges))
+            .map(|(from, to)| ((from - center) * scale_factor, (to - center) * scale_factor))
+            .collect()
+    };
+    for (face_path, face_z, surface, bounds) in front_faces {
+        let mut front_geometry: VertexBuffers<(Vec3, f32), u16> = VertexBuffers::new();
+        let fill_rule = fill_path(
+            &mut tessellator,
+            face_path,
+            &options.tessellation,
+            &mut front_geometry,
+            |position, attributes| {
+                (
+                    Vec3 {
+                        // Subtract center to make rotation happen around the center of each glyph
+                        x: (position.x - center_x) * scale_factor,
+                        y: (position.y - center_y) * scale_factor,
+                        z: face_z,
+                    },
+                    // Only the lip carries an edge mask attribute, the rest is cap
+                    attributes.first().copied().unwrap_or(1.0),
+                )
+            },
+        )?;
+        if fill_rule != options.tessellation.fill_rule {
+            issues.push(GlyphIssue::FallbackFillRule);
+        }
+        if let Some(max_edge) = inflate_edge {
+            subdivide_cap(
+                &mut front_geometry,
+                max_edge,
+                |(v, _)| *v,
+                |(a, mask_a), (b, mask_b)| ((*a + *b) / 2.0, (mask_a + mask_b) / 2.0),
+            );
+        }
+
+        let front_v_offset = final_positions.len();
+        for (v_pos, mask) in &front_geometry.vertices {
+            final_positions.push(*v_pos);
+            final_mask.push(*mask);
+            final_normals.push(Vec3::NEG_Z); // Front face normal (0,0,-1)
+
+            final_uvs.push(cap_uv(*v_pos));
+        }
+        let front_i_offset = final_indices.len();
+        for index in &front_geometry.indices {
+            final_indices.push((front_v_offset + *index as usize) as u16);
+        }
+        if let Some(inflate) = inflate {
+            inflate_cap(
+                &mut final_positions,
+                &mut final_normals,
+                &final_indices[front_i_offset..],
+                front_v_offset,
+                &cap_edges(&bounds),
+                inflate,
+            );
+        }
+        final_surface.resize(final_positions.len(), surface as u32 as f32);
+        charts.push(front_v_offset..final_positions.len());
+        caps.push(front_v_offset..final_positions.len());
+        lightmap.extend(front_geometry.vertices.iter().map(|(v, _)| v.truncate()));
     }
-    for index in &front_geometry.indices {
-        final_indices.push(front_v_offset + *index);
+
+    // 1b. The rim is a flat ring between the outline and its outward offset, set back a little
+    // behind the front face. Holes shrink, dropping the parts that collapse.
+    let rim_start = final_positions.len();
+    if let Some(rim) = options.front_rim.filter(|rim| rim.width > 0.0) {
+        let distance = -rim.width / scale_factor;
+        let mut ring = contours.clone();
+        ring.extend(contours.iter().flat_map(|contour| {
+            let winding = contour.signed_area();
+            contour
+                .offset(distance)
+                .split_loops()
+                .into_iter()
+                .filter(move |rim| rim.signed_area() * winding > 0.0)
+        }));
+        let rim_z = front_z + (rim.width * RIM_SETBACK).min(extrusion_depth);
+
+        // The ring is what lies inside exactly one of the two outlines
+        let mut rim_geometry: VertexBuffers<Vec3, u16> = VertexBuffers::new();
+        fill_path(
+            &mut tessellator,
+            &path_from_contours(&ring),
+            &TessellationOptions {
+                fill_rule: FillRule::EvenOdd,
+                fallback_chain: false,
+                // Grown contours may cross each other, which only the sweep fills
+                cap_tessellator: CapTessellator::Sweep,
+                ..options.tessellation
+            },
+            &mut rim_geometry,
+            |position, _| Vec3 {
+                x: (position.x - center_x) * scale_factor,
+                y: (position.y - center_y) * scale_factor,
+                z: rim_z,
+            },
+        )?;
+
+        let rim_v_offset = final_positions.len();
+        for v_pos in &rim_geometry.vertices {
+            final_positions.push(*v_pos);
+            final_normals.push(Vec3::NEG_Z);
+            final_uvs.push(cap_uv(*v_pos));
+        }
+        for index in &rim_geometry.indices {
+            final_indices.push((rim_v_offset + *index as usize) as u16);
+        }
+        charts.push(rim_v_offset..final_positions.len());
+        caps.push(rim_v_offset..final_positions.len());
+        lightmap.extend(rim_geometry.vertices.iter().map(|v| v.truncate()));
+        final_mask.resize(final_positions.len(), 0.0);
+        final_surface.resize(final_positions.len(), TextSurface::Edge as u32 as f32);
     }
+    let rim_end = final_positions.len();
 
     // 2. Tessellate back face (z=back_z)
     let mut back_geometry: VertexBuffers<Vec3, u16> = VertexBuffers::new();
-    tessellator
-        .tessellate_path(
-            &path, // Tessellate the same path
-            &FillOptions::default(),
-            &mut BuffersBuilder::new(&mut back_geometry, |vertex: FillVertex| Vec3 {
-                // Subtract center to make rotation happen around the center of each glyph
-                x: (vertex.position().x - center_x) * scale_factor,
-                y: (vertex.position().y - center_y) * scale_factor,
-                z: back_z, // Shifted in Z
-            }),
-        )
-        .map_err(|_| MeshTextError::TessellationFailed)?;
+    let fill_rule = fill_path(
+        &mut tessellator,
+        // The same outline, or the last ring of a rounded back
+        back_outline.as_ref().unwrap_or(&outline),
+        &options.tessellation,
+        &mut back_geometry,
+        |position, _| Vec3 {
+            // Subtract center to make rotation happen around the center of each glyph
+            x: (position.x - center_x) * scale_factor,
+            y: (position.y - center_y) * scale_factor,
+            z: back_z, // Shifted in Z
+        },
+    )?;
+    if fill_rule != options.tessellation.fill_rule
+        && !issues.contains(&GlyphIssue::FallbackFillRule)
+    {
+        issues.push(GlyphIssue::FallbackFillRule);
+    }
+    if let Some(max_edge) = inflate_edge {
+        subdivide_cap(&mut back_geometry, max_edge, |v| *v, |a, b| (*a + *b) / 2.0);
+    }
 
-    let back_v_offset = final_positions.len() as u16;
+    let back_v_offset = final_positions.len();
     for v_pos in &back_geometry.vertices {
         final_positions.push(*v_pos);
         final_normals.push(Vec3::Z); // Back face normal (0,0,1)
 
-        // Add UV mapping for back face - can be the same as front face
-        // or mirrored depending on preference
-        let uv_x = (v_pos.x / (units_per_em as f32 * scale_factor) + 0.5) * 0.5 + 0.5;
-        let uv_y = (v_pos.y / (units_per_em as f32 * scale_factor) + 0.5) * 0.5 + 0.5;
-        final_uvs.push(Vec2::new(uv_x, uv_y));
+        // Same UV mapping as the front face
+        final_uvs.push(cap_uv(*v_pos));
     }
     // Add back face indices with reversed winding for correct culling and normals
+    let back_i_offset = final_indices.len();
     for i in (0..back_geometry.indices.len()).step_by(3) {
         if i + 2 < back_geometry.indices.len() {
             // Ensure we have a full triangle
-            final_indices.push(back_v_offset + back_geometry.indices[i + 2]);
-            final_indices.push(back_v_offset + back_geometry.indices[i + 1]);
-            final_indices.push(back_v_offset + back_geometry.indices[i]);
+            final_indices.push((back_v_offset + back_geometry.indices[i + 2] as usize) as u16);
+            final_indices.push((back_v_offset + back_geometry.indices[i + 1] as usize) as u16);
+            final_indices.push((back_v_offset + back_geometry.indices[i] as usize) as u16);
         }
     }
+    if let Some(inflate) = inflate {
+        inflate_cap(
+            &mut final_positions,
+            &mut final_normals,
+            &final_indices[back_i_offset..],
+            back_v_offset,
+            &cap_edges(&[rings.last().map_or(&contours, |ring| &ring.contours)]),
+            inflate,
+        );
+    }
+    final_mask.resize(final_positions.len(), 1.0);
+    final_surface.resize(final_positions.len(), TextSurface::Back as u32 as f32);
+    // Mirrored, as seen from behind
+    charts.push(back_v_offset..final_positions.len());
+    caps.push(back_v_offset..final_positions.len());
+    lightmap.extend(back_geometry.vertices.iter().map(|v| Vec2::new(-v.x, v.y)));
+
+    // Walls are unrolled along their contour, with the distance walked as U and depth as V
+    let wall_lightmap = |lightmap: &mut Vec<Vec2>,
+                         (p1, p2): (lyon::geom::Point<f32>, lyon::geom::Point<f32>),
+                         from: Vec2,
+                         offset: f32,
+                         (z0, z1): (f32, f32)| {
+        let u = |p: lyon::geom::Point<f32>| {
+            (offset + (Vec2::new(p.x, p.y) + center - from).length()) * scale_factor
+        };
+        for z in [z0, z1] {
+            lightmap.extend([Vec2::new(u(p1), z), Vec2::new(u(p2), z)]);
+        }
+    };
+
+    // With surface noise the walls are split into cells for the displacement to show
+    let noise = options
+        .surface_noise
+        .filter(|noise| noise.amplitude != 0.0 && noise.frequency > 0.0);
+    let mut wall_heights = vec![(&contours, walls_back_z - front_z)];
+    if let Some(inset) = &inset {
+        wall_heights.push((&inset.contours, inset.depth));
+    }
+    let density = noise.map_or(0.0, |noise| {
+        let mut density = noise.frequency * NOISE_CELLS_PER_FEATURE;
+        let cells = |density: f32| -> usize {
+            wall_heights
+                .iter()
+                .flat_map(|(contours, height)| {
+                    contours.iter().flat_map(Contour::edges).map(|(from, to)| {
+                        let (along, across) =
+                            wall_cells(from.distance(to) * scale_factor, *height, density);
+                        along * across
+                    })
+                })
+                .sum()
+        };
+        while cells(density) > MAX_NOISE_CELLS && density > f32::EPSILON {
+            density /= 2.0;
+        }
+        density
+    });
+    // Walls that are displaced afterwards, with their indices and depth
+    let mut noisy_walls = Vec::new();
+
+    // Texture U of the walls per layout unit walked, when the walls are mapped by distance
+    let uv_scale = options.wall_uv_scale.map(|scale| scale * scale_factor);
+    // Texture V of the walls, from 0 at the front to 1 at the back, or by distance
+    let wall_v = |z: f32| match options.wall_uv_scale {
+        Some(scale) => (z - front_z) * scale,
+        None if back_z > front_z => (z - front_z) / (back_z - front_z),
+        None => 0.0,
+    };
 
-    // 3. Generate side faces by iterating over path segments
-    let mut last_point_opt: Option<lyon::geom::Point<f32>> = None;
-    let mut v_texture_offset = 0.0; // Tracks accumulated length for texture mapping
+    // Quads stacked along the depth of each edge without noise, which is when walls are smoothed
+    let rows = options.depth_segments.max(1) as usize;
 
-    for event in path.iter() {
-        match event {
-            PathEvent::Begin { at } => {
-                last_point_opt = Some(at);
-                // Reset texture coordinate offset at the start of each subpath
-                v_texture_offset = 0.0;
+    // Adds the wall from p1 to p2, split into cells that share its texture and lightmap. U runs
+    // from u1 to u2.
+    let add_wall = |positions: &mut Vec<Vec3>,
+                    indices: &mut Vec<u16>,
+                    normals: &mut Vec<Vec3>,
+                    uvs: &mut Vec<Vec2>,
+                    lightmap: &mut Vec<Vec2>,
+                    (p1, p2): (lyon::geom::Point<f32>, lyon::geom::Point<f32>),
+                    from: Vec2,
+                    (u1, u2): (f32, f32),
+                    walked: f32,
+                    (z0, z1): (f32, f32)| {
+        let length = (p2 - p1).length() * scale_factor;
+        let (along, across) = wall_cells(length, z1 - z0, density);
+        let across = across.max(rows);
+        for i in 0..along {
+            let (s0, s1) = (i as f32 / along as f32, (i + 1) as f32 / along as f32);
+            let (q1, q2) = (p1.lerp(p2, s0), p1.lerp(p2, s1));
+            for j in 0..across {
+                let (t0, t1) = (j as f32 / across as f32, (j + 1) as f32 / across as f32);
+                let zs = (z0 + (z1 - z0) * t0, z0 + (z1 - z0) * t1);
+                add_side_quad(
+                    positions,
+                    indices,
+                    normals,
+                    uvs,
+                    q1,
+                    q2,
+                    scale_factor,
+                    zs,
+                    (u1 + (u2 - u1) * s0, u1 + (u2 - u1) * s1),
+                    (wall_v(zs.0), wall_v(zs.1)),
+                );
+                wall_lightmap(lightmap, (q1, q2), from, walked, zs);
             }
-            PathEvent::Line { from, to } => {
-                if last_point_opt.is_some() {
-                    // For straight line segments, just add a quad directly
-                    // Center the points using the same center values used for the front/back faces
-                    let centered_from = point(from.x - center_x, from.y - center_y);
-                    let centered_to = point(to.x - center_x, to.y - center_y);
-
-                    add_side_quad(
-                        &mut final_positions,
-                        &mut final_indices,
-                        &mut final_normals,
-                        &mut final_uvs,
-                        centered_from,
-                        centered_to,
-                        scale_factor,
-                        extrusion_depth,
-                        v_texture_offset,
-                    );
+        }
+    };
 
-                    // Update texture offset
-                    let dx = to.x - from.x;
-                    let dy = to.y - from.y;
-                    v_texture_offset += (dx * dx + dy * dy).sqrt();
+    // The inner walls of the lip drop from the front face down to the recessed cap. They face
+    // the opposite way of the outer walls, so their segments are walked in reverse. Where the
+    // cap reaches a contour without a lip there is no lip to drop from.
+    let on_edge_tolerance = units_per_em * ON_EDGE_TOLERANCE;
+    if let Some(inset) = &inset {
+        let plain: Vec<&Contour> = contours
+            .iter()
+            .zip(&inset.widths)
+            .filter(|&(_, &width)| width <= 0.0)
+            .map(|(contour, _)| contour)
+            .collect();
+        for contour in &inset.contours {
+            let chart_start = final_positions.len();
+            let index_start = final_indices.len();
+            let mut walked = 0.0;
+            let mut skipped = false;
+            let wall_u = wall_u(contour, options.wall_seam, uv_scale);
+            for ((from, to), (u_from, u_to)) in contour.edges().zip(wall_u) {
+                let on_plain = plain.iter().any(|plain| {
+                    plain.edges().any(|edge| {
+                        on_segment(from, edge, on_edge_tolerance)
+                            && on_segment(to, edge, on_edge_tolerance)
+                    })
+                });
+                if on_plain {
+                    skipped = true;
+                    walked += from.distance(to);
+                    continue;
                 }
-                last_point_opt = Some(to);
+                let (p1, p2) = (
+                    point(to.x - center_x, to.y - center_y),
+                    point(from.x - center_x, from.y - center_y),
+                );
+                add_wall(
+                    &mut final_positions,
+                    &mut final_indices,
+                    &mut final_normals,
+                    &mut final_uvs,
+                    &mut lightmap,
+                    (p1, p2),
+                    from,
+                    (u_to, u_from),
+                    walked,
+                    (front_z, inset.z),
+                );
+                walked += from.distance(to);
+            }
+            if final_positions.len() == chart_start {
+                continue;
             }
-            PathEvent::End { last, first, close } => {
-                // If the path is closed, connect the last point to the first point
-                if close && last_point_opt.is_some() {
-                    // Center the points using the same center values
-                    let centered_last = point(last.x - center_x, last.y - center_y);
-                    let centered_first = point(first.x - center_x, first.y - center_y);
-
-                    add_side_quad(
+            charts.push(chart_start..final_positions.len());
+            noisy_walls.push((
+                chart_start..final_positions.len(),
+                index_start..final_indices.len(),
+                (front_z, inset.z),
+            ));
+            // Quads on both sides of a skipped edge aren't neighbours
+            if noise.is_none()
+                && !skipped
+                && let Some(max_angle) = options.wall_smoothing_angle
+            {
+                smooth_wall_normals(
+                    &mut final_normals[chart_start..],
+                    rows,
+                    contour.closed,
+                    true,
+                    max_angle,
+                );
+            }
+        }
+        final_mask.resize(final_positions.len(), 1.0);
+        final_surface.resize(final_positions.len(), TextSurface::Edge as u32 as f32);
+    }
+
+    // 3. Generate side faces along the outline, the same way as the inner walls of the lip.
+    // Walls of contours left out of the bevel rise from the recessed cap, and only reach the
+    // front face where the lip still runs along them. They are split where the cap meets the
+    // lip so the bands of the wall share their vertices.
+    for (c, contour) in contours.iter().enumerate() {
+        let chart_start = final_positions.len();
+        let index_start = final_indices.len();
+        let mut walked = 0.0; // Distance along the contour, for the lightmap
+        let plain = inset.as_ref().filter(|inset| inset.widths[c] <= 0.0);
+        let wall_u = wall_u(contour, options.wall_seam, uv_scale);
+        for ((from, to), (u1, u2)) in contour.edges().zip(wall_u) {
+            let pieces = match plain {
+                Some(inset) => edge_pieces((from, to), &inset.contours, on_edge_tolerance),
+                None => vec![(0.0, 1.0, false)],
+            };
+            for (s0, s1, covered) in pieces {
+                let (a, b) = (from.lerp(to, s0), from.lerp(to, s1));
+                let (p1, p2) = (
+                    point(a.x - center_x, a.y - center_y),
+                    point(b.x - center_x, b.y - center_y),
+                );
+                let u = (u1 + (u2 - u1) * s0, u1 + (u2 - u1) * s1);
+                let bands = match plain {
+                    None => [Some((front_z, walls_back_z)), None],
+                    Some(inset) if covered => [Some((inset.z, walls_back_z)), None],
+                    Some(inset) => [Some((inset.z, walls_back_z)), Some((front_z, inset.z))],
+                };
+                for band in bands.into_iter().flatten() {
+                    add_wall(
                         &mut final_positions,
                         &mut final_indices,
                         &mut final_normals,
                         &mut final_uvs,
-                        centered_last,
-                        centered_first,
-                        scale_factor,
-                        extrusion_depth,
-                        v_texture_offset,
+                        &mut lightmap,
+                        (p1, p2),
+                        from,
+                        u,
+                        walked,
+                        band,
                     );
                 }
+            }
+            walked += from.distance(to);
+        }
+        charts.push(chart_start..final_positions.len());
+        noisy_walls.push((
+            chart_start..final_positions.len(),
+            index_start..final_indices.len(),
+            (front_z, walls_back_z),
+        ));
+        // Split walls have a varying number of quads per edge
+        if noise.is_none()
+            && plain.is_none()
+            && let Some(max_angle) = options.wall_smoothing_angle
+        {
+            smooth_wall_normals(
+                &mut final_normals[chart_start..],
+                rows,
+                contour.closed,
+                false,
+                max_angle,
+            );
+        }
+    }
+    final_mask.resize(final_positions.len(), 0.0);
+    final_surface.resize(final_positions.len(), TextSurface::Side as u32 as f32);
 
-                // Reset for next potential sub-path
-                last_point_opt = None;
-                v_texture_offset = 0.0;
+    // 3b. The rounded back continues the walls from ring to ring. Its normals turn from the
+    // walls' towards the back face, and its edge mask ramps up to the back face's.
+    if options.rounded_back.is_some() && rings.len() > 1 {
+        let radius = rings.last().map_or(0.0, |ring| ring.inset) * scale_factor;
+        let depth = back_z - walls_back_z;
+        let normal = |horizontal: Vec2, angle: f32| {
+            (horizontal * depth * angle.cos())
+                .extend(radius * angle.sin())
+                .normalize_or_zero()
+        };
+        for (c, contour) in contours.iter().enumerate() {
+            let chart_start = final_positions.len();
+            let wall_normals = edge_end_normals(contour, options.wall_smoothing_angle);
+            let wall_u = wall_u(contour, options.wall_seam, uv_scale);
+            // Distance along the contour to the start of each edge, for the lightmap
+            let walked: Vec<f32> = contour
+                .edges()
+                .scan(0.0, |walked, (from, to)| {
+                    let start = *walked;
+                    *walked += from.distance(to);
+                    Some(start)
+                })
+                .collect();
+            let mut push = |(position, normal, uv, lightmap_uv, mask): RingVertex| {
+                final_positions.push(position);
+                final_normals.push(normal);
+                final_uvs.push(uv);
+                lightmap.push(lightmap_uv);
+                final_mask.push(mask);
+                (final_positions.len() - 1) as u16
+            };
+            // The vertices at the start and the end of each edge on the previous ring. Rings
+            // share their vertices with the quads on both sides, and so do neighbouring edges
+            // unless a hard corner or a seam of the walls runs between them.
+            let mut previous: Vec<(u16, u16)> = Vec::new();
+            let mut arc = walls_back_z;
+            for (r, ring) in rings.iter().enumerate() {
+                if r > 0 {
+                    let near = &rings[r - 1];
+                    arc += Vec2::new((ring.inset - near.inset) * scale_factor, ring.z - near.z)
+                        .length();
+                }
+                // U and V carry on from the walls, and the lightmap is unrolled like the walls,
+                // with the distance walked down the rounding as V
+                let v = wall_v(ring.z);
+                let mask = ring.angle / FRAC_PI_2;
+                let vertex = |p: Vec2, n: Vec2, u: f32, walked: f32| {
+                    (
+                        ((p - center) * scale_factor).extend(ring.z),
+                        normal(n, ring.angle),
+                        Vec2::new(u, v),
+                        Vec2::new(walked * scale_factor, arc),
+                        mask,
+                    )
+                };
+                // Vertices only need the same lightmap coordinates when there is a lightmap
+                let same = |a: &RingVertex, b: &RingVertex| {
+                    a.0 == b.0 && a.1 == b.1 && a.2 == b.2 && (!options.lightmap_uvs || a.3 == b.3)
+                };
+                let edges: Vec<_> = ring.contours[c].edges().zip(contour.edges()).collect();
+                let mut row: Vec<(u16, u16)> = Vec::with_capacity(edges.len());
+                let mut first = None;
+                let mut last_end: Option<(u16, RingVertex)> = None;
+                for (i, &((a, b), (from, to))) in edges.iter().enumerate() {
+                    let (n_from, n_to) = wall_normals[i];
+                    let (u_from, u_to) = wall_u[i];
+                    let start = vertex(a, n_from, u_from, walked[i]);
+                    let end = vertex(b, n_to, u_to, walked[i] + from.distance(to));
+                    let start_index = match last_end {
+                        Some((index, shared)) if same(&shared, &start) => index,
+                        _ => push(start),
+                    };
+                    let first = *first.get_or_insert((start_index, start));
+                    // The last edge of a closed contour ends where the first one starts
+                    let end_index = match first {
+                        (index, shared)
+                            if contour.closed && i + 1 == edges.len() && same(&shared, &end) =>
+                        {
+                            index
+                        }
+                        _ => push(end),
+                    };
+                    last_end = Some((end_index, end));
+                    row.push((start_index, end_index));
+                }
+                for (&(near_a, near_b), &(far_a, far_b)) in previous.iter().zip(&row) {
+                    final_indices.extend([near_a, near_b, far_b, near_a, far_b, far_a]);
+                }
+                previous = row;
             }
-            _ => panic!("We only have begin, end and lineTo events"),
+            charts.push(chart_start..final_positions.len());
         }
+        final_surface.resize(final_positions.len(), TextSurface::Side as u32 as f32);
     }
 
+    // Vertex indices are truncated to u16 as they're added, so past the last one they would
+    // wrap around onto other vertices
+    if final_positions.len() > u16::MAX as usize {
+        return Err(MeshTextError::TessellationFailed);
+    }
+
+    // 3c. Noise moves the walls once they're all in place
+    if let Some(noise) = noise {
+        for (vertices, indices, depth) in noisy_walls {
+            displace_wall(
+                &mut final_positions,
+                &mut final_normals,
+                &final_indices[indices],
+                vertices,
+                depth,
+                noise,
+                options.wall_smoothing_angle,
+            );
+        }
+    }
+
+    let depth_fraction = options.depth_attribute.then(|| {
+        final_positions
+            .iter()
+            .map(|v| {
+                if back_z > 0.0 {
+                    (v.z / back_z).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    });
+
+    let vertex_colors = options.front_rim.map(|rim| {
+        (0..final_positions.len())
+            .map(|i| {
+                if (rim_start..rim_end).contains(&i) {
+                    rim.color
+                } else {
+                    [1.0; 3]
+                }
+            })
+            .collect()
+    });
+
+    let geometry = ExtrudedGlyphGeometry {
+        vertices: final_positions,
+        indices: final_indices,
+        normals: final_normals,
+        uvs: final_uvs,
+        uv1: options
+            .lightmap_uvs
+            .then(|| pack_charts(&charts, &lightmap)),
+        edge_mask: options.edge_mask.then_some(final_mask),
+        vertex_colors,
+        skinned: options.skinned,
+        depth_fraction,
+        surface: options.surface_attribute.then_some(final_surface),
+        topology: OutputTopology::Triangles,
+        caps,
+        issues,
+    };
+    debug_assert!(
+        geometry.winding_matches_normals(),
+        "extruded glyph has triangles wound against their normals"
+    );
+    let geometry = geometry.with_topology(options.topology);
+
     // Return the glyph dimensions for correct positioning
-    Ok((
-        ExtrudedGlyphGeometry {
-            vertices: final_positions,
-            indices: final_indices,
-            normals: final_normals,
-            uvs: final_uvs,
-        },
-        center_x * scale_factor,
-        center_y * scale_factor,
-    ))
+    Ok((geometry, center_x * scale_factor, center_y * scale_factor))
+}
+
+/// Whether `p` lies on the segment from `a` to `b`, within `tolerance`.
+fn on_segment(p: Vec2, (a, b): (Vec2, Vec2), tolerance: f32) -> bool {
+    let ab = b - a;
+    let t = ((p - a).dot(ab) / ab.length_squared().max(f32::MIN_POSITIVE)).clamp(0.0, 1.0);
+    p.distance(a + ab * t) <= tolerance
+}
+
+/// Splits the edge from `a` to `b` where edges of `contours` lying on it start and end. Gives
+/// the pieces as fractions of the edge, and whether an edge of `contours` covers them.
+fn edge_pieces(
+    (a, b): (Vec2, Vec2),
+    contours: &[Contour],
+    tolerance: f32,
+) -> Vec<(f32, f32, bool)> {
+    let ab = b - a;
+    let length_squared = ab.length_squared().max(f32::MIN_POSITIVE);
+    let along = |p: Vec2| ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    let spans: Vec<(f32, f32)> = contours
+        .iter()
+        .flat_map(Contour::edges)
+        .filter(|&(from, to)| {
+            on_segment(from, (a, b), tolerance) && on_segment(to, (a, b), tolerance)
+        })
+        .map(|(from, to)| {
+            let (s0, s1) = (along(from), along(to));
+            (s0.min(s1), s0.max(s1))
+        })
+        .collect();
+    let step = tolerance / length_squared.sqrt();
+    let mut bounds: Vec<f32> = [0.0, 1.0]
+        .into_iter()
+        .chain(spans.iter().flat_map(|&(s0, s1)| [s0, s1]))
+        .collect();
+    bounds.sort_by(f32::total_cmp);
+    bounds.dedup_by(|next, previous| *next - *previous <= step);
+    // The end of the edge may have merged into a bound just before it
+    match bounds.as_mut_slice() {
+        [_] => bounds.push(1.0),
+        [.., last] => *last = 1.0,
+        [] => {}
+    }
+    bounds
+        .windows(2)
+        .map(|pair| {
+            let middle = (pair[0] + pair[1]) / 2.0;
+            let covered = spans.iter().any(|&(s0, s1)| s0 <= middle && middle <= s1);
+            (pair[0], pair[1], covered)
+        })
+        .collect()
+}
+
+/// Averages the normals of consecutive wall quads where they meet at less than `max_angle`.
+///
+/// `normals` holds the quads of one contour as added by [`add_side_quad`], in the order of the
+/// contour, with `rows` quads stacked along the depth of each edge. Each row is smoothed into
+/// the same row of the next edge. `reversed` is set when each quad starts at the end of its
+/// contour edge.
+fn smooth_wall_normals(
+    normals: &mut [Vec3],
+    rows: usize,
+    closed: bool,
+    reversed: bool,
+    max_angle: f32,
+) {
+    let edges = normals.len() / 4 / rows;
+    let faces: Vec<Vec3> = (0..edges * rows).map(|quad| normals[quad * 4]).collect();
+    // Corners of a quad at the start and at the end of its contour edge
+    let (start, end) = if reversed {
+        ([1, 3], [0, 2])
+    } else {
+        ([0, 2], [1, 3])
+    };
+    let min_cos = max_angle.cos();
+    for edge in 0..edges {
+        if !closed && edge + 1 == edges {
+            break;
+        }
+        for row in 0..rows {
+            let quad = edge * rows + row;
+            let next = (edge + 1) % edges * rows + row;
+            let (a, b) = (faces[quad], faces[next]);
+            if a == Vec3::ZERO || b == Vec3::ZERO || a.dot(b) < min_cos {
+                continue;
+            }
+            let smooth = (a + b).normalize_or_zero();
+            for corner in end {
+                normals[quad * 4 + corner] = smooth;
+            }
+            for corner in start {
+                normals[next * 4 + corner] = smooth;
+            }
+        }
+    }
+}
+
+/// Bulges the cap made of the vertices from `first_vertex` on and the triangles of `indices`
+/// out along its normal, by `inflate.amount` eased in over `inflate.falloff` from the `edges`
+/// bounding the cap, and smooths its normals. The edges stay in place, joined to the walls or
+/// the lip around the cap.
+fn inflate_cap(
+    positions: &mut [Vec3],
+    normals: &mut [Vec3],
+    indices: &[u16],
+    first_vertex: usize,
+    edges: &[(Vec2, Vec2)],
+    inflate: InflateParameters,
+) {
+    let vertices = first_vertex..positions.len();
+    let distance = |p: Vec2| {
+        edges
+            .iter()
+            .map(|&(a, b)| {
+                let ab = b - a;
+                let t = ((p - a).dot(ab) / ab.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+                p.distance(a + ab * t)
+            })
+            .fold(f32::MAX, f32::min)
+    };
+    for i in vertices.clone() {
+        // A quarter sine, so the bulge rises from the edge and flattens in the middle
+        let t = (distance(positions[i].truncate()) / inflate.falloff).min(1.0);
+        let bulge = (t * FRAC_PI_2).sin();
+        positions[i] += normals[i] * inflate.amount * bulge;
+    }
+
+    // Each vertex is shaded by the average of its triangles
+    let mut smoothed = vec![Vec3::ZERO; vertices.len()];
+    for tri in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| tri[k] as usize);
+        let face = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+        for vertex in [a, b, c] {
+            smoothed[vertex - vertices.start] += face;
+        }
+    }
+    for (normal, smoothed) in normals[vertices].iter_mut().zip(smoothed) {
+        if let Some(smoothed) = smoothed.try_normalize() {
+            *normal = smoothed;
+        }
+    }
+}
+
+/// Splits the triangles of a cap until no edge is longer than `max_edge`, or the cap has
+/// [`INFLATE_MAX_CAP_VERTICES`]. Edges are split at their midpoint, shared by the triangles on
+/// both sides, so the cap stays in one piece. `midpoint` interpolates two vertices.
+fn subdivide_cap<T>(
+    geometry: &mut VertexBuffers<T, u16>,
+    max_edge: f32,
+    position: impl Fn(&T) -> Vec3,
+    midpoint: impl Fn(&T, &T) -> T,
+) {
+    loop {
+        let mut midpoints: HashMap<(u16, u16), u16> = HashMap::new();
+        let mut triangles = Vec::with_capacity(geometry.indices.len());
+        for tri in geometry.indices.chunks_exact(3) {
+            let v = [tri[0], tri[1], tri[2]];
+            // Midpoint of the edge from corner k to the next one, if it is split
+            let split = [0, 1, 2].map(|k| {
+                let (a, b) = (v[k], v[(k + 1) % 3]);
+                let (pa, pb) = (
+                    position(&geometry.vertices[a as usize]),
+                    position(&geometry.vertices[b as usize]),
+                );
+                if pa.distance(pb) <= max_edge
+                    || geometry.vertices.len() >= INFLATE_MAX_CAP_VERTICES
+                {
+                    return midpoints.get(&(a.min(b), a.max(b))).copied();
+                }
+                Some(*midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    let middle = midpoint(
+                        &geometry.vertices[a as usize],
+                        &geometry.vertices[b as usize],
+                    );
+                    geometry.vertices.push(middle);
+                    (geometry.vertices.len() - 1) as u16
+                }))
+            });
+            match split.iter().filter(|m| m.is_some()).count() {
+                0 => triangles.extend(v),
+                3 => {
+                    let [m0, m1, m2] = split.map(Option::unwrap);
+                    triangles.extend([v[0], m0, m2, m0, v[1], m1, m2, m1, v[2], m0, m1, m2]);
+                }
+                count => {
+                    // Turn the triangle so its first edge is split and, with two splits, so
+                    // is its second
+                    let k = (0..3)
+                        .find(|&k| {
+                            split[k].is_some() && (count == 1 || split[(k + 1) % 3].is_some())
+                        })
+                        .unwrap_or(0);
+                    let [a, b, c] = [v[k], v[(k + 1) % 3], v[(k + 2) % 3]];
+                    let m_ab = split[k].unwrap_or(a);
+                    match split[(k + 1) % 3] {
+                        Some(m_bc) if count == 2 => {
+                            triangles.extend([m_ab, b, m_bc, a, m_ab, m_bc, a, m_bc, c])
+                        }
+                        _ => triangles.extend([a, m_ab, c, m_ab, b, c]),
+                    }
+                }
+            }
+        }
+        let done = midpoints.is_empty();
+        geometry.indices = triangles;
+        if done {
+            break;
+        }
+    }
+
+    // Splitting keeps the long slivers of the tessellation, which would fold once bulged.
+    // Flipping the diagonals of every pair of triangles whose corners share a circle with a
+    // fourth corner inside, until none do, leaves well shaped triangles.
+    let xy = |i: u16| position(&geometry.vertices[i as usize]).truncate();
+    let orientation = |a: Vec2, b: Vec2, c: Vec2| (b - a).perp_dot(c - a);
+    for _ in 0..MAX_FLIP_PASSES {
+        let mut across: HashMap<(u16, u16), (usize, u16)> = HashMap::new();
+        for (t, tri) in geometry.indices.chunks_exact(3).enumerate() {
+            for k in 0..3 {
+                across.insert((tri[k], tri[(k + 1) % 3]), (t, tri[(k + 2) % 3]));
+            }
+        }
+        let mut flipped = vec![false; geometry.indices.len() / 3];
+        let mut any = false;
+        for t in 0..flipped.len() {
+            for k in 0..3 {
+                if flipped[t] {
+                    break;
+                }
+                let tri = &geometry.indices[t * 3..t * 3 + 3];
+                let (a, b, c) = (tri[k], tri[(k + 1) % 3], tri[(k + 2) % 3]);
+                let Some(&(u, d)) = across.get(&(b, a)) else {
+                    continue;
+                };
+                if flipped[u] || u == t {
+                    continue;
+                }
+                let [pa, pb, pc, pd] = [a, b, c, d].map(xy);
+                let sign = orientation(pa, pb, pc).signum();
+                // Both new triangles must keep the winding, i.e. the pair forms a convex quad
+                if orientation(pa, pd, pc) * sign <= 0.0 || orientation(pd, pb, pc) * sign <= 0.0 {
+                    continue;
+                }
+                if in_circle(pa, pb, pc, pd) * sign <= 0.0 {
+                    continue;
+                }
+                geometry.indices[t * 3..t * 3 + 3].copy_from_slice(&[a, d, c]);
+                geometry.indices[u * 3..u * 3 + 3].copy_from_slice(&[d, b, c]);
+                flipped[t] = true;
+                flipped[u] = true;
+                any = true;
+            }
+        }
+        if !any {
+            break;
+        }
+    }
+}
+
+/// Positive when `d` lies inside the circle through `a`, `b` and `c`, wound counter-clockwise.
+fn in_circle(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> f32 {
+    let [a, b, c] = [a - d, b - d, c - d];
+    let lift = |p: Vec2| p.length_squared();
+    a.x * (b.y * lift(c) - lift(b) * c.y) - a.y * (b.x * lift(c) - lift(b) * c.x)
+        + lift(a) * (b.x * c.y - b.y * c.x)
+}
+
+/// Texture U at both ends of each edge of `contour`: the distance walked from the `seam`,
+/// relative to the perimeter so a texture wraps around the walls exactly once, or times `scale`
+/// when given.
+fn wall_u(contour: &Contour, seam: WallSeam, scale: Option<f32>) -> Vec<(f32, f32)> {
+    let lengths: Vec<f32> = contour
+        .edges()
+        .map(|(from, to)| from.distance(to))
+        .collect();
+    let perimeter: f32 = lengths.iter().sum();
+    let start = match seam {
+        WallSeam::Lowest if contour.closed => contour
+            .points
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+            .map_or(0, |(i, _)| i),
+        _ => 0,
+    };
+    let scale = scale.unwrap_or(1.0 / perimeter.max(f32::EPSILON));
+    let mut u = vec![(0.0, 0.0); lengths.len()];
+    let mut walked = 0.0;
+    for k in 0..lengths.len() {
+        let i = (start + k) % lengths.len();
+        let next = walked + lengths[i];
+        u[i] = (walked * scale, next * scale);
+        walked = next;
+    }
+    u
+}
+
+/// Cells a wall of `length` and `height` is split into along and across, at `density` cells per
+/// layout unit. A single cell without noise.
+fn wall_cells(length: f32, height: f32, density: f32) -> (usize, usize) {
+    let cells = |size: f32| ((size * density).ceil() as usize).clamp(1, 64);
+    (cells(length), cells(height))
+}
+
+/// Moves the wall vertices in `vertices` by `noise`, fading out towards both ends of `depth`,
+/// where the walls meet the caps. The walls are then shaded by their displaced triangles
+/// from `indices`. Vertices that shared a position keep sharing their normal where their
+/// walls met at less than `max_angle`, or were flush without it, so corners stay hard.
+fn displace_wall(
+    positions: &mut [Vec3],
+    normals: &mut [Vec3],
+    indices: &[u16],
+    vertices: std::ops::Range<usize>,
+    (z0, z1): (f32, f32),
+    noise: SurfaceNoise,
+    max_angle: Option<f32>,
+) {
+    let key = |p: Vec3| (p * 1024.0).round().as_ivec3().to_array();
+    let mut shared: HashMap<[i32; 3], Vec<usize>> = HashMap::new();
+    for i in vertices.clone() {
+        shared.entry(key(positions[i])).or_default().push(i);
+    }
+    let flat: Vec<Vec3> = normals[vertices.clone()].to_vec();
+
+    let mask = Vec3::from_array(noise.axis_mask);
+    for position in &mut positions[vertices.clone()] {
+        let t = if z1 > z0 {
+            (position.z - z0) / (z1 - z0)
+        } else {
+            0.0
+        };
+        let fade = (t.clamp(0.0, 1.0) * std::f32::consts::PI).sin();
+        let offset = vector_noise(*position * noise.frequency, noise.seed);
+        *position += offset * mask * noise.amplitude * fade;
+    }
+
+    let mut faces = vec![Vec3::ZERO; vertices.len()];
+    for tri in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| tri[k] as usize);
+        let face = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+        for vertex in [a, b, c] {
+            faces[vertex - vertices.start] += face;
+        }
+    }
+    let min_cos = max_angle.map_or(1.0 - 1e-4, f32::cos);
+    for same in shared.values() {
+        for &i in same {
+            let flat_i = flat[i - vertices.start];
+            let smooth: Vec3 = same
+                .iter()
+                .filter(|&&j| flat[j - vertices.start].dot(flat_i) >= min_cos)
+                .map(|&j| faces[j - vertices.start])
+                .sum();
+            if let Some(smooth) = smooth.try_normalize() {
+                normals[i] = smooth;
+            }
+        }
+    }
+}
+
+/// The recessed face of a front inset: the inset `contours` bound a cap at `z`, `depth` behind
+/// the front face, and the lip between them and the outline. `widths` holds the width of the
+/// lip along each contour of the outline, in layout units, and is 0 where the cap reaches the
+/// outline.
+struct InsetFace {
+    contours: Vec<Contour>,
+    depth: f32,
+    z: f32,
+    lip: Path,
+    cap: Path,
+    widths: Vec<f32>,
+}
+
+/// Position, normal, UV, lightmap coordinates and edge mask of a vertex of a rounded back
+type RingVertex = (Vec3, Vec3, Vec2, Vec2, f32);
+
+/// A ring of a rounded back: the outline inset by `inset` font units, at `z`. `angle` runs
+/// along the quarter ellipse from 0 at the walls to a right angle at the back face.
+struct BackRing {
+    contours: Vec<Contour>,
+    inset: f32,
+    z: f32,
+    angle: f32,
+}
+
+/// The rings of `rounded`, from the outline at the end of the walls to the back face at
+/// `back_z`. The radius is halved until the innermost ring keeps the shape of the outline:
+/// every contour in one piece, winding its own way and clear of the others. Empty when no
+/// radius does, or when there is nothing to round.
+fn rounded_back_rings(
+    contours: &[Contour],
+    rounded: RoundedBack,
+    scale_factor: f32,
+    back_z: f32,
+) -> Result<Vec<BackRing>, MeshTextError> {
+    let depth = rounded.depth.min(back_z);
+    if rounded.radius <= 0.0 || depth <= 0.0 || rounded.segments == 0 {
+        return Ok(Vec::new());
+    }
+    let inset = |distance: f32| -> Vec<Contour> {
+        contours
+            .iter()
+            .map(|contour| contour.offset(distance))
+            .collect()
+    };
+    // Fonts may overlap their own contours, which the rings then do as well
+    let pieces = resolve_overlaps(contours)?.len();
+    let mut radius = rounded.radius / scale_factor;
+    let mut found = false;
+    for _ in 0..ROUNDED_BACK_ATTEMPTS {
+        let ring = inset(radius);
+        let simple = contours.iter().zip(&ring).all(|(contour, inset)| {
+            inset.split_loops().len() == 1 && inset.signed_area() * contour.signed_area() > 0.0
+        });
+        if simple && resolve_overlaps(&ring)?.len() == pieces {
+            found = true;
+            break;
+        }
+        radius /= 2.0;
+    }
+    if !found {
+        return Ok(Vec::new());
+    }
+
+    Ok((0..=rounded.segments)
+        .map(|k| {
+            let step = k as f32 / rounded.segments as f32;
+            let angle = step.powf(rounded.profile_power) * FRAC_PI_2;
+            let inset_distance = radius * (1.0 - angle.cos());
+            BackRing {
+                contours: inset(inset_distance),
+                inset: inset_distance,
+                z: back_z - depth + depth * angle.sin(),
+                angle,
+            }
+        })
+        .collect())
+}
+
+/// Outward wall normals at the start and the end of each edge of `contour`, shared with the
+/// neighbouring edge where the walls meet at less than `max_angle`, like
+/// [`smooth_wall_normals`].
+fn edge_end_normals(contour: &Contour, max_angle: Option<f32>) -> Vec<(Vec2, Vec2)> {
+    let faces: Vec<Vec2> = contour
+        .edges()
+        .map(|(from, to)| {
+            let d = to - from;
+            Vec2::new(d.y, -d.x).normalize_or_zero()
+        })
+        .collect();
+    let n = faces.len();
+    let smooth = |face: Vec2, other: Option<Vec2>| match (max_angle, other) {
+        (Some(max_angle), Some(other))
+            if face != Vec2::ZERO && other != Vec2::ZERO && face.dot(other) >= max_angle.cos() =>
+        {
+            (face + other).normalize_or_zero()
+        }
+        _ => face,
+    };
+    (0..n)
+        .map(|i| {
+            let prev = (contour.closed || i > 0).then(|| faces[(i + n - 1) % n]);
+            let next = (contour.closed || i + 1 < n).then(|| faces[(i + 1) % n]);
+            (smooth(faces[i], prev), smooth(faces[i], next))
+        })
+        .collect()
+}
+
+/// Maps cap vertices, given in layout units around the glyph center, to texture coordinates.
+fn cap_uv_mapping(
+    contours: &[Contour],
+    center: Vec2,
+    units_per_em: f32,
+    scale_factor: f32,
+    mode: UvMode,
+) -> impl Fn(Vec3) -> Vec2 {
+    let em = units_per_em * scale_factor;
+    let (min, max) = match mode {
+        UvMode::Em | UvMode::TextBlock => (Vec2::ZERO, Vec2::ONE),
+        UvMode::GlyphBounds => {
+            let points = contours.iter().flat_map(|contour| &contour.points);
+            let min = points.clone().fold(Vec2::MAX, |min, p| min.min(*p));
+            let max = points.fold(Vec2::MIN, |max, p| max.max(*p));
+            ((min - center) * scale_factor, (max - center) * scale_factor)
+        }
+    };
+    let size = (max - min).max(Vec2::splat(f32::EPSILON));
+    move |position| match mode {
+        UvMode::Em => (position.truncate() / em + 0.5) * 0.5 + 0.5,
+        UvMode::GlyphBounds => {
+            let t = (position.truncate() - min) / size;
+            Vec2::new(t.x, 1.0 - t.y)
+        }
+        // Placed in the text block by the layout
+        UvMode::TextBlock => Vec2::new(position.x, -position.y),
+    }
+}
+
+/// Fills the flattened `path` with the configured tessellator and fill rule, falling back to
+/// the other rule if enabled. `vertex` makes the vertices of `output` from their position
+/// and the custom attributes of the path. Returns the fill rule used.
+fn fill_path<V>(
+    tessellator: &mut FillTessellator,
+    path: &Path,
+    options: &TessellationOptions,
+    output: &mut VertexBuffers<V, u16>,
+    vertex: impl Fn(lyon::geom::Point<f32>, &[f32]) -> V,
+) -> Result<FillRule, MeshTextError> {
+    if options.cap_tessellator == CapTessellator::EarClipping {
+        ear_clip_path(path, options.fill_rule, output, vertex)?;
+        return Ok(options.fill_rule);
+    }
+    let output = &mut BuffersBuilder::new(output, |mut fill_vertex: FillVertex| {
+        let position = fill_vertex.position();
+        vertex(position, fill_vertex.interpolated_attributes())
+    });
+    let fill = |fill_rule| FillOptions::tolerance(options.tolerance).with_fill_rule(fill_rule);
+    match tessellator.tessellate_path(path, &fill(options.fill_rule), output) {
+        Ok(()) => Ok(options.fill_rule),
+        Err(e) if options.fallback_chain => {
+            let fallback = match options.fill_rule {
+                FillRule::EvenOdd => FillRule::NonZero,
+                FillRule::NonZero => FillRule::EvenOdd,
+            };
+            warn!(
+                "Tessellation with {:?} failed ({:?}), retrying with {:?}",
+                options.fill_rule, e, fallback
+            );
+            tessellator
+                .tessellate_path(path, &fill(fallback), output)
+                .map(|()| fallback)
+                .map_err(|_| MeshTextError::TessellationFailed)
+        }
+        Err(_) => Err(MeshTextError::TessellationFailed),
+    }
+}
+
+/// Fills the flattened `path` by [`ear_clip`], with a vertex for every point of the path.
+fn ear_clip_path<V>(
+    path: &Path,
+    fill_rule: FillRule,
+    output: &mut VertexBuffers<V, u16>,
+    vertex: impl Fn(lyon::geom::Point<f32>, &[f32]) -> V,
+) -> Result<(), MeshTextError> {
+    let mut contours: Vec<Contour> = Vec::new();
+    let mut vertices = Vec::new();
+    for event in path.iter_with_attributes() {
+        let (at, attributes) = match event {
+            Event::Begin { at } => {
+                contours.push(Contour {
+                    points: Vec::new(),
+                    closed: true,
+                });
+                at
+            }
+            // Curves don't reach the fill, as outlines are flattened first
+            Event::Line { to, .. } | Event::Quadratic { to, .. } | Event::Cubic { to, .. } => to,
+            Event::End { .. } => continue,
+        };
+        if let Some(contour) = contours.last_mut() {
+            contour.points.push(Vec2::new(at.x, at.y));
+            vertices.push(vertex(at, attributes));
+        }
+    }
+    let first = output.vertices.len();
+    if first + vertices.len() > u16::MAX as usize {
+        return Err(MeshTextError::TessellationFailed);
+    }
+    output.vertices.extend(vertices);
+    output.indices.extend(
+        ear_clip(&contours, fill_rule)
+            .into_iter()
+            .map(|index| (first + index as usize) as u16),
+    );
+    Ok(())
+}
+
+/// Builds the path of the lip between the outline and the inset contours, with the edge mask
+/// as a custom attribute: 0 along the outline and 1 along the inset contours.
+fn lip_path(outline: &[Contour], inset: &[Contour]) -> Path {
+    let mut builder = Path::builder_with_attributes(1);
+    for (contours, mask) in [(outline, 0.0), (inset, 1.0)] {
+        for contour in contours {
+            let Some((first, rest)) = contour.points.split_first() else {
+                continue;
+            };
+            builder.begin(point(first.x, first.y), &[mask]);
+            for p in rest {
+                builder.line_to(point(p.x, p.y), &[mask]);
+            }
+            builder.end(contour.closed);
+        }
+    }
+    builder.build()
 }
 
 // Helper function for adding side quads during extrusion
 #[allow(clippy::too_many_arguments)]
-fn add_side_quad(
+pub(crate) fn add_side_quad(
     positions: &mut Vec<Vec3>,
     indices: &mut Vec<u16>,
     normals: &mut Vec<Vec3>,
@@ -214,15 +2215,16 @@ fn add_side_quad(
     p1_orig: lyon::geom::Point<f32>,
     p2_orig: lyon::geom::Point<f32>,
     scale: f32,
-    depth: f32,
-    v_texture_coord: f32, // Texture coordinate for mapping along the extrusion
+    (front_z, back_z): (f32, f32),
+    (u1, u2): (f32, f32), // Texture U at p1 and p2
+    (v1, v2): (f32, f32), // Texture V at the front and the back
 ) {
-    let p1_front = Vec3::new(p1_orig.x * scale, p1_orig.y * scale, 0.0);
-    let p2_front = Vec3::new(p2_orig.x * scale, p2_orig.y * scale, 0.0);
-    let p1_back = Vec3::new(p1_orig.x * scale, p1_orig.y * scale, depth);
-    let p2_back = Vec3::new(p2_orig.x * scale, p2_orig.y * scale, depth);
+    let p1_front = Vec3::new(p1_orig.x * scale, p1_orig.y * scale, front_z);
+    let p2_front = Vec3::new(p2_orig.x * scale, p2_orig.y * scale, front_z);
+    let p1_back = Vec3::new(p1_orig.x * scale, p1_orig.y * scale, back_z);
+    let p2_back = Vec3::new(p2_orig.x * scale, p2_orig.y * scale, back_z);
 
-    let base_idx = positions.len() as u16;
+    let base_idx = positions.len();
     positions.extend_from_slice(&[p1_front, p2_front, p1_back, p2_back]);
 
     // Calculate side normal based on the 2D segment direction
@@ -234,18 +2236,6 @@ fn add_side_quad(
 
     normals.extend_from_slice(&[side_normal, side_normal, side_normal, side_normal]);
 
-    // Calculate texture coordinates
-    // U coordinate will be based on position along the contour
-    // Distance from p1 to p2 to calculate u texture coordinate
-    let segment_length = ((p2_orig.x - p1_orig.x).powi(2) + (p2_orig.y - p1_orig.y).powi(2)).sqrt();
-    // Use segment_length to normalize UV coordinates
-    let u1 = 0.0; // Start of segment
-    let u2 = segment_length * scale; // End of segment, scaled
-
-    // V coordinate will be 0.0 at front face and 1.0 at back face
-    let v1 = v_texture_coord; // Front face
-    let v2 = v_texture_coord + 1.0; // Back face
-
     uvs.extend_from_slice(&[
         Vec2::new(u1, v1), // p1_front
         Vec2::new(u2, v1), // p2_front
@@ -257,12 +2247,15 @@ fn add_side_quad(
     // Tri 1: (p1_front, p2_front, p2_back) -> (base_idx+0, base_idx+1, base_idx+3)
     // Tri 2: (p1_front, p2_back, p1_back)  -> (base_idx+0, base_idx+3, base_idx+2)
     // This winding should make the normal (dy, -dx, 0) point outwards.
-    indices.extend_from_slice(&[
-        base_idx,
-        base_idx + 1,
-        base_idx + 3,
-        base_idx,
-        base_idx + 3,
-        base_idx + 2,
-    ]);
+    indices.extend(
+        [
+            base_idx,
+            base_idx + 1,
+            base_idx + 3,
+            base_idx,
+            base_idx + 3,
+            base_idx + 2,
+        ]
+        .map(|index| index as u16),
+    );
 }
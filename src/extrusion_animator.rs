@@ -0,0 +1,410 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::mesh::morph::{MeshMorphWeights, MorphTargetImage};
+use cosmic_text::{Attrs, AttrsOwned, Font, LayoutGlyph};
+
+use crate::async_generation::TrackedTask;
+use crate::entity_pool::{GlyphEntityPool, PooledText, update_pooled_text};
+use crate::extrude_glyph::ExtrudedGlyphGeometry;
+use crate::mesh_text_plugin::layout_text;
+use crate::morph::{anchor_target, morph_target};
+use crate::text_glyphs::tessellate_font_glyph;
+use crate::{FrontInset, InputText, MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// The depths an [`ExtrusionAnimator`] interpolates. They replace the
+/// [`depth`](Parameters::depth), [`bevel_width`](Parameters::bevel_width) and
+/// [`hole_bevel_width`](Parameters::hole_bevel_width) of the animated text, so the lip is as
+/// wide as the inset of the keyframes everywhere.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtrusionKeyframe {
+    pub extrusion_depth: f32,
+    pub front_inset: Option<FrontInset>,
+}
+
+impl ExtrusionKeyframe {
+    /// Interpolates towards `other`. An inset that only one of the keyframes has appears or
+    /// disappears at the end.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let front_inset = match (self.front_inset, other.front_inset) {
+            (Some(a), Some(b)) => Some(FrontInset {
+                width: a.width.lerp(b.width, t),
+                depth: a.depth.lerp(b.depth, t),
+            }),
+            (a, b) => {
+                if t < 1.0 {
+                    a
+                } else {
+                    b
+                }
+            }
+        };
+        Self {
+            extrusion_depth: self.extrusion_depth.lerp(other.extrusion_depth, t),
+            front_inset,
+        }
+    }
+}
+
+/// A laid out glyph and the font to tessellate it from.
+#[derive(Clone)]
+struct AnimatedGlyph {
+    glyph: LayoutGlyph,
+    line_y: f32,
+    font: Option<Arc<Font>>,
+}
+
+/// A glyph of one step, tessellated at the start and the end of the step.
+struct StepGlyph {
+    base: ExtrudedGlyphGeometry,
+    target: ExtrudedGlyphGeometry,
+    transform: Transform,
+}
+
+/// Background generation of a step.
+type StepTask = TrackedTask<Vec<StepGlyph>>;
+
+/// Time covered by one generated step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StepSpan {
+    start: Duration,
+    end: Duration,
+}
+
+/// Animates the extrusion depth and front inset of a text, e.g. to make it inflate.
+///
+/// Instead of regenerating every frame, the glyphs are generated in the background at
+/// `rate` steps per second. Each step carries a morph target towards the next one, so the
+/// geometry blends smoothly between steps. Glyph entities are spawned as children from the
+/// [`GlyphEntityPool`]. The plugin animates `StandardMaterial` text; add
+/// [`animate_extrusion`] for other materials yourself.
+///
+/// The animation stops at the first step whose parameters don't validate, e.g. with a negative
+/// depth in a keyframe, until it is restarted.
+#[derive(Component)]
+#[require(Transform, Visibility)]
+pub struct ExtrusionAnimator<M: Material = StandardMaterial> {
+    pub from: ExtrusionKeyframe,
+    pub to: ExtrusionKeyframe,
+    pub duration: Duration,
+    pub elapsed: Duration,
+    /// Generated steps per second
+    pub rate: f32,
+    text: String,
+    attrs: AttrsOwned,
+    params: Parameters,
+    material: Handle<M>,
+    layout: Option<Vec<AnimatedGlyph>>,
+    glyphs: PooledText,
+    current: Option<(StepSpan, bool)>,
+    pending: Option<(StepSpan, StepTask)>,
+    /// Set when a step had invalid parameters
+    stopped: bool,
+}
+
+impl<M: Material> ExtrusionAnimator<M> {
+    /// Animates `text` laid out with `params` from `from` to `to` over `duration`, generating
+    /// 10 steps per second.
+    pub fn new(
+        text: impl Into<String>,
+        attrs: &Attrs,
+        params: Parameters,
+        material: Handle<M>,
+        from: ExtrusionKeyframe,
+        to: ExtrusionKeyframe,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            elapsed: Duration::ZERO,
+            rate: 10.0,
+            text: text.into(),
+            attrs: AttrsOwned::new(attrs),
+            params,
+            material,
+            layout: None,
+            glyphs: PooledText::default(),
+            current: None,
+            pending: None,
+            stopped: false,
+        }
+    }
+
+    /// Starts over, e.g. after changing the keyframes.
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.current = None;
+        self.pending = None;
+        self.stopped = false;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current
+            .is_some_and(|(span, _)| span.end >= self.duration && self.elapsed >= self.duration)
+    }
+
+    pub fn glyphs(&self) -> &[Entity] {
+        self.glyphs.glyphs()
+    }
+
+    fn keyframe(&self, at: Duration) -> ExtrusionKeyframe {
+        let t = if self.duration.is_zero() {
+            1.0
+        } else {
+            at.as_secs_f32() / self.duration.as_secs_f32()
+        };
+        self.from.lerp(&self.to, t.clamp(0.0, 1.0))
+    }
+
+    /// Blend weight of the current step.
+    fn weight(&self) -> Option<f32> {
+        let (span, morphed) = self.current?;
+        if !morphed {
+            return None;
+        }
+        let length = (span.end - span.start).as_secs_f32();
+        if length <= 0.0 {
+            return Some(1.0);
+        }
+        let t = self.elapsed.saturating_sub(span.start).as_secs_f32() / length;
+        Some(t.clamp(0.0, 1.0))
+    }
+
+    /// Starts generating the step from `start` in the background, once the parameters at both
+    /// of its ends validate.
+    fn request(&mut self, start: Duration, scale: Vec3) -> Result<(), MeshTextError> {
+        let interval = Duration::from_secs_f32(1.0 / self.rate.max(f32::EPSILON));
+        let span = StepSpan {
+            start,
+            end: (start + interval).min(self.duration),
+        };
+        // The keyframes take the place of the depth and lip widths in other units
+        let parameters = |keyframe: ExtrusionKeyframe| Parameters {
+            extrusion_depth: keyframe.extrusion_depth,
+            depth: None,
+            front_inset: keyframe.front_inset,
+            bevel_width: None,
+            hole_bevel_width: None,
+            ..self.params.clone()
+        };
+        let (base, target) = (
+            parameters(self.keyframe(span.start)),
+            parameters(self.keyframe(span.end)),
+        );
+        base.validate()?;
+        target.validate()?;
+        let base_options = base.extrusion_options();
+        let target_options = target.extrusion_options();
+        // Shifting the end of the step keeps the blended depth anchored
+        let anchor = self.params.depth_anchor;
+        let front_z = anchor.front_z(base_options.extrusion_depth * scale.z);
+        let policy = self.params.missing_glyph_policy;
+        let layout = self.layout.clone().unwrap_or_default();
+
+        let task = TrackedTask::spawn(layout.len(), move |state| {
+            let mut glyphs = Vec::new();
+            for AnimatedGlyph {
+                glyph,
+                line_y,
+                font,
+            } in &layout
+            {
+                state.check()?;
+                let data = font.as_deref().map(Font::data);
+                let Some((base, center_x, center_y)) =
+                    tessellate_font_glyph(data, glyph, policy, &base_options)?
+                else {
+                    continue;
+                };
+                let Some((mut target, ..)) =
+                    tessellate_font_glyph(data, glyph, policy, &target_options)?
+                else {
+                    continue;
+                };
+                anchor_target(
+                    &mut target,
+                    anchor,
+                    base_options.extrusion_depth,
+                    target_options.extrusion_depth,
+                );
+                // Same placement as `generate_meshes`
+                let x = (glyph.x + glyph.x_offset + center_x) * scale.x;
+                let y = (-(line_y + glyph.y + glyph.y_offset) + center_y) * scale.y;
+                glyphs.push(StepGlyph {
+                    base,
+                    target,
+                    transform: Transform::from_xyz(x, y, front_z).with_scale(scale),
+                });
+            }
+            Ok(glyphs)
+        });
+        self.pending = Some((span, task));
+        Ok(())
+    }
+
+    /// Builds the meshes of a finished step, blending towards its end with a morph target
+    /// when the topology allows it.
+    #[allow(clippy::too_many_arguments)]
+    fn install(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        span: StepSpan,
+        glyphs: Vec<StepGlyph>,
+        pool: &mut GlyphEntityPool,
+        meshes: &mut Assets<Mesh>,
+        images: &mut Assets<Image>,
+    ) {
+        let targets: Option<Vec<_>> = glyphs
+            .iter()
+            .map(|glyph| {
+                let attributes = morph_target(&glyph.base, &glyph.target).ok()?;
+                MorphTargetImage::new(
+                    [attributes.into_iter()].into_iter(),
+                    glyph.base.vertices.len(),
+                    RenderAssetUsages::RENDER_WORLD,
+                )
+                .ok()
+            })
+            .collect();
+        let morphed = targets.is_some();
+
+        let entries = match targets {
+            Some(targets) => glyphs
+                .into_iter()
+                .zip(targets)
+                .map(|(glyph, targets)| {
+                    let mut mesh = Mesh::from(glyph.base);
+                    mesh.set_morph_targets(images.add(targets.0));
+                    (mesh, glyph.transform)
+                })
+                .collect::<Vec<_>>(),
+            // Swap to the end of the step when the geometry can't be blended
+            None => glyphs
+                .into_iter()
+                .map(|glyph| (Mesh::from(glyph.target), glyph.transform))
+                .collect(),
+        };
+        let entries = entries
+            .into_iter()
+            .map(|(mesh, transform)| MeshTextEntry {
+                mesh: meshes.add(mesh),
+                transform,
+                material: self.material.clone(),
+            })
+            .collect();
+        update_pooled_text(commands, pool, entity, &mut self.glyphs, entries, meshes);
+
+        self.current = Some((span, morphed));
+        let weight = self.weight();
+        for &glyph in self.glyphs.glyphs() {
+            match weight {
+                Some(weight) => {
+                    if let Ok(weights) = MeshMorphWeights::new(vec![weight]) {
+                        commands.entity(glyph).insert(weights);
+                    }
+                }
+                None => {
+                    commands.entity(glyph).remove::<MeshMorphWeights>();
+                }
+            }
+        }
+    }
+}
+
+/// Advances [`ExtrusionAnimator`]s, installs finished steps, requests the next ones and
+/// updates the blend weights.
+#[allow(clippy::too_many_arguments)]
+pub fn animate_extrusion<M: Material>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut animators: Query<(Entity, &mut ExtrusionAnimator<M>)>,
+    mut weights: Query<&mut MeshMorphWeights>,
+    mut pool: ResMut<GlyphEntityPool>,
+    mut settings: ResMut<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (entity, mut animator) in &mut animators {
+        let animator = &mut *animator;
+        animator.elapsed = (animator.elapsed + time.delta()).min(animator.duration);
+
+        if animator.layout.is_none() {
+            let input = InputText::Simple {
+                text: animator.text.clone(),
+                material: Handle::<StandardMaterial>::default(),
+                attrs: animator.attrs.as_attrs(),
+            };
+            let font_system = &mut settings.font_system;
+            let layout = match layout_text(input, font_system, &mut animator.params) {
+                Ok((tx, _)) => tx
+                    .layout_runs()
+                    .flat_map(|run| run.glyphs.iter().map(move |glyph| (glyph, run.line_y)))
+                    .map(|(glyph, line_y)| AnimatedGlyph {
+                        glyph: glyph.clone(),
+                        line_y,
+                        font: font_system.get_font(glyph.font_id),
+                    })
+                    .collect(),
+                Err(e) => {
+                    warn!("Failed to lay out animated text: {e}");
+                    Vec::new()
+                }
+            };
+            animator.layout = Some(layout);
+        }
+
+        // A step generated ahead waits until its time has come
+        let due = |span: &StepSpan| animator.current.is_none() || animator.elapsed >= span.start;
+        if let Some((span, task)) = animator.pending.as_mut().filter(|(span, _)| due(span))
+            && let Some(result) = task.poll()
+        {
+            let span = *span;
+            animator.pending = None;
+            match result {
+                Ok(glyphs) => animator.install(
+                    &mut commands,
+                    entity,
+                    span,
+                    glyphs,
+                    &mut pool,
+                    &mut meshes,
+                    &mut images,
+                ),
+                Err(e) => warn!("Failed to generate animated text: {e}"),
+            }
+        }
+
+        // Generate one step ahead, catching up if generation fell behind
+        if animator.pending.is_none() && !animator.stopped {
+            let next = match animator.current {
+                None => Some(animator.elapsed),
+                Some((span, _)) if span.end < animator.duration => {
+                    Some(span.end.max(animator.elapsed))
+                }
+                Some(_) => None,
+            };
+            if let Some(start) = next {
+                let scale = animator.params.world_scale(settings.text_scale_factor);
+                if let Err(e) = animator.request(start, scale) {
+                    warn!("Stopped animating text with invalid parameters: {e}");
+                    animator.stopped = true;
+                }
+            }
+        }
+
+        if let Some(weight) = animator.weight() {
+            for &glyph in animator.glyphs.glyphs() {
+                if let Ok(mut weights) = weights.get_mut(glyph)
+                    && let Some(w) = weights.weights_mut().first_mut()
+                {
+                    *w = weight;
+                }
+            }
+        }
+    }
+}
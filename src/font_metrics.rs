@@ -0,0 +1,114 @@
+//! Metrics of the fonts text is set in, for custom decorations and alignment without parsing
+//! the font again.
+
+use cosmic_text::ttf_parser::Face;
+use cosmic_text::{Attrs, Buffer, FontSystem, Metrics, Shaping};
+
+use crate::MeshTextError;
+
+/// Height of the capitals as a fraction of the em, for fonts that don't tell theirs and have
+/// no "H"
+const DEFAULT_CAP_HEIGHT: f32 = 0.7;
+/// Height of the lowercase as a fraction of the em, for fonts that don't tell theirs and have
+/// no "x"
+const DEFAULT_X_HEIGHT: f32 = 0.5;
+/// Top of the underline below the baseline as a fraction of the em, for fonts that don't tell
+/// theirs
+const DEFAULT_UNDERLINE_POSITION: f32 = -0.1;
+/// Thickness of underlines and strikeouts as a fraction of the em, for fonts that don't tell
+/// theirs
+const DEFAULT_LINE_THICKNESS: f32 = 0.05;
+
+/// Vertical metrics of a font at a font size, in layout units above the baseline, so
+/// descenders and underlines are negative. Glyphs are placed from the baseline of their line,
+/// so these line up with the glyph meshes like the layout of the line does.
+///
+/// Cap and x-heights missing from the font are measured on its "H" and "x", and other
+/// missing metrics are replaced by typical fractions of the em.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetricsInfo {
+    pub font_size: f32,
+    pub ascender: f32,
+    pub descender: f32,
+    /// Extra space the font asks for between lines
+    pub line_gap: f32,
+    /// Height of flat capitals such as "H"
+    pub cap_height: f32,
+    /// Height of flat lowercase letters such as "x"
+    pub x_height: f32,
+    /// Top of the underline
+    pub underline_position: f32,
+    pub underline_thickness: f32,
+    /// Top of the strikeout
+    pub strikeout_position: f32,
+    pub strikeout_thickness: f32,
+}
+
+impl FontMetricsInfo {
+    /// The metrics of `face` at `font_size`.
+    pub fn from_face(face: &Face, font_size: f32) -> Self {
+        let units_per_em = face.units_per_em() as f32;
+        let scale = font_size / units_per_em;
+        let em = |fraction: f32| fraction * font_size;
+        let height = |height: Option<i16>, letter: char, default: f32| {
+            height
+                .filter(|&height| height > 0)
+                .or_else(|| {
+                    let glyph = face.glyph_index(letter)?;
+                    Some(face.glyph_bounding_box(glyph)?.y_max)
+                })
+                .filter(|&height| height > 0)
+                .map_or(em(default), |height| height as f32 * scale)
+        };
+        let x_height = height(face.x_height(), 'x', DEFAULT_X_HEIGHT);
+        let underline = face.underline_metrics();
+        let underline_thickness = underline.map_or(em(DEFAULT_LINE_THICKNESS), |line| {
+            line.thickness as f32 * scale
+        });
+        let strikeout = face.strikeout_metrics();
+        let strikeout_thickness =
+            strikeout.map_or(underline_thickness, |line| line.thickness as f32 * scale);
+        Self {
+            font_size,
+            ascender: face.ascender() as f32 * scale,
+            descender: face.descender() as f32 * scale,
+            line_gap: face.line_gap() as f32 * scale,
+            cap_height: height(face.capital_height(), 'H', DEFAULT_CAP_HEIGHT),
+            x_height,
+            underline_position: underline.map_or(em(DEFAULT_UNDERLINE_POSITION), |line| {
+                line.position as f32 * scale
+            }),
+            underline_thickness,
+            // Strikeouts cross the middle of the lowercase by default
+            strikeout_position: strikeout.map_or((x_height + strikeout_thickness) / 2.0, |line| {
+                line.position as f32 * scale
+            }),
+            strikeout_thickness,
+        }
+    }
+}
+
+/// The metrics of the font cosmic-text sets text with `attrs` in, at `font_size` or the size
+/// `attrs` sets with [`Attrs::metrics`]. The font is the one an "x" is shaped with, so font
+/// fallback picks the same font as for the text.
+pub fn font_metrics(
+    font_system: &mut FontSystem,
+    attrs: &Attrs,
+    font_size: f32,
+) -> Result<FontMetricsInfo, MeshTextError> {
+    if font_size <= 0.0 || !font_size.is_finite() {
+        return Err(MeshTextError::InvalidFontSize(font_size));
+    }
+    let mut buffer = Buffer::new_empty(Metrics::new(font_size, font_size));
+    buffer.set_text(font_system, "x", attrs, Shaping::Advanced);
+    buffer.shape_until_scroll(font_system, false);
+    let glyph = buffer
+        .layout_runs()
+        .find_map(|run| run.glyphs.first().cloned())
+        .ok_or(MeshTextError::NoMatchingFont)?;
+    let font = font_system
+        .get_font(glyph.font_id)
+        .ok_or(MeshTextError::NoMatchingFont)?;
+    let face = Face::parse(font.data(), 0).map_err(|_| MeshTextError::NoMatchingFont)?;
+    Ok(FontMetricsInfo::from_face(&face, glyph.font_size))
+}
@@ -0,0 +1,214 @@
+//! Subsetting TrueType fonts to the glyphs a game shows, to embed smaller fonts.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use cosmic_text::fontdb::{Database, Source};
+use cosmic_text::ttf_parser::Face;
+use cosmic_text::{Attrs, Buffer, FontSystem, Metrics, Shaping};
+
+use crate::MeshTextError;
+
+/// Tables of per-glyph device metrics and signatures that no longer match a subset font.
+/// They are optional, so they are dropped.
+const DROPPED_TABLES: [&[u8; 4]; 4] = [b"DSIG", b"hdmx", b"LTSH", b"VDMX"];
+
+// Flags of composite glyph components
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+fn read_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Sum of the big-endian words of `data`, zero padded to a whole word.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// The tables of a font file, by tag.
+fn tables(font: &[u8]) -> Option<Vec<([u8; 4], &[u8])>> {
+    let count = read_u16(font, 4)? as usize;
+    (0..count)
+        .map(|i| {
+            let record = 12 + 16 * i;
+            let tag = font.get(record..record + 4)?.try_into().ok()?;
+            let offset = read_u32(font, record + 8)? as usize;
+            let length = read_u32(font, record + 12)? as usize;
+            Some((tag, font.get(offset..offset.checked_add(length)?)?))
+        })
+        .collect()
+}
+
+/// The glyphs shown for `texts`: those their characters map to, and those shaping picks,
+/// e.g. ligatures and contextual forms.
+fn used_glyphs(font: &[u8], face: &Face, texts: &[&str]) -> BTreeSet<u16> {
+    let mut glyphs = BTreeSet::from([0]);
+    glyphs.extend(
+        texts
+            .iter()
+            .flat_map(|text| text.chars())
+            .filter_map(|c| face.glyph_index(c))
+            .map(|id| id.0),
+    );
+
+    let mut db = Database::new();
+    db.load_font_source(Source::Binary(Arc::new(font.to_vec())));
+    let mut font_system = FontSystem::new_with_locale_and_db(String::from("en-US"), db);
+    let mut buffer = Buffer::new(&mut font_system, Metrics::new(16.0, 16.0));
+    for text in texts {
+        buffer.set_text(&mut font_system, text, &Attrs::new(), Shaping::Advanced);
+        buffer.shape_until_scroll(&mut font_system, false);
+        glyphs.extend(
+            buffer
+                .layout_runs()
+                .flat_map(|run| run.glyphs.iter().map(|glyph| glyph.glyph_id)),
+        );
+    }
+    glyphs
+}
+
+/// The glyphs a composite glyph is built from, `None` if it is malformed.
+fn components(glyph: &[u8]) -> Option<Vec<u16>> {
+    let mut components = Vec::new();
+    let mut at = 10;
+    loop {
+        let flags = read_u16(glyph, at)?;
+        components.push(read_u16(glyph, at + 2)?);
+        at += 4 + if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        at += if flags & WE_HAVE_A_SCALE != 0 {
+            2
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(components);
+        }
+    }
+}
+
+/// Subsets a TrueType font to the glyphs needed to show `texts`, e.g. at build time, and
+/// returns the font file to embed instead.
+///
+/// The outlines of all other glyphs are removed. Glyph ids, character maps and shaping tables
+/// stay as they are, so the subset shapes the texts exactly like the original font and text
+/// laid out with it can use the same glyph meshes. Text using other characters shows nothing
+/// for them. Only fonts with TrueType outlines can be subset, not CFF-based OpenType fonts.
+pub fn subset_font(font: &[u8], texts: &[&str]) -> Result<Vec<u8>, MeshTextError> {
+    let face = Face::parse(font, 0).map_err(|_| MeshTextError::SubsetFailed("unreadable font"))?;
+    let tables = tables(font).ok_or(MeshTextError::SubsetFailed("malformed table directory"))?;
+    let table = |tag: &[u8; 4]| tables.iter().find(|(t, _)| t == tag).map(|(_, data)| *data);
+    let (Some(head), Some(loca), Some(glyf)) = (table(b"head"), table(b"loca"), table(b"glyf"))
+    else {
+        return Err(MeshTextError::SubsetFailed("no TrueType outlines"));
+    };
+    let long_offsets = read_u16(head, 50) == Some(1);
+    let glyph_count = face.number_of_glyphs() as usize;
+    let glyph = |id: usize| -> Option<&[u8]> {
+        let (start, end) = if long_offsets {
+            (read_u32(loca, 4 * id)?, read_u32(loca, 4 * id + 4)?)
+        } else {
+            (
+                2 * read_u16(loca, 2 * id)? as u32,
+                2 * read_u16(loca, 2 * id + 2)? as u32,
+            )
+        };
+        glyf.get(start as usize..end as usize)
+    };
+
+    // Keep the components of kept composite glyphs too
+    let mut keep = used_glyphs(font, &face, texts);
+    let mut pending: Vec<u16> = keep.iter().copied().collect();
+    while let Some(id) = pending.pop() {
+        let data = glyph(id as usize).ok_or(MeshTextError::SubsetFailed("malformed glyph"))?;
+        if data.len() >= 10 && (read_u16(data, 0).unwrap_or_default() as i16) < 0 {
+            let parts = components(data).ok_or(MeshTextError::SubsetFailed("malformed glyph"))?;
+            pending.extend(parts.into_iter().filter(|&part| keep.insert(part)));
+        }
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity(4 * (glyph_count + 1));
+    for id in 0..glyph_count {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if keep.contains(&(id as u16)) {
+            new_glyf.extend_from_slice(glyph(id).unwrap_or_default());
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+    let mut new_head = head.to_vec();
+    // Long loca offsets, and the checksum adjustment computed below
+    new_head[50..52].copy_from_slice(&1u16.to_be_bytes());
+    new_head[8..12].fill(0);
+
+    let mut out_tables: Vec<([u8; 4], Vec<u8>)> = tables
+        .iter()
+        .filter(|(tag, _)| !DROPPED_TABLES.contains(&tag))
+        .map(|(tag, data)| {
+            let data = match tag {
+                b"glyf" => std::mem::take(&mut new_glyf),
+                b"loca" => std::mem::take(&mut new_loca),
+                b"head" => std::mem::take(&mut new_head),
+                _ => data.to_vec(),
+            };
+            (*tag, data)
+        })
+        .collect();
+    out_tables.sort_by_key(|(tag, _)| *tag);
+
+    // Offset table, table records, then the tables on 4-byte boundaries
+    let count = out_tables.len() as u16;
+    let entry_selector = count.max(1).ilog2() as u16;
+    let search_range = 16 * (1u16 << entry_selector);
+    let mut out = Vec::new();
+    out.extend_from_slice(&font[0..4]);
+    for value in [
+        count,
+        search_range,
+        entry_selector,
+        16 * count - search_range,
+    ] {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    let mut offset = 12 + 16 * out_tables.len();
+    let mut head_offset = None;
+    for (tag, data) in &out_tables {
+        out.extend_from_slice(tag);
+        out.extend_from_slice(&checksum(data).to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        if tag == b"head" {
+            head_offset = Some(offset);
+        }
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in &out_tables {
+        out.extend_from_slice(data);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+    if let Some(head_offset) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&out));
+        out[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    Ok(out)
+}
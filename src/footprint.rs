@@ -0,0 +1,133 @@
+//! Flat convex footprints of generated text, e.g. as navigation obstacles or shadow occluders.
+
+use bevy::prelude::*;
+use cosmic_text::ttf_parser::Face;
+
+use crate::contour::contours_from_path;
+use crate::extrude_glyph::glyph_path;
+use crate::mesh_text_plugin::layout_text;
+use crate::{InputText, MeshTextError, MissingGlyphPolicy, Parameters, Settings};
+
+/// Which glyphs share a footprint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FootprintGrouping {
+    /// One footprint per glyph with an outline
+    #[default]
+    Glyph,
+    /// One footprint per word, i.e. per run of glyphs between whitespace or line breaks
+    Word,
+}
+
+/// The convex footprints of `text`, laid out like [`crate::generate_meshes`] does with the
+/// same parameters. Each footprint is a counter-clockwise convex polygon around the outlines of
+/// a glyph or a word, in the plane of the text and in the space of the glyph transforms.
+///
+/// Footprints cover the outlines only, not a `front_rim` around them.
+pub fn text_footprints<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    grouping: FootprintGrouping,
+) -> Result<Vec<Vec<Vec2>>, MeshTextError> {
+    let scale = params.world_scale(fonts.text_scale_factor).truncate();
+    let (tx, _) = layout_text(text, &mut fonts.font_system, &mut params)?;
+
+    let mut footprints = Vec::new();
+    for run in tx.layout_runs() {
+        let mut word = Vec::new();
+        for glyph in run.glyphs {
+            if run.text[glyph.start..glyph.end].trim().is_empty() {
+                footprints.extend(hull_of(std::mem::take(&mut word)));
+                continue;
+            }
+            if glyph.glyph_id == 0 && params.missing_glyph_policy == MissingGlyphPolicy::Skip {
+                continue;
+            }
+            let Some(font) = fonts.font_system.get_font(glyph.font_id) else {
+                continue;
+            };
+            let Ok(face) = Face::parse(font.data(), 0) else {
+                continue;
+            };
+            let Ok(path) = glyph_path(
+                &face,
+                glyph.glyph_id,
+                &params.tessellation,
+                params.synthetic_style,
+            ) else {
+                continue;
+            };
+            // Same Y-down to Y-up conversion as the glyph placement in `generate_meshes`
+            let origin = Vec2::new(
+                glyph.x + glyph.x_offset,
+                -(run.line_y + glyph.y + glyph.y_offset),
+            );
+            let units = glyph.font_size / face.units_per_em() as f32;
+            let points = contours_from_path(&path)
+                .into_iter()
+                .flat_map(|contour| contour.points)
+                .map(|p| (origin + p * units) * scale);
+            word.extend(points);
+            if grouping == FootprintGrouping::Glyph {
+                footprints.extend(hull_of(std::mem::take(&mut word)));
+            }
+        }
+        footprints.extend(hull_of(word));
+    }
+    Ok(footprints)
+}
+
+fn hull_of(points: Vec<Vec2>) -> Option<Vec<Vec2>> {
+    let hull = convex_hull(points);
+    (hull.len() >= 3).then_some(hull)
+}
+
+/// The convex hull of `points`, counter-clockwise and without collinear points.
+pub fn convex_hull(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    // Andrew's monotone chain: the lower hull left to right, then the upper hull back
+    let mut lower = half_hull(points.iter().copied());
+    let mut upper = half_hull(points.iter().rev().copied());
+    // Each half ends where the other starts
+    lower.pop();
+    upper.pop();
+    lower.extend(upper);
+    lower
+}
+
+/// The points of a hull turning left only, from the first of `points` to the last.
+fn half_hull(points: impl Iterator<Item = Vec2>) -> Vec<Vec2> {
+    let mut hull: Vec<Vec2> = Vec::new();
+    for p in points {
+        while let [.., a, b] = hull[..]
+            && (b - a).perp_dot(p - a) <= 0.0
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    hull
+}
+
+/// A footprint of generated text, as spawned by [`spawn_footprints`]. Navigation and lighting
+/// plugins can turn it into an obstacle or an occluder of their own.
+#[derive(Component, Debug, Clone, PartialEq)]
+#[require(Transform)]
+pub struct TextFootprint {
+    /// Counter-clockwise convex polygon in the plane of the text
+    pub polygon: Vec<Vec2>,
+}
+
+/// Spawns every footprint as a [`TextFootprint`] child of `text`, the entity the glyphs are
+/// children of, so the footprints follow the text.
+pub fn spawn_footprints(commands: &mut Commands, text: Entity, footprints: Vec<Vec<Vec2>>) {
+    commands.entity(text).with_children(|parent| {
+        for polygon in footprints {
+            parent.spawn(TextFootprint { polygon });
+        }
+    });
+}
@@ -0,0 +1,424 @@
+use bevy::prelude::*;
+use lyon::geom::point;
+
+use crate::extrude_glyph::{ExtrudedGlyphGeometry, OutputTopology, add_side_quad};
+use crate::mesh_text_plugin::{layout_text, staggered_glyph_entry};
+use crate::text_glyphs::tessellate_layout_glyph;
+use crate::{InputText, MeshGlyph, MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// How glyphs are split by [`fracture_glyph`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FractureOptions {
+    /// Number of Voronoi sites scattered over each glyph. Sites that miss the glyph are
+    /// dropped, and cells that cover a concave part of the glyph break into several pieces,
+    /// so the number of pieces can differ.
+    pub pieces: usize,
+    /// Seed for the site placement. The same seed always produces the same pieces.
+    pub seed: u64,
+}
+
+impl Default for FractureOptions {
+    fn default() -> Self {
+        Self { pieces: 8, seed: 0 }
+    }
+}
+
+/// A piece of a fractured glyph.
+#[derive(Debug, Clone)]
+pub struct GlyphFragment {
+    /// The piece, centered on its own centroid
+    pub geometry: ExtrudedGlyphGeometry,
+    /// Position of the piece's centroid in the unfractured glyph mesh
+    pub offset: Vec2,
+}
+
+/// Splits an extruded glyph into the cells of a Voronoi diagram over its front face.
+///
+/// `geometry` has to be a plain extrusion of `extrusion_depth`, without a front inset. The
+/// glyph is clipped to every cell and the clipped part is split into convex pieces, so a cell
+/// over a bowl or a counter, e.g. of "O" or "B", gives several pieces. A convex piece can only
+/// follow one segment of a counter's flattened curve, so glyphs with counters break into many
+/// thin pieces there. Every piece is extruded with walls along the cuts, and the pieces fit
+/// back together into the original glyph.
+pub fn fracture_glyph(
+    geometry: &ExtrudedGlyphGeometry,
+    extrusion_depth: f32,
+    options: &FractureOptions,
+) -> Vec<GlyphFragment> {
+    // The front face is every triangle facing -Z on the z=0 plane
+    let triangles: Vec<[(Vec2, Vec2); 3]> = geometry
+        .indices
+        .chunks_exact(3)
+        .filter(|tri| {
+            tri.iter().all(|&i| {
+                geometry.normals[i as usize] == Vec3::NEG_Z
+                    && geometry.vertices[i as usize].z == 0.0
+            })
+        })
+        .map(|tri| {
+            [0, 1, 2].map(|k| {
+                let i = tri[k] as usize;
+                (geometry.vertices[i].truncate(), geometry.uvs[i])
+            })
+        })
+        .collect();
+    let Some((min, max)) =
+        triangles
+            .iter()
+            .flatten()
+            .fold(None, |bounds: Option<(Vec2, Vec2)>, (p, _)| {
+                let (min, max) = bounds.unwrap_or((*p, *p));
+                Some((min.min(*p), max.max(*p)))
+            })
+    else {
+        return Vec::new();
+    };
+
+    let sites = scatter_sites(&triangles, min, max, options);
+    let bounds = vec![
+        Vec2::new(min.x, min.y),
+        Vec2::new(max.x, min.y),
+        Vec2::new(max.x, max.y),
+        Vec2::new(min.x, max.y),
+    ];
+
+    let mut fragments = Vec::new();
+    for (i, &site) in sites.iter().enumerate() {
+        // The Voronoi cell is the bounds clipped by the bisector with every other site
+        let cell = sites
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .fold(bounds.clone(), |cell, (_, &other)| {
+                clip(&cell, (site + other) / 2.0, other - site)
+            });
+
+        let clipped: Vec<Vec<(Vec2, Vec2)>> = triangles
+            .iter()
+            .filter_map(|triangle| {
+                let clipped = cell
+                    .windows(2)
+                    .chain([[cell[cell.len() - 1], cell[0]].as_slice()])
+                    .fold(triangle.map(|(p, _)| p).to_vec(), |polygon, edge| {
+                        let direction = edge[1] - edge[0];
+                        // The cell is counter-clockwise, so its inside is left of every edge
+                        clip(&polygon, edge[0], -direction.perp())
+                    });
+                let piece: Vec<(Vec2, Vec2)> = clipped
+                    .into_iter()
+                    .map(|p| (p, interpolate_uv(triangle, p)))
+                    .collect();
+                simplify(piece)
+            })
+            .collect();
+        fragments.extend(
+            merge_convex(clipped)
+                .iter()
+                .filter_map(|piece| extrude_piece(piece, extrusion_depth, geometry.skinned)),
+        );
+    }
+    fragments
+}
+
+/// Generates `text` like [`crate::generate_meshes`], but with every glyph fractured into
+/// pieces. Returns the pieces of each glyph, placed so they form the unbroken text.
+///
+/// The pieces are not cached, and the front inset, rounded back, inflation, surface noise, edge
+/// mask and vertex attributes are ignored. Pieces are always triangles, whatever the
+/// [`topology`](Parameters::topology).
+pub fn generate_fractured_meshes<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    fracture: FractureOptions,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<Vec<MeshTextEntry<M>>>, MeshTextError> {
+    let scale = params.world_scale(fonts.text_scale_factor);
+    let font_system = &mut fonts.font_system;
+    let (tx, materials) = layout_text(text, font_system, &mut params)?;
+    let front_z = params.front_z(scale);
+
+    let options = crate::extrude_glyph::ExtrusionOptions {
+        front_inset: None,
+        front_rim: None,
+        rounded_back: None,
+        inflate: None,
+        surface_noise: None,
+        edge_mask: false,
+        depth_attribute: false,
+        surface_attribute: false,
+        lightmap_uvs: false,
+        // Pieces are cut from the triangles of the glyph
+        topology: OutputTopology::Triangles,
+        ..params.extrusion_options()
+    };
+
+    let mut glyphs = Vec::new();
+    for (source, glyph, line_y) in tx.layout_glyphs() {
+        // Like `generate_meshes`, a glyph that fails to tessellate is skipped
+        let tessellated =
+            tessellate_layout_glyph(font_system, glyph, params.missing_glyph_policy, &options);
+        let (geometry, center_x_layout, center_y_layout) = match tessellated {
+            Ok(Some(tessellated)) => tessellated,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("Failed to tessellate glyph {}: {e}", glyph.glyph_id);
+                continue;
+            }
+        };
+
+        let material = materials
+            .get(glyph.metadata)
+            .unwrap_or_else(|| &materials[0])
+            .clone();
+        let center = Vec2::new(center_x_layout, center_y_layout);
+
+        glyphs.push(
+            fracture_glyph(&geometry, options.extrusion_depth, &fracture)
+                .into_iter()
+                .map(|fragment| {
+                    let piece = MeshGlyph::at_layout_size(
+                        glyph,
+                        line_y,
+                        center + fragment.offset,
+                        meshes.add(fragment.geometry),
+                        material.clone(),
+                    );
+                    staggered_glyph_entry(&source, piece, scale, front_z, &params)
+                })
+                .collect(),
+        );
+    }
+
+    Ok(glyphs)
+}
+
+/// Scatters up to `options.pieces` sites over the front face triangles.
+fn scatter_sites(
+    triangles: &[[(Vec2, Vec2); 3]],
+    min: Vec2,
+    max: Vec2,
+    options: &FractureOptions,
+) -> Vec<Vec2> {
+    // xorshift64*, seeded with a nonzero state
+    let mut state = options.seed ^ 0x9e37_79b9_7f4a_7c15;
+    let mut next = move || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        (state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 40) as f32 / (1u64 << 24) as f32
+    };
+
+    let mut sites = Vec::new();
+    // Sample the bounds and keep the sites that land on the glyph
+    for _ in 0..options.pieces * 16 {
+        if sites.len() == options.pieces {
+            break;
+        }
+        let site = min + (max - min) * Vec2::new(next(), next());
+        if triangles.iter().any(|triangle| contains(triangle, site)) {
+            sites.push(site);
+        }
+    }
+    sites
+}
+
+fn contains(triangle: &[(Vec2, Vec2); 3], p: Vec2) -> bool {
+    let [a, b, c] = triangle.map(|(p, _)| p);
+    let d1 = (b - a).perp_dot(p - a);
+    let d2 = (c - b).perp_dot(p - b);
+    let d3 = (a - c).perp_dot(p - c);
+    !((d1 < 0.0 || d2 < 0.0 || d3 < 0.0) && (d1 > 0.0 || d2 > 0.0 || d3 > 0.0))
+}
+
+fn interpolate_uv(triangle: &[(Vec2, Vec2); 3], p: Vec2) -> Vec2 {
+    let [(a, uv_a), (b, uv_b), (c, uv_c)] = *triangle;
+    let area = (b - a).perp_dot(c - a);
+    if area == 0.0 {
+        return uv_a;
+    }
+    let wb = (p - a).perp_dot(c - a) / -area;
+    let wc = (b - a).perp_dot(p - a) / area;
+    uv_a * (1.0 - wb - wc) + uv_b * wb + uv_c * wc
+}
+
+/// Sutherland-Hodgman clip of `polygon` against the half-plane of points `p` with
+/// `(p - origin).dot(normal) <= 0`.
+fn clip(polygon: &[Vec2], origin: Vec2, normal: Vec2) -> Vec<Vec2> {
+    let distance = |p: Vec2| (p - origin).dot(normal);
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (i, &p) in polygon.iter().enumerate() {
+        let q = polygon[(i + 1) % polygon.len()];
+        let (dp, dq) = (distance(p), distance(q));
+        if dp <= 0.0 {
+            clipped.push(p);
+        }
+        if (dp < 0.0 && dq > 0.0) || (dp > 0.0 && dq < 0.0) {
+            clipped.push(p + (q - p) * (dp / (dp - dq)));
+        }
+    }
+    clipped
+}
+
+/// Quantized position, to match the ends of edges shared by two pieces
+fn key(p: Vec2) -> (i64, i64) {
+    ((p.x * 1024.0).round() as i64, (p.y * 1024.0).round() as i64)
+}
+
+/// Turns of less than this (the sine of the angle) count as straight.
+const STRAIGHT: f32 = 1e-4;
+
+/// Makes a clipped convex `piece` counter-clockwise and drops its repeated and collinear
+/// points. Returns `None` when nothing with an area is left.
+fn simplify(mut piece: Vec<(Vec2, Vec2)>) -> Option<Vec<(Vec2, Vec2)>> {
+    piece.dedup_by(|a, b| key(a.0) == key(b.0));
+    while piece.len() > 1 && key(piece[0].0) == key(piece[piece.len() - 1].0) {
+        piece.pop();
+    }
+    let mut i = 0;
+    while piece.len() >= 3 && i < piece.len() {
+        let n = piece.len();
+        let (a, b, c) = (piece[(i + n - 1) % n].0, piece[i].0, piece[(i + 1) % n].0);
+        let (ab, bc) = (b - a, c - b);
+        if ab.perp_dot(bc).abs() <= STRAIGHT * ab.length() * bc.length() && ab.dot(bc) > 0.0 {
+            piece.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    if piece.len() < 3 {
+        return None;
+    }
+    if signed_area(&piece) < 0.0 {
+        piece.reverse();
+    }
+    (signed_area(&piece) > 0.0).then_some(piece)
+}
+
+fn is_convex(piece: &[(Vec2, Vec2)]) -> bool {
+    let n = piece.len();
+    (0..n).all(|i| {
+        let (a, b, c) = (piece[i].0, piece[(i + 1) % n].0, piece[(i + 2) % n].0);
+        let (ab, bc) = (b - a, c - b);
+        ab.perp_dot(bc) >= -STRAIGHT * ab.length() * bc.length()
+    })
+}
+
+/// Merges counter-clockwise convex `pieces` across the edges they share for as long as the
+/// merged pieces stay convex, like Hertel-Mehlhorn. Pieces that only touch at a point, or not
+/// at all, are never merged, so every merged piece is connected.
+fn merge_convex(mut pieces: Vec<Vec<(Vec2, Vec2)>>) -> Vec<Vec<(Vec2, Vec2)>> {
+    'merge: loop {
+        for i in 0..pieces.len() {
+            for j in i + 1..pieces.len() {
+                let (a, b) = (&pieces[i], &pieces[j]);
+                for ia in 0..a.len() {
+                    let (from, to) = (key(a[ia].0), key(a[(ia + 1) % a.len()].0));
+                    let Some(ib) = (0..b.len())
+                        .find(|&ib| key(b[ib].0) == to && key(b[(ib + 1) % b.len()].0) == from)
+                    else {
+                        continue;
+                    };
+                    // All of `a` from the end of the shared edge back to its start, then the
+                    // rest of `b`
+                    let merged: Vec<(Vec2, Vec2)> = (0..a.len())
+                        .map(|k| a[(ia + 1 + k) % a.len()])
+                        .chain((0..b.len() - 2).map(|k| b[(ib + 2 + k) % b.len()]))
+                        .collect();
+                    if is_convex(&merged)
+                        && let Some(merged) = simplify(merged)
+                    {
+                        pieces[i] = merged;
+                        pieces.swap_remove(j);
+                        continue 'merge;
+                    }
+                }
+            }
+        }
+        return pieces;
+    }
+}
+
+/// Extrudes a counter-clockwise convex piece, with walls along all of its edges.
+fn extrude_piece(
+    piece: &[(Vec2, Vec2)],
+    extrusion_depth: f32,
+    skinned: bool,
+) -> Option<GlyphFragment> {
+    let area = signed_area(piece);
+    if area <= 0.0 {
+        return None;
+    }
+    let offset = (0..piece.len())
+        .map(|i| {
+            let (a, b) = (piece[i].0, piece[(i + 1) % piece.len()].0);
+            (a + b) * a.perp_dot(b)
+        })
+        .sum::<Vec2>()
+        / (6.0 * area);
+    let piece: Vec<(Vec2, Vec2)> = piece.iter().map(|(p, uv)| (*p - offset, *uv)).collect();
+
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+
+    for (z, normal) in [(0.0, Vec3::NEG_Z), (extrusion_depth, Vec3::Z)] {
+        let base = positions.len() as u16;
+        for (p, uv) in &piece {
+            positions.push(p.extend(z));
+            normals.push(normal);
+            uvs.push(*uv);
+        }
+        for k in 1..piece.len() as u16 - 1 {
+            // The front face is seen from -Z, so it winds clockwise
+            if normal == Vec3::NEG_Z {
+                indices.extend_from_slice(&[base, base + k + 1, base + k]);
+            } else {
+                indices.extend_from_slice(&[base, base + k, base + k + 1]);
+            }
+        }
+    }
+
+    for i in 0..piece.len() {
+        let (a, b) = (piece[i].0, piece[(i + 1) % piece.len()].0);
+        add_side_quad(
+            &mut positions,
+            &mut indices,
+            &mut normals,
+            &mut uvs,
+            point(a.x, a.y),
+            point(b.x, b.y),
+            1.0,
+            (0.0, extrusion_depth),
+            (0.0, a.distance(b)),
+            (0.0, 1.0),
+        );
+    }
+
+    Some(GlyphFragment {
+        geometry: ExtrudedGlyphGeometry {
+            vertices: positions,
+            indices,
+            normals,
+            uvs,
+            uv1: None,
+            edge_mask: None,
+            vertex_colors: None,
+            skinned,
+            depth_fraction: None,
+            surface: None,
+            topology: OutputTopology::Triangles,
+            caps: Vec::new(),
+            issues: Vec::new(),
+        },
+        offset,
+    })
+}
+
+fn signed_area(piece: &[(Vec2, Vec2)]) -> f32 {
+    (0..piece.len())
+        .map(|i| piece[i].0.perp_dot(piece[(i + 1) % piece.len()].0))
+        .sum::<f32>()
+        / 2.0
+}
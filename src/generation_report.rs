@@ -0,0 +1,70 @@
+//! What generating a text ran into, for applications to log or collect problems with real
+//! world fonts instead of reading the warnings of the crate.
+
+use crate::glyph_cache::GlyphCacheStats;
+
+/// Something meshing a glyph ran into, worked around or fatal to the glyph. Outline issues
+/// are only found when a glyph is tessellated, not when its mesh comes from the glyph cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlyphIssue {
+    /// The outline failed to fill with the fill rule of the options and was filled with the
+    /// other one, see [`crate::TessellationOptions::fallback_chain`]
+    FallbackFillRule,
+    /// This many open contours were closed or extruded as ribbons, see
+    /// [`crate::OpenContours`]
+    OpenContours(usize),
+    /// This many hairline contours were dropped or thickened, see [`crate::HairlineContours`]
+    HairlineContours(usize),
+    /// The lip of the front inset collapsed along this many contours, too thin for it, which
+    /// were left without one
+    CollapsedBevel(usize),
+    /// The glyph is missing from every font, and was skipped or replaced by a placeholder
+    /// following [`crate::MissingGlyphPolicy`]
+    MissingGlyph,
+    /// The glyph failed to tessellate, with this error, and was skipped
+    TessellationFailed(String),
+}
+
+/// An issue of one glyph of a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationWarning {
+    /// Index of the layout run the glyph is on
+    pub line: usize,
+    /// Index of the glyph among all laid out glyphs
+    pub index: usize,
+    /// First character of the glyph's cluster
+    pub character: char,
+    pub glyph_id: u16,
+    pub issue: GlyphIssue,
+}
+
+/// What generating a text ran into, from [`crate::generate_meshes_with_report`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenerationReport {
+    /// Issues of the glyphs, in layout order
+    pub warnings: Vec<GenerationWarning>,
+    /// Number of glyphs whose mesh came from the glyph cache
+    pub cache_hits: usize,
+    /// Number of glyphs tessellated into a mesh for this text. Glyphs without an outline,
+    /// like spaces, have no mesh and are not counted.
+    pub tessellated: usize,
+    /// Size of the glyph cache after generating
+    pub cache: GlyphCacheStats,
+}
+
+impl GenerationReport {
+    /// Whether no glyph ran into an issue.
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// What meshing one glyph ran into, filled in as it is meshed.
+#[derive(Debug, Default)]
+pub(crate) struct GlyphLog {
+    /// Whether the mesh came from the glyph cache
+    pub cached: bool,
+    /// Whether the glyph was tessellated into a mesh
+    pub tessellated: bool,
+    pub issues: Vec<GlyphIssue>,
+}
@@ -0,0 +1,293 @@
+use bevy::prelude::*;
+use cosmic_text::FontSystem;
+use cosmic_text::fontdb::ID;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::extrude_glyph::{ExtrusionOptions, ExtrusionOptionsKey};
+use crate::glyph_store::{BakedTextMesh, GlyphStore};
+
+/// Font size glyph meshes are tessellated at when they are shared across font sizes. See
+/// [`TessellationOptions::size_specific`](crate::TessellationOptions::size_specific).
+pub const CANONICAL_FONT_SIZE: f32 = 100.0;
+
+/// Identifies a tessellated glyph mesh. Geometry is baked at a given font size and with
+/// given extrusion options, so both are part of the key, the options bit for bit. Meshes
+/// shared across sizes are keyed by [`CANONICAL_FONT_SIZE`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GlyphCacheKey {
+    pub font_id: ID,
+    pub glyph_id: u16,
+    font_size_bits: u32,
+    options: ExtrusionOptionsKey,
+    placeholder: bool,
+}
+
+impl GlyphCacheKey {
+    pub fn new(font_id: ID, glyph_id: u16, font_size: f32, options: &ExtrusionOptions) -> Self {
+        Self {
+            font_id,
+            glyph_id,
+            font_size_bits: font_size.to_bits(),
+            options: options.cache_key(),
+            placeholder: false,
+        }
+    }
+
+    /// Key for the generated placeholder of glyphs missing from `font_id`.
+    pub fn placeholder(font_id: ID, font_size: f32, options: &ExtrusionOptions) -> Self {
+        Self {
+            placeholder: true,
+            ..Self::new(font_id, 0, font_size, options)
+        }
+    }
+
+    pub fn font_size(&self) -> f32 {
+        f32::from_bits(self.font_size_bits)
+    }
+
+    pub fn options(&self) -> ExtrusionOptionsKey {
+        self.options.clone()
+    }
+
+    /// Hash of the key with `font`, a fingerprint of the font data, in place of the font id,
+    /// which only holds for the running font database. Different keys may share a hash.
+    pub(crate) fn persistent_hash(&self, font: u64) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (font, self.glyph_id, self.font_size_bits).hash(&mut hasher);
+        (&self.options, self.placeholder).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// A cached glyph mesh together with its layout center offsets, at the font size of its
+/// [`GlyphCacheKey`]. Meshes shared across sizes scale them with the mesh.
+#[derive(Debug, Clone)]
+pub struct CachedGlyph {
+    pub mesh: Handle<Mesh>,
+    pub center_x_layout: f32,
+    pub center_y_layout: f32,
+    /// Estimated size of the vertex and index data backing `mesh`
+    pub byte_size: usize,
+    last_used: u64,
+}
+
+/// Glyph meshes shared across all `generate_meshes` calls.
+///
+/// The cache holds strong handles, so cached meshes stay alive until they are
+/// evicted with [`GlyphCache::trim_to`] or [`GlyphCache::clear`]. With a
+/// [`GlyphStore`](GlyphCache::set_store), meshes also outlive the app on disk.
+#[derive(Debug, Default)]
+pub struct GlyphCache {
+    entries: HashMap<GlyphCacheKey, CachedGlyph>,
+    total_bytes: usize,
+    tick: u64,
+    store: Option<GlyphStore>,
+}
+
+impl GlyphCache {
+    /// Looks up a glyph and marks it as recently used.
+    pub fn get(&mut self, key: &GlyphCacheKey) -> Option<&CachedGlyph> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|entry| {
+            entry.last_used = tick;
+            &*entry
+        })
+    }
+
+    pub fn insert(
+        &mut self,
+        key: GlyphCacheKey,
+        mesh: Handle<Mesh>,
+        center_x_layout: f32,
+        center_y_layout: f32,
+        byte_size: usize,
+    ) {
+        self.tick += 1;
+        let entry = CachedGlyph {
+            mesh,
+            center_x_layout,
+            center_y_layout,
+            byte_size,
+            last_used: self.tick,
+        };
+        self.total_bytes += byte_size;
+        if let Some(old) = self.entries.insert(key, entry) {
+            self.total_bytes -= old.byte_size;
+        }
+    }
+
+    /// Backs the cache with a disk cache: glyphs missing from memory are loaded from `store`
+    /// before they are tessellated, and newly tessellated glyphs are saved to it.
+    pub fn set_store(&mut self, store: Option<GlyphStore>) {
+        self.store = store;
+    }
+
+    pub fn store(&self) -> Option<&GlyphStore> {
+        self.store.as_ref()
+    }
+
+    /// Looks up a glyph like [`get`](Self::get), loading it from the store when it isn't
+    /// cached.
+    pub(crate) fn get_or_load(
+        &mut self,
+        font_system: &mut FontSystem,
+        key: &GlyphCacheKey,
+        meshes: &mut Assets<Mesh>,
+    ) -> Option<&CachedGlyph> {
+        if !self.entries.contains_key(key) {
+            let baked = self.load_stored(font_system, key)?;
+            self.insert_baked(font_system, key.clone(), baked, meshes);
+        }
+        self.get(key)
+    }
+
+    /// The stored mesh of a glyph, to [`insert_baked`](Self::insert_baked) later.
+    pub(crate) fn load_stored(
+        &mut self,
+        font_system: &mut FontSystem,
+        key: &GlyphCacheKey,
+    ) -> Option<BakedTextMesh> {
+        self.store.as_mut()?.load(font_system, key)
+    }
+
+    /// Adds the mesh of a glyph and saves it to the store. Returns the handle of the mesh.
+    pub(crate) fn insert_baked(
+        &mut self,
+        font_system: &mut FontSystem,
+        key: GlyphCacheKey,
+        baked: BakedTextMesh,
+        meshes: &mut Assets<Mesh>,
+    ) -> Handle<Mesh> {
+        if let Some(store) = &mut self.store
+            && let Err(e) = store.save(font_system, &key, &baked)
+        {
+            warn!("Failed to save glyph: {e}");
+        }
+        let byte_size = baked.geometry.estimated_byte_size();
+        let mesh = meshes.add(baked.geometry);
+        let (center_x, center_y) = (baked.center_x_layout, baked.center_y_layout);
+        self.insert(key, mesh.clone(), center_x, center_y, byte_size);
+        mesh
+    }
+
+    /// Number of cached glyph meshes.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Estimated memory used by the cached vertex and index data, in bytes.
+    pub fn estimated_bytes(&self) -> usize {
+        self.total_bytes
+    }
+
+    pub fn stats(&self) -> GlyphCacheStats {
+        GlyphCacheStats {
+            mesh_count: self.len(),
+            estimated_bytes: self.total_bytes,
+        }
+    }
+
+    /// Evicts the least recently used glyphs until the estimated size is at most `bytes`.
+    /// Returns the number of evicted glyphs.
+    pub fn trim_to(&mut self, bytes: usize) -> usize {
+        if self.total_bytes <= bytes {
+            return 0;
+        }
+
+        let mut by_age: Vec<(u64, GlyphCacheKey)> = self
+            .entries
+            .iter()
+            .map(|(key, entry)| (entry.last_used, key.clone()))
+            .collect();
+        by_age.sort_unstable_by_key(|(last_used, _)| *last_used);
+
+        let mut evicted = 0;
+        for (_, key) in by_age {
+            if self.total_bytes <= bytes {
+                break;
+            }
+            if let Some(entry) = self.entries.remove(&key) {
+                self.total_bytes -= entry.byte_size;
+                evicted += 1;
+            }
+        }
+        evicted
+    }
+
+    /// Reports how many unique meshes back the glyphs showing `meshes`, e.g. the `Mesh3d`
+    /// handles of every text entity in a scene.
+    pub fn sharing_report<'a>(
+        &self,
+        meshes: impl IntoIterator<Item = &'a Handle<Mesh>>,
+    ) -> MeshSharingReport {
+        let cached: HashSet<AssetId<Mesh>> =
+            self.entries.values().map(|entry| entry.mesh.id()).collect();
+        let mut unique = HashSet::new();
+        let mut glyphs = 0;
+        for mesh in meshes {
+            glyphs += 1;
+            unique.insert(mesh.id());
+        }
+        MeshSharingReport {
+            glyphs,
+            unique_meshes: unique.len(),
+            cached_meshes: unique.intersection(&cached).count(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.total_bytes = 0;
+    }
+}
+
+/// How many unique meshes back a set of glyphs, from [`GlyphCache::sharing_report`].
+///
+/// Materials are not part of the cache key, so glyphs with the same font and extrusion share a
+/// mesh whatever their material or color, and most extrusions share it across font sizes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MeshSharingReport {
+    /// Number of glyphs
+    pub glyphs: usize,
+    /// Number of distinct meshes used by the glyphs
+    pub unique_meshes: usize,
+    /// How many of the distinct meshes come from the glyph cache. The others were generated
+    /// without the cache, e.g. morph or fracture meshes.
+    pub cached_meshes: usize,
+}
+
+impl MeshSharingReport {
+    /// Average number of glyphs sharing each mesh.
+    pub fn glyphs_per_mesh(&self) -> f32 {
+        if self.unique_meshes == 0 {
+            0.0
+        } else {
+            self.glyphs as f32 / self.unique_meshes as f32
+        }
+    }
+}
+
+/// Snapshot of the glyph cache size, refreshed by the plugin every frame.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GlyphCacheStats {
+    /// Number of cached glyph meshes
+    pub mesh_count: usize,
+    /// Estimated size of the cached vertex and index data, in bytes
+    pub estimated_bytes: usize,
+}
+
+pub(crate) fn update_glyph_cache_stats(
+    settings: Res<crate::Settings>,
+    mut stats: ResMut<GlyphCacheStats>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    stats.set_if_neq(settings.glyph_cache.stats());
+}
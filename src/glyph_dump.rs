@@ -0,0 +1,151 @@
+//! Writing the geometry of a single glyph to a file, for bug reports and regression tests.
+
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use cosmic_text::{Attrs, FontSystem, Metrics};
+
+use crate::extrude_glyph::{ExtrudedGlyphGeometry, OutputTopology};
+use crate::text_glyphs::{TextGlyphs, tessellate_layout_glyph};
+use crate::{MeshTextError, Parameters};
+
+/// Generates the mesh of `character` with `params` and writes it to `path`, as Wavefront OBJ
+/// for a `.obj` file or ASCII PLY for a `.ply` file, e.g. to attach a broken glyph to a bug
+/// report or to compare it against a known good mesh.
+///
+/// The glyph is tessellated at [`Parameters::font_size`] without the glyph cache, in layout
+/// units with its origin at the layout center of the glyph. Files hold the positions, normals
+/// and UVs, and triangles, line segments or points as the topology asks for. Glyphs without
+/// an outline, like spaces, are written without vertices.
+pub fn debug_dump_glyph(
+    character: char,
+    attrs: &Attrs,
+    font_system: &mut FontSystem,
+    params: &Parameters,
+    path: impl AsRef<Path>,
+) -> Result<(), MeshTextError> {
+    let path = path.as_ref();
+    let ply = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("obj") => false,
+        Some(extension) if extension.eq_ignore_ascii_case("ply") => true,
+        _ => return Err(MeshTextError::InvalidInput),
+    };
+    params.validate()?;
+    if character.is_control() {
+        return Err(MeshTextError::InvalidInput);
+    }
+
+    let metrics = Metrics {
+        font_size: params.font_size,
+        line_height: params.line_height,
+    };
+    let text = character.to_string();
+    let mut tx = TextGlyphs::new(
+        metrics,
+        [(text.as_str(), attrs.clone())],
+        attrs,
+        font_system,
+        None,
+    );
+    tx.measure(None, None, font_system);
+    let glyph = tx
+        .layout_runs()
+        .find_map(|run| run.glyphs.first().cloned())
+        .ok_or(MeshTextError::InvalidInput)?;
+    let options = params.extrusion_options();
+    let geometry =
+        tessellate_layout_glyph(font_system, &glyph, params.missing_glyph_policy, &options)?
+            .map(|(geometry, _, _)| geometry);
+
+    let mut out = BufWriter::new(std::fs::File::create(path)?);
+    let empty = ExtrudedGlyphGeometry {
+        topology: options.topology,
+        ..ExtrudedGlyphGeometry::default()
+    };
+    let geometry = geometry.as_ref().unwrap_or(&empty);
+    if ply {
+        write_ply(geometry, &mut out)?;
+    } else {
+        write_obj(geometry, &mut out)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Writes `geometry` as Wavefront OBJ.
+pub fn write_obj(geometry: &ExtrudedGlyphGeometry, out: &mut impl Write) -> std::io::Result<()> {
+    for vertex in &geometry.vertices {
+        writeln!(out, "v {} {} {}", vertex.x, vertex.y, vertex.z)?;
+    }
+    for normal in &geometry.normals {
+        writeln!(out, "vn {} {} {}", normal.x, normal.y, normal.z)?;
+    }
+    for uv in &geometry.uvs {
+        writeln!(out, "vt {} {}", uv.x, uv.y)?;
+    }
+    // OBJ indices start at 1
+    let corner = |index: u16| {
+        let index = index as usize + 1;
+        format!("{index}/{index}/{index}")
+    };
+    match geometry.topology {
+        OutputTopology::Triangles => {
+            for triangle in geometry.indices.chunks_exact(3) {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(corner);
+                writeln!(out, "f {a} {b} {c}")?;
+            }
+        }
+        OutputTopology::Lines => {
+            for segment in geometry.indices.chunks_exact(2) {
+                writeln!(out, "l {} {}", segment[0] + 1, segment[1] + 1)?;
+            }
+        }
+        OutputTopology::Points => {
+            for point in &geometry.indices {
+                writeln!(out, "p {}", point + 1)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes `geometry` as ASCII PLY. Line segments are written as edges, and points as bare
+/// vertices.
+pub fn write_ply(geometry: &ExtrudedGlyphGeometry, out: &mut impl Write) -> std::io::Result<()> {
+    let (faces, edges) = match geometry.topology {
+        OutputTopology::Triangles => (geometry.indices.len() / 3, 0),
+        OutputTopology::Lines => (0, geometry.indices.len() / 2),
+        OutputTopology::Points => (0, 0),
+    };
+    writeln!(out, "ply")?;
+    writeln!(out, "format ascii 1.0")?;
+    writeln!(out, "element vertex {}", geometry.vertices.len())?;
+    for property in ["x", "y", "z", "nx", "ny", "nz", "s", "t"] {
+        writeln!(out, "property float {property}")?;
+    }
+    writeln!(out, "element face {faces}")?;
+    writeln!(out, "property list uchar uint vertex_indices")?;
+    writeln!(out, "element edge {edges}")?;
+    writeln!(out, "property uint vertex1")?;
+    writeln!(out, "property uint vertex2")?;
+    writeln!(out, "end_header")?;
+    for ((vertex, normal), uv) in geometry
+        .vertices
+        .iter()
+        .zip(&geometry.normals)
+        .zip(&geometry.uvs)
+    {
+        writeln!(
+            out,
+            "{} {} {} {} {} {} {} {}",
+            vertex.x, vertex.y, vertex.z, normal.x, normal.y, normal.z, uv.x, uv.y
+        )?;
+    }
+    for triangle in geometry.indices.chunks_exact(3).take(faces) {
+        writeln!(out, "3 {} {} {}", triangle[0], triangle[1], triangle[2])?;
+    }
+    for segment in geometry.indices.chunks_exact(2).take(edges) {
+        writeln!(out, "{} {}", segment[0], segment[1])?;
+    }
+    Ok(())
+}
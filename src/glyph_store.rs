@@ -0,0 +1,349 @@
+//! Glyph meshes baked to disk, so texts with many unique glyphs are only tessellated once.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hasher};
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use cosmic_text::FontSystem;
+use cosmic_text::fontdb::ID;
+
+use crate::MeshTextError;
+use crate::extrude_glyph::{ExtrudedGlyphGeometry, ExtrusionOptionsKey, OutputTopology};
+use crate::glyph_cache::GlyphCacheKey;
+
+/// Starts every baked mesh. The digit is bumped whenever the layout or the generated geometry
+/// changes, so stores written by older versions are regenerated.
+const MAGIC: &[u8; 4] = b"BTM3";
+
+/// The geometry of a glyph mesh with its layout center offsets, as the glyph cache holds it,
+/// in a form that can be written to disk.
+#[derive(Debug, Clone)]
+pub struct BakedTextMesh {
+    /// Options the mesh was extruded with, checked against the glyph loading it, as stores
+    /// name meshes by a hash that options may share
+    pub options: ExtrusionOptionsKey,
+    pub geometry: ExtrudedGlyphGeometry,
+    pub center_x_layout: f32,
+    pub center_y_layout: f32,
+}
+
+impl BakedTextMesh {
+    /// Serializes the mesh in a compact little-endian format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let geometry = &self.geometry;
+        let mut out = Vec::with_capacity(geometry.estimated_byte_size() + 64);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&self.options.to_bytes());
+        put_f32(&mut out, self.center_x_layout);
+        put_f32(&mut out, self.center_y_layout);
+        out.push(match geometry.topology {
+            OutputTopology::Triangles => 0,
+            OutputTopology::Lines => 1,
+            OutputTopology::Points => 2,
+        });
+        out.push(geometry.skinned as u8);
+        put_arrays(&mut out, geometry.vertices.iter().map(|v| v.to_array()));
+        put_arrays(&mut out, geometry.normals.iter().map(|v| v.to_array()));
+        put_arrays(&mut out, geometry.uvs.iter().map(|v| v.to_array()));
+        put_u32(&mut out, geometry.indices.len() as u32);
+        for index in &geometry.indices {
+            out.extend_from_slice(&index.to_le_bytes());
+        }
+        put_optional(&mut out, geometry.uv1.as_ref(), |v| v.to_array());
+        put_optional(&mut out, geometry.edge_mask.as_ref(), |&v| [v]);
+        put_optional(&mut out, geometry.vertex_colors.as_ref(), |&v| v);
+        put_optional(&mut out, geometry.depth_fraction.as_ref(), |&v| [v]);
+        put_optional(&mut out, geometry.surface.as_ref(), |&v| [v]);
+        out
+    }
+
+    /// Reads a mesh written by [`to_bytes`](Self::to_bytes), `None` for truncated or corrupt
+    /// data and meshes baked by another version of the crate.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        if reader.take(MAGIC.len())? != MAGIC {
+            return None;
+        }
+        let (options, rest) = ExtrusionOptionsKey::from_bytes(reader.0)?;
+        reader.0 = rest;
+        let center_x_layout = reader.f32()?;
+        let center_y_layout = reader.f32()?;
+        let topology = match reader.u8()? {
+            0 => OutputTopology::Triangles,
+            1 => OutputTopology::Lines,
+            2 => OutputTopology::Points,
+            _ => return None,
+        };
+        let skinned = match reader.u8()? {
+            0 => false,
+            1 => true,
+            _ => return None,
+        };
+        let vertices: Vec<Vec3> = reader.arrays()?.into_iter().map(Vec3::from).collect();
+        let normals = reader.arrays()?.into_iter().map(Vec3::from).collect();
+        let uvs = reader.arrays()?.into_iter().map(Vec2::from).collect();
+        let index_count = reader.u32()? as usize;
+        let indices: Vec<u16> = reader
+            .take(index_count.checked_mul(2)?)?
+            .chunks_exact(2)
+            .map(|index| u16::from_le_bytes([index[0], index[1]]))
+            .collect();
+        let geometry = ExtrudedGlyphGeometry {
+            vertices,
+            indices,
+            normals,
+            uvs,
+            uv1: reader.optional(Vec2::from)?,
+            edge_mask: reader.optional(|[v]| v)?,
+            vertex_colors: reader.optional(|v| v)?,
+            skinned,
+            depth_fraction: reader.optional(|[v]| v)?,
+            surface: reader.optional(|[v]| v)?,
+            topology,
+            caps: Vec::new(),
+            issues: Vec::new(),
+        };
+        if !reader.0.is_empty() || !is_consistent(&geometry) {
+            return None;
+        }
+        Some(Self {
+            options,
+            geometry,
+            center_x_layout,
+            center_y_layout,
+        })
+    }
+}
+
+/// Whether every vertex attribute covers every vertex and every index names one, so the
+/// geometry converts to a valid mesh.
+fn is_consistent(geometry: &ExtrudedGlyphGeometry) -> bool {
+    let count = geometry.vertices.len();
+    let covers = |len: Option<usize>| len.is_none_or(|len| len == count);
+    geometry.normals.len() == count
+        && geometry.uvs.len() == count
+        && covers(geometry.uv1.as_ref().map(Vec::len))
+        && covers(geometry.edge_mask.as_ref().map(Vec::len))
+        && covers(geometry.vertex_colors.as_ref().map(Vec::len))
+        && covers(geometry.depth_fraction.as_ref().map(Vec::len))
+        && covers(geometry.surface.as_ref().map(Vec::len))
+        && geometry
+            .indices
+            .iter()
+            .all(|&index| (index as usize) < count)
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_f32(out: &mut Vec<u8>, value: f32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_arrays<const N: usize>(out: &mut Vec<u8>, items: impl ExactSizeIterator<Item = [f32; N]>) {
+    put_u32(out, items.len() as u32);
+    for item in items {
+        for value in item {
+            put_f32(out, value);
+        }
+    }
+}
+
+fn put_optional<T, const N: usize>(
+    out: &mut Vec<u8>,
+    items: Option<&Vec<T>>,
+    to_array: impl Fn(&T) -> [f32; N],
+) {
+    out.push(items.is_some() as u8);
+    if let Some(items) = items {
+        put_arrays(out, items.iter().map(to_array));
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let (head, rest) = self.0.split_at_checked(len)?;
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        Some(f32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn arrays<const N: usize>(&mut self) -> Option<Vec<[f32; N]>> {
+        let count = self.u32()? as usize;
+        // Taken up front, so a corrupt count fails before allocating
+        let bytes = self.take(count.checked_mul(N * 4)?)?;
+        Some(
+            bytes
+                .chunks_exact(N * 4)
+                .map(|item| {
+                    std::array::from_fn(|i| {
+                        f32::from_le_bytes(item[i * 4..i * 4 + 4].try_into().unwrap())
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Reads arrays written by `put_optional`. The outer `Option` fails on corrupt data.
+    fn optional<T, const N: usize>(
+        &mut self,
+        from_array: impl Fn([f32; N]) -> T,
+    ) -> Option<Option<Vec<T>>> {
+        match self.u8()? {
+            0 => Some(None),
+            1 => Some(Some(self.arrays()?.into_iter().map(from_array).collect())),
+            _ => None,
+        }
+    }
+}
+
+/// A directory of [`BakedTextMesh`]es, one file per glyph mesh, that outlives the app.
+///
+/// Files are named after a hash of the font data, the glyph, its size and the extrusion
+/// options, so a store can be shared between fonts and options and never returns the mesh of
+/// a changed font. The hashes are not guaranteed to stay the same across Rust versions, in
+/// which case the glyphs are baked again.
+#[derive(Debug, Clone)]
+pub struct GlyphStore {
+    dir: PathBuf,
+    /// Fingerprint of the data of every font looked up so far
+    fonts: HashMap<ID, u64>,
+    /// Meshes read by [`open`](Self::open) that weren't loaded yet, by file hash
+    preloaded: HashMap<u64, BakedTextMesh>,
+    /// Hashes of the meshes known to be on disk
+    on_disk: HashSet<u64>,
+    /// Whether `on_disk` lists the whole directory, so other glyphs needn't be looked for
+    indexed: bool,
+}
+
+impl GlyphStore {
+    /// A store in `dir`, which is created with the first saved glyph. Glyphs are read from
+    /// disk as they are loaded.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            fonts: HashMap::new(),
+            preloaded: HashMap::new(),
+            on_disk: HashSet::new(),
+            indexed: false,
+        }
+    }
+
+    /// A store in `dir` with every mesh in it read up front, e.g. at startup, so loading a
+    /// glyph never touches the disk. Files that can't be read are baked again.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, MeshTextError> {
+        let mut store = Self::new(dir);
+        store.indexed = true;
+        let entries = match std::fs::read_dir(&store.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(store),
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_none_or(|extension| extension != "glyph")
+            {
+                continue;
+            }
+            let Some(hash) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| u64::from_str_radix(stem, 16).ok())
+            else {
+                continue;
+            };
+            let Some(baked) = std::fs::read(&path)
+                .ok()
+                .and_then(|bytes| BakedTextMesh::from_bytes(&bytes))
+            else {
+                continue;
+            };
+            store.preloaded.insert(hash, baked);
+            store.on_disk.insert(hash);
+        }
+        Ok(store)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The baked mesh of `key`, `None` when it wasn't saved, can't be read or was baked with
+    /// other options sharing its file.
+    pub fn load(
+        &mut self,
+        font_system: &mut FontSystem,
+        key: &GlyphCacheKey,
+    ) -> Option<BakedTextMesh> {
+        let hash = self.hash(font_system, key)?;
+        // Preloaded meshes are handed out once; the glyph cache holds them from then on
+        if let Some(baked) = self.preloaded.remove(&hash) {
+            return (baked.options == key.options()).then_some(baked);
+        }
+        if self.indexed && !self.on_disk.contains(&hash) {
+            return None;
+        }
+        let baked = BakedTextMesh::from_bytes(&std::fs::read(self.path(hash)).ok()?)?;
+        self.on_disk.insert(hash);
+        (baked.options == key.options()).then_some(baked)
+    }
+
+    /// Writes the baked mesh of `key`, unless the store already holds it.
+    pub fn save(
+        &mut self,
+        font_system: &mut FontSystem,
+        key: &GlyphCacheKey,
+        baked: &BakedTextMesh,
+    ) -> Result<(), MeshTextError> {
+        let Some(hash) = self.hash(font_system, key) else {
+            return Err(MeshTextError::InvalidInput);
+        };
+        if self.on_disk.contains(&hash) {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        // Written next to the file first, so readers never see half a mesh
+        let path = self.path(hash);
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, baked.to_bytes())?;
+        std::fs::rename(&partial, &path)?;
+        self.on_disk.insert(hash);
+        Ok(())
+    }
+
+    /// Hash naming the file of `key`, `None` when its font is not in `font_system`.
+    fn hash(&mut self, font_system: &mut FontSystem, key: &GlyphCacheKey) -> Option<u64> {
+        let font = match self.fonts.get(&key.font_id) {
+            Some(&font) => font,
+            None => {
+                let mut hasher = DefaultHasher::new();
+                hasher.write(font_system.get_font(key.font_id)?.data());
+                let font = hasher.finish();
+                self.fonts.insert(key.font_id, font);
+                font
+            }
+        };
+        Some(key.persistent_hash(font))
+    }
+
+    fn path(&self, hash: u64) -> PathBuf {
+        self.dir.join(format!("{hash:016x}.glyph"))
+    }
+}
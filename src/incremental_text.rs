@@ -0,0 +1,185 @@
+//! Text with many unique glyphs, e.g. CJK, tessellated a few glyphs per frame.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::bounds::insert_glyph_bounds;
+use crate::extrude_glyph::UvMode;
+use crate::glyph_store::BakedTextMesh;
+use crate::mesh_text_plugin::{generate_meshes_from_glyphs, layout_text};
+use crate::text_glyphs::{BakedGlyph, TextGlyphs, glyph_cache_key, tessellate_layout_glyph};
+use crate::{InputText, Parameters, Settings};
+
+/// How many new glyphs [`IncrementalText`]s may tessellate per frame.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct IncrementalGlyphs {
+    /// Most glyph meshes tessellated per frame over all incremental texts of a material type.
+    /// Glyphs from the glyph cache or its store don't count.
+    pub glyphs_per_frame: usize,
+}
+
+impl Default for IncrementalGlyphs {
+    fn default() -> Self {
+        Self {
+            glyphs_per_frame: 16,
+        }
+    }
+}
+
+enum IncrementalState<M: Asset> {
+    Text(InputText<'static, M>),
+    /// Laid out, with the glyphs still to bake, each once
+    LaidOut(TextGlyphs, Vec<Handle<M>>, Vec<BakedGlyph>),
+    /// Taken while the text is being laid out
+    Empty,
+}
+
+/// Text that fills the glyph cache over several frames before it's spawned, for texts with
+/// thousands of unique glyphs like CJK pages, whose tessellation would stall a frame.
+///
+/// The text is laid out right away. Every frame its glyphs missing from the glyph cache are
+/// loaded from the [`GlyphStore`](crate::GlyphStore) of the cache, or tessellated up to
+/// [`IncrementalGlyphs::glyphs_per_frame`] and saved to it. Once all glyphs are cached, the
+/// component is removed and the glyphs are spawned as its children.
+///
+/// Glyphs with [`UvMode::TextBlock`] UVs can't be cached, so such texts are generated at once.
+#[derive(Component)]
+#[require(Transform, Visibility)]
+pub struct IncrementalText<M: Asset = StandardMaterial> {
+    state: IncrementalState<M>,
+    params: Parameters,
+}
+
+impl<M: Asset> IncrementalText<M> {
+    pub fn new(text: InputText<'static, M>, params: Parameters) -> Self {
+        Self {
+            state: IncrementalState::Text(text),
+            params,
+        }
+    }
+
+    /// Number of glyphs still to load or tessellate, `None` before the text is laid out.
+    pub fn remaining(&self) -> Option<usize> {
+        match &self.state {
+            IncrementalState::LaidOut(_, _, pending) => Some(pending.len()),
+            _ => None,
+        }
+    }
+}
+
+/// The glyphs of `tx` to bake, once per cache key, in reverse so they pop in order.
+fn pending_glyphs(
+    tx: &TextGlyphs,
+    settings: &mut Settings,
+    params: &Parameters,
+) -> Vec<BakedGlyph> {
+    let options = params.extrusion_options();
+    if options.uv_mode == UvMode::TextBlock {
+        return Vec::new();
+    }
+    let mut seen = HashSet::new();
+    let mut pending: Vec<_> = tx
+        .layout_runs()
+        .flat_map(|run| run.glyphs.iter())
+        .map(|glyph| BakedGlyph::new(glyph, &options))
+        .filter(|baked| {
+            glyph_cache_key(&baked.glyph, params.missing_glyph_policy, &baked.options)
+                .is_some_and(|key| settings.glyph_cache.get(&key).is_none() && seen.insert(key))
+        })
+        .collect();
+    pending.reverse();
+    pending
+}
+
+/// Lays out new [`IncrementalText`]s, bakes their glyphs within the budget of
+/// [`IncrementalGlyphs`] and spawns the texts whose glyphs are all cached.
+pub fn update_incremental_text<M: Material>(
+    mut commands: Commands,
+    mut texts: Query<(Entity, &mut IncrementalText<M>)>,
+    incremental: Res<IncrementalGlyphs>,
+    mut settings: ResMut<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let mut budget = incremental.glyphs_per_frame;
+    for (entity, mut text) in &mut texts {
+        let text = &mut *text;
+        let state = std::mem::replace(&mut text.state, IncrementalState::Empty);
+        let (tx, materials, mut pending) = match state {
+            IncrementalState::Text(input) => {
+                match layout_text(input, &mut settings.font_system, &mut text.params) {
+                    Ok((tx, materials)) => {
+                        let pending = pending_glyphs(&tx, &mut settings, &text.params);
+                        (tx, materials, pending)
+                    }
+                    Err(e) => {
+                        warn!("Failed to lay out incremental text: {e}");
+                        commands.entity(entity).remove::<IncrementalText<M>>();
+                        continue;
+                    }
+                }
+            }
+            IncrementalState::LaidOut(tx, materials, pending) => (tx, materials, pending),
+            IncrementalState::Empty => continue,
+        };
+
+        let policy = text.params.missing_glyph_policy;
+        while let Some(baked) = pending.last() {
+            let Some(key) = glyph_cache_key(&baked.glyph, policy, &baked.options) else {
+                pending.pop();
+                continue;
+            };
+            let Settings {
+                font_system,
+                glyph_cache,
+                ..
+            } = &mut *settings;
+            // Another text may have brought the glyph in since
+            if glyph_cache
+                .get_or_load(font_system, &key, &mut meshes)
+                .is_some()
+            {
+                pending.pop();
+                continue;
+            }
+            if budget == 0 {
+                break;
+            }
+            budget -= 1;
+            match tessellate_layout_glyph(font_system, &baked.glyph, policy, &baked.options) {
+                Ok(Some((geometry, center_x_layout, center_y_layout))) => {
+                    let baked = BakedTextMesh {
+                        options: key.options(),
+                        geometry,
+                        center_x_layout,
+                        center_y_layout,
+                    };
+                    glyph_cache.insert_baked(font_system, key, baked, &mut meshes);
+                }
+                // Glyphs without an outline have no mesh to cache
+                Ok(None) => {}
+                Err(e) => error!("Failed to tessalate glyph {:?}", e),
+            }
+            pending.pop();
+        }
+        if !pending.is_empty() {
+            text.state = IncrementalState::LaidOut(tx, materials, pending);
+            continue;
+        }
+
+        commands.entity(entity).remove::<IncrementalText<M>>();
+        match generate_meshes_from_glyphs(&tx, &materials, &mut settings, &text.params, &mut meshes)
+        {
+            Ok(entries) => {
+                commands.entity(entity).with_children(|parent| {
+                    for entry in entries {
+                        let mut glyph = parent.spawn(entry.transform);
+                        insert_glyph_bounds(&mut glyph, &entry.mesh, &meshes);
+                        glyph.insert((Mesh3d(entry.mesh), MeshMaterial3d(entry.material)));
+                    }
+                });
+            }
+            Err(e) => warn!("Failed to generate incremental text: {e}"),
+        }
+    }
+}
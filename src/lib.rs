@@ -1,5 +1,6 @@
 use bevy::{
     asset::{Asset, Handle},
+    math::{Vec2, Vec3},
     render::mesh::Mesh,
     transform::components::Transform,
 };
@@ -8,15 +9,112 @@ pub use cosmic_text::{
     LetterSpacing, Stretch, Style, Weight, fontdb::ID,
 };
 
+pub mod async_generation;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod bounds;
+pub mod clip;
+pub mod color_materials;
 pub mod command_encoder;
+pub mod composite;
+pub mod contour;
+pub mod debug_text;
+pub mod deferred_text;
+pub mod ear_clipping;
+pub mod editor;
+pub mod entity_pool;
+pub mod exclusion;
 pub mod extrude_glyph;
+pub mod extrusion_animator;
+pub mod font_metrics;
+pub mod font_subset;
+pub mod footprint;
+pub mod fracture;
+pub mod generation_report;
+pub mod glyph_cache;
+pub mod glyph_dump;
+pub mod glyph_store;
+pub mod incremental_text;
+pub mod lightmap;
+pub mod markup;
+pub mod material;
+pub mod merged_text;
 pub mod mesh_text_plugin;
+pub mod morph;
+pub mod noise;
+pub mod number_format;
+pub mod outline;
+pub mod panel;
+pub mod projection;
+#[cfg(feature = "raycast")]
+pub mod raycast;
+pub mod rules;
+pub mod screen_anchor;
+pub mod scrolling_text;
+pub mod shadow;
+pub mod shared_fonts;
+pub mod skeleton;
+pub mod spawn;
+pub mod sticker;
+pub mod styles;
+#[cfg(feature = "svg")]
+pub mod svg;
+pub mod text_cache;
 pub mod text_glyphs;
+pub mod text_lines;
+pub mod timer_text;
 
+pub use async_generation::{TextGenerationHandle, generate_meshes_async};
+pub use bounds::{GlyphBoundingSphere, glyph_bounds};
+pub use clip::generate_clipped_meshes;
+pub use color_materials::ColorMaterials;
+pub use composite::{CompositePart, InlineIcon, generate_composite_meshes};
+pub use contour::ClipOperation;
+pub use debug_text::DebugText3d;
+pub use deferred_text::DeferredText;
+pub use editor::MeshTextEditor;
+pub use entity_pool::{GlyphEntityPool, PooledText};
+pub use exclusion::ExclusionZone;
+pub use extrude_glyph::{
+    CapTessellator, HairlineContours, OpenContours, OutputTopology, TessellationOptions, UvMode,
+    WallSeam,
+};
+pub use extrusion_animator::{ExtrusionAnimator, ExtrusionKeyframe};
+pub use font_metrics::{FontMetricsInfo, font_metrics};
+pub use font_subset::subset_font;
+pub use footprint::{FootprintGrouping, text_footprints};
+pub use generation_report::{GenerationReport, GenerationWarning, GlyphIssue};
+pub use glyph_cache::{GlyphCache, GlyphCacheStats, MeshSharingReport};
+pub use glyph_dump::debug_dump_glyph;
+pub use glyph_store::{BakedTextMesh, GlyphStore};
+pub use incremental_text::{IncrementalGlyphs, IncrementalText};
+pub use lyon::tessellation::FillRule;
+pub use markup::parse_markup;
+pub use merged_text::{ATTRIBUTE_GLYPH, ATTRIBUTE_TEXT_PROGRESS, generate_merged_meshes};
 pub use mesh_text_plugin::*;
+pub use morph::{generate_morph_meshes, generate_text_morph_meshes};
+pub use number_format::{NumberLocale, format_number};
+pub use outline::{OutlineOptions, generate_outline_meshes};
+pub use panel::{PanelParameters, generate_meshes_with_panel};
+pub use rules::{RuleParameters, generate_rules};
+pub use screen_anchor::ScreenAnchor;
+pub use scrolling_text::ScrollingText;
+pub use shadow::generate_shadow_meshes;
+pub use shared_fonts::SharedFonts;
+pub use spawn::{TextSpawnDesc, spawn_text_3d};
+pub use sticker::{StickerEntry, StickerParameters, generate_sticker_meshes};
+pub use styles::{TextStyle, TextStyles};
+pub use text_cache::TextCache;
+pub use text_glyphs::TextGlyphs;
+pub use text_lines::{TextLine, generate_meshes_with_lines};
 
+use extrude_glyph::ExtrusionOptions;
 use thiserror::Error;
 
+/// Most rows the walls can be split into along the depth. Each row adds four vertices per
+/// contour edge, and a glyph mesh holds at most 65535 vertices.
+pub const MAX_DEPTH_SEGMENTS: u32 = 64;
+
 #[derive(Debug, Error)]
 pub enum MeshTextError {
     #[error("Tessellation process failed")]
@@ -27,6 +125,70 @@ pub enum MeshTextError {
 
     #[error("The input provided was invalid")]
     InvalidInput,
+
+    #[error("Morph target does not share the topology of the base mesh")]
+    MorphTopologyMismatch,
+
+    #[error("Failed to build morph targets: {0}")]
+    MorphBuildFailed(#[from] bevy::render::mesh::morph::MorphBuildError),
+
+    #[error("Generation was cancelled")]
+    Cancelled,
+
+    #[error("Invalid markup tag: {0}")]
+    InvalidMarkup(String),
+
+    #[error("Font could not be subset: {0}")]
+    SubsetFailed(&'static str),
+
+    #[error("Extrusion depth must not be negative, got {0}")]
+    NegativeExtrusionDepth(f32),
+
+    #[error("Font size must be positive, got {0}")]
+    InvalidFontSize(f32),
+
+    #[error("{feature} of {width} must be less than half the font size of {font_size}")]
+    EdgeTooWide {
+        feature: &'static str,
+        width: f32,
+        font_size: f32,
+    },
+
+    #[error("{0} needs at least one segment")]
+    ZeroSegments(&'static str),
+
+    #[error("{0} segments must be at most {1}, got {2}")]
+    TooManySegments(&'static str, u32, u32),
+
+    #[error("{0} in world units needs an explicit Parameters::scale")]
+    WorldUnitsNeedScale(&'static str),
+
+    #[error("Maximum width of {max_width} is narrower than one glyph at font size {font_size}")]
+    MaxWidthTooSmall { max_width: f32, font_size: f32 },
+
+    #[error("Layout grid {0} must be positive, got {1}")]
+    InvalidGrid(&'static str, f32),
+
+    #[error("Fit box must be positive, got {0}")]
+    InvalidFitBox(Vec2),
+
+    #[error("Rounded back profile power must be positive, got {0}")]
+    InvalidProfilePower(f32),
+
+    #[error("Drop cap must span at least one line")]
+    DropCapWithoutLines,
+
+    #[error("Exclusion zone must have finite coordinates, got {0:?}")]
+    InvalidExclusionZone(ExclusionZone),
+
+    #[error("Lightmap UVs can't be combined with the {0}, the text materials bind it to UV_1")]
+    LightmapUvsWithAttribute(&'static str),
+
+    #[error("No font matches the text attributes")]
+    NoMatchingFont,
+
+    #[error("Failed to read or write a file: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 /// A extruded glyph mesh.
@@ -42,6 +204,9 @@ pub struct MeshGlyph<M: Asset> {
     pub glyph_center_x_layout: f32,
     pub glyph_center_y_layout: f32,
     pub height: f32,
+    /// Scale from the mesh to layout units. Meshes shared across font sizes are scaled along
+    /// X and Y.
+    pub mesh_scale: Vec3,
     pub mesh: Handle<Mesh>,
     pub material: Handle<M>,
 }
@@ -86,9 +251,272 @@ pub struct MeshTextEntry<M: Asset> {
     pub material: Handle<M>,
 }
 
+impl<M: Asset> Clone for MeshTextEntry<M> {
+    fn clone(&self) -> Self {
+        Self {
+            mesh: self.mesh.clone(),
+            transform: self.transform,
+            material: self.material.clone(),
+        }
+    }
+}
+
+/// What to render for glyphs that are missing from every loaded font.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingGlyphPolicy {
+    /// Render the font's `.notdef` glyph, falling back to a placeholder if it has no outline
+    #[default]
+    Notdef,
+    /// Render a generated hollow box as wide as the glyph's advance
+    Placeholder,
+    /// Leave a gap where the glyph would be
+    Skip,
+}
+
+/// Where the extruded text sits along Z, relative to the plane of its entity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DepthAnchor {
+    /// The front faces lie in the plane and the text extends behind it, towards +Z
+    #[default]
+    Front,
+    /// The text is centered on the plane, so it looks the same turning either way around Y
+    Center,
+    /// The back faces lie in the plane and the text stands out in front of it, towards -Z
+    Back,
+}
+
+impl DepthAnchor {
+    /// Z of the front faces of text `depth` deep in the space of the text.
+    pub fn front_z(self, depth: f32) -> f32 {
+        match self {
+            DepthAnchor::Front => 0.0,
+            DepthAnchor::Center => -depth / 2.0,
+            DepthAnchor::Back => -depth,
+        }
+    }
+}
+
+/// Places every glyph in a cell of the same width, like a terminal, whatever the advances of
+/// the font.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutGrid {
+    /// Width of a cell, in layout units like the font size. Each cluster of the text takes one
+    /// cell and its glyphs are centered in it.
+    pub cell_width: f32,
+    /// Rounds the X and Y of the glyph transforms to multiples of this, in the world units of
+    /// the transforms
+    pub snap: Option<f32>,
+}
+
+impl LayoutGrid {
+    /// `translation` with X and Y rounded to the [`snap`](Self::snap) grid.
+    pub fn snap_translation(&self, translation: Vec3) -> Vec3 {
+        match self.snap {
+            Some(snap) => ((translation.truncate() / snap).round() * snap).extend(translation.z),
+            None => translation,
+        }
+    }
+}
+
+/// Styles faked from the outlines, for fonts without an italic or a bold face.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyntheticStyle {
+    /// Slant of a fake italic, in radians: the outlines are sheared to lean right, about the
+    /// baseline. Most italics lean by around 0.2.
+    pub skew: f32,
+    /// Grows the outlines outward by this fraction of the em for a fake bold, e.g. 0.02, or
+    /// thins the strokes with a negative value
+    pub embolden: f32,
+}
+
+impl SyntheticStyle {
+    /// The shear of [`skew`](Self::skew), in font units with Y up.
+    pub fn skew_transform(&self) -> lyon::math::Transform {
+        lyon::math::Transform::new(1.0, 0.0, self.skew.tan(), 1.0, 0.0, 0.0)
+    }
+}
+
+/// Steps the glyphs along Z by their place in the text, e.g. lines of a title card on layers
+/// of their own, or letters climbing a stair.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stagger {
+    /// Z offset of every line from the line above it, in world units
+    pub per_line: f32,
+    /// Z offset of every glyph from the one before it on its line, in world units. Glyphs
+    /// without a mesh, like spaces, take a step too.
+    pub per_glyph: f32,
+}
+
+/// A first letter sunk into the lines below it, as in old books.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropCapParameters {
+    /// Number of lines the letter spans, from the height of the capitals of the first line
+    /// to the baseline of the last
+    pub lines: usize,
+    /// Space between the letter and the lines beside it, in layout units
+    pub gap: f32,
+}
+
+impl Stagger {
+    /// Z offset of the glyph at `column` on `line`, both counted from 0.
+    pub fn offset(&self, line: usize, column: usize) -> f32 {
+        line as f32 * self.per_line + column as f32 * self.per_glyph
+    }
+}
+
+/// Insets the front face from the outline, leaving a raised lip around a recessed face like an
+/// embossed keycap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrontInset {
+    /// Width of the lip, in layout units like the extrusion depth
+    pub width: f32,
+    /// How far the inner face sits behind the lip. Clamped to the extrusion depth.
+    pub depth: f32,
+}
+
+/// Contours of the outline that a [`FrontInset`] runs along. The recessed face reaches the
+/// others, which then rise straight from it. The back is never inset, see [`RoundedBack`] for
+/// softening it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub enum BevelEdges {
+    /// The outer contours and the holes
+    #[default]
+    All,
+    /// Only the outer contours, e.g. to chamfer the silhouette and keep the counters sharp
+    Outer,
+    /// Only the holes
+    Holes,
+}
+
+impl BevelEdges {
+    /// Whether the inset runs along an outer contour, or along a hole if `hole` is set.
+    pub fn includes(self, hole: bool) -> bool {
+        match self {
+            BevelEdges::All => true,
+            BevelEdges::Outer => !hole,
+            BevelEdges::Holes => hole,
+        }
+    }
+}
+
+/// Extrusion depth in a unit that keeps text in proportion when it is resized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub enum Depth {
+    /// Fraction of the font size, so scaling a title up deepens it along with its glyphs
+    Em(f32),
+    /// World units, so texts of every size are equally deep. Needs [`Parameters::scale`].
+    World(f32),
+}
+
+impl Depth {
+    /// The depth in layout units of text of `font_size`, drawn at `world_scale` along Z.
+    pub fn to_layout_units(self, font_size: f32, world_scale: f32) -> f32 {
+        match self {
+            Depth::Em(depth) => depth * font_size,
+            Depth::World(depth) => depth / world_scale,
+        }
+    }
+}
+
+/// Width of a bevel-like edge, e.g. the lip of a [`FrontInset`], in a unit that keeps the edge
+/// looking the same whatever the font size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub enum BevelWidth {
+    /// Fraction of the font size, so the edge keeps its proportions to the glyphs at any size
+    Em(f32),
+    /// World units, so texts of every size get the same edge. Needs [`Parameters::scale`].
+    /// In [`ExtrusionOptions`], which know no world scale, these are layout units.
+    WorldUnits(f32),
+    /// Units of the font's design grid, e.g. a width measured in a font editor
+    FontUnits(f32),
+}
+
+impl BevelWidth {
+    /// The width in layout units for a glyph of `font_size` from a font with `units_per_em`,
+    /// in text drawn at `world_scale`.
+    pub fn to_layout_units(self, font_size: f32, units_per_em: f32, world_scale: f32) -> f32 {
+        match self {
+            BevelWidth::Em(width) => width * font_size,
+            BevelWidth::WorldUnits(width) => width / world_scale,
+            BevelWidth::FontUnits(width) => width * font_size / units_per_em,
+        }
+    }
+}
+
+/// A thin flat ring just outside the front face, e.g. a dark outline that keeps unlit text
+/// readable at a distance where its silhouette would alias.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrontRim {
+    /// Width of the ring, in layout units like the extrusion depth
+    pub width: f32,
+    /// Linear RGB vertex color of the ring. The rest of the glyph stays white.
+    pub color: [f32; 3],
+}
+
+/// Puffs the glyphs up like cartoon letters: the caps bulge out along their normals, rising
+/// from the outline to `amount` over `falloff`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub struct InflateParameters {
+    /// How far the caps bulge out, in layout units
+    pub amount: f32,
+    /// Distance from the outline, in layout units, over which the caps round up to their full
+    /// bulge. Wide strokes stay flat in the middle.
+    pub falloff: f32,
+}
+
+/// Roughens the walls with 3D noise, e.g. for stone-carved or hand-made looking text. The walls
+/// are split into cells a fraction of the noise features apart, and displaced less towards
+/// the caps, so the caps and their edges stay in place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub struct SurfaceNoise {
+    /// Picks the noise pattern
+    pub seed: u32,
+    /// Noise features per layout unit
+    pub frequency: f32,
+    /// Largest displacement, in layout units
+    pub amplitude: f32,
+    /// Scales the displacement along each axis, e.g. `[1.0, 1.0, 0.0]` to keep it in the
+    /// plane of the text
+    pub axis_mask: [f32; 3],
+}
+
+/// Rounds the back of the glyphs like balloon letters: over the last `depth` of the extrusion,
+/// the walls bend inward along a quarter ellipse, `radius` deep into the glyph, and meet a
+/// smaller back face.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundedBack {
+    /// How far the back face shrinks into the glyph, in layout units. Strokes thinner than
+    /// twice the radius would fold over, so glyphs with such strokes get a smaller radius.
+    pub radius: f32,
+    /// Depth of the rounding, in layout units. Clamped to the extrusion depth.
+    pub depth: f32,
+    /// Number of rings between the walls and the back face
+    pub segments: u32,
+    /// How the rings are spread along the rounding. 1 spaces them evenly, larger values
+    /// cluster them near the walls, where highlights run along the rim, and smaller values
+    /// near the back face. Must be positive.
+    pub profile_power: f32,
+}
+
+#[derive(Clone)]
 pub struct Parameters {
     /// Extrusion depth
     pub extrusion_depth: f32,
+    /// Extrusion depth in a unit of choice, in place of
+    /// [`extrusion_depth`](Self::extrusion_depth)
+    pub depth: Option<Depth>,
     /// Font size
     pub font_size: f32,
     /// Line height
@@ -99,4 +527,315 @@ pub struct Parameters {
     pub max_width: Option<f32>,
     /// Maximum height of the textbox.
     pub max_height: Option<f32>,
+    /// Areas the lines flow around, e.g. a picture set into the text. Ignored on a
+    /// [`grid`](Self::grid).
+    pub exclusion_zones: Vec<ExclusionZone>,
+    /// Sets the first letter of the text as a drop cap, with the lines beside it flowed
+    /// around it like around [`exclusion_zones`](Self::exclusion_zones). Ignored on a
+    /// [`grid`](Self::grid).
+    pub drop_cap: Option<DropCapParameters>,
+    /// What to render for glyphs missing from every font
+    pub missing_glyph_policy: MissingGlyphPolicy,
+    /// Optional lip around a recessed front face
+    pub front_inset: Option<FrontInset>,
+    /// Width of the lip of [`front_inset`](Self::front_inset) in a unit of choice, in place of
+    /// its width in layout units
+    pub bevel_width: Option<BevelWidth>,
+    /// Contours the lip of [`front_inset`](Self::front_inset) runs along
+    pub bevel_edges: BevelEdges,
+    /// Width of the lip of [`front_inset`](Self::front_inset) along holes, in place of its
+    /// width elsewhere, e.g. a narrower lip so small counters don't close up
+    pub hole_bevel_width: Option<BevelWidth>,
+    /// Optional colored ring around the front face
+    pub front_rim: Option<FrontRim>,
+    /// Optional rounding of the back edges
+    pub rounded_back: Option<RoundedBack>,
+    /// Optional bulge of the caps
+    pub inflate: Option<InflateParameters>,
+    /// Optional noise roughening the walls
+    pub surface_noise: Option<SurfaceNoise>,
+    /// Bake a mask into the vertex color alpha that is 0 on the outer walls and the outer edge
+    /// of the lip, and 1 on the caps, so shaders can tint the edges.
+    pub edge_mask: bool,
+    /// Skin every glyph mesh to a single joint, for use with [`skeleton::spawn_skinned_text`]
+    pub skinned: bool,
+    /// Add the depth fraction attribute used by [`material::TextRevealExtension`]
+    pub depth_attribute: bool,
+    /// Add the surface id attribute used by [`material::TextSurfaceExtension`]
+    pub surface_attribute: bool,
+    /// Texture coordinates of the front and back caps
+    pub uv_mode: UvMode,
+    /// Add non-overlapping lightmap coordinates in `UV_1`, for baked lighting. The text materials
+    /// bind the depth and surface attributes to the `UV_1` slot, so neither can be combined with
+    /// lightmap UVs, and a `TextDissolveMaterial` ignores the lightmap UVs of merged meshes.
+    pub lightmap_uvs: bool,
+    /// Shade walls meeting at less than this angle, in radians, as one smooth surface
+    pub wall_smoothing_angle: Option<f32>,
+    /// Where the texture of the walls wraps around each contour
+    pub wall_seam: WallSeam,
+    /// Texture repeats per unit of the glyph mesh on the walls, instead of wrapping 0..1 around
+    /// each contour and across the depth
+    pub wall_uv_scale: Option<f32>,
+    /// Number of rows the walls and the inner walls of the front inset are split into along
+    /// the depth, e.g. for vertex shaders bending or twisting the text. At most
+    /// [`MAX_DEPTH_SEGMENTS`].
+    pub depth_segments: u32,
+    /// Fill rule and curve tolerance used to tessellate the outlines
+    pub tessellation: TessellationOptions,
+    /// Generate wireframes or point clouds of the glyph edges instead of solid meshes
+    pub topology: OutputTopology,
+    /// World scale per axis, e.g. to squash the depth without changing the size of the faces.
+    /// Defaults to `Settings::text_scale_factor` on every axis.
+    pub scale: Option<Vec3>,
+    /// Back the text with a turned copy of itself, so it reads correctly from behind, like a
+    /// two-sided sign. The text is then twice as deep.
+    pub double_sided: bool,
+    /// Where the text sits along Z relative to its entity
+    pub depth_anchor: DepthAnchor,
+    /// Optional grid of fixed width cells the glyphs are laid out on, e.g. for retro terminal
+    /// text from a proportional font
+    pub grid: Option<LayoutGrid>,
+    /// Size of a box in world units, e.g. a sign, that the text is fitted into. The font size
+    /// becomes the largest that fits the laid out text into the box, with the line height
+    /// scaled along. Needs a [`scale`](Self::scale).
+    pub fit_box: Option<Vec2>,
+    /// Optional Z offsets per line and per glyph, baked into the glyph transforms
+    pub stagger: Option<Stagger>,
+    /// Optional fake italic and bold, or thinner strokes
+    pub synthetic_style: Option<SyntheticStyle>,
+}
+
+impl Parameters {
+    /// The options shaping each glyph's geometry.
+    pub fn extrusion_options(&self) -> ExtrusionOptions {
+        ExtrusionOptions {
+            extrusion_depth: self.layout_depth(),
+            front_inset: self.front_inset,
+            bevel_width: self.bevel_width.map(|width| self.glyph_bevel_width(width)),
+            bevel_edges: self.bevel_edges,
+            hole_bevel_width: self
+                .hole_bevel_width
+                .map(|width| self.glyph_bevel_width(width)),
+            front_rim: self.front_rim,
+            rounded_back: self.rounded_back,
+            inflate: self.inflate,
+            surface_noise: self.surface_noise,
+            edge_mask: self.edge_mask,
+            skinned: self.skinned,
+            depth_attribute: self.depth_attribute,
+            surface_attribute: self.surface_attribute,
+            uv_mode: self.uv_mode,
+            lightmap_uvs: self.lightmap_uvs,
+            wall_smoothing_angle: self.wall_smoothing_angle,
+            wall_seam: self.wall_seam,
+            wall_uv_scale: self.wall_uv_scale,
+            depth_segments: self.depth_segments,
+            silhouette: false,
+            tessellation: self.tessellation,
+            topology: self.topology,
+            synthetic_style: self.synthetic_style,
+        }
+    }
+
+    /// A bevel width for the glyph meshes. World units become a fraction of the font size, as
+    /// glyph meshes know no world scale.
+    fn glyph_bevel_width(&self, width: BevelWidth) -> BevelWidth {
+        match width {
+            BevelWidth::WorldUnits(width) => {
+                BevelWidth::Em(width / (self.world_scale(1.0).x * self.font_size))
+            }
+            width => width,
+        }
+    }
+
+    /// Sets the same world scale on every axis, instead of the plugin's default.
+    pub fn with_scale(self, scale: f32) -> Self {
+        Self {
+            scale: Some(Vec3::splat(scale)),
+            ..self
+        }
+    }
+
+    /// Z of the front faces of the glyphs in the space of the text, placing the whole depth of
+    /// the text, its back side included, by `depth_anchor`.
+    pub fn front_z(&self, scale: Vec3) -> f32 {
+        let sides = if self.double_sided { 2.0 } else { 1.0 };
+        self.depth_anchor
+            .front_z(sides * self.layout_depth() * scale.z)
+    }
+
+    /// The extrusion depth in layout units, from [`depth`](Self::depth) when it is set.
+    pub fn layout_depth(&self) -> f32 {
+        self.depth.map_or(self.extrusion_depth, |depth| {
+            depth.to_layout_units(self.font_size, self.world_scale(1.0).z)
+        })
+    }
+
+    /// The world scale of the text, falling back to `text_scale_factor` on every axis.
+    pub fn world_scale(&self, text_scale_factor: f32) -> Vec3 {
+        self.scale.unwrap_or(Vec3::splat(text_scale_factor))
+    }
+
+    /// Rejects parameters that can't produce sensible meshes. Every generator checks its
+    /// parameters before laying out the text.
+    ///
+    /// The font size must be positive and the extrusion depth not negative. The front inset and
+    /// the rounded back must stay within half the font size, which would swallow whole glyphs,
+    /// and the rounded back and the walls need a segment. The profile power of the rounded back
+    /// must be positive. Depths and bevel widths in world units
+    /// and a [`fit_box`](Self::fit_box), which must be positive, need a [`scale`](Self::scale).
+    /// The maximum width must fit a glyph, taken to be as wide as the font size, and the cells
+    /// of a [`grid`](Self::grid) and its snap must be positive.
+    pub fn validate(&self) -> Result<(), MeshTextError> {
+        if self.font_size <= 0.0 || self.font_size.is_nan() {
+            return Err(MeshTextError::InvalidFontSize(self.font_size));
+        }
+        if matches!(self.depth, Some(Depth::World(_))) && self.scale.is_none() {
+            return Err(MeshTextError::WorldUnitsNeedScale("Depth"));
+        }
+        if matches!(self.bevel_width, Some(BevelWidth::WorldUnits(_))) && self.scale.is_none() {
+            return Err(MeshTextError::WorldUnitsNeedScale("Bevel width"));
+        }
+        if matches!(self.hole_bevel_width, Some(BevelWidth::WorldUnits(_))) && self.scale.is_none()
+        {
+            return Err(MeshTextError::WorldUnitsNeedScale("Hole bevel width"));
+        }
+        if self.drop_cap.is_some_and(|drop_cap| drop_cap.lines == 0) {
+            return Err(MeshTextError::DropCapWithoutLines);
+        }
+        if let Some(zone) = self.exclusion_zones.iter().find(|zone| !zone.is_finite()) {
+            return Err(MeshTextError::InvalidExclusionZone(zone.clone()));
+        }
+        if self.lightmap_uvs && self.depth_attribute {
+            return Err(MeshTextError::LightmapUvsWithAttribute("depth attribute"));
+        }
+        if self.lightmap_uvs && self.surface_attribute {
+            return Err(MeshTextError::LightmapUvsWithAttribute("surface attribute"));
+        }
+        if let Some(fit_box) = self.fit_box {
+            if self.scale.is_none() {
+                return Err(MeshTextError::WorldUnitsNeedScale("Fit box"));
+            }
+            if !fit_box.cmpgt(Vec2::ZERO).all() {
+                return Err(MeshTextError::InvalidFitBox(fit_box));
+            }
+        }
+        let depth = self.layout_depth();
+        if depth < 0.0 || depth.is_nan() {
+            return Err(MeshTextError::NegativeExtrusionDepth(depth));
+        }
+        // Font units depend on the font of each glyph, so they aren't checked
+        let lip_width = |width: Option<BevelWidth>| {
+            self.front_inset.and_then(|inset| match width {
+                None => Some(inset.width),
+                Some(BevelWidth::FontUnits(_)) => None,
+                Some(width) => {
+                    Some(width.to_layout_units(self.font_size, 1.0, self.world_scale(1.0).x))
+                }
+            })
+        };
+        let edges = [
+            ("Front inset width", lip_width(self.bevel_width)),
+            (
+                "Hole bevel width",
+                lip_width(self.hole_bevel_width.or(self.bevel_width)),
+            ),
+            (
+                "Rounded back radius",
+                self.rounded_back.map(|back| back.radius),
+            ),
+        ];
+        for (feature, width) in edges {
+            if let Some(width) = width
+                && width >= self.font_size / 2.0
+            {
+                return Err(MeshTextError::EdgeTooWide {
+                    feature,
+                    width,
+                    font_size: self.font_size,
+                });
+            }
+        }
+        if let Some(back) = self.rounded_back {
+            if back.segments == 0 {
+                return Err(MeshTextError::ZeroSegments("Rounded back"));
+            }
+            if !(back.profile_power > 0.0 && back.profile_power.is_finite()) {
+                return Err(MeshTextError::InvalidProfilePower(back.profile_power));
+            }
+        }
+        if self.depth_segments == 0 {
+            return Err(MeshTextError::ZeroSegments("Depth"));
+        }
+        if self.depth_segments > MAX_DEPTH_SEGMENTS {
+            return Err(MeshTextError::TooManySegments(
+                "Depth",
+                MAX_DEPTH_SEGMENTS,
+                self.depth_segments,
+            ));
+        }
+        if let Some(max_width) = self.max_width
+            && max_width < self.font_size
+        {
+            return Err(MeshTextError::MaxWidthTooSmall {
+                max_width,
+                font_size: self.font_size,
+            });
+        }
+        if let Some(grid) = self.grid {
+            let sizes = [("cell width", Some(grid.cell_width)), ("snap", grid.snap)];
+            for (name, size) in sizes {
+                if let Some(size) = size
+                    && (size <= 0.0 || size.is_nan())
+                {
+                    return Err(MeshTextError::InvalidGrid(name, size));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            extrusion_depth: 2.5,
+            depth: None,
+            font_size: 14.0,
+            line_height: 16.0,
+            alignment: None,
+            max_width: None,
+            max_height: None,
+            exclusion_zones: Vec::new(),
+            drop_cap: None,
+            missing_glyph_policy: MissingGlyphPolicy::default(),
+            front_inset: None,
+            bevel_width: None,
+            bevel_edges: BevelEdges::default(),
+            hole_bevel_width: None,
+            front_rim: None,
+            rounded_back: None,
+            inflate: None,
+            surface_noise: None,
+            edge_mask: false,
+            skinned: false,
+            depth_attribute: false,
+            surface_attribute: false,
+            uv_mode: UvMode::default(),
+            lightmap_uvs: false,
+            wall_smoothing_angle: None,
+            wall_seam: WallSeam::default(),
+            wall_uv_scale: None,
+            depth_segments: 1,
+            tessellation: TessellationOptions::default(),
+            topology: OutputTopology::default(),
+            scale: None,
+            double_sided: false,
+            depth_anchor: DepthAnchor::default(),
+            grid: None,
+            fit_box: None,
+            stagger: None,
+            synthetic_style: None,
+        }
+    }
 }
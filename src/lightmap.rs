@@ -0,0 +1,63 @@
+//! Lightmap texture coordinates for baked lighting.
+
+use std::ops::Range;
+
+use bevy::math::Vec2;
+
+/// Space between packed charts, relative to the square root of their total area
+const PADDING: f32 = 0.02;
+
+/// Packs charts into the unit square, keeping their proportions and one common scale so
+/// every surface gets the same texel density.
+///
+/// Each chart is a range of vertices with flat 2D coordinates in `local`, e.g. a cap in the
+/// XY plane or a wall unrolled along its contour. Returns the packed coordinate of every
+/// vertex; vertices outside every chart end up at the origin.
+pub fn pack_charts(charts: &[Range<usize>], local: &[Vec2]) -> Vec<Vec2> {
+    let bounds: Vec<(Vec2, Vec2)> = charts
+        .iter()
+        .map(|chart| {
+            local[chart.clone()]
+                .iter()
+                .fold((Vec2::MAX, Vec2::MIN), |(min, max), p| {
+                    (min.min(*p), max.max(*p))
+                })
+        })
+        .map(|(min, max)| (min, (max - min).max(Vec2::ZERO)))
+        .collect();
+
+    let area: f32 = bounds.iter().map(|(_, size)| size.x * size.y).sum();
+    let padding = PADDING * area.sqrt().max(f32::EPSILON);
+    let padded = |size: Vec2| size + padding;
+
+    // Shelves of charts sorted by height, about as wide as they end up tall
+    let width = bounds
+        .iter()
+        .map(|(_, size)| padded(*size).x)
+        .fold(area.sqrt() * 1.2, f32::max);
+    let mut order: Vec<usize> = (0..charts.len()).collect();
+    order.sort_by(|&a, &b| bounds[b].1.y.total_cmp(&bounds[a].1.y));
+
+    let mut offsets = vec![Vec2::ZERO; charts.len()];
+    let (mut cursor, mut shelf_height, mut extent) = (Vec2::ZERO, 0.0f32, Vec2::ZERO);
+    for index in order {
+        let size = padded(bounds[index].1);
+        if cursor.x > 0.0 && cursor.x + size.x > width {
+            cursor = Vec2::new(0.0, cursor.y + shelf_height);
+            shelf_height = 0.0;
+        }
+        offsets[index] = cursor + padding / 2.0;
+        extent = extent.max(cursor + size);
+        cursor.x += size.x;
+        shelf_height = shelf_height.max(size.y);
+    }
+
+    let scale = 1.0 / extent.max_element().max(f32::EPSILON);
+    let mut packed = vec![Vec2::ZERO; local.len()];
+    for ((chart, (min, _)), offset) in charts.iter().zip(&bounds).zip(&offsets) {
+        for vertex in chart.clone() {
+            packed[vertex] = (local[vertex] - *min + *offset) * scale;
+        }
+    }
+    packed
+}
@@ -0,0 +1,121 @@
+//! Inline markup setting material properties per span, e.g. a glowing keyword in a sentence.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use cosmic_text::Attrs;
+
+use crate::{InputText, MeshTextError};
+
+/// Bit patterns of a [`SpanStyle`], to share materials between equal styles.
+type StyleKey = (Option<[u32; 4]>, Option<u32>);
+
+/// Material properties set by the tags enclosing a span.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct SpanStyle {
+    emissive: Option<LinearRgba>,
+    metallic: Option<f32>,
+}
+
+impl SpanStyle {
+    fn key(&self) -> StyleKey {
+        (
+            self.emissive.map(|c| c.to_f32_array().map(f32::to_bits)),
+            self.metallic.map(f32::to_bits),
+        )
+    }
+}
+
+/// Parses text with material tags into rich text, e.g.
+/// `"Press [emissive=#00ffff]jump[/emissive] to [metallic=0.8]fly[/metallic]"`.
+///
+/// `[emissive=<hex color>]` makes the enclosed text glow in the sRGB color and
+/// `[metallic=<0..1>]` sets its metallic factor. Tags nest, and each closes with
+/// `[/emissive]` or `[/metallic]`; tags left open run to the end of the text. `[[` is a
+/// literal `[`.
+///
+/// Spans outside of any tag use `base`. The others get a copy of it with their properties,
+/// shared by the spans with the same properties. Every span uses `attrs`.
+pub fn parse_markup<'a>(
+    source: &str,
+    attrs: Attrs<'a>,
+    base: &Handle<StandardMaterial>,
+    materials: &mut Assets<StandardMaterial>,
+) -> Result<InputText<'a, StandardMaterial>, MeshTextError> {
+    let invalid = |tag: &str| MeshTextError::InvalidMarkup(tag.to_string());
+    let mut emissive: Vec<LinearRgba> = Vec::new();
+    let mut metallic: Vec<f32> = Vec::new();
+    let mut spans: Vec<(String, SpanStyle)> = Vec::new();
+    let mut push = |text: &str, style: SpanStyle| match spans.last_mut() {
+        Some((last, last_style)) if *last_style == style => last.push_str(text),
+        _ if text.is_empty() => {}
+        _ => spans.push((text.to_string(), style)),
+    };
+
+    let mut rest = source;
+    while !rest.is_empty() {
+        let style = SpanStyle {
+            emissive: emissive.last().copied(),
+            metallic: metallic.last().copied(),
+        };
+        let Some(open) = rest.find('[') else {
+            push(rest, style);
+            break;
+        };
+        push(&rest[..open], style);
+        rest = &rest[open..];
+        if let Some(after) = rest.strip_prefix("[[") {
+            push("[", style);
+            rest = after;
+            continue;
+        }
+        let close = rest.find(']').ok_or_else(|| invalid(rest))?;
+        let tag = &rest[1..close];
+        rest = &rest[close + 1..];
+        match tag.split_once('=') {
+            Some(("emissive", color)) => {
+                let color = Srgba::hex(color.trim()).map_err(|_| invalid(tag))?;
+                emissive.push(color.into());
+            }
+            Some(("metallic", value)) => {
+                let value: f32 = value.trim().parse().map_err(|_| invalid(tag))?;
+                metallic.push(value.clamp(0.0, 1.0));
+            }
+            None if tag == "/emissive" => {
+                emissive.pop().ok_or_else(|| invalid(tag))?;
+            }
+            None if tag == "/metallic" => {
+                metallic.pop().ok_or_else(|| invalid(tag))?;
+            }
+            _ => return Err(invalid(tag)),
+        }
+    }
+
+    let mut styled: HashMap<StyleKey, Handle<StandardMaterial>> = HashMap::new();
+    let span_materials = spans
+        .iter()
+        .map(|(_, style)| {
+            if *style == SpanStyle::default() {
+                return base.clone();
+            }
+            styled
+                .entry(style.key())
+                .or_insert_with(|| {
+                    let mut material = materials.get(base).cloned().unwrap_or_default();
+                    if let Some(emissive) = style.emissive {
+                        material.emissive = emissive;
+                    }
+                    if let Some(metallic) = style.metallic {
+                        material.metallic = metallic;
+                    }
+                    materials.add(material)
+                })
+                .clone()
+        })
+        .collect();
+    Ok(InputText::Rich {
+        attrs: vec![attrs; spans.len()],
+        words: spans.into_iter().map(|(text, _)| text).collect(),
+        materials: span_materials,
+    })
+}
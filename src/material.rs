@@ -0,0 +1,235 @@
+//! Optional `StandardMaterial` extensions that read the attributes generated by the mesher.
+
+use bevy::asset::embedded_asset;
+use bevy::pbr::{
+    ExtendedMaterial, MaterialExtension, MaterialExtensionKey, MaterialExtensionPipeline,
+};
+use bevy::prelude::*;
+use bevy::render::mesh::{MeshVertexAttribute, MeshVertexBufferLayoutRef};
+use bevy::render::render_resource::{
+    AsBindGroup, FragmentState, RenderPipelineDescriptor, ShaderDefVal, ShaderRef,
+    SpecializedMeshPipelineError,
+};
+
+use crate::extrude_glyph::{ATTRIBUTE_DEPTH_FRACTION, ATTRIBUTE_SURFACE};
+use crate::merged_text::ATTRIBUTE_TEXT_PROGRESS;
+
+/// Registers the text material extensions.
+pub struct TextMaterialPlugin;
+
+impl Plugin for TextMaterialPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "shaders/text_reveal.wgsl");
+        embedded_asset!(app, "shaders/text_surface.wgsl");
+        embedded_asset!(app, "shaders/text_dissolve.wgsl");
+        app.add_plugins((
+            MaterialPlugin::<TextRevealMaterial>::default(),
+            MaterialPlugin::<TextSurfaceMaterial>::default(),
+            MaterialPlugin::<TextDissolveMaterial>::default(),
+        ));
+    }
+}
+
+/// `StandardMaterial` that only draws the part of the text closer to the front than
+/// `progress`.
+pub type TextRevealMaterial = ExtendedMaterial<StandardMaterial, TextRevealExtension>;
+
+/// Clips text meshes along the extrusion axis, so animating `progress` from 0 to 1 grows the
+/// text from its front face to the full depth. The clipped part is left out of the depth,
+/// normal and deferred prepasses and of shadows too.
+///
+/// The meshes have to be generated with [`crate::Parameters::depth_attribute`].
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct TextRevealExtension {
+    /// Fraction of the extrusion depth that is visible
+    #[uniform(100)]
+    pub progress: f32,
+}
+
+impl MaterialExtension for TextRevealExtension {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://bevy_mesh_text_3d/shaders/text_reveal.wgsl".into()
+    }
+
+    fn prepass_fragment_shader() -> ShaderRef {
+        "embedded://bevy_mesh_text_3d/shaders/text_reveal.wgsl".into()
+    }
+
+    fn deferred_fragment_shader() -> ShaderRef {
+        "embedded://bevy_mesh_text_3d/shaders/text_reveal.wgsl".into()
+    }
+
+    fn specialize(
+        pipeline: &MaterialExtensionPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialExtensionKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        bind_to_uv_b(descriptor, layout, ATTRIBUTE_DEPTH_FRACTION)?;
+        discard_in_prepass(descriptor, pipeline.fragment_shader.as_ref());
+        Ok(())
+    }
+}
+
+/// `StandardMaterial` with a separate base color per surface of the text.
+pub type TextSurfaceMaterial = ExtendedMaterial<StandardMaterial, TextSurfaceExtension>;
+
+/// Tints every [`crate::extrude_glyph::TextSurface`] with its own color, multiplied with the
+/// base color, so a single entity can have e.g. a white face and dark sides. Works with forward
+/// and deferred rendering; the prepasses only write depth and normals and use the
+/// `StandardMaterial` shader.
+///
+/// The meshes have to be generated with [`crate::Parameters::surface_attribute`].
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+pub struct TextSurfaceExtension {
+    #[uniform(100)]
+    pub front: LinearRgba,
+    /// The lip of a front inset and its inner walls
+    #[uniform(100)]
+    pub edge: LinearRgba,
+    #[uniform(100)]
+    pub side: LinearRgba,
+    #[uniform(100)]
+    pub back: LinearRgba,
+}
+
+impl Default for TextSurfaceExtension {
+    fn default() -> Self {
+        Self {
+            front: LinearRgba::WHITE,
+            edge: LinearRgba::WHITE,
+            side: LinearRgba::WHITE,
+            back: LinearRgba::WHITE,
+        }
+    }
+}
+
+impl MaterialExtension for TextSurfaceExtension {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://bevy_mesh_text_3d/shaders/text_surface.wgsl".into()
+    }
+
+    fn deferred_fragment_shader() -> ShaderRef {
+        "embedded://bevy_mesh_text_3d/shaders/text_surface.wgsl".into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialExtensionPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialExtensionKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        bind_to_uv_b(descriptor, layout, ATTRIBUTE_SURFACE)
+    }
+}
+
+/// `StandardMaterial` that only draws the part of the text before `progress` along it.
+pub type TextDissolveMaterial = ExtendedMaterial<StandardMaterial, TextDissolveExtension>;
+
+/// Clips merged text meshes along the text, so animating `progress` from 0 to 1 reveals the
+/// text from its first letter to its last, and back from 1 to 0 dissolves it. The edge glows
+/// in `edge_color` over `edge_width`, for burning text. The dissolved part is left out of the
+/// depth, normal and deferred prepasses and of shadows too.
+///
+/// The meshes have to be generated with [`crate::generate_merged_meshes`].
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct TextDissolveExtension {
+    /// Emissive color of the edge
+    #[uniform(100)]
+    pub edge_color: LinearRgba,
+    /// Fraction of the text that is visible
+    #[uniform(100)]
+    pub progress: f32,
+    /// Width of the glowing edge as a fraction of the text
+    #[uniform(100)]
+    pub edge_width: f32,
+}
+
+impl MaterialExtension for TextDissolveExtension {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://bevy_mesh_text_3d/shaders/text_dissolve.wgsl".into()
+    }
+
+    fn prepass_fragment_shader() -> ShaderRef {
+        "embedded://bevy_mesh_text_3d/shaders/text_dissolve.wgsl".into()
+    }
+
+    fn deferred_fragment_shader() -> ShaderRef {
+        "embedded://bevy_mesh_text_3d/shaders/text_dissolve.wgsl".into()
+    }
+
+    fn specialize(
+        pipeline: &MaterialExtensionPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialExtensionKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        bind_to_uv_b(descriptor, layout, ATTRIBUTE_TEXT_PROGRESS)?;
+        discard_in_prepass(descriptor, pipeline.fragment_shader.as_ref());
+        Ok(())
+    }
+}
+
+/// Binds `attribute` to the second UV slot of the main pass and prepass pipelines, in place of
+/// `UV_1`, so it reaches the fragment shader as `in.uv_b` without a custom vertex shader. The
+/// deferred passes and shadows go through the prepass pipeline too.
+pub fn bind_to_uv_b(
+    descriptor: &mut RenderPipelineDescriptor,
+    layout: &MeshVertexBufferLayoutRef,
+    attribute: MeshVertexAttribute,
+) -> Result<(), SpecializedMeshPipelineError> {
+    let location = match descriptor.label.as_deref() {
+        Some(label) if label.ends_with("mesh_pipeline") => 3,
+        Some("prepass_pipeline") => 2,
+        _ => return Ok(()),
+    };
+    if !layout.0.contains(attribute.id) {
+        return Ok(());
+    }
+
+    // Keep the attributes the pipeline picked and only swap the one at the UV_1 location
+    let bound = layout
+        .0
+        .get_layout(&[attribute.at_shader_location(location)])?;
+    let Some(buffer) = descriptor.vertex.buffers.first_mut() else {
+        return Ok(());
+    };
+    buffer
+        .attributes
+        .retain(|attribute| attribute.shader_location != location);
+    buffer.attributes.extend(bound.attributes);
+
+    // Meshes with UV_1 already have the define
+    let push_define = |defs: &mut Vec<ShaderDefVal>| {
+        let define = ShaderDefVal::from("VERTEX_UVS_B");
+        if !defs.contains(&define) {
+            defs.push(define);
+        }
+    };
+    push_define(&mut descriptor.vertex.shader_defs);
+    if let Some(fragment) = descriptor.fragment.as_mut() {
+        push_define(&mut fragment.shader_defs);
+    }
+    Ok(())
+}
+
+/// Gives the depth-only prepass and shadow pipelines `shader` as fragment shader, with the
+/// shader defs of the vertex stage. Bevy draws opaque meshes there without one, so the
+/// fragments a material discards would still write depth and cast shadows.
+pub fn discard_in_prepass(
+    descriptor: &mut RenderPipelineDescriptor,
+    shader: Option<&Handle<Shader>>,
+) {
+    if descriptor.label.as_deref() != Some("prepass_pipeline") || descriptor.fragment.is_some() {
+        return;
+    }
+    let Some(shader) = shader else {
+        return;
+    };
+    descriptor.fragment = Some(FragmentState {
+        shader: shader.clone(),
+        shader_defs: descriptor.vertex.shader_defs.clone(),
+        entry_point: "fragment".into(),
+        targets: Vec::new(),
+    });
+}
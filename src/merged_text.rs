@@ -0,0 +1,228 @@
+//! Whole texts merged into one mesh per material, for many labels at a low entity count.
+
+use bevy::asset::{AssetId, RenderAssetUsages};
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, MeshVertexAttribute, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_resource::VertexFormat;
+
+use crate::extrude_glyph::{ATTRIBUTE_DEPTH_FRACTION, ATTRIBUTE_SURFACE};
+use crate::mesh_text_plugin::{add_back_side, layout_text, staggered_glyph_entry};
+use crate::text_glyphs::GlyphSource;
+use crate::{InputText, MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// Vertex attribute of merged text meshes holding, per vertex, the index of its glyph in the
+/// text and the code point of the glyph's first character.
+///
+/// Glyphs are counted in layout order, including spaces and other glyphs without a mesh, so
+/// the index of a letter doesn't change with the glyphs around it.
+pub const ATTRIBUTE_GLYPH: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_Glyph", 284_190_519, VertexFormat::Float32x2);
+
+/// Vertex attribute of merged text meshes holding how far along the text a vertex is, from 0
+/// at the left edge of the first glyph to 1 at the right edge of the last one.
+///
+/// Every glyph, including spaces, covers an equal share, and vertices within a glyph go from
+/// the start to the end of its share from left to right, so thresholding the value in a
+/// shader dissolves or reveals the text letter by letter.
+pub const ATTRIBUTE_TEXT_PROGRESS: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_TextProgress", 284_190_520, VertexFormat::Float32);
+
+/// Optional attributes of the glyph meshes carried over into the merged mesh.
+const CARRIED_ATTRIBUTES: [MeshVertexAttribute; 4] = [
+    Mesh::ATTRIBUTE_UV_1,
+    Mesh::ATTRIBUTE_COLOR,
+    ATTRIBUTE_DEPTH_FRACTION,
+    ATTRIBUTE_SURFACE,
+];
+
+/// The merged mesh of one material.
+struct MergedMesh<M: Asset> {
+    material: Handle<M>,
+    mesh: Mesh,
+}
+
+impl<M: Asset> MergedMesh<M> {
+    fn new(material: Handle<M>, topology: PrimitiveTopology) -> Self {
+        let mesh = Mesh::new(
+            topology,
+            RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, Vec::<[f32; 3]>::new())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, Vec::<[f32; 2]>::new())
+        .with_inserted_attribute(ATTRIBUTE_GLYPH, Vec::<[f32; 2]>::new())
+        .with_inserted_attribute(ATTRIBUTE_TEXT_PROGRESS, Vec::<f32>::new())
+        .with_inserted_indices(Indices::U32(Vec::new()));
+        Self { material, mesh }
+    }
+
+    /// Appends `glyph` placed at `transform`, tagged with `source` out of `glyph_count` laid
+    /// out glyphs.
+    fn append(
+        &mut self,
+        glyph: &Mesh,
+        transform: &Transform,
+        source: GlyphSource,
+        glyph_count: usize,
+    ) {
+        let (Some(positions), Some(normals), Some(uvs)) = (
+            glyph
+                .attribute(Mesh::ATTRIBUTE_POSITION)
+                .and_then(VertexAttributeValues::as_float3),
+            glyph
+                .attribute(Mesh::ATTRIBUTE_NORMAL)
+                .and_then(VertexAttributeValues::as_float3),
+            glyph.attribute(Mesh::ATTRIBUTE_UV_0),
+        ) else {
+            return;
+        };
+        let start = self.mesh.count_vertices() as u32;
+        let affine = transform.compute_affine();
+        let normal_matrix = Mat3::from(affine.matrix3).inverse().transpose();
+        let tag = [source.index as f32, source.character as u32 as f32];
+
+        for attribute in CARRIED_ATTRIBUTES {
+            // Only carried over while every glyph so far had it
+            let merged_has = self.mesh.attribute(attribute).is_some();
+            match (glyph.attribute(attribute), start == 0 || merged_has) {
+                (Some(values), true) => {
+                    let values = values.clone();
+                    match self.mesh.attribute_mut(attribute) {
+                        Some(merged) => extend(merged, values),
+                        None => self.mesh.insert_attribute(attribute, values),
+                    }
+                }
+                _ => {
+                    self.mesh.remove_attribute(attribute);
+                }
+            }
+        }
+        if let Some(VertexAttributeValues::Float32x3(merged)) =
+            self.mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            merged.extend(
+                positions
+                    .iter()
+                    .map(|&p| affine.transform_point3(Vec3::from(p)).to_array()),
+            );
+        }
+        if let Some(VertexAttributeValues::Float32x3(merged)) =
+            self.mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+        {
+            merged.extend(normals.iter().map(|&n| {
+                (normal_matrix * Vec3::from(n))
+                    .normalize_or_zero()
+                    .to_array()
+            }));
+        }
+        if let Some(merged) = self.mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0) {
+            extend(merged, uvs.clone());
+        }
+        if let Some(VertexAttributeValues::Float32x2(merged)) =
+            self.mesh.attribute_mut(ATTRIBUTE_GLYPH)
+        {
+            merged.extend(std::iter::repeat_n(tag, positions.len()));
+        }
+        if let Some(VertexAttributeValues::Float32(merged)) =
+            self.mesh.attribute_mut(ATTRIBUTE_TEXT_PROGRESS)
+        {
+            // Across the glyph in its own space, so the mirrored back side reads the same way
+            let (min_x, max_x) = positions
+                .iter()
+                .fold((f32::MAX, f32::MIN), |(min, max), p| {
+                    (min.min(p[0]), max.max(p[0]))
+                });
+            let width = (max_x - min_x).max(f32::EPSILON);
+            let share = 1.0 / glyph_count.max(1) as f32;
+            merged.extend(positions.iter().map(|p| {
+                let across = ((p[0] - min_x) / width).clamp(0.0, 1.0);
+                (source.index as f32 + across) * share
+            }));
+        }
+        if let (Some(Indices::U32(merged)), Some(indices)) =
+            (self.mesh.indices_mut(), glyph.indices())
+        {
+            merged.extend(indices.iter().map(|i| start + i as u32));
+        }
+    }
+}
+
+/// Appends `values` to `merged`, if both hold the same format.
+fn extend(merged: &mut VertexAttributeValues, values: VertexAttributeValues) {
+    use VertexAttributeValues as V;
+    match (merged, values) {
+        (V::Float32(merged), V::Float32(values)) => merged.extend(values),
+        (V::Float32x2(merged), V::Float32x2(values)) => merged.extend(values),
+        (V::Float32x3(merged), V::Float32x3(values)) => merged.extend(values),
+        (V::Float32x4(merged), V::Float32x4(values)) => merged.extend(values),
+        _ => {}
+    }
+}
+
+/// Generates `text` like [`crate::generate_meshes`], merged into one mesh per material in the
+/// space of the text, so a whole label is a single entity.
+///
+/// Every vertex carries its glyph in [`ATTRIBUTE_GLYPH`], for shaders animating letters one
+/// by one, e.g. waves or color cycling, and its position along the text in
+/// [`ATTRIBUTE_TEXT_PROGRESS`], for dissolve and reveal effects. The merged meshes are not
+/// shared through the glyph cache, and glyph meshes skinned with [`Parameters::skinned`] lose
+/// their joints.
+pub fn generate_merged_meshes<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    let (tx, materials) = layout_text(text, &mut fonts.font_system, &mut params)?;
+    if materials.is_empty() {
+        return Err(MeshTextError::InvalidInput);
+    }
+    let Settings {
+        font_system,
+        text_scale_factor,
+        glyph_cache,
+    } = fonts;
+    let scale = params.world_scale(*text_scale_factor);
+    let front_z = params.front_z(scale);
+
+    let (sources, mut entries): (Vec<_>, Vec<_>) = tx
+        .generate_mesh_glyphs_with_source(font_system, glyph_cache, &params, meshes, &materials)
+        .map(|(source, glyph)| {
+            let entry = staggered_glyph_entry(&source, glyph, scale, front_z, &params);
+            (source, entry)
+        })
+        .unzip();
+    if params.double_sided {
+        add_back_side(&mut entries, params.layout_depth() * scale.z);
+    }
+
+    let glyph_count = tx.layout_runs().map(|run| run.glyphs.len()).sum();
+    let mut merged: Vec<MergedMesh<M>> = Vec::new();
+    // The back side copies come after the front glyphs, in the same order
+    for (entry, source) in entries.iter().zip(sources.iter().cycle()) {
+        let Some(glyph) = meshes.get(&entry.mesh) else {
+            continue;
+        };
+        let id: AssetId<M> = entry.material.id();
+        let index = match merged.iter().position(|m| m.material.id() == id) {
+            Some(index) => index,
+            None => {
+                merged.push(MergedMesh::new(
+                    entry.material.clone(),
+                    glyph.primitive_topology(),
+                ));
+                merged.len() - 1
+            }
+        };
+        merged[index].append(glyph, &entry.transform, *source, glyph_count);
+    }
+
+    Ok(merged
+        .into_iter()
+        .map(|merged| MeshTextEntry {
+            mesh: meshes.add(merged.mesh),
+            material: merged.material,
+            transform: Transform::IDENTITY,
+        })
+        .collect())
+}
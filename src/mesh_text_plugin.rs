@@ -1,44 +1,441 @@
-use crate::text_glyphs::TextGlyphs;
-use crate::{InputText, MeshTextError};
-use crate::{MeshTextEntry, Parameters};
+use crate::async_generation::finish_text_generation;
+use crate::color_materials::ColorMaterials;
+use crate::debug_text::{DebugText3d, draw_debug_text};
+use crate::deferred_text::update_deferred_text;
+use crate::editor::update_mesh_text_editors;
+use crate::entity_pool::GlyphEntityPool;
+use crate::extrusion_animator::animate_extrusion;
+use crate::generation_report::{GenerationReport, GenerationWarning};
+use crate::glyph_cache::{GlyphCache, GlyphCacheStats, update_glyph_cache_stats};
+use crate::incremental_text::{IncrementalGlyphs, update_incremental_text};
+use crate::screen_anchor::update_screen_anchors;
+use crate::scrolling_text::update_scrolling_text;
+use crate::text_cache::TextCache;
+use crate::text_glyphs::{GlyphSource, TextGlyphs, mesh_glyph};
+use crate::timer_text::{tick_timer_text, update_timer_text};
+use crate::{InputText, MeshTextError, UvMode};
+use crate::{LayoutGrid, MeshGlyph, MeshTextEntry, Parameters};
 use bevy::prelude::*;
+use bevy::render::view::VisibilitySystems;
 use cosmic_text::fontdb::{Database, Source};
+use cosmic_text::{Attrs, FontSystem, Metrics};
+use std::collections::HashMap;
 use std::sync::Arc;
-use cosmic_text::{FontSystem, Metrics};
 
+/// Sets up the font system, the glyph cache and the systems of the crate.
+///
+/// The scale factor is only the default world scale. Texts can use their own with
+/// [`Parameters::scale`].
 pub struct MeshTextPlugin(f32);
 
 impl MeshTextPlugin {
+    /// Uses `text_scale_factor` as the default world scale of every text.
     pub fn new(text_scale_factor: f32) -> Self {
         Self(text_scale_factor)
     }
 }
 
+impl Default for MeshTextPlugin {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
 impl Plugin for MeshTextPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Settings {
-            font_system: {
-                // Load a single, embedded font into a custom font database so we avoid an expensive scan of the host system fonts.
-                // NOTE: Replace the placeholder "Roboto-Bold.ttf" with the actual font you want to embed.
-                let font_source = Source::Binary(Arc::new(include_bytes!("../assets/centurygothic_bold.ttf").to_vec()));
-
-                let mut font_db = Database::new();
-                font_db.load_font_source(font_source);
-
-                // Initialise the FontSystem with a fixed locale and our prepared database
-                FontSystem::new_with_locale_and_db(String::from("en-US"), font_db)
-            },
-            text_scale_factor: self.0,
-        });
+        app.insert_resource(Settings::new(embedded_font_system(), self.0))
+            .init_resource::<FontContexts>()
+            .init_resource::<ColorMaterials>()
+            .init_resource::<TextCache<StandardMaterial>>()
+            .init_resource::<GlyphCacheStats>()
+            .init_resource::<GlyphEntityPool>()
+            .init_resource::<DebugText3d>()
+            .init_resource::<IncrementalGlyphs>()
+            .add_systems(
+                Update,
+                (
+                    (tick_timer_text, update_timer_text).chain(),
+                    animate_extrusion::<StandardMaterial>,
+                    finish_text_generation::<StandardMaterial>,
+                    update_incremental_text::<StandardMaterial>,
+                    update_scrolling_text::<StandardMaterial>,
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    draw_debug_text,
+                    update_mesh_text_editors::<StandardMaterial>,
+                    update_screen_anchors,
+                )
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(
+                PostUpdate,
+                update_deferred_text::<StandardMaterial>
+                    .after(TransformSystem::TransformPropagate)
+                    .after(VisibilitySystems::UpdateFrusta),
+            )
+            .add_systems(Last, update_glyph_cache_stats);
+        #[cfg(feature = "raycast")]
+        app.init_resource::<crate::raycast::GlyphBvhs>();
     }
 }
 
+/// Builds a `FontSystem` containing only the embedded font.
+pub fn embedded_font_system() -> FontSystem {
+    // Load a single, embedded font into a custom font database so we avoid an expensive scan of the host system fonts.
+    // NOTE: Replace the placeholder "Roboto-Bold.ttf" with the actual font you want to embed.
+    let font_source = Source::Binary(Arc::new(
+        include_bytes!("../assets/centurygothic_bold.ttf").to_vec(),
+    ));
+
+    let mut font_db = Database::new();
+    font_db.load_font_source(font_source);
+
+    // Initialise the FontSystem with a fixed locale and our prepared database
+    FontSystem::new_with_locale_and_db(String::from("en-US"), font_db)
+}
+
+/// Lays out `text` and generates its glyph meshes, ready to spawn with the returned
+/// transforms. Glyph meshes are shared through the glyph cache of `fonts`.
+///
+/// Only `fonts` and `meshes` are used, so any `Settings`, not only the resource of the
+/// plugin, and any mesh storage work, e.g. in exclusive systems or asset processors.
 pub fn generate_meshes<M: Asset>(
     text: InputText<M>,
-    fonts: &mut ResMut<Settings>,
+    fonts: &mut Settings,
     params: Parameters,
-    meshes: &mut ResMut<Assets<Mesh>>,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    generate_meshes_with_report(text, fonts, params, meshes).map(|(entries, _)| entries)
+}
+
+/// Generates the glyph meshes of `text` like [`generate_meshes`], with a report of what the
+/// glyphs ran into, e.g. fonts missing glyphs or outlines that needed repairs, for
+/// applications to log or collect.
+pub fn generate_meshes_with_report<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    meshes: &mut Assets<Mesh>,
+) -> Result<(Vec<MeshTextEntry<M>>, GenerationReport), MeshTextError> {
+    let (tx, materials) = layout_text(text, &mut fonts.font_system, &mut params)?;
+    let Settings {
+        font_system,
+        text_scale_factor,
+        glyph_cache,
+    } = fonts;
+    let mut report = GenerationReport::default();
+    let entries = glyph_entries(
+        &tx,
+        &materials,
+        font_system,
+        glyph_cache,
+        *text_scale_factor,
+        &params,
+        meshes,
+        &mut report,
+    )?;
+    Ok((entries, report))
+}
+
+/// Generates the glyph meshes of `text` like [`generate_meshes`] from a bare `FontSystem`,
+/// without the plugin or a [`Settings`] context.
+///
+/// Meshes are only shared between the glyphs of this text, and the world scale is
+/// [`Parameters::scale`] or `1.0`. Keep a [`Settings`] around instead to share glyph meshes
+/// between calls.
+pub fn generate_meshes_with_font_system<M: Asset>(
+    text: InputText<M>,
+    font_system: &mut FontSystem,
+    mut params: Parameters,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    let (tx, materials) = layout_text(text, font_system, &mut params)?;
+    glyph_entries(
+        &tx,
+        &materials,
+        font_system,
+        &mut GlyphCache::default(),
+        1.0,
+        &params,
+        meshes,
+        &mut GenerationReport::default(),
+    )
+}
+
+/// Generates glyph meshes for text that was already shaped and laid out, e.g. with
+/// [`TextGlyphs::from_buffer`]. Glyphs pick their material by the `metadata` of their span,
+/// falling back to the first material.
+///
+/// The layout is taken as it is. `params` are validated, and give the extrusion options, the
+/// [`scale`](Parameters::scale), and the [`stagger`](Parameters::stagger),
+/// [`grid`](Parameters::grid), [`double_sided`](Parameters::double_sided) and
+/// [`depth_anchor`](Parameters::depth_anchor) the glyphs are placed with; their font size,
+/// line height and other layout settings are ignored.
+pub fn generate_meshes_from_glyphs<M: Asset>(
+    tx: &TextGlyphs,
+    materials: &[Handle<M>],
+    fonts: &mut Settings,
+    params: &Parameters,
+    meshes: &mut Assets<Mesh>,
 ) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    params.validate()?;
+    let Settings {
+        font_system,
+        text_scale_factor,
+        glyph_cache,
+    } = fonts;
+    glyph_entries(
+        tx,
+        materials,
+        font_system,
+        glyph_cache,
+        *text_scale_factor,
+        params,
+        meshes,
+        &mut GenerationReport::default(),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn glyph_entries<M: Asset>(
+    tx: &TextGlyphs,
+    materials: &[Handle<M>],
+    font_system: &mut FontSystem,
+    glyph_cache: &mut GlyphCache,
+    text_scale_factor: f32,
+    params: &Parameters,
+    meshes: &mut Assets<Mesh>,
+    report: &mut GenerationReport,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    if materials.is_empty() {
+        return Err(MeshTextError::InvalidInput);
+    }
+    let scale = params.world_scale(text_scale_factor);
+    let front_z = params.front_z(scale);
+
+    let mut entries = Vec::new();
+    for (source, glyph_data, log) in
+        tx.generate_mesh_glyphs_logged(font_system, glyph_cache, params, meshes, materials)
+    {
+        report.cache_hits += log.cached as usize;
+        report.tessellated += log.tessellated as usize;
+        report
+            .warnings
+            .extend(log.issues.into_iter().map(|issue| GenerationWarning {
+                line: source.line,
+                index: source.index,
+                character: source.character,
+                glyph_id: source.glyph_id,
+                issue,
+            }));
+        if let Some(glyph_data) = glyph_data {
+            entries.push(staggered_glyph_entry(
+                &source, glyph_data, scale, front_z, params,
+            ));
+        }
+    }
+    report.cache = glyph_cache.stats();
+    if params.double_sided {
+        add_back_side(&mut entries, params.layout_depth() * scale.z);
+    }
+    Ok(entries)
+}
+
+/// Tessellates and caches the glyphs of `chars` ahead of time, e.g. the digits of a
+/// scoreboard during a loading screen, so later `generate_meshes` calls with the same `attrs`
+/// and extrusion options find them in the cache. Returns the number of newly cached meshes,
+/// or the error of `params` that don't validate.
+///
+/// Each character is shaped on its own, so ligatures and contextual forms are not warmed.
+/// Nothing is cached with [`UvMode::TextBlock`], whose meshes are
+/// never shared.
+pub fn prewarm_glyphs(
+    chars: impl IntoIterator<Item = char>,
+    attrs: &Attrs,
+    fonts: &mut Settings,
+    params: &Parameters,
+    meshes: &mut Assets<Mesh>,
+) -> Result<usize, MeshTextError> {
+    params.validate()?;
+    let options = params.extrusion_options();
+    if options.uv_mode == UvMode::TextBlock {
+        return Ok(0);
+    }
+    // One character per line, so none is shaped together with its neighbors
+    let text = chars
+        .into_iter()
+        .filter(|c| !c.is_control())
+        .fold(String::new(), |mut text, c| {
+            text.push(c);
+            text.push('\n');
+            text
+        });
+    if text.is_empty() {
+        return Ok(0);
+    }
+
+    let Settings {
+        font_system,
+        glyph_cache,
+        ..
+    } = fonts;
+    let metrics = Metrics {
+        font_size: params.font_size,
+        line_height: params.line_height,
+    };
+    let mut tx = TextGlyphs::new(
+        metrics,
+        [(text.as_str(), attrs.clone())],
+        attrs,
+        font_system,
+        None,
+    );
+    tx.measure(None, None, font_system);
+
+    let before = glyph_cache.len();
+    let materials = [Handle::<StandardMaterial>::default()];
+    for run in tx.layout_runs() {
+        for glyph in run.glyphs {
+            mesh_glyph(
+                font_system,
+                glyph_cache,
+                params,
+                &options,
+                meshes,
+                &materials,
+                glyph,
+                run.line_y,
+                None,
+            );
+        }
+    }
+    Ok(glyph_cache.len() - before)
+}
+
+/// Places a glyph mesh at its visual center in world space, with its front face at `front_z`,
+/// snapped to the world grid of `grid`. The center is offset from the glyph's origin on the
+/// baseline of its line, so glyphs of mixed sizes share the baseline.
+pub(crate) fn glyph_entry<M: Asset>(
+    glyph_data: MeshGlyph<M>,
+    scale: Vec3,
+    front_z: f32,
+    grid: Option<LayoutGrid>,
+) -> MeshTextEntry<M> {
+    // Calculate the target world position for the glyph's visual center.
+    // glyph_data.x, .y, .x_offset, .y_offset, .line_y are from CosmicText layout.
+    // glyph_data.glyph_center_x_layout and .glyph_center_y_layout are the offsets
+    // from the glyph's own layout origin to its visual center, in layout units.
+    // (Layout units are scaled by font_size relative to font design units).
+
+    let target_center_x_layout_units =
+        glyph_data.x + glyph_data.x_offset + glyph_data.glyph_center_x_layout;
+
+    // Calculate the Y position for the glyph's visual center in Bevy's Y-up world space.
+    // 1. Sum CosmicText's Y-down layout components:
+    //    line_y: baseline position (Y increases downwards from top of text buffer).
+    //    glyph.y: glyph's offset from baseline (Y increases downwards if positive).
+    //    glyph.y_offset: additional Y offset in the same system.
+    let sum_y_components_layout_down = glyph_data.line_y + glyph_data.y + glyph_data.y_offset;
+
+    // 2. Convert the sum to a Y-up Bevy coordinate. If CosmicText Y=0 (top) is Bevy Y=H,
+    //    and CosmicText Y=H (bottom) is Bevy Y=0, this would be (H_text_block - sum_y_components_layout_down).
+    //    Simpler: if mapping Cosmic Y=0 to Bevy Y=0 and flipping axis: Bevy_Y_up = -Cosmic_Y_down.
+    let glyph_origin_y_layout_bevy_up = -sum_y_components_layout_down;
+
+    // 3. Add the glyph's intrinsic Y-up center offset.
+    //    glyph_data.glyph_center_y_layout is the Y-up distance from the glyph's font origin to its visual center.
+    let target_center_y_layout_units_bevy_up =
+        glyph_origin_y_layout_bevy_up + glyph_data.glyph_center_y_layout;
+
+    let world_x = target_center_x_layout_units * scale.x;
+    let world_y = target_center_y_layout_units_bevy_up * scale.y; // Use the new Y-up calculation
+
+    let translation = Vec3::new(world_x, world_y, front_z);
+    MeshTextEntry {
+        mesh: glyph_data.mesh,
+        material: glyph_data.material,
+        transform: Transform::from_translation(
+            grid.map_or(translation, |grid| grid.snap_translation(translation)),
+        )
+        .with_scale(scale * glyph_data.mesh_scale),
+    }
+}
+
+/// Places a glyph like [`glyph_entry`], moved along Z by the [`Parameters::stagger`] of its
+/// place in the text.
+pub(crate) fn staggered_glyph_entry<M: Asset>(
+    source: &GlyphSource,
+    glyph_data: MeshGlyph<M>,
+    scale: Vec3,
+    front_z: f32,
+    params: &Parameters,
+) -> MeshTextEntry<M> {
+    let stagger = params
+        .stagger
+        .map_or(0.0, |stagger| stagger.offset(source.line, source.column));
+    glyph_entry(glyph_data, scale, front_z + stagger, params.grid)
+}
+
+/// Appends a copy of every glyph turned to face backwards, for [`Parameters::double_sided`].
+///
+/// The copies sit right behind the glyphs, `depth` deep, and are laid out mirrored so the
+/// text reads from left to right from behind. They share the meshes of the front glyphs, so
+/// their winding and UVs stay correct.
+pub(crate) fn add_back_side<M: Asset>(entries: &mut Vec<MeshTextEntry<M>>, depth: f32) {
+    let mirror = back_side_mirror(entries);
+    add_mirrored_back_side(entries, mirror, depth);
+}
+
+/// The sum of the lowest and highest X of `entries`, which their back side is mirrored about.
+pub(crate) fn back_side_mirror<M: Asset>(entries: &[MeshTextEntry<M>]) -> f32 {
+    let (min_x, max_x) = entries
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(min, max), entry| {
+            let x = entry.transform.translation.x;
+            (min.min(x), max.max(x))
+        });
+    min_x + max_x
+}
+
+/// Appends the back side of `entries` like [`add_back_side`], mirrored about `mirror` from
+/// [`back_side_mirror`], e.g. of the glyphs that other entries like rules belong to.
+pub(crate) fn add_mirrored_back_side<M: Asset>(
+    entries: &mut Vec<MeshTextEntry<M>>,
+    mirror: f32,
+    depth: f32,
+) {
+    let back: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            let mut transform = entry.transform;
+            transform.translation.x = mirror - transform.translation.x;
+            // The turned glyph spans from -depth to 0, moved behind the front glyph
+            transform.translation.z += 2.0 * depth;
+            transform.rotate_y(std::f32::consts::PI);
+            MeshTextEntry {
+                mesh: entry.mesh.clone(),
+                material: entry.material.clone(),
+                transform,
+            }
+        })
+        .collect();
+    entries.extend(back);
+}
+
+/// Validates `params`, then shapes `text` and lays it out within the bounds they give.
+///
+/// With a [`Parameters::fit_box`], the font size and line height of `params` are replaced by
+/// those the text fits the box with, and the box is taken out, so the caller generates the
+/// glyphs at that size and laying them out again doesn't fit them again.
+pub(crate) fn layout_text<M: Asset>(
+    text: InputText<M>,
+    font_system: &mut FontSystem,
+    params: &mut Parameters,
+) -> Result<(TextGlyphs, Vec<Handle<M>>), MeshTextError> {
+    params.validate()?;
     if !text.is_valid() {
         error!("Invalid text input");
         return Err(MeshTextError::InvalidInput);
@@ -72,68 +469,160 @@ pub fn generate_meshes<M: Asset>(
         line_height: params.line_height,
     };
 
-    let text_scale_factor = fonts.text_scale_factor;
-
     let mut tx = TextGlyphs::new(
         default_metrics,
         spans,
         &default_attrs,
-        &mut fonts.font_system,
+        font_system,
         params.alignment,
     );
-    let (_width, _height) = tx.measure(params.max_width, params.max_height, &mut fonts.font_system);
-    let processed_glyphs = tx.generate_mesh_glyphs(
-        &mut fonts.font_system,
-        params.extrusion_depth,
-        meshes,
-        &materials,
-    );
+    if let Some(fit_box) = params.fit_box.take() {
+        let target = fit_box / params.world_scale(1.0).truncate();
+        let font_size = fit_font_size(&mut tx, font_system, params, target);
+        params.line_height *= font_size / params.font_size;
+        params.font_size = font_size;
+        params.validate()?;
+    } else {
+        measure_layout(&mut tx, font_system, params);
+    }
 
-    let mut meshes = Vec::new();
-
-    for glyph_data in processed_glyphs {
-        // Calculate the target world position for the glyph's visual center.
-        // glyph_data.x, .y, .x_offset, .y_offset, .line_y are from CosmicText layout.
-        // glyph_data.glyph_center_x_layout and .glyph_center_y_layout are the offsets
-        // from the glyph's own layout origin to its visual center, in layout units.
-        // (Layout units are scaled by font_size relative to font design units).
-
-        let target_center_x_layout_units =
-            glyph_data.x + glyph_data.x_offset + glyph_data.glyph_center_x_layout;
-
-        // Calculate the Y position for the glyph's visual center in Bevy's Y-up world space.
-        // 1. Sum CosmicText's Y-down layout components:
-        //    line_y: baseline position (Y increases downwards from top of text buffer).
-        //    glyph.y: glyph's offset from baseline (Y increases downwards if positive).
-        //    glyph.y_offset: additional Y offset in the same system.
-        let sum_y_components_layout_down = glyph_data.line_y + glyph_data.y + glyph_data.y_offset;
-
-        // 2. Convert the sum to a Y-up Bevy coordinate. If CosmicText Y=0 (top) is Bevy Y=H,
-        //    and CosmicText Y=H (bottom) is Bevy Y=0, this would be (H_text_block - sum_y_components_layout_down).
-        //    Simpler: if mapping Cosmic Y=0 to Bevy Y=0 and flipping axis: Bevy_Y_up = -Cosmic_Y_down.
-        let glyph_origin_y_layout_bevy_up = -sum_y_components_layout_down;
-
-        // 3. Add the glyph's intrinsic Y-up center offset.
-        //    glyph_data.glyph_center_y_layout is the Y-up distance from the glyph's font origin to its visual center.
-        let target_center_y_layout_units_bevy_up =
-            glyph_origin_y_layout_bevy_up + glyph_data.glyph_center_y_layout;
-
-        let world_x = target_center_x_layout_units * text_scale_factor;
-        let world_y = target_center_y_layout_units_bevy_up * text_scale_factor; // Use the new Y-up calculation
-
-        meshes.push(MeshTextEntry {
-            mesh: glyph_data.mesh,
-            material: glyph_data.material,
-            transform: Transform::from_xyz(world_x, world_y, 0.0)
-                .with_scale(Vec3::splat(text_scale_factor)),
+    Ok((tx, materials))
+}
+
+/// Doublings of the font size tried while looking for a size that fits the box, and halvings
+/// of the gap around it after
+const FIT_STEPS: usize = 12;
+
+/// Lays out `tx` at the metrics it has and returns its size in layout units.
+fn measure_layout(tx: &mut TextGlyphs, font_system: &mut FontSystem, params: &Parameters) -> Vec2 {
+    let (_width, mut height) = tx.measure(params.max_width, params.max_height, font_system);
+    if let Some(grid) = params.grid {
+        tx.snap_to_grid(grid.cell_width, params.max_width, params.alignment);
+    } else if params.drop_cap.is_some() || !params.exclusion_zones.is_empty() {
+        match params.drop_cap {
+            Some(drop_cap) => tx.set_drop_cap(
+                drop_cap,
+                &params.exclusion_zones,
+                params.max_width,
+                font_system,
+            ),
+            None => tx.flow_around(&params.exclusion_zones, params.max_width),
+        }
+        height = tx.layout_runs().fold(0.0, |height: f32, run| {
+            height.max(run.line_top + run.line_height)
         });
     }
+    let width = tx
+        .layout_runs()
+        .fold(0.0, |width: f32, run| width.max(run.line_w));
+    Vec2::new(width, height)
+}
+
+/// Searches the largest font size `tx` fits in `target` with, in layout units, and leaves it
+/// laid out at that size. Only the layout is redone for every size, the text isn't shaped
+/// again. The line height scales with the font size, and the size stays within the maximum
+/// width. Text without glyphs keeps its size.
+fn fit_font_size(
+    tx: &mut TextGlyphs,
+    font_system: &mut FontSystem,
+    params: &Parameters,
+    target: Vec2,
+) -> f32 {
+    measure_layout(tx, font_system, params);
+    if tx.layout_runs().all(|run| run.glyphs.is_empty()) {
+        return params.font_size;
+    }
+    let mut fits = |font_size: f32| {
+        let line_height = params.line_height * font_size / params.font_size;
+        tx.buffer_mut()
+            .set_metrics(font_system, Metrics::new(font_size, line_height));
+        measure_layout(tx, font_system, params).cmple(target).all()
+    };
 
-    Ok(meshes)
+    let largest = params.max_width.unwrap_or(f32::INFINITY);
+    let (mut low, mut high) = (params.font_size, params.font_size);
+    if fits(params.font_size) {
+        for _ in 0..FIT_STEPS {
+            if low == largest {
+                break;
+            }
+            high = (low * 2.0).min(largest);
+            if !fits(high) {
+                break;
+            }
+            low = high;
+        }
+    } else {
+        for _ in 0..FIT_STEPS {
+            high = low;
+            low /= 2.0;
+            if fits(low) {
+                break;
+            }
+        }
+    }
+    for _ in 0..FIT_STEPS {
+        if low == high {
+            break;
+        }
+        let middle = (low + high) / 2.0;
+        if fits(middle) {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    fits(low);
+    low
 }
 
+/// A font context: the fonts to lay out text with and the glyph meshes generated from them.
+///
+/// The plugin adds one as a resource. Texts that need other fonts, e.g. a decorative font
+/// database next to the UI fonts, can pass their own `Settings` to `generate_meshes`, for
+/// instance from [`FontContexts`].
 #[derive(Resource)]
 pub struct Settings {
     pub font_system: FontSystem,
     pub text_scale_factor: f32,
+    /// Glyph meshes reused across `generate_meshes` calls
+    pub glyph_cache: GlyphCache,
+}
+
+impl Settings {
+    /// Creates a font context with an empty glyph cache.
+    pub fn new(font_system: FontSystem, text_scale_factor: f32) -> Self {
+        Self {
+            font_system,
+            text_scale_factor,
+            glyph_cache: GlyphCache::default(),
+        }
+    }
+}
+
+/// Named font contexts besides the [`Settings`] resource, each with its own font database and
+/// glyph cache.
+#[derive(Resource, Default)]
+pub struct FontContexts {
+    contexts: HashMap<String, Settings>,
+}
+
+impl FontContexts {
+    pub fn insert(&mut self, name: impl Into<String>, settings: Settings) -> &mut Self {
+        self.contexts.insert(name.into(), settings);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Settings> {
+        self.contexts.get(name)
+    }
+
+    /// The context `name`, to pass to `generate_meshes`.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Settings> {
+        self.contexts.get_mut(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Settings> {
+        self.contexts.remove(name)
+    }
 }
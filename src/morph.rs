@@ -0,0 +1,299 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::mesh::morph::{MorphAttributes, MorphTargetImage};
+use cosmic_text::FontSystem;
+
+use crate::extrude_glyph::{ExtrudedGlyphGeometry, ExtrusionOptions, OutputTopology};
+use crate::mesh_text_plugin::{layout_text, staggered_glyph_entry};
+use crate::text_glyphs::tessellate_layout_glyph;
+use crate::{
+    DepthAnchor, InputText, MeshGlyph, MeshTextEntry, MeshTextError, Parameters, Settings,
+};
+
+/// Computes the morph target that moves `base` onto `target`.
+///
+/// Both geometries must share their topology, which is the case when they only differ in
+/// depths: the extrusion depth or the depth of the front inset.
+pub fn morph_target(
+    base: &ExtrudedGlyphGeometry,
+    target: &ExtrudedGlyphGeometry,
+) -> Result<Vec<MorphAttributes>, MeshTextError> {
+    if base.vertices.len() != target.vertices.len() || base.indices != target.indices {
+        return Err(MeshTextError::MorphTopologyMismatch);
+    }
+
+    Ok(base
+        .vertices
+        .iter()
+        .zip(&base.normals)
+        .zip(target.vertices.iter().zip(&target.normals))
+        .map(|((position, normal), (target_position, target_normal))| {
+            MorphAttributes::new(
+                target_position - position,
+                target_normal - normal,
+                Vec3::ZERO,
+            )
+        })
+        .collect())
+}
+
+/// Moves `target`, extruded `target_depth` deep, along Z so that it sits by `anchor` where a
+/// glyph extruded `base_depth` deep would, and the text stays anchored while it blends.
+pub(crate) fn anchor_target(
+    target: &mut ExtrudedGlyphGeometry,
+    anchor: DepthAnchor,
+    base_depth: f32,
+    target_depth: f32,
+) {
+    let shift = anchor.front_z(target_depth) - anchor.front_z(base_depth);
+    if shift != 0.0 {
+        for vertex in &mut target.vertices {
+            vertex.z += shift;
+        }
+    }
+}
+
+/// Generates glyph meshes with a single morph target that blends from the extrusion of
+/// `params` (weight 0) to the extrusion of `target` (weight 1).
+///
+/// Layout, materials and the missing glyph policy come from `params`; only the depths of
+/// `target` are used, e.g. to animate text growing out of a flat plane. Spawn the entries with
+/// a `MeshMorphWeights` component to drive the blend. The meshes are not cached, and are
+/// always triangles, whatever the [`topology`](Parameters::topology).
+pub fn generate_morph_meshes<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    target: Parameters,
+    meshes: &mut Assets<Mesh>,
+    images: &mut ResMut<Assets<Image>>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    let scale = params.world_scale(fonts.text_scale_factor);
+    let font_system = &mut fonts.font_system;
+    let (tx, materials) = layout_text(text, font_system, &mut params)?;
+    let front_z = params.front_z(scale);
+
+    let options = morph_options(&params);
+    let target_options = morph_options(&target);
+
+    let mut entries = Vec::new();
+    for (source, glyph, line_y) in tx.layout_glyphs() {
+        let policy = params.missing_glyph_policy;
+        let Some((base, center_x_layout, center_y_layout)) =
+            tessellate_layout_glyph(font_system, glyph, policy, &options)?
+        else {
+            continue;
+        };
+        let Some((mut morphed, ..)) =
+            tessellate_layout_glyph(font_system, glyph, policy, &target_options)?
+        else {
+            continue;
+        };
+
+        anchor_target(
+            &mut morphed,
+            params.depth_anchor,
+            params.layout_depth(),
+            target.layout_depth(),
+        );
+        let attributes = morph_target(&base, &morphed)?;
+        let mesh = morphed_mesh(base, attributes, images)?;
+
+        let material = materials
+            .get(glyph.metadata)
+            .unwrap_or_else(|| &materials[0])
+            .clone();
+        let center = Vec2::new(center_x_layout, center_y_layout);
+        let glyph = MeshGlyph::at_layout_size(glyph, line_y, center, meshes.add(mesh), material);
+        entries.push(staggered_glyph_entry(
+            &source, glyph, scale, front_z, &params,
+        ));
+    }
+
+    Ok(entries)
+}
+
+/// The extrusion options of `params` for glyphs to morph, which are always triangles.
+fn morph_options(params: &Parameters) -> ExtrusionOptions {
+    ExtrusionOptions {
+        topology: OutputTopology::Triangles,
+        ..params.extrusion_options()
+    }
+}
+
+/// A mesh of `base` with `attributes` as its single morph target.
+fn morphed_mesh(
+    base: ExtrudedGlyphGeometry,
+    attributes: Vec<MorphAttributes>,
+    images: &mut Assets<Image>,
+) -> Result<Mesh, MeshTextError> {
+    let morph_targets = MorphTargetImage::new(
+        [attributes.into_iter()].into_iter(),
+        base.vertices.len(),
+        RenderAssetUsages::RENDER_WORLD,
+    )?;
+    let mut mesh = Mesh::from(base);
+    mesh.set_morph_targets(images.add(morph_targets.0));
+    Ok(mesh)
+}
+
+/// A tessellated glyph of one of the texts of a string morph, with the entry placing it like
+/// [`crate::generate_meshes`] would. The entry gets its mesh once the morph is built.
+struct PlacedGlyph<M: Asset> {
+    geometry: ExtrudedGlyphGeometry,
+    entry: MeshTextEntry<M>,
+}
+
+/// Lays out and tessellates `text`, returning its glyphs in layout order with `None` for
+/// glyphs without an outline.
+fn placed_glyphs<M: Asset>(
+    text: InputText<M>,
+    font_system: &mut FontSystem,
+    mut params: Parameters,
+    scale: Vec3,
+) -> Result<Vec<Option<PlacedGlyph<M>>>, MeshTextError> {
+    let (tx, materials) = layout_text(text, font_system, &mut params)?;
+    if materials.is_empty() {
+        return Err(MeshTextError::InvalidInput);
+    }
+    let front_z = params.front_z(scale);
+    let options = morph_options(&params);
+
+    let mut glyphs = Vec::new();
+    for (source, glyph, line_y) in tx.layout_glyphs() {
+        let tessellated =
+            tessellate_layout_glyph(font_system, glyph, params.missing_glyph_policy, &options)?;
+        glyphs.push(
+            tessellated.map(|(geometry, center_x_layout, center_y_layout)| {
+                let material = materials
+                    .get(glyph.metadata)
+                    .unwrap_or_else(|| &materials[0])
+                    .clone();
+                let center = Vec2::new(center_x_layout, center_y_layout);
+                let placed =
+                    MeshGlyph::at_layout_size(glyph, line_y, center, Handle::default(), material);
+                PlacedGlyph {
+                    geometry,
+                    entry: staggered_glyph_entry(&source, placed, scale, front_z, &params),
+                }
+            }),
+        );
+    }
+    Ok(glyphs)
+}
+
+/// Appends `other`, moved by `offset`, to `geometry`. Optional attributes are kept when both
+/// have them.
+fn append_geometry(
+    geometry: &mut ExtrudedGlyphGeometry,
+    other: ExtrudedGlyphGeometry,
+    offset: Vec3,
+) -> Result<(), MeshTextError> {
+    let first = geometry.vertices.len();
+    if first + other.vertices.len() > u16::MAX as usize {
+        return Err(MeshTextError::TessellationFailed);
+    }
+    geometry
+        .vertices
+        .extend(other.vertices.iter().map(|&vertex| vertex + offset));
+    geometry
+        .indices
+        .extend(other.indices.iter().map(|&index| index + first as u16));
+    geometry.normals.extend(other.normals);
+    geometry.uvs.extend(other.uvs);
+    fn extend<T>(values: &mut Option<Vec<T>>, other: Option<Vec<T>>) {
+        match (values.as_mut(), other) {
+            (Some(values), Some(other)) => values.extend(other),
+            _ => *values = None,
+        }
+    }
+    extend(&mut geometry.uv1, other.uv1);
+    extend(&mut geometry.edge_mask, other.edge_mask);
+    extend(&mut geometry.vertex_colors, other.vertex_colors);
+    extend(&mut geometry.depth_fraction, other.depth_fraction);
+    extend(&mut geometry.surface, other.surface);
+    geometry.issues.extend(other.issues);
+    Ok(())
+}
+
+/// Morph target shrinking every vertex of `geometry` into its center, around which glyphs
+/// are tessellated.
+fn shrink(geometry: &ExtrudedGlyphGeometry) -> Vec<MorphAttributes> {
+    geometry
+        .vertices
+        .iter()
+        .map(|&vertex| MorphAttributes::new(-vertex, Vec3::ZERO, Vec3::ZERO))
+        .collect()
+}
+
+/// Collapses every vertex of `geometry` into its center and returns the morph target growing
+/// them back out of it.
+fn grow(geometry: &mut ExtrudedGlyphGeometry) -> Vec<MorphAttributes> {
+    geometry
+        .vertices
+        .iter_mut()
+        .map(|vertex| MorphAttributes::new(std::mem::take(vertex), Vec3::ZERO, Vec3::ZERO))
+        .collect()
+}
+
+/// Generates glyph meshes with a single morph target that turns the text `from` (weight 0)
+/// into the text `to` (weight 1), both laid out with `params`.
+///
+/// Glyphs are matched by their place in the text. A glyph matched with the same glyph slides
+/// to where it sits in `to`, and one matched with another glyph shrinks away while the other
+/// grows in its place. Glyphs of the longer text without a match shrink away or grow where
+/// they are. Entries take the material of their glyph in `from`, or in `to` for glyphs only
+/// `to` has. Spawn the entries with a `MeshMorphWeights` component to drive the blend. The
+/// meshes are not cached, and are always triangles, whatever the
+/// [`topology`](Parameters::topology).
+pub fn generate_text_morph_meshes<M: Asset>(
+    from: InputText<M>,
+    to: InputText<M>,
+    fonts: &mut Settings,
+    params: Parameters,
+    meshes: &mut Assets<Mesh>,
+    images: &mut ResMut<Assets<Image>>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    let scale = params.world_scale(fonts.text_scale_factor);
+    let mut from = placed_glyphs(from, &mut fonts.font_system, params.clone(), scale)?;
+    let mut to = placed_glyphs(to, &mut fonts.font_system, params, scale)?;
+    let count = from.len().max(to.len());
+    from.resize_with(count, || None);
+    to.resize_with(count, || None);
+
+    let mut entries = Vec::new();
+    for pair in from.into_iter().zip(to) {
+        let (geometry, attributes, mut entry) = match pair {
+            (Some(from), Some(mut to)) => {
+                // From the place of `from` to that of `to`, in the space of the mesh
+                let offset = (to.entry.transform.translation - from.entry.transform.translation)
+                    / from.entry.transform.scale;
+                if from.geometry.vertices == to.geometry.vertices
+                    && from.geometry.indices == to.geometry.indices
+                {
+                    let slide = MorphAttributes::new(offset, Vec3::ZERO, Vec3::ZERO);
+                    let attributes = vec![slide; from.geometry.vertices.len()];
+                    (from.geometry, attributes, from.entry)
+                } else {
+                    let mut attributes = shrink(&from.geometry);
+                    attributes.extend(grow(&mut to.geometry));
+                    let mut geometry = from.geometry;
+                    append_geometry(&mut geometry, to.geometry, offset)?;
+                    (geometry, attributes, from.entry)
+                }
+            }
+            (Some(from), None) => {
+                let attributes = shrink(&from.geometry);
+                (from.geometry, attributes, from.entry)
+            }
+            (None, Some(mut to)) => {
+                let attributes = grow(&mut to.geometry);
+                (to.geometry, attributes, to.entry)
+            }
+            (None, None) => continue,
+        };
+        entry.mesh = meshes.add(morphed_mesh(geometry, attributes, images)?);
+        entries.push(entry);
+    }
+    Ok(entries)
+}
@@ -0,0 +1,59 @@
+//! Gradient noise for [`crate::SurfaceNoise`].
+
+use bevy::prelude::*;
+
+/// Directions of the gradients at the lattice points, the edges of a cube
+const GRADIENTS: [Vec3; 12] = [
+    Vec3::new(1.0, 1.0, 0.0),
+    Vec3::new(-1.0, 1.0, 0.0),
+    Vec3::new(1.0, -1.0, 0.0),
+    Vec3::new(-1.0, -1.0, 0.0),
+    Vec3::new(1.0, 0.0, 1.0),
+    Vec3::new(-1.0, 0.0, 1.0),
+    Vec3::new(1.0, 0.0, -1.0),
+    Vec3::new(-1.0, 0.0, -1.0),
+    Vec3::new(0.0, 1.0, 1.0),
+    Vec3::new(0.0, -1.0, 1.0),
+    Vec3::new(0.0, 1.0, -1.0),
+    Vec3::new(0.0, -1.0, -1.0),
+];
+
+fn hash(cell: IVec3, seed: u32) -> u32 {
+    let mut h = seed
+        ^ (cell.x as u32).wrapping_mul(0x8da6_b343)
+        ^ (cell.y as u32).wrapping_mul(0xd816_3841)
+        ^ (cell.z as u32).wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2c1b_3c6d);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297a_2d39);
+    h ^ (h >> 15)
+}
+
+/// Perlin-style gradient noise, roughly between -1 and 1, with features about one unit apart.
+/// The same `seed` always gives the same noise.
+pub fn gradient_noise(p: Vec3, seed: u32) -> f32 {
+    let cell = p.floor();
+    let f = p - cell;
+    let cell = cell.as_ivec3();
+    // Quintic fade, so the noise is smooth across cells
+    let fade = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+
+    let mut value = 0.0;
+    for corner in 0..8 {
+        let offset = IVec3::new(corner & 1, (corner >> 1) & 1, (corner >> 2) & 1);
+        let gradient = GRADIENTS[hash(cell + offset, seed) as usize % GRADIENTS.len()];
+        let weight = Vec3::select(offset.cmpeq(IVec3::ONE), fade, 1.0 - fade);
+        value += weight.x * weight.y * weight.z * gradient.dot(f - offset.as_vec3());
+    }
+    value
+}
+
+/// Three independent channels of [`gradient_noise`], one per axis.
+pub fn vector_noise(p: Vec3, seed: u32) -> Vec3 {
+    Vec3::new(
+        gradient_noise(p, seed),
+        gradient_noise(p, seed.wrapping_add(0x9e37_79b9)),
+        gradient_noise(p, seed.wrapping_add(0x3c6e_f372)),
+    )
+}
@@ -0,0 +1,86 @@
+/// Separators used when formatting numbers for display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberLocale {
+    /// Inserted between groups of three integer digits, `None` disables grouping
+    pub thousands_separator: Option<char>,
+    pub decimal_separator: char,
+}
+
+impl NumberLocale {
+    /// `1,234,567.89`
+    pub const EN_US: Self = Self {
+        thousands_separator: Some(','),
+        decimal_separator: '.',
+    };
+    /// `1.234.567,89`
+    pub const DE_DE: Self = Self {
+        thousands_separator: Some('.'),
+        decimal_separator: ',',
+    };
+    /// `1 234 567,89` with a narrow no-break space
+    pub const FR_FR: Self = Self {
+        thousands_separator: Some('\u{202F}'),
+        decimal_separator: ',',
+    };
+    /// `1'234'567.89`
+    pub const DE_CH: Self = Self {
+        thousands_separator: Some('\''),
+        decimal_separator: '.',
+    };
+
+    /// Looks up the separators for a BCP 47 language tag such as `"de-DE"`.
+    /// Unknown tags fall back to [`NumberLocale::EN_US`].
+    pub fn from_tag(tag: &str) -> Self {
+        match tag {
+            "de-CH" => Self::DE_CH,
+            _ => match tag.split(['-', '_']).next().unwrap_or_default() {
+                "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" => Self::DE_DE,
+                "fr" | "pl" | "ru" | "cs" | "sv" | "fi" | "nb" | "uk" => Self::FR_FR,
+                _ => Self::EN_US,
+            },
+        }
+    }
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        Self::EN_US
+    }
+}
+
+/// Formats `value` with `decimals` fractional digits using the separators of `locale`.
+///
+/// The result only contains digits, the two separators and a leading `-`, so HUD counters
+/// built from it keep hitting the same cached glyph meshes.
+pub fn format_number(value: f64, decimals: usize, locale: NumberLocale) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = formatted
+        .split_once('.')
+        .map_or((formatted.as_str(), None), |(integer, fraction)| {
+            (integer, Some(fraction))
+        });
+
+    let mut result = String::with_capacity(formatted.len() + integer.len() / 3 + 1);
+    // Negative values that round to zero are displayed without a sign
+    if value < 0.0 && formatted.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+        result.push('-');
+    }
+    for (i, digit) in integer.chars().enumerate() {
+        if let Some(separator) = locale.thousands_separator
+            && i > 0
+            && (integer.len() - i) % 3 == 0
+        {
+            result.push(separator);
+        }
+        result.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        result.push(locale.decimal_separator);
+        result.push_str(fraction);
+    }
+    result
+}
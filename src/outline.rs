@@ -0,0 +1,100 @@
+//! Line meshes of glyph outlines, e.g. to draw glowing outlines around solid text.
+
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+
+use crate::clip::{bounds, glyph_outline};
+use crate::contour::{Contour, grow_contours};
+use crate::mesh_text_plugin::layout_text;
+use crate::{InputText, MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// Where [`generate_outline_meshes`] draws the outlines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutlineOptions {
+    /// Depth of the outlines in layout units, from the front face into the text. Negative
+    /// depths sit in front of the text, depths past the extrusion depth behind it.
+    pub z: f32,
+    /// Extra rings around every outline, each this many layout units outside of it. Negative
+    /// distances draw rings inside.
+    pub offsets: Vec<f32>,
+}
+
+/// The outline of a glyph and its offset rings as a line list, around `center`.
+fn outline_mesh(contours: &[Contour], center: Vec2, options: &OutlineOptions) -> Mesh {
+    let rings = contours.iter().flat_map(|contour| {
+        std::iter::once(contour.clone()).chain(
+            options
+                .offsets
+                .iter()
+                .flat_map(|&distance| grow_contours(std::slice::from_ref(contour), distance)),
+        )
+    });
+
+    let mut positions: Vec<Vec3> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+    for ring in rings {
+        let start = positions.len() as u32;
+        let n = ring.points.len() as u32;
+        positions.extend(ring.points.iter().map(|&p| (p - center).extend(options.z)));
+        for (i, _) in ring.edges().enumerate() {
+            let i = i as u32;
+            indices.extend([start + i, start + (i + 1) % n]);
+        }
+    }
+    let normals = vec![Vec3::NEG_Z; positions.len()];
+    Mesh::new(
+        PrimitiveTopology::LineList,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Generates a `LineList` mesh of the outline of every glyph of `text`, laid out like
+/// [`crate::generate_meshes`], so the lines trace the solid glyphs generated with the same
+/// parameters and render with any line material.
+///
+/// Each glyph gets one mesh, centered on its outline, holding its contours at `outline.z` and
+/// its offset rings. Glyphs without an outline, and stand-ins for missing glyphs other than
+/// `.notdef`, are skipped. Outlines are only drawn for the front of double-sided text.
+pub fn generate_outline_meshes<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    outline: &OutlineOptions,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    let (tx, materials) = layout_text(text, &mut fonts.font_system, &mut params)?;
+    if materials.is_empty() {
+        return Err(MeshTextError::InvalidInput);
+    }
+    let scale = params.world_scale(fonts.text_scale_factor);
+    let front_z = params.front_z(scale);
+
+    let mut entries = Vec::new();
+    for run in tx.layout_runs() {
+        for glyph in run.glyphs {
+            let Some(contours) = glyph_outline(&mut fonts.font_system, glyph, run.line_y, &params)
+            else {
+                continue;
+            };
+            let Some(bounds) = bounds(&contours) else {
+                continue;
+            };
+            let center = bounds.center();
+            let material = materials
+                .get(glyph.metadata)
+                .unwrap_or_else(|| &materials[0])
+                .clone();
+            entries.push(MeshTextEntry {
+                mesh: meshes.add(outline_mesh(&contours, center, outline)),
+                material,
+                transform: Transform::from_translation((center * scale.truncate()).extend(front_z))
+                    .with_scale(scale),
+            });
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,111 @@
+//! Rounded background panels behind blocks of text, e.g. for nameplates.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::contour::Contour;
+use crate::extrude_glyph::{ExtrusionOptions, extrude_contours};
+use crate::mesh_text_plugin::{generate_meshes_from_glyphs, layout_text};
+use crate::{InputText, MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// Shape of the panel behind a text block. Lengths are fractions of the font size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanelParameters {
+    /// Space between the laid out text and the edges of the panel, horizontally and vertically
+    pub padding: Vec2,
+    /// Radius of the corners, clamped to half the shorter side of the panel. The corners keep
+    /// their radius whatever the size of the text, like a 9-slice sprite.
+    pub corner_radius: f32,
+    /// Number of segments of each rounded corner
+    pub corner_segments: u32,
+    /// Depth of the panel
+    pub depth: f32,
+}
+
+impl Default for PanelParameters {
+    fn default() -> Self {
+        Self {
+            padding: Vec2::new(0.5, 0.25),
+            corner_radius: 0.25,
+            corner_segments: 4,
+            depth: 0.1,
+        }
+    }
+}
+
+/// A counter-clockwise rounded rectangle of `size` centered on the origin.
+fn rounded_rectangle(size: Vec2, radius: f32, segments: u32) -> Contour {
+    let half = size / 2.0;
+    let radius = radius.clamp(0.0, half.min_element());
+    let segments = if radius > 0.0 { segments.max(1) } else { 0 };
+    // Corner centers counter-clockwise from the bottom right, with the start of their arc
+    let corners = [
+        (Vec2::new(half.x, -half.y), -FRAC_PI_2),
+        (Vec2::new(half.x, half.y), 0.0),
+        (Vec2::new(-half.x, half.y), FRAC_PI_2),
+        (Vec2::new(-half.x, -half.y), 2.0 * FRAC_PI_2),
+    ];
+    let points = corners
+        .into_iter()
+        .flat_map(|(corner, start)| {
+            let center = corner - corner.signum() * radius;
+            (0..=segments).map(move |i| {
+                let angle = start + FRAC_PI_2 * i as f32 / segments.max(1) as f32;
+                center + radius * Vec2::from_angle(angle)
+            })
+        })
+        .collect();
+    Contour {
+        points,
+        closed: true,
+    }
+}
+
+/// Generates the glyph meshes of `text` like [`crate::generate_meshes`], followed by one
+/// extruded panel behind them, e.g. for a nameplate.
+///
+/// The panel is a rounded rectangle around the laid out text, sitting right behind the front
+/// glyphs, and is extruded with the same inset, rim and rounding as the glyphs. It uses the
+/// first material of `text`. The turned glyphs of [`Parameters::double_sided`] text are moved
+/// behind the panel.
+pub fn generate_meshes_with_panel<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    panel: PanelParameters,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    let (tx, materials) = layout_text(text, &mut fonts.font_system, &mut params)?;
+    let mut entries = generate_meshes_from_glyphs(&tx, &materials, fonts, &params, meshes)?;
+
+    let Rect { min, max } = tx.bounds().unwrap_or_default();
+    let padding = panel.padding * params.font_size;
+    let depth = panel.depth * params.font_size;
+    let outline = rounded_rectangle(
+        max - min + 2.0 * padding,
+        panel.corner_radius * params.font_size,
+        panel.corner_segments,
+    );
+    let options = ExtrusionOptions {
+        extrusion_depth: depth,
+        ..params.extrusion_options()
+    };
+    let geometry = extrude_contours(&[outline], &options)?;
+
+    let scale = params.world_scale(fonts.text_scale_factor);
+    let back_z = params.front_z(scale) + params.layout_depth() * scale.z;
+    if params.double_sided {
+        let front = entries.len() / 2;
+        for entry in &mut entries[front..] {
+            entry.transform.translation.z += depth * scale.z;
+        }
+    }
+    let center = (min + max) / 2.0 * scale.truncate();
+    entries.push(MeshTextEntry {
+        mesh: meshes.add(geometry),
+        material: materials[0].clone(),
+        transform: Transform::from_translation(center.extend(back_z)).with_scale(scale),
+    });
+    Ok(entries)
+}
@@ -0,0 +1,203 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, VertexAttributeValues};
+
+use crate::MeshTextEntry;
+
+/// A surface text can be projected onto.
+pub trait ProjectionTarget {
+    /// Intersects the line through `origin` along the normalized `direction` with the surface,
+    /// returning the signed distance from `origin` to the intersection closest to it.
+    fn cast(&self, origin: Vec3, direction: Vec3) -> Option<f32>;
+}
+
+/// A regular grid of heights over the XZ plane, e.g. terrain.
+#[derive(Debug, Clone)]
+pub struct Heightfield {
+    /// World XZ position of the first sample
+    pub origin: Vec2,
+    /// Distance between neighbouring samples
+    pub cell_size: f32,
+    /// Number of samples along X and Z
+    pub size: UVec2,
+    /// Heights, row by row along X
+    pub heights: Vec<f32>,
+}
+
+impl Heightfield {
+    /// Bilinearly interpolated height at world position `xz`, clamped to the grid.
+    pub fn height_at(&self, xz: Vec2) -> f32 {
+        let max = (self.size.as_vec2() - 1.0).max(Vec2::ZERO);
+        let grid = ((xz - self.origin) / self.cell_size).clamp(Vec2::ZERO, max);
+        let cell = grid.floor().min((max - 1.0).max(Vec2::ZERO));
+        let t = grid - cell;
+        let sample = |x: u32, z: u32| {
+            let x = x.min(self.size.x - 1);
+            let z = z.min(self.size.y - 1);
+            self.heights[(z * self.size.x + x) as usize]
+        };
+        let (x, z) = (cell.x as u32, cell.y as u32);
+        let top = sample(x, z).lerp(sample(x + 1, z), t.x);
+        let bottom = sample(x, z + 1).lerp(sample(x + 1, z + 1), t.x);
+        top.lerp(bottom, t.y)
+    }
+}
+
+impl ProjectionTarget for Heightfield {
+    fn cast(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        if direction.y.abs() < 1e-4 || self.heights.is_empty() {
+            return None;
+        }
+        // Fixed-point iteration on the height under the moving intersection, exact for
+        // vertical projection and close for moderate slopes and angles
+        let mut t = 0.0;
+        for _ in 0..8 {
+            let xz = (origin + direction * t).xz();
+            t = (self.height_at(xz) - origin.y) / direction.y;
+        }
+        t.is_finite().then_some(t)
+    }
+}
+
+/// The triangles of a mesh in world space.
+#[derive(Debug, Clone, Default)]
+pub struct MeshSurface {
+    triangles: Vec<[Vec3; 3]>,
+}
+
+impl MeshSurface {
+    /// Collects the triangles of `mesh`, placed with `transform`. Returns `None` for meshes
+    /// without positions or with a topology other than a triangle list.
+    pub fn new(mesh: &Mesh, transform: &GlobalTransform) -> Option<Self> {
+        if mesh.primitive_topology() != bevy::render::mesh::PrimitiveTopology::TriangleList {
+            return None;
+        }
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return None;
+        };
+        let positions: Vec<Vec3> = positions
+            .iter()
+            .map(|&p| transform.transform_point(Vec3::from(p)))
+            .collect();
+        let indices: Vec<usize> = match mesh.indices() {
+            Some(Indices::U16(indices)) => indices.iter().map(|&i| i as usize).collect(),
+            Some(Indices::U32(indices)) => indices.iter().map(|&i| i as usize).collect(),
+            None => (0..positions.len()).collect(),
+        };
+        let triangles = indices
+            .chunks_exact(3)
+            .map(|tri| [positions[tri[0]], positions[tri[1]], positions[tri[2]]])
+            .collect();
+        Some(Self { triangles })
+    }
+}
+
+impl ProjectionTarget for MeshSurface {
+    fn cast(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        // Möller-Trumbore against every triangle, keeping hits on either side of `origin`
+        self.triangles
+            .iter()
+            .filter_map(|[a, b, c]| {
+                let (ab, ac) = (*b - *a, *c - *a);
+                let p = direction.cross(ac);
+                let det = ab.dot(p);
+                if det.abs() < 1e-8 {
+                    return None;
+                }
+                let s = origin - *a;
+                let u = s.dot(p) / det;
+                let q = s.cross(ab);
+                let v = direction.dot(q) / det;
+                (u >= 0.0 && v >= 0.0 && u + v <= 1.0).then(|| ac.dot(q) / det)
+            })
+            .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+    }
+}
+
+/// Whether glyphs are moved as a whole or bent to follow the surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProjectionMode {
+    /// Move every glyph along the projection direction until its center touches the surface
+    #[default]
+    PerGlyph,
+    /// Move every vertex by the distance to the surface under its front face position. Glyphs
+    /// get their own copy of the mesh; normals are kept as they are.
+    PerVertex,
+}
+
+/// How text is projected onto a surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceProjection {
+    /// World transform of the text, e.g. rotated to lie flat on the ground
+    pub text_transform: Transform,
+    /// Direction to project along in world space, usually `Vec3::NEG_Y`
+    pub direction: Vec3,
+    /// Distance kept between the surface and the text
+    pub hover: f32,
+    /// Whether glyphs move as a whole or bend to the surface
+    pub mode: ProjectionMode,
+}
+
+impl Default for SurfaceProjection {
+    fn default() -> Self {
+        Self {
+            text_transform: Transform::default(),
+            direction: Vec3::NEG_Y,
+            hover: 0.0,
+            mode: ProjectionMode::PerGlyph,
+        }
+    }
+}
+
+/// Places `entries` onto `target`, e.g. to paint street names onto terrain.
+///
+/// Afterwards the entry transforms are in world space, so spawn them without the text's
+/// transform. Glyphs that miss the surface keep their position.
+pub fn project_onto_surface<M: Asset>(
+    entries: &mut [MeshTextEntry<M>],
+    meshes: &mut Assets<Mesh>,
+    target: &impl ProjectionTarget,
+    projection: &SurfaceProjection,
+) {
+    let direction = projection.direction.normalize_or_zero();
+    if direction == Vec3::ZERO {
+        return;
+    }
+
+    for entry in entries {
+        entry.transform = projection.text_transform * entry.transform;
+        match projection.mode {
+            ProjectionMode::PerGlyph => {
+                let origin = entry.transform.translation;
+                if let Some(t) = target.cast(origin, direction) {
+                    entry.transform.translation += direction * (t - projection.hover);
+                }
+            }
+            ProjectionMode::PerVertex => {
+                let Some(mesh) = meshes.get(&entry.mesh) else {
+                    continue;
+                };
+                let mut mesh = mesh.clone();
+                let Some(VertexAttributeValues::Float32x3(positions)) =
+                    mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+                else {
+                    continue;
+                };
+                let to_local = entry.transform.compute_matrix().inverse();
+                for position in positions.iter_mut() {
+                    let local = Vec3::from(*position);
+                    // Sample the surface under the front face so the extrusion keeps its depth
+                    let origin = entry.transform.transform_point(local.with_z(0.0));
+                    let Some(t) = target.cast(origin, direction) else {
+                        continue;
+                    };
+                    let world =
+                        entry.transform.transform_point(local) + direction * (t - projection.hover);
+                    *position = to_local.transform_point3(world).into();
+                }
+                entry.mesh = meshes.add(mesh);
+            }
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Precise picking of glyph meshes by their triangles, without a physics engine.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{PrimitiveTopology, VertexAttributeValues};
+
+use crate::MeshTextEntry;
+
+/// Largest number of triangles in a leaf of the [`TriangleBvh`].
+const LEAF_TRIANGLES: usize = 4;
+
+/// A node of the hierarchy: the bounds of its triangles, and either its first child, with the
+/// second one right after it, or a range of triangles.
+#[derive(Debug, Clone, Copy)]
+struct BvhNode {
+    min: Vec3,
+    max: Vec3,
+    start: u32,
+    /// Number of triangles of a leaf, 0 for inner nodes
+    count: u32,
+}
+
+/// A bounding volume hierarchy over the triangles of a mesh, for casting rays against it.
+#[derive(Debug, Clone, Default)]
+pub struct TriangleBvh {
+    nodes: Vec<BvhNode>,
+    triangles: Vec<[Vec3; 3]>,
+    /// Index of each triangle in the mesh, in the order of `triangles`
+    order: Vec<u32>,
+}
+
+/// Where a ray hits a glyph mesh.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextRayHit {
+    /// Distance along the ray
+    pub distance: f32,
+    pub point: Vec3,
+    /// Normal of the hit triangle, on the side of the mesh it faces
+    pub normal: Vec3,
+    /// Index of the hit triangle in the mesh
+    pub triangle: usize,
+}
+
+impl TriangleBvh {
+    /// Builds the hierarchy over the triangles of a triangle list `mesh`. Returns `None` for
+    /// meshes without positions and other topologies.
+    pub fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return None;
+        }
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return None;
+        };
+        let indices: Vec<usize> = match mesh.indices() {
+            Some(indices) => indices.iter().collect(),
+            None => (0..positions.len()).collect(),
+        };
+        let triangles = indices
+            .chunks_exact(3)
+            .map(|tri| {
+                tri.iter()
+                    .map(|&i| positions.get(i).copied().map(Vec3::from))
+            })
+            .map(|mut tri| Some([tri.next()??, tri.next()??, tri.next()??]))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self::new(triangles))
+    }
+
+    /// Builds the hierarchy over `triangles`.
+    pub fn new(triangles: Vec<[Vec3; 3]>) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::with_capacity(2 * triangles.len() / LEAF_TRIANGLES + 1),
+            order: (0..triangles.len() as u32).collect(),
+            triangles,
+        };
+        bvh.nodes.push(bvh.leaf(0, bvh.triangles.len()));
+        bvh.split(0);
+        bvh
+    }
+
+    fn leaf(&self, start: usize, count: usize) -> BvhNode {
+        let (min, max) = self.triangles[start..start + count]
+            .iter()
+            .flatten()
+            .fold((Vec3::MAX, Vec3::MIN), |(min, max), &p| {
+                (min.min(p), max.max(p))
+            });
+        BvhNode {
+            min,
+            max,
+            start: start as u32,
+            count: count as u32,
+        }
+    }
+
+    /// Splits a leaf at the median of its triangle centroids along their widest axis, and its
+    /// halves after it.
+    fn split(&mut self, node: usize) {
+        let BvhNode { start, count, .. } = self.nodes[node];
+        let (start, count) = (start as usize, count as usize);
+        if count <= LEAF_TRIANGLES {
+            return;
+        }
+        let centroid = |tri: &[Vec3; 3]| (tri[0] + tri[1] + tri[2]) / 3.0;
+        let (min, max) = self.triangles[start..start + count]
+            .iter()
+            .fold((Vec3::MAX, Vec3::MIN), |(min, max), tri| {
+                (min.min(centroid(tri)), max.max(centroid(tri)))
+            });
+        let extent = max - min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+
+        // Sort the triangles and their mesh indices together by their centroid
+        let mut range: Vec<_> = self.triangles[start..start + count]
+            .iter()
+            .copied()
+            .zip(self.order[start..start + count].iter().copied())
+            .collect();
+        range.sort_by(|(a, _), (b, _)| centroid(a)[axis].total_cmp(&centroid(b)[axis]));
+        for (k, (tri, index)) in range.into_iter().enumerate() {
+            self.triangles[start + k] = tri;
+            self.order[start + k] = index;
+        }
+
+        let half = count / 2;
+        let left = self.nodes.len();
+        self.nodes.push(self.leaf(start, half));
+        self.nodes.push(self.leaf(start + half, count - half));
+        self.nodes[node].start = left as u32;
+        self.nodes[node].count = 0;
+        self.split(left);
+        self.split(left + 1);
+    }
+
+    /// The closest hit of `ray` on the triangles, in the space of the mesh. Both sides of the
+    /// triangles are hit.
+    pub fn cast(&self, ray: Ray3d) -> Option<TextRayHit> {
+        self.cast_with(ray.origin, *ray.direction)
+    }
+
+    /// Like [`cast`](Self::cast), with a direction that need not be normalized. Distances are
+    /// measured in multiples of `direction`.
+    fn cast_with(&self, origin: Vec3, direction: Vec3) -> Option<TextRayHit> {
+        if self.nodes.is_empty() || self.triangles.is_empty() {
+            return None;
+        }
+        let inverse = direction.recip();
+        let mut best: Option<(f32, usize)> = None;
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = self.nodes[node];
+            let limit = best.map_or(f32::MAX, |(t, _)| t);
+            if !hits_box(origin, inverse, node.min, node.max, limit) {
+                continue;
+            }
+            let start = node.start as usize;
+            if node.count == 0 {
+                stack.extend([start, start + 1]);
+                continue;
+            }
+            for i in start..start + node.count as usize {
+                if let Some(t) = hit_triangle(origin, direction, &self.triangles[i])
+                    && best.is_none_or(|(best, _)| t < best)
+                {
+                    best = Some((t, i));
+                }
+            }
+        }
+
+        let (distance, i) = best?;
+        let [a, b, c] = self.triangles[i];
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+        Some(TextRayHit {
+            distance,
+            point: origin + direction * distance,
+            normal: if normal.dot(direction) > 0.0 {
+                -normal
+            } else {
+                normal
+            },
+            triangle: self.order[i] as usize,
+        })
+    }
+}
+
+/// Whether the ray enters the box before `limit`, by the slab test.
+fn hits_box(origin: Vec3, inverse: Vec3, min: Vec3, max: Vec3, limit: f32) -> bool {
+    let (t0, t1) = ((min - origin) * inverse, (max - origin) * inverse);
+    let near = t0.min(t1).max_element().max(0.0);
+    let far = t0.max(t1).min_element().min(limit);
+    near <= far
+}
+
+/// Distance to the triangle along the ray, by Möller-Trumbore.
+fn hit_triangle(origin: Vec3, direction: Vec3, [a, b, c]: &[Vec3; 3]) -> Option<f32> {
+    let (ab, ac) = (*b - *a, *c - *a);
+    let p = direction.cross(ac);
+    let det = ab.dot(p);
+    if det.abs() < f32::EPSILON * ab.length_squared().max(ac.length_squared()) {
+        return None;
+    }
+    let to_origin = origin - *a;
+    let u = to_origin.dot(p) / det;
+    let q = to_origin.cross(ab);
+    let v = direction.dot(q) / det;
+    if u < 0.0 || v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = ac.dot(q) / det;
+    (t >= 0.0).then_some(t)
+}
+
+/// Triangle hierarchies of glyph meshes, built the first time a mesh is cast against. Glyphs
+/// share their meshes, so each hierarchy serves every glyph showing the mesh.
+///
+/// The plugin adds it as a resource.
+#[derive(Resource, Debug, Default)]
+pub struct GlyphBvhs {
+    bvhs: HashMap<AssetId<Mesh>, TriangleBvh>,
+}
+
+impl GlyphBvhs {
+    /// The hierarchy of `mesh`, built if needed. Returns `None` for meshes that are not loaded
+    /// or have no positions.
+    pub fn get_or_build(
+        &mut self,
+        mesh: &Handle<Mesh>,
+        meshes: &Assets<Mesh>,
+    ) -> Option<&TriangleBvh> {
+        match self.bvhs.entry(mesh.id()) {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => Some(entry.insert(TriangleBvh::from_mesh(meshes.get(mesh)?)?)),
+        }
+    }
+
+    /// Drops the hierarchy of `mesh`, e.g. after changing the mesh.
+    pub fn remove(&mut self, mesh: &Handle<Mesh>) {
+        self.bvhs.remove(&mesh.id());
+    }
+
+    pub fn clear(&mut self) {
+        self.bvhs.clear();
+    }
+}
+
+/// Casts `ray` against the triangles of a glyph. The ray, and the returned hit, are in the
+/// space the entry's transform places the glyph in, e.g. that of the text entity.
+pub fn raycast<M: Asset>(
+    entry: &MeshTextEntry<M>,
+    ray: Ray3d,
+    meshes: &Assets<Mesh>,
+    bvhs: &mut GlyphBvhs,
+) -> Option<TextRayHit> {
+    let bvh = bvhs.get_or_build(&entry.mesh, meshes)?;
+    let matrix = entry.transform.compute_matrix();
+    let to_local = matrix.inverse();
+    // Without normalizing the direction, distances stay those along the ray
+    let hit = bvh.cast_with(
+        to_local.transform_point3(ray.origin),
+        to_local.transform_vector3(*ray.direction),
+    )?;
+    Some(TextRayHit {
+        point: matrix.transform_point3(hit.point),
+        normal: to_local
+            .transpose()
+            .transform_vector3(hit.normal)
+            .normalize_or_zero(),
+        ..hit
+    })
+}
+
+/// Casts `ray` against every glyph of a text and returns the closest hit with the index of its
+/// entry.
+pub fn raycast_text<M: Asset>(
+    entries: &[MeshTextEntry<M>],
+    ray: Ray3d,
+    meshes: &Assets<Mesh>,
+    bvhs: &mut GlyphBvhs,
+) -> Option<(usize, TextRayHit)> {
+    entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| Some((i, raycast(entry, ray, meshes, bvhs)?)))
+        .min_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance))
+}
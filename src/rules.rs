@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+
+use crate::extrude_glyph::extrude_rectangle;
+use crate::mesh_text_plugin::{
+    add_mirrored_back_side, back_side_mirror, layout_text, staggered_glyph_entry,
+};
+use crate::{InputText, MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// Placement of horizontal rules relative to each line of text. All values are
+/// fractions of the font size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuleParameters {
+    /// Thickness of the rule
+    pub thickness: f32,
+    /// Distance of the rule's center below the baseline
+    pub offset: f32,
+    /// How far the rule extends past the first and last glyph of the line
+    pub overhang: f32,
+}
+
+impl Default for RuleParameters {
+    fn default() -> Self {
+        Self {
+            thickness: 0.08,
+            offset: 0.2,
+            overhang: 0.0,
+        }
+    }
+}
+
+/// Generates one extruded rule per laid out line of `text`, spanning the line's glyphs.
+///
+/// The text is laid out and its glyphs placed exactly like [`crate::generate_meshes`] does
+/// with the same parameters, so the rules line up with the glyph meshes, on their grid and at
+/// the stagger of the first glyph of their line, and are extruded like them, with the same
+/// depth, bevel and attributes, at the same scale. Double-sided text gets its rules on the
+/// back too. The glyph meshes are cached in `fonts` like those of `generate_meshes`. Rules
+/// use the first material of `text`.
+pub fn generate_rules<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    rule: RuleParameters,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    // Laying out can change the font size, and with it the depth and the front
+    let (tx, materials) = layout_text(text, &mut fonts.font_system, &mut params)?;
+    let Settings {
+        font_system,
+        text_scale_factor,
+        glyph_cache,
+    } = fonts;
+    let scale = params.world_scale(*text_scale_factor);
+    let front_z = params.front_z(scale);
+
+    // World X span and Z of the placed glyphs of every line
+    let widths: Vec<f32> = tx
+        .layout_glyphs()
+        .iter()
+        .map(|(_, glyph, _)| glyph.w)
+        .collect();
+    let mut lines: Vec<Option<(f32, f32, f32)>> = vec![None; tx.layout_runs().count()];
+    let mut glyphs = Vec::new();
+    for (source, glyph_data) in
+        tx.generate_mesh_glyphs_with_source(font_system, glyph_cache, &params, meshes, &materials)
+    {
+        let center = glyph_data.x_offset + glyph_data.glyph_center_x_layout;
+        let entry = staggered_glyph_entry(&source, glyph_data, scale, front_z, &params);
+        let start = entry.transform.translation.x - center * scale.x;
+        let end = start + widths[source.index] * scale.x;
+        let z = entry.transform.translation.z;
+        lines[source.line] = Some(match lines[source.line] {
+            Some((line_start, line_end, z)) => (line_start.min(start), line_end.max(end), z),
+            None => (start, end, z),
+        });
+        glyphs.push(entry);
+    }
+
+    let options = params.extrusion_options();
+    let thickness = rule.thickness * params.font_size;
+    let overhang = rule.overhang * params.font_size;
+
+    let mut rules = Vec::new();
+    for (run, line) in tx.layout_runs().zip(lines) {
+        // Lines without glyph meshes have nothing to underline
+        let Some((start, end, z)) = line else {
+            continue;
+        };
+
+        let width = (end - start) / scale.x + 2.0 * overhang;
+        let geometry = extrude_rectangle(Vec2::new(width, thickness), &options)?;
+
+        // Same Y-down to Y-up conversion as the glyph placement in `generate_meshes`
+        let center_y = -(run.line_y + rule.offset * params.font_size);
+
+        rules.push(MeshTextEntry {
+            mesh: meshes.add(geometry),
+            material: materials[0].clone(),
+            transform: Transform::from_xyz((start + end) / 2.0, center_y * scale.y, z)
+                .with_scale(scale),
+        });
+    }
+
+    if params.double_sided {
+        add_mirrored_back_side(
+            &mut rules,
+            back_side_mirror(&glyphs),
+            params.layout_depth() * scale.z,
+        );
+    }
+    Ok(rules)
+}
@@ -0,0 +1,189 @@
+//! Pinning world-space text to the screen and sizing it in pixels, e.g. for HUD-style scores
+//! in a 3D scene.
+
+use bevy::prelude::*;
+use bevy::transform::helper::TransformHelper;
+
+/// Keeps a text entity pinned to a point of a camera's viewport, at a fixed distance in front
+/// of the camera and facing it, e.g. a score in the top right corner. The text follows the
+/// camera as it moves and the viewport as it resizes.
+///
+/// The text entity should be a root entity whose glyphs are its children, placed as they come
+/// from `generate_meshes`. Its scale is kept.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[require(Transform, Visibility)]
+pub struct ScreenAnchor {
+    pub camera: Entity,
+    /// Point of the viewport to pin the text to, from (0, 0) at the top left to (1, 1) at the
+    /// bottom right
+    pub viewport_point: Vec2,
+    /// Distance in front of the camera
+    pub depth: f32,
+    /// Shift from the viewport point, in logical pixels, right and down
+    pub offset: Vec2,
+    /// Size of the text block in the entity's local units, e.g. the size measured by
+    /// [`crate::TextGlyphs::measure`] times the text scale
+    pub size: Vec2,
+    /// Point of the text block pinned to the viewport point, from (0, 0) at its top left to
+    /// (1, 1) at its bottom right
+    pub pivot: Vec2,
+}
+
+impl ScreenAnchor {
+    /// Pins the matching point of the text block to `viewport_point`, so text in a corner
+    /// stays inside the viewport.
+    pub fn new(camera: Entity, viewport_point: Vec2, depth: f32) -> Self {
+        Self {
+            camera,
+            viewport_point,
+            depth,
+            offset: Vec2::ZERO,
+            size: Vec2::ZERO,
+            pivot: viewport_point,
+        }
+    }
+
+    pub fn top_left(camera: Entity, depth: f32) -> Self {
+        Self::new(camera, Vec2::new(0.0, 0.0), depth)
+    }
+
+    pub fn top_right(camera: Entity, depth: f32) -> Self {
+        Self::new(camera, Vec2::new(1.0, 0.0), depth)
+    }
+
+    pub fn bottom_left(camera: Entity, depth: f32) -> Self {
+        Self::new(camera, Vec2::new(0.0, 1.0), depth)
+    }
+
+    pub fn bottom_right(camera: Entity, depth: f32) -> Self {
+        Self::new(camera, Vec2::new(1.0, 1.0), depth)
+    }
+
+    pub fn center(camera: Entity, depth: f32) -> Self {
+        Self::new(camera, Vec2::splat(0.5), depth)
+    }
+
+    /// Sets the size of the text block, needed to pin any point but its top left corner.
+    pub fn with_size(self, size: Vec2) -> Self {
+        Self { size, ..self }
+    }
+
+    /// Shifts the text by `offset` logical pixels, e.g. a margin from the corner.
+    pub fn with_offset(self, offset: Vec2) -> Self {
+        Self { offset, ..self }
+    }
+}
+
+/// The transform of text pinned by `anchor`, seen by a camera at `camera` with `projection`
+/// and a viewport of `viewport_size` logical pixels. `scale` is the text's own scale.
+///
+/// Returns `None` for custom projections and empty viewports.
+pub fn screen_anchor_transform(
+    anchor: &ScreenAnchor,
+    camera: &GlobalTransform,
+    projection: &Projection,
+    viewport_size: Vec2,
+    scale: Vec3,
+) -> Option<Transform> {
+    if viewport_size.min_element() <= 0.0 {
+        return None;
+    }
+    // The viewport in view space at the anchor's depth
+    let area = match projection {
+        Projection::Perspective(perspective) => {
+            let half_height = anchor.depth * (perspective.fov / 2.0).tan();
+            let half_size = Vec2::new(half_height * viewport_size.x / viewport_size.y, half_height);
+            Rect::from_corners(-half_size, half_size)
+        }
+        Projection::Orthographic(orthographic) => orthographic.area,
+        Projection::Custom(_) => return None,
+    };
+
+    let pixel = (anchor.viewport_point * viewport_size + anchor.offset) / viewport_size;
+    let point = Vec3::new(
+        area.min.x + pixel.x * area.width(),
+        area.max.y - pixel.y * area.height(),
+        -anchor.depth,
+    );
+    // Text is laid out rightwards and downwards from its origin
+    let pivot = Vec3::new(
+        anchor.pivot.x * anchor.size.x,
+        -anchor.pivot.y * anchor.size.y,
+        0.0,
+    );
+
+    let (_, rotation, _) = camera.to_scale_rotation_translation();
+    Some(Transform {
+        translation: camera.transform_point(point) - rotation * (pivot * scale),
+        rotation,
+        scale,
+    })
+}
+
+/// Moves every [`ScreenAnchor`]ed text to its place in front of its camera.
+pub fn update_screen_anchors(
+    mut texts: Query<(&ScreenAnchor, &mut Transform)>,
+    cameras: Query<(&Camera, &Projection)>,
+    transforms: TransformHelper,
+) {
+    for (anchor, mut transform) in &mut texts {
+        let Ok((camera, projection)) = cameras.get(anchor.camera) else {
+            continue;
+        };
+        let Some(viewport_size) = camera.logical_viewport_size() else {
+            continue;
+        };
+        // The camera may have moved this frame, so its global transform is computed afresh
+        let Ok(camera_transform) = transforms.compute_global_transform(anchor.camera) else {
+            continue;
+        };
+        if let Some(anchored) = screen_anchor_transform(
+            anchor,
+            &camera_transform,
+            projection,
+            viewport_size,
+            transform.scale,
+        ) {
+            transform.set_if_neq(anchored);
+        }
+    }
+}
+
+/// The world scale at which text of `font_size` is `pixel_height` pixels tall on screen, for
+/// an orthographic camera showing `projection` on a viewport `viewport_height` pixels tall.
+/// Heights are measured by the em, like font sizes.
+///
+/// Pass it to [`crate::Parameters::with_scale`], or use it as the plugin's
+/// `text_scale_factor`, e.g. for CAD-style labels that should be as crisp as UI text.
+pub fn orthographic_text_scale(
+    projection: &OrthographicProjection,
+    viewport_height: f32,
+    font_size: f32,
+    pixel_height: f32,
+) -> f32 {
+    if viewport_height <= 0.0 || font_size <= 0.0 {
+        return 0.0;
+    }
+    let units_per_pixel = projection.area.height() / viewport_height;
+    pixel_height * units_per_pixel / font_size
+}
+
+/// Like [`orthographic_text_scale`], with the size of `camera`'s viewport in physical pixels.
+/// Returns `None` for other projections and before the camera knows its viewport.
+pub fn pixel_perfect_scale(
+    camera: &Camera,
+    projection: &Projection,
+    font_size: f32,
+    pixel_height: f32,
+) -> Option<f32> {
+    let Projection::Orthographic(orthographic) = projection else {
+        return None;
+    };
+    let viewport = camera.physical_viewport_size()?;
+    Some(orthographic_text_scale(
+        orthographic,
+        viewport.y as f32,
+        font_size,
+        pixel_height,
+    ))
+}
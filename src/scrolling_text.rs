@@ -0,0 +1,161 @@
+//! Long text shown through a scroll window, like film credits, with glyph meshes and
+//! entities only for the lines in the window.
+
+use bevy::prelude::*;
+use cosmic_text::{FontSystem, Scroll};
+
+use crate::entity_pool::{GlyphEntityPool, PooledText, update_pooled_text};
+use crate::mesh_text_plugin::{generate_meshes_from_glyphs, layout_text};
+use crate::text_glyphs::TextGlyphs;
+use crate::{InputText, Parameters, Settings};
+
+enum ScrollState<M: Asset> {
+    Text(InputText<'static, M>),
+    /// Laid out, with the tops of the paragraphs laid out so far on the way down, in layout
+    /// units. The last entry is the bottom of the last of them.
+    LaidOut(TextGlyphs, Vec<Handle<M>>, Vec<f32>),
+    /// Taken while the text is being laid out
+    Empty,
+}
+
+/// Text far longer than it's shown, like scrolling credits, of which only the lines in a
+/// window `window_height` high and `scroll` below the top of the text are meshed.
+///
+/// Glyph entities are children of the text, taken from and returned to the
+/// [`GlyphEntityPool`] as lines scroll in and out of the window, and placed with the top of
+/// the window at the origin of the text. Only the lines in the window are shaped, using
+/// cosmic-text's scrolling: paragraphs are laid out once on the way down to find where the
+/// window starts, and dropped again once they leave it.
+///
+/// A line is in the window when its baseline is. The window stops at the end of the text,
+/// showing the last lines when scrolled further. [`Parameters::fit_box`] is ignored.
+#[derive(Component)]
+#[require(Transform, Visibility, PooledText)]
+pub struct ScrollingText<M: Asset = StandardMaterial> {
+    state: ScrollState<M>,
+    params: Parameters,
+    /// Distance from the top of the text to the top of the window, in layout units
+    pub scroll: f32,
+    /// Height of the window in layout units
+    pub window_height: f32,
+    /// Scroll and window height the glyphs were generated for
+    shown: Option<(f32, f32)>,
+}
+
+impl<M: Asset> ScrollingText<M> {
+    pub fn new(text: InputText<'static, M>, params: Parameters, window_height: f32) -> Self {
+        Self {
+            state: ScrollState::Text(text),
+            params: Parameters {
+                fit_box: None,
+                ..params
+            },
+            scroll: 0.0,
+            window_height,
+            shown: None,
+        }
+    }
+}
+
+/// Moves the window of `tx` `scroll` below the top of the text, laying out the paragraphs
+/// above it not in `tops` yet. Lines leaving the window lose their layout.
+fn scroll_to(
+    tx: &mut TextGlyphs,
+    tops: &mut Vec<f32>,
+    font_system: &mut FontSystem,
+    params: &Parameters,
+    scroll: f32,
+    window_height: f32,
+) {
+    let scroll = scroll.max(0.0);
+    let buffer = tx.buffer_mut();
+    buffer.set_size(font_system, params.max_width, Some(window_height));
+    let line_height = buffer.metrics().line_height;
+    let paragraphs = buffer.lines.len();
+    if tops.is_empty() {
+        tops.push(0.0);
+    }
+    while tops.len() <= paragraphs && tops.last().is_some_and(|&top| top <= scroll) {
+        let line = tops.len() - 1;
+        let height: f32 = buffer
+            .line_layout(font_system, line)
+            .map_or(line_height, |layout| {
+                layout
+                    .iter()
+                    .map(|layout_line| layout_line.line_height_opt.unwrap_or(line_height))
+                    .sum()
+            });
+        tops.push(tops[line] + height);
+    }
+    let line = tops
+        .partition_point(|&top| top <= scroll)
+        .saturating_sub(1)
+        .min(paragraphs.saturating_sub(1));
+    buffer.set_scroll(Scroll::new(line, scroll - tops[line], 0.0));
+    buffer.shape_until_scroll(font_system, true);
+    if let Some(grid) = params.grid {
+        tx.snap_to_grid(grid.cell_width, params.max_width, params.alignment);
+    }
+}
+
+/// Lays out new [`ScrollingText`]s and regenerates the glyphs of those whose window moved.
+pub fn update_scrolling_text<M: Material>(
+    mut commands: Commands,
+    mut texts: Query<(Entity, &mut ScrollingText<M>, &mut PooledText)>,
+    mut settings: ResMut<Settings>,
+    mut pool: ResMut<GlyphEntityPool>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (entity, mut text, mut pooled) in &mut texts {
+        let text = text.bypass_change_detection();
+        let window = (text.scroll, text.window_height);
+        if text.shown == Some(window) {
+            continue;
+        }
+        let state = std::mem::replace(&mut text.state, ScrollState::Empty);
+        let (mut tx, materials, mut tops) = match state {
+            ScrollState::Text(input) => {
+                // Only the lines at the top are shaped to begin with
+                let mut params = Parameters {
+                    max_height: Some(text.window_height),
+                    ..text.params.clone()
+                };
+                match layout_text(input, &mut settings.font_system, &mut params) {
+                    Ok((tx, materials)) => (tx, materials, Vec::new()),
+                    Err(e) => {
+                        warn!("Failed to lay out scrolling text: {e}");
+                        commands.entity(entity).remove::<ScrollingText<M>>();
+                        continue;
+                    }
+                }
+            }
+            ScrollState::LaidOut(tx, materials, tops) => (tx, materials, tops),
+            ScrollState::Empty => continue,
+        };
+
+        scroll_to(
+            &mut tx,
+            &mut tops,
+            &mut settings.font_system,
+            &text.params,
+            text.scroll,
+            text.window_height,
+        );
+        match generate_meshes_from_glyphs(&tx, &materials, &mut settings, &text.params, &mut meshes)
+        {
+            Ok(entries) => {
+                update_pooled_text(
+                    &mut commands,
+                    &mut pool,
+                    entity,
+                    &mut pooled,
+                    entries,
+                    &meshes,
+                );
+            }
+            Err(e) => warn!("Failed to generate scrolling text: {e}"),
+        }
+        text.shown = Some(window);
+        text.state = ScrollState::LaidOut(tx, materials, tops);
+    }
+}
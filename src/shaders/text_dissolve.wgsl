@@ -0,0 +1,115 @@
+#ifdef PREPASS_PIPELINE
+#ifdef DEFERRED_PREPASS
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::alpha_discard,
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_deferred_functions::deferred_output,
+}
+#else ifdef PREPASS_FRAGMENT
+// The normal and motion vector prepasses, which have no PBR input
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_prepass_functions,
+}
+#else
+#import bevy_pbr::{
+    prepass_io::VertexOutput,
+    pbr_prepass_functions,
+}
+#endif
+#else
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::{alpha_discard, apply_pbr_lighting, main_pass_post_lighting_processing},
+    forward_io::{VertexOutput, FragmentOutput},
+}
+#endif
+
+struct TextDissolve {
+    edge_color: vec4<f32>,
+    progress: f32,
+    edge_width: f32,
+}
+
+@group(2) @binding(100) var<uniform> text_dissolve: TextDissolve;
+
+// Discards the part of the text after the progress, and returns how far the rest is from it
+fn clip(in: VertexOutput) -> f32 {
+    // The text progress is bound to the second UV slot, see `TextDissolveExtension::specialize`
+#ifdef VERTEX_UVS_B
+    let remaining = text_dissolve.progress - in.uv_b.x;
+    if remaining < 0.0 {
+        discard;
+    }
+    return remaining;
+#else
+    return text_dissolve.progress;
+#endif
+}
+
+#ifdef PREPASS_PIPELINE
+#ifdef DEFERRED_PREPASS
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    let remaining = clip(in);
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+    if remaining < text_dissolve.edge_width {
+        pbr_input.material.emissive = text_dissolve.edge_color;
+    }
+    return deferred_output(in, pbr_input);
+}
+#else ifdef PREPASS_FRAGMENT
+@fragment
+fn fragment(in: VertexOutput) -> FragmentOutput {
+    clip(in);
+    pbr_prepass_functions::prepass_alpha_discard(in);
+
+    var out: FragmentOutput;
+#ifdef UNCLIPPED_DEPTH_ORTHO_EMULATION
+    out.frag_depth = in.unclipped_depth;
+#endif
+#ifdef NORMAL_PREPASS
+    out.normal = vec4(normalize(in.world_normal) * 0.5 + vec3(0.5), 1.0);
+#endif
+#ifdef MOTION_VECTOR_PREPASS
+    out.motion_vector = pbr_prepass_functions::calculate_motion_vector(
+        in.world_position,
+        in.previous_world_position,
+    );
+#endif
+    return out;
+}
+#else
+// Depth only, see `discard_in_prepass`
+@fragment
+fn fragment(in: VertexOutput) {
+    clip(in);
+    pbr_prepass_functions::prepass_alpha_discard(in);
+}
+#endif
+#else
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    let remaining = clip(in);
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+    if remaining < text_dissolve.edge_width {
+        pbr_input.material.emissive = text_dissolve.edge_color;
+    }
+
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+    return out;
+}
+#endif
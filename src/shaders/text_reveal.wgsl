@@ -0,0 +1,103 @@
+#ifdef PREPASS_PIPELINE
+#ifdef DEFERRED_PREPASS
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::alpha_discard,
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_deferred_functions::deferred_output,
+}
+#else ifdef PREPASS_FRAGMENT
+// The normal and motion vector prepasses, which have no PBR input
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_prepass_functions,
+}
+#else
+#import bevy_pbr::{
+    prepass_io::VertexOutput,
+    pbr_prepass_functions,
+}
+#endif
+#else
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::{alpha_discard, apply_pbr_lighting, main_pass_post_lighting_processing},
+    forward_io::{VertexOutput, FragmentOutput},
+}
+#endif
+
+struct TextReveal {
+    progress: f32,
+}
+
+@group(2) @binding(100) var<uniform> text_reveal: TextReveal;
+
+// Discards the part of the text deeper than the progress
+fn clip(in: VertexOutput) {
+    // The depth fraction is bound to the second UV slot, see `TextRevealExtension::specialize`
+#ifdef VERTEX_UVS_B
+    if in.uv_b.x > text_reveal.progress {
+        discard;
+    }
+#endif
+}
+
+#ifdef PREPASS_PIPELINE
+#ifdef DEFERRED_PREPASS
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    clip(in);
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+    return deferred_output(in, pbr_input);
+}
+#else ifdef PREPASS_FRAGMENT
+@fragment
+fn fragment(in: VertexOutput) -> FragmentOutput {
+    clip(in);
+    pbr_prepass_functions::prepass_alpha_discard(in);
+
+    var out: FragmentOutput;
+#ifdef UNCLIPPED_DEPTH_ORTHO_EMULATION
+    out.frag_depth = in.unclipped_depth;
+#endif
+#ifdef NORMAL_PREPASS
+    out.normal = vec4(normalize(in.world_normal) * 0.5 + vec3(0.5), 1.0);
+#endif
+#ifdef MOTION_VECTOR_PREPASS
+    out.motion_vector = pbr_prepass_functions::calculate_motion_vector(
+        in.world_position,
+        in.previous_world_position,
+    );
+#endif
+    return out;
+}
+#else
+// Depth only, see `discard_in_prepass`
+@fragment
+fn fragment(in: VertexOutput) {
+    clip(in);
+    pbr_prepass_functions::prepass_alpha_discard(in);
+}
+#endif
+#else
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    clip(in);
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+    return out;
+}
+#endif
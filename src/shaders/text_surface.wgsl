@@ -0,0 +1,53 @@
+// The forward and deferred passes; the other prepasses use the `StandardMaterial` shader
+
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::alpha_discard,
+}
+
+#ifdef PREPASS_PIPELINE
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_deferred_functions::deferred_output,
+}
+#else
+#import bevy_pbr::{
+    forward_io::{VertexOutput, FragmentOutput},
+    pbr_functions::{apply_pbr_lighting, main_pass_post_lighting_processing},
+}
+#endif
+
+struct TextSurface {
+    front: vec4<f32>,
+    edge: vec4<f32>,
+    side: vec4<f32>,
+    back: vec4<f32>,
+}
+
+@group(2) @binding(100) var<uniform> text_surface: TextSurface;
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+
+    // The surface id is bound to the second UV slot, see `TextSurfaceExtension::specialize`
+#ifdef VERTEX_UVS_B
+    let colors = array(text_surface.front, text_surface.edge, text_surface.side, text_surface.back);
+    let surface = min(u32(round(in.uv_b.x)), 3u);
+    pbr_input.material.base_color *= colors[surface];
+#endif
+
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+#ifdef PREPASS_PIPELINE
+    let out = deferred_output(in, pbr_input);
+#else
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+#endif
+    return out;
+}
@@ -0,0 +1,76 @@
+//! Simplified glyph meshes that cast the shadows of detailed text.
+
+use bevy::prelude::*;
+
+use crate::extrude_glyph::{ExtrusionOptions, TessellationOptions};
+use crate::mesh_text_plugin::{add_back_side, glyph_entry, layout_text};
+use crate::text_glyphs::mesh_glyph;
+use crate::{InputText, MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// Curve tolerance of shadow casters, in font units: about a hundredth of the em of common
+/// fonts, too little to show in a shadow.
+const SHADOW_TOLERANCE: f32 = 10.0;
+
+/// Options for a low-poly shadow caster of glyphs extruded with `options`: the filled
+/// silhouette of the outline, coarsely flattened and extruded to the same depth, without a lip,
+/// rim, rounding or any extra attributes.
+pub fn shadow_caster_options(options: &ExtrusionOptions) -> ExtrusionOptions {
+    let tessellation = options.tessellation;
+    ExtrusionOptions {
+        silhouette: true,
+        synthetic_style: options.synthetic_style,
+        tessellation: TessellationOptions {
+            tolerance: tessellation.tolerance.max(SHADOW_TOLERANCE),
+            merge_coplanar_walls: true,
+            ..tessellation
+        },
+        ..ExtrusionOptions::new(options.extrusion_depth)
+    }
+}
+
+/// Generates a shadow caster for every glyph `generate_meshes` would generate with the same
+/// parameters, placed the same way, see [`shadow_caster_options`].
+///
+/// Spawn the detailed glyphs with `NotShadowCaster` and the shadow casters with
+/// `NotShadowReceiver` on a `RenderLayers` that the lights see and the cameras don't. The
+/// casters are cached like any glyph mesh.
+pub fn generate_shadow_meshes<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+    let (tx, materials) = layout_text(text, &mut fonts.font_system, &mut params)?;
+    let options = shadow_caster_options(&params.extrusion_options());
+    let Settings {
+        font_system,
+        text_scale_factor,
+        glyph_cache,
+    } = fonts;
+    let scale = params.world_scale(*text_scale_factor);
+    let front_z = params.front_z(scale);
+
+    let mut entries = Vec::new();
+    for run in tx.layout_runs() {
+        for glyph in run.glyphs {
+            entries.extend(
+                mesh_glyph(
+                    font_system,
+                    glyph_cache,
+                    &params,
+                    &options,
+                    meshes,
+                    &materials,
+                    glyph,
+                    run.line_y,
+                    None,
+                )
+                .map(|glyph| glyph_entry(glyph, scale, front_z, params.grid)),
+            );
+        }
+    }
+    if params.double_sided {
+        add_back_side(&mut entries, params.layout_depth() * scale.z);
+    }
+    Ok(entries)
+}
@@ -0,0 +1,86 @@
+//! Font contexts shared between systems and tasks, locked one at a time instead of through
+//! exclusive access to a resource.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+
+use bevy::prelude::*;
+use cosmic_text::FontSystem;
+
+use crate::Settings;
+
+/// A pool of font contexts behind locks, for generating text without `ResMut<Settings>`.
+///
+/// Systems take `Res<SharedFonts>`, so the scheduler runs them side by side, and tasks take a
+/// clone, which shares the pool. Each caller [`lock`](Self::lock)s a context for as long as it
+/// lays out or generates text, and waits only when every context is in use. Every context has
+/// its own glyph cache, so use them all with the same mesh storage.
+///
+/// The plugin doesn't add one; insert e.g. `SharedFonts::with_copies(&font_system, 1.0, 4)`.
+#[derive(Resource, Clone)]
+pub struct SharedFonts {
+    contexts: Arc<[Mutex<Settings>]>,
+    /// Context to wait for when all are locked, so waiting callers spread over the pool
+    next: Arc<AtomicUsize>,
+}
+
+impl SharedFonts {
+    /// Pools `contexts`.
+    ///
+    /// # Panics
+    ///
+    /// Panics without contexts.
+    pub fn new(contexts: impl IntoIterator<Item = Settings>) -> Self {
+        let contexts: Arc<[_]> = contexts.into_iter().map(Mutex::new).collect();
+        assert!(!contexts.is_empty(), "SharedFonts needs a font context");
+        Self {
+            contexts,
+            next: Arc::default(),
+        }
+    }
+
+    /// Pools `count` contexts with copies of the fonts and the locale of `font_system`, and
+    /// empty glyph caches. The copies assign the same ids to the fonts, so glyphs laid out
+    /// with one context can be generated with another.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `count` is 0.
+    pub fn with_copies(font_system: &FontSystem, text_scale_factor: f32, count: usize) -> Self {
+        Self::new((0..count).map(|_| {
+            let copy = FontSystem::new_with_locale_and_db(
+                font_system.locale().to_string(),
+                font_system.db().clone(),
+            );
+            Settings::new(copy, text_scale_factor)
+        }))
+    }
+
+    /// Number of contexts in the pool, i.e. how many callers can generate text at once.
+    pub fn contexts(&self) -> usize {
+        self.contexts.len()
+    }
+
+    /// Locks a context no one else is using, or waits for one when all are in use.
+    pub fn lock(&self) -> MutexGuard<'_, Settings> {
+        if let Some(context) = self.try_lock() {
+            return context;
+        }
+        let next = self.next.fetch_add(1, Ordering::Relaxed) % self.contexts.len();
+        self.contexts[next]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks a context no one else is using, `None` when all are in use.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, Settings>> {
+        // A context poisoned by a panic while generating is still usable
+        self.contexts
+            .iter()
+            .find_map(|context| match context.try_lock() {
+                Ok(context) => Some(context),
+                Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+                Err(TryLockError::WouldBlock) => None,
+            })
+    }
+}
@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use bevy::render::mesh::skinning::{SkinnedMesh, SkinnedMeshInverseBindposes};
+
+use crate::MeshTextEntry;
+
+/// Spawns `entries` as a skinned rig: a text root with one joint per glyph, and one mesh per
+/// glyph skinned entirely to its joint.
+///
+/// The glyph meshes have to be generated with [`crate::Parameters::skinned`]. Joints are
+/// placed at the glyph transforms and named `glyph 0`, `glyph 1`, ... so animation clips can
+/// target them by name. Returns the root entity.
+pub fn spawn_skinned_text<M: Material>(
+    commands: &mut Commands,
+    entries: Vec<MeshTextEntry<M>>,
+    inverse_bindposes: &mut Assets<SkinnedMeshInverseBindposes>,
+) -> Entity {
+    // Glyph vertices are already in joint space, so every joint shares the identity bindpose
+    let inverse_bindposes = inverse_bindposes.add(vec![Mat4::IDENTITY]);
+
+    let root = commands
+        .spawn((
+            Name::new("text"),
+            Transform::default(),
+            Visibility::default(),
+        ))
+        .id();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let joint = commands
+            .spawn((Name::new(format!("glyph {index}")), entry.transform))
+            .id();
+        commands.entity(root).add_child(joint);
+
+        let mesh = commands
+            .spawn((
+                Mesh3d(entry.mesh),
+                MeshMaterial3d(entry.material),
+                SkinnedMesh {
+                    inverse_bindposes: inverse_bindposes.clone(),
+                    joints: vec![joint],
+                },
+            ))
+            .id();
+        commands.entity(root).add_child(mesh);
+    }
+    root
+}
@@ -0,0 +1,75 @@
+//! One call from a string to spawned text, for the common case of a single-material label.
+
+use bevy::prelude::*;
+use cosmic_text::Attrs;
+
+use crate::bounds::insert_glyph_bounds;
+use crate::{InputText, MeshTextError, Parameters, Settings, generate_meshes};
+
+/// Text to spawn with [`spawn_text_3d`].
+#[derive(Clone)]
+pub struct TextSpawnDesc<'a> {
+    pub text: String,
+    pub attrs: Attrs<'a>,
+    pub params: Parameters,
+    /// Base color of the material created for the glyphs
+    pub color: Color,
+    /// Material of the glyphs, used instead of creating one from `color`
+    pub material: Option<Handle<StandardMaterial>>,
+    /// Transform of the root entity
+    pub transform: Transform,
+}
+
+impl TextSpawnDesc<'_> {
+    /// White `text` with the default font and parameters, at the origin.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            attrs: Attrs::new(),
+            params: Parameters::default(),
+            color: Color::WHITE,
+            material: None,
+            transform: Transform::default(),
+        }
+    }
+}
+
+/// Generates the glyph meshes of `desc` and spawns them as children of a new root entity
+/// placed at `desc.transform`. Returns the root, to move, animate or despawn the text as a
+/// whole.
+pub fn spawn_text_3d(
+    commands: &mut Commands,
+    fonts: &mut Settings,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    desc: TextSpawnDesc,
+) -> Result<Entity, MeshTextError> {
+    let material = desc.material.unwrap_or_else(|| {
+        materials.add(StandardMaterial {
+            base_color: desc.color,
+            ..default()
+        })
+    });
+    let entries = generate_meshes(
+        InputText::Simple {
+            text: desc.text.clone(),
+            material,
+            attrs: desc.attrs,
+        },
+        fonts,
+        desc.params,
+        meshes,
+    )?;
+
+    let root = commands
+        .spawn((Name::new(desc.text), desc.transform, Visibility::default()))
+        .with_children(|parent| {
+            for entry in entries {
+                let mut glyph = parent.spawn(entry.transform);
+                insert_glyph_bounds(&mut glyph, &entry.mesh, meshes);
+                glyph.insert((Mesh3d(entry.mesh), MeshMaterial3d(entry.material)));
+            }
+        })
+        .id();
+    Ok(root)
+}
@@ -0,0 +1,117 @@
+//! Sticker text: every glyph backed by a wider shell of its outline in a material of its own.
+
+use bevy::prelude::*;
+
+use crate::clip::{bounds, glyph_outline};
+use crate::contour::{grow_contours, resolve_overlaps};
+use crate::extrude_glyph::{ExtrusionOptions, extrude_contours};
+use crate::mesh_text_plugin::{glyph_entry, layout_text};
+use crate::text_glyphs::mesh_glyph;
+use crate::{InputText, MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// The shell behind every glyph of sticker text. Distances are in layout units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StickerParameters {
+    /// How far the shell reaches beyond the glyph outline
+    pub width: f32,
+    /// Extrusion depth of the shell
+    pub depth: f32,
+    /// Distance from the front face of the glyph back to the front face of the shell, so the
+    /// glyph stands out of it
+    pub setback: f32,
+}
+
+impl Default for StickerParameters {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            depth: 1.0,
+            setback: 0.5,
+        }
+    }
+}
+
+/// A glyph of sticker text and the shell behind it.
+pub struct StickerEntry<M: Asset> {
+    pub glyph: MeshTextEntry<M>,
+    pub shell: MeshTextEntry<M>,
+}
+
+/// Generates `text` like [`crate::generate_meshes`], with a shell behind every glyph: its
+/// outline grown by `sticker.width` and extruded in `shell_material`, for the classic sticker
+/// look of a white border around colored letters.
+///
+/// The glyphs keep their shared meshes and every option of `params`, e.g. a beveled front
+/// inset. Shells are plain extrusions generated once per call, and holes narrower than twice
+/// the width are filled. Glyphs without an outline get neither, and double-sided text only
+/// gets shells on the front.
+pub fn generate_sticker_meshes<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    sticker: StickerParameters,
+    shell_material: Handle<M>,
+    meshes: &mut Assets<Mesh>,
+) -> Result<Vec<StickerEntry<M>>, MeshTextError> {
+    if sticker.depth < 0.0 {
+        return Err(MeshTextError::NegativeExtrusionDepth(sticker.depth));
+    }
+    if sticker.width < 0.0 {
+        return Err(MeshTextError::InvalidInput);
+    }
+    let (tx, materials) = layout_text(text, &mut fonts.font_system, &mut params)?;
+    if materials.is_empty() {
+        return Err(MeshTextError::InvalidInput);
+    }
+    let Settings {
+        font_system,
+        text_scale_factor,
+        glyph_cache,
+    } = fonts;
+    let scale = params.world_scale(*text_scale_factor);
+    let front_z = params.front_z(scale);
+    let options = params.extrusion_options();
+    let shell_options = ExtrusionOptions {
+        tessellation: options.tessellation,
+        ..ExtrusionOptions::new(sticker.depth)
+    };
+
+    let mut entries = Vec::new();
+    for run in tx.layout_runs() {
+        for glyph in run.glyphs {
+            let Some(contours) = glyph_outline(font_system, glyph, run.line_y, &params) else {
+                continue;
+            };
+            let shell = resolve_overlaps(&grow_contours(&contours, sticker.width))?;
+            let Some(shell_bounds) = bounds(&shell) else {
+                continue;
+            };
+            let Some(mesh_glyph) = mesh_glyph(
+                font_system,
+                glyph_cache,
+                &params,
+                &options,
+                meshes,
+                &materials,
+                glyph,
+                run.line_y,
+                None,
+            ) else {
+                continue;
+            };
+            entries.push(StickerEntry {
+                glyph: glyph_entry(mesh_glyph, scale, front_z, params.grid),
+                shell: MeshTextEntry {
+                    mesh: meshes.add(extrude_contours(&shell, &shell_options)?),
+                    material: shell_material.clone(),
+                    transform: Transform::from_translation(
+                        (shell_bounds.center() * scale.truncate())
+                            .extend(front_z + sticker.setback * scale.z),
+                    )
+                    .with_scale(scale),
+                },
+            });
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use cosmic_text::{Attrs, Family, Style, Weight};
+
+use crate::{BevelEdges, BevelWidth, Depth, FrontInset, InputText, Parameters};
+
+/// Creates the material for text spawned with a style.
+pub type MaterialFactory =
+    Arc<dyn Fn(&mut Assets<StandardMaterial>) -> Handle<StandardMaterial> + Send + Sync>;
+
+/// A named text style: font, size, extrusion and material.
+#[derive(Clone)]
+#[cfg_attr(feature = "ron", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "ron", serde(default))]
+pub struct TextStyle {
+    /// Font family name, or the default font when `None`
+    pub family: Option<String>,
+    /// Font weight, 400 is regular and 700 is bold
+    pub weight: u16,
+    pub italic: bool,
+    pub font_size: f32,
+    pub line_height: f32,
+    pub extrusion_depth: f32,
+    /// Extrusion depth in a unit of choice, e.g. [`Depth::Em`] so every size of the style
+    /// has the same proportions
+    pub depth: Option<Depth>,
+    /// Optional lip around a recessed front face
+    pub front_inset: Option<FrontInset>,
+    /// Width of the lip in a unit of choice, e.g. [`BevelWidth::Em`] so the lip looks the
+    /// same at every font size of the style
+    pub bevel_width: Option<BevelWidth>,
+    /// Contours the lip runs along
+    pub bevel_edges: BevelEdges,
+    /// Width of the lip along holes, in place of the bevel width
+    pub hole_bevel_width: Option<BevelWidth>,
+    /// sRGB base color of the material created when there is no material factory
+    pub color: [f32; 4],
+    /// Creates the material for this style, overriding `color`
+    #[cfg_attr(feature = "ron", serde(skip))]
+    pub material: Option<MaterialFactory>,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        let params = Parameters::default();
+        Self {
+            family: None,
+            weight: Weight::NORMAL.0,
+            italic: false,
+            font_size: params.font_size,
+            line_height: params.line_height,
+            extrusion_depth: params.extrusion_depth,
+            depth: None,
+            front_inset: None,
+            bevel_width: None,
+            bevel_edges: BevelEdges::default(),
+            hole_bevel_width: None,
+            color: [1.0; 4],
+            material: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for TextStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextStyle")
+            .field("family", &self.family)
+            .field("weight", &self.weight)
+            .field("italic", &self.italic)
+            .field("font_size", &self.font_size)
+            .field("line_height", &self.line_height)
+            .field("extrusion_depth", &self.extrusion_depth)
+            .field("depth", &self.depth)
+            .field("front_inset", &self.front_inset)
+            .field("bevel_width", &self.bevel_width)
+            .field("bevel_edges", &self.bevel_edges)
+            .field("hole_bevel_width", &self.hole_bevel_width)
+            .field("color", &self.color)
+            .field("material", &self.material.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl TextStyle {
+    /// Font attributes of the style.
+    pub fn attrs(&self) -> Attrs<'_> {
+        let attrs = Attrs::new()
+            .weight(Weight(self.weight))
+            .style(if self.italic {
+                Style::Italic
+            } else {
+                Style::Normal
+            });
+        match &self.family {
+            Some(family) => attrs.family(Family::Name(family)),
+            None => attrs,
+        }
+    }
+
+    /// Generation parameters of the style.
+    pub fn parameters(&self) -> Parameters {
+        Parameters {
+            extrusion_depth: self.extrusion_depth,
+            depth: self.depth,
+            font_size: self.font_size,
+            line_height: self.line_height,
+            front_inset: self.front_inset,
+            bevel_width: self.bevel_width,
+            bevel_edges: self.bevel_edges,
+            hole_bevel_width: self.hole_bevel_width,
+            ..default()
+        }
+    }
+
+    /// Creates a material for the style.
+    pub fn material(&self, materials: &mut Assets<StandardMaterial>) -> Handle<StandardMaterial> {
+        match &self.material {
+            Some(factory) => factory(materials),
+            None => {
+                let [r, g, b, a] = self.color;
+                materials.add(StandardMaterial {
+                    base_color: Color::srgba(r, g, b, a),
+                    ..default()
+                })
+            }
+        }
+    }
+}
+
+/// Named text styles, so e.g. all `"heading"` or `"damage_number"` texts look the same and
+/// can be tweaked in one place.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TextStyles {
+    styles: HashMap<String, TextStyle>,
+}
+
+impl TextStyles {
+    pub fn insert(&mut self, name: impl Into<String>, style: TextStyle) -> &mut Self {
+        self.styles.insert(name.into(), style);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TextStyle> {
+        self.styles.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut TextStyle> {
+        self.styles.get_mut(name)
+    }
+
+    /// The input and parameters to pass to [`crate::generate_meshes`] for `text` in the style
+    /// `name`.
+    pub fn text(
+        &self,
+        name: &str,
+        text: impl Into<String>,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Option<(InputText<'_, StandardMaterial>, Parameters)> {
+        let style = self.get(name)?;
+        Some((
+            InputText::Simple {
+                text: text.into(),
+                material: style.material(materials),
+                attrs: style.attrs(),
+            },
+            style.parameters(),
+        ))
+    }
+
+    /// Parses presets from a RON map of style names to styles. Fields left out of a style
+    /// keep their defaults.
+    #[cfg(feature = "ron")]
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        Ok(Self {
+            styles: ron::from_str(source)?,
+        })
+    }
+
+    /// Adds the presets from `source`, replacing existing styles with the same name.
+    #[cfg(feature = "ron")]
+    pub fn extend_from_ron(&mut self, source: &str) -> Result<(), ron::error::SpannedError> {
+        self.styles.extend(Self::from_ron(source)?.styles);
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+//! Shapes from SVG path data, to extrude logos and icons like glyphs.
+
+use lyon::geom::{Angle, ArcFlags, SvgArc, point, vector};
+use lyon::path::{Builder, Path};
+
+use crate::MeshTextError;
+use crate::contour::{Contour, contours_from_path};
+
+type Point = lyon::geom::Point<f32>;
+
+/// Parses the `d` attribute of an SVG `<path>` into closed contours, ready for
+/// [`crate::extrude_glyph::extrude_contours`].
+///
+/// Y is flipped so the shape is upright in Bevy's Y-up space. Like SVG fills, every subpath
+/// is treated as closed.
+pub fn parse_svg_path(data: &str) -> Result<Vec<Contour>, MeshTextError> {
+    let path = svg_path(data)?;
+    Ok(contours_from_path(&path)
+        .into_iter()
+        .map(|contour| Contour {
+            closed: true,
+            ..contour
+        })
+        .collect())
+}
+
+/// Builds a path from SVG path data, with Y flipped like [`parse_svg_path`].
+///
+/// All commands are supported, with arcs approximated by quadratic curves.
+pub fn svg_path(data: &str) -> Result<Path, MeshTextError> {
+    let mut lexer = Lexer {
+        data: data.as_bytes(),
+        pos: 0,
+    };
+    let mut builder = SvgBuilder {
+        builder: Path::builder(),
+        open: false,
+        start: point(0.0, 0.0),
+        current: point(0.0, 0.0),
+    };
+    // Control point of the previous curve, reflected by the smooth curve commands
+    let mut last_cubic: Option<Point> = None;
+    let mut last_quadratic: Option<Point> = None;
+
+    let mut command = None;
+    while let Some(next) = lexer.peek() {
+        if next.is_ascii_alphabetic() {
+            lexer.pos += 1;
+            command = Some(next);
+        } else if command.is_none_or(|c: u8| c.eq_ignore_ascii_case(&b'z')) {
+            // Numbers need a command, and a close takes none
+            return Err(MeshTextError::PathBuildingFailed);
+        }
+        let Some(c) = command else {
+            return Err(MeshTextError::PathBuildingFailed);
+        };
+        let relative = c.is_ascii_lowercase();
+        let origin = if relative {
+            builder.current.to_vector()
+        } else {
+            vector(0.0, 0.0)
+        };
+        let (cubic, quadratic) = (last_cubic.take(), last_quadratic.take());
+
+        match c.to_ascii_uppercase() {
+            b'M' => {
+                let to = lexer.point()? + origin;
+                builder.move_to(to);
+                // Further coordinate pairs are lines
+                command = Some(if relative { b'l' } else { b'L' });
+            }
+            b'Z' => builder.close(),
+            b'L' => {
+                let to = lexer.point()? + origin;
+                builder.line_to(to);
+            }
+            b'H' => {
+                let x = lexer.number()? + origin.x;
+                builder.line_to(point(x, builder.current.y));
+            }
+            b'V' => {
+                let y = lexer.number()? + origin.y;
+                builder.line_to(point(builder.current.x, y));
+            }
+            b'C' | b'S' => {
+                let ctrl1 = if c.eq_ignore_ascii_case(&b'C') {
+                    lexer.point()? + origin
+                } else {
+                    reflect(cubic, builder.current)
+                };
+                let ctrl2 = lexer.point()? + origin;
+                let to = lexer.point()? + origin;
+                builder.cubic_to(ctrl1, ctrl2, to);
+                last_cubic = Some(ctrl2);
+            }
+            b'Q' | b'T' => {
+                let ctrl = if c.eq_ignore_ascii_case(&b'Q') {
+                    lexer.point()? + origin
+                } else {
+                    reflect(quadratic, builder.current)
+                };
+                let to = lexer.point()? + origin;
+                builder.quadratic_to(ctrl, to);
+                last_quadratic = Some(ctrl);
+            }
+            b'A' => {
+                let radii = vector(lexer.number()?.abs(), lexer.number()?.abs());
+                let x_rotation = Angle::degrees(lexer.number()?);
+                let flags = ArcFlags {
+                    large_arc: lexer.flag()?,
+                    sweep: lexer.flag()?,
+                };
+                let to = lexer.point()? + origin;
+                let arc = SvgArc {
+                    from: builder.current,
+                    to,
+                    radii,
+                    x_rotation,
+                    flags,
+                };
+                arc.for_each_quadratic_bezier(&mut |curve| {
+                    builder.quadratic_to(curve.ctrl, curve.to);
+                });
+            }
+            _ => return Err(MeshTextError::PathBuildingFailed),
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// The first control point of a smooth curve: the previous control point mirrored around the
+/// current point, or the current point itself.
+fn reflect(ctrl: Option<Point>, current: Point) -> Point {
+    ctrl.map_or(current, |ctrl| current + (current - ctrl))
+}
+
+/// Tracks the subpaths of SVG path data, which may draw without a move and continue after a
+/// close, and flips Y on the way into the path.
+struct SvgBuilder {
+    builder: Builder,
+    open: bool,
+    start: Point,
+    current: Point,
+}
+
+impl SvgBuilder {
+    fn flip(p: Point) -> Point {
+        point(p.x, -p.y)
+    }
+
+    fn move_to(&mut self, to: Point) {
+        if self.open {
+            self.builder.end(false);
+        }
+        self.builder.begin(Self::flip(to));
+        (self.open, self.start, self.current) = (true, to, to);
+    }
+
+    /// Starts a subpath at the current point when drawing without a move.
+    fn ensure_open(&mut self) {
+        if !self.open {
+            self.move_to(self.current);
+        }
+    }
+
+    fn line_to(&mut self, to: Point) {
+        self.ensure_open();
+        self.builder.line_to(Self::flip(to));
+        self.current = to;
+    }
+
+    fn quadratic_to(&mut self, ctrl: Point, to: Point) {
+        self.ensure_open();
+        self.builder
+            .quadratic_bezier_to(Self::flip(ctrl), Self::flip(to));
+        self.current = to;
+    }
+
+    fn cubic_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        self.ensure_open();
+        self.builder
+            .cubic_bezier_to(Self::flip(ctrl1), Self::flip(ctrl2), Self::flip(to));
+        self.current = to;
+    }
+
+    fn close(&mut self) {
+        if self.open {
+            self.builder.end(true);
+            self.open = false;
+        }
+        self.current = self.start;
+    }
+
+    fn finish(mut self) -> Path {
+        if self.open {
+            self.builder.end(false);
+        }
+        self.builder.build()
+    }
+}
+
+/// Reads the numbers and flags of SVG path data.
+struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    fn skip_separators(&mut self) {
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_whitespace() || *c == b',')
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// The next character after the separators, if any.
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_separators();
+        self.data.get(self.pos).copied()
+    }
+
+    fn number(&mut self) -> Result<f32, MeshTextError> {
+        self.skip_separators();
+        let start = self.pos;
+        let digits = |lexer: &mut Self| {
+            let from = lexer.pos;
+            while lexer.data.get(lexer.pos).is_some_and(u8::is_ascii_digit) {
+                lexer.pos += 1;
+            }
+            lexer.pos > from
+        };
+        if matches!(self.data.get(self.pos), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+        let mut any = digits(self);
+        // Numbers like "1.5.5" are two numbers, "1.5" and ".5"
+        if self.data.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            any |= digits(self);
+        }
+        if any && matches!(self.data.get(self.pos), Some(b'e' | b'E')) {
+            let mantissa_end = self.pos;
+            self.pos += 1;
+            if matches!(self.data.get(self.pos), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if !digits(self) {
+                self.pos = mantissa_end;
+            }
+        }
+        if !any {
+            return Err(MeshTextError::PathBuildingFailed);
+        }
+        std::str::from_utf8(&self.data[start..self.pos])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .ok_or(MeshTextError::PathBuildingFailed)
+    }
+
+    fn point(&mut self) -> Result<Point, MeshTextError> {
+        Ok(point(self.number()?, self.number()?))
+    }
+
+    /// Arc flags are a single digit and may be written without a separator.
+    fn flag(&mut self) -> Result<bool, MeshTextError> {
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(MeshTextError::PathBuildingFailed),
+        };
+        self.pos += 1;
+        Ok(flag)
+    }
+}
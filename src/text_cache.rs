@@ -0,0 +1,204 @@
+//! Whole-text cache above the glyph cache, so common labels are generated once.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use bevy::asset::UntypedAssetId;
+use bevy::prelude::*;
+use cosmic_text::AttrsOwned;
+
+use crate::{
+    ExclusionZone, InputText, MeshTextEntry, MeshTextError, Parameters, Settings, generate_meshes,
+};
+
+/// The spans of a text with their material, and a hash of everything else shaping its
+/// entries.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct TextCacheKey {
+    spans: Vec<(String, AttrsOwned, UntypedAssetId)>,
+    params_hash: u64,
+}
+
+impl TextCacheKey {
+    fn new<M: Asset>(text: &InputText<M>, params: &Parameters, text_scale_factor: f32) -> Self {
+        let spans = match text {
+            InputText::Simple {
+                text,
+                material,
+                attrs,
+            } => vec![(
+                text.clone(),
+                AttrsOwned::new(attrs),
+                material.id().untyped(),
+            )],
+            InputText::Rich {
+                words,
+                materials,
+                attrs,
+            } => words
+                .iter()
+                .zip(materials)
+                .zip(attrs)
+                .map(|((word, material), attrs)| {
+                    (
+                        word.clone(),
+                        AttrsOwned::new(attrs),
+                        material.id().untyped(),
+                    )
+                })
+                .collect(),
+        };
+        Self {
+            spans,
+            params_hash: params_hash(params, text_scale_factor),
+        }
+    }
+}
+
+/// Hash of the layout and placement parameters, on top of the extrusion options. Every field of
+/// [`Parameters`] is named, so new ones can't be left out of the key unnoticed.
+fn params_hash(params: &Parameters, text_scale_factor: f32) -> u64 {
+    let Parameters {
+        font_size,
+        line_height,
+        alignment,
+        max_width,
+        max_height,
+        exclusion_zones,
+        drop_cap,
+        missing_glyph_policy,
+        double_sided,
+        depth_anchor,
+        grid,
+        fit_box,
+        stagger,
+        // Hashed as the world scale
+        scale: _,
+        // Hashed with the extrusion options
+        extrusion_depth: _,
+        depth: _,
+        front_inset: _,
+        bevel_width: _,
+        bevel_edges: _,
+        hole_bevel_width: _,
+        front_rim: _,
+        rounded_back: _,
+        inflate: _,
+        surface_noise: _,
+        edge_mask: _,
+        skinned: _,
+        depth_attribute: _,
+        surface_attribute: _,
+        uv_mode: _,
+        lightmap_uvs: _,
+        wall_smoothing_angle: _,
+        wall_seam: _,
+        wall_uv_scale: _,
+        depth_segments: _,
+        tessellation: _,
+        topology: _,
+        synthetic_style: _,
+    } = params;
+    let point_bits = |points: &[Vec2]| -> Vec<[u32; 2]> {
+        points
+            .iter()
+            .map(|point| point.to_array().map(f32::to_bits))
+            .collect()
+    };
+
+    let mut hasher = DefaultHasher::new();
+    params.extrusion_options().cache_hash().hash(&mut hasher);
+    font_size.to_bits().hash(&mut hasher);
+    line_height.to_bits().hash(&mut hasher);
+    alignment.map(|align| align as u8).hash(&mut hasher);
+    max_width.map(f32::to_bits).hash(&mut hasher);
+    max_height.map(f32::to_bits).hash(&mut hasher);
+    exclusion_zones.len().hash(&mut hasher);
+    for zone in exclusion_zones {
+        match zone {
+            ExclusionZone::Rect(rect) => (0u8, point_bits(&[rect.min, rect.max])).hash(&mut hasher),
+            ExclusionZone::Polygon(points) => (1u8, point_bits(points)).hash(&mut hasher),
+        }
+    }
+    drop_cap
+        .map(|drop_cap| (drop_cap.lines, drop_cap.gap.to_bits()))
+        .hash(&mut hasher);
+    (*missing_glyph_policy as u8).hash(&mut hasher);
+    params
+        .world_scale(text_scale_factor)
+        .to_array()
+        .map(f32::to_bits)
+        .hash(&mut hasher);
+    double_sided.hash(&mut hasher);
+    (*depth_anchor as u8).hash(&mut hasher);
+    grid.map(|grid| (grid.cell_width.to_bits(), grid.snap.map(f32::to_bits)))
+        .hash(&mut hasher);
+    fit_box
+        .map(|fit_box| fit_box.to_array().map(f32::to_bits))
+        .hash(&mut hasher);
+    stagger
+        .map(|stagger| (stagger.per_line.to_bits(), stagger.per_glyph.to_bits()))
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Generated texts, returned again for the same text, attributes, materials and parameters,
+/// e.g. for labels like "Loading..." or item names spawned over and over.
+///
+/// The plugin adds one for `StandardMaterial` texts of the [`Settings`] resource. A cache
+/// belongs to one font context: texts generated with other fonts need a cache of their own.
+/// Like the glyph cache, it holds strong handles, so its meshes stay alive until
+/// [`clear`](Self::clear) is called.
+#[derive(Resource)]
+pub struct TextCache<M: Asset> {
+    entries: HashMap<TextCacheKey, Vec<MeshTextEntry<M>>>,
+    hits: usize,
+}
+
+impl<M: Asset> Default for TextCache<M> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            hits: 0,
+        }
+    }
+}
+
+impl<M: Asset> TextCache<M> {
+    /// Returns the entries generated before for the same text and parameters, or generates
+    /// them with [`generate_meshes`] and keeps a copy.
+    pub fn generate(
+        &mut self,
+        text: InputText<M>,
+        fonts: &mut Settings,
+        params: Parameters,
+        meshes: &mut Assets<Mesh>,
+    ) -> Result<Vec<MeshTextEntry<M>>, MeshTextError> {
+        let key = TextCacheKey::new(&text, &params, fonts.text_scale_factor);
+        if let Some(entries) = self.entries.get(&key) {
+            self.hits += 1;
+            return Ok(entries.clone());
+        }
+        let entries = generate_meshes(text, fonts, params, meshes)?;
+        self.entries.insert(key, entries.clone());
+        Ok(entries)
+    }
+
+    /// Number of cached texts.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of texts returned from the cache since it was created.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -1,15 +1,86 @@
 use bevy::prelude::*;
 use cosmic_text::{
-    Align, Attrs, Buffer, FontSystem, Metrics, Shaping,
+    Align, Attrs, AttrsOwned, Buffer, Font, FontSystem, LayoutGlyph, LayoutRun, Metrics, Shaping,
     ttf_parser::{Face, GlyphId},
 };
-use std::collections::HashMap;
 
-use crate::MeshGlyph;
-use crate::extrude_glyph::tessalate_glyph;
+use crate::MeshTextError;
+use crate::exclusion::{ExclusionZone, free_segments};
+use crate::extrude_glyph::{
+    ExtrudedGlyphGeometry, ExtrusionOptions, UvMode, placeholder_glyph, tessalate_glyph,
+};
+use crate::generation_report::{GlyphIssue, GlyphLog};
+use crate::glyph_cache::{CANONICAL_FONT_SIZE, GlyphCache, GlyphCacheKey};
+use crate::glyph_store::BakedTextMesh;
+use crate::{DropCapParameters, FontMetricsInfo, MeshGlyph, MissingGlyphPolicy, Parameters};
+
+/// Where the glyphs of a line sit in the text block, for [`UvMode::TextBlock`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BlockUv {
+    pub width: f32,
+    pub line_top: f32,
+    pub line_height: f32,
+}
+
+/// Where a meshed glyph comes from in the laid out text.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GlyphSource {
+    /// Index of the layout run the glyph is on
+    pub line: usize,
+    /// Index of the glyph among all laid out glyphs, including those without a mesh
+    pub index: usize,
+    /// Index of the glyph on its line, including those without a mesh
+    pub column: usize,
+    /// First character of the glyph's cluster
+    pub character: char,
+    /// Font glyph id, 0 for a glyph missing from every font
+    pub glyph_id: u16,
+}
 
 pub struct TextGlyphs {
     buffer: cosmic_text::Buffer,
+    /// The glyphs of every layout run moved into the cells of a [`crate::LayoutGrid`], in
+    /// place of those of the buffer
+    grid_runs: Option<Vec<GridRun>>,
+    /// Lines broken again around [`ExclusionZone`]s, in place of those of the buffer
+    flowed_runs: Option<Vec<FlowedRun>>,
+}
+
+/// A layout run placed on a grid of cells.
+struct GridRun {
+    glyphs: Vec<LayoutGlyph>,
+    width: f32,
+}
+
+/// A line of words flowed around exclusion zones.
+struct FlowedRun {
+    /// Index of the buffer line, the paragraph, the words come from
+    line_i: usize,
+    rtl: bool,
+    glyphs: Vec<LayoutGlyph>,
+    line_y: f32,
+    line_top: f32,
+    line_height: f32,
+    width: f32,
+}
+
+/// Lines in a row exclusion zones may leave without room for the next word before the rest
+/// of the text is dropped, so zones reaching far down don't grow the layout without end.
+const MAX_BLANK_LINES: usize = 1000;
+
+/// The glyphs of a drop cap, in place of the first cluster of a paragraph.
+struct DropCapGlyphs {
+    line_i: usize,
+    /// End of the cluster in the text of the paragraph
+    end: usize,
+    glyphs: Vec<LayoutGlyph>,
+}
+
+/// The glyphs of a word, from the left of the word, and the space before it.
+struct FlowWord {
+    glyphs: Vec<LayoutGlyph>,
+    width: f32,
+    gap: f32,
 }
 
 impl TextGlyphs {
@@ -31,7 +102,39 @@ impl TextGlyphs {
             Shaping::Advanced,
             alignment,
         );
-        Self { buffer }
+        Self {
+            buffer,
+            grid_runs: None,
+            flowed_runs: None,
+        }
+    }
+
+    /// Wraps a buffer shaped and laid out by the caller, e.g. with custom shaping or spans
+    /// from an editor. The `metadata` of each span indexes the materials passed to
+    /// [`crate::generate_meshes_from_glyphs`].
+    pub fn from_buffer(buffer: Buffer) -> Self {
+        Self {
+            buffer,
+            grid_runs: None,
+            flowed_runs: None,
+        }
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Mutable access to the buffer. Reshape it before generating meshes after changes. The
+    /// glyphs are taken off their grid and out of lines flowed around exclusion zones, if they
+    /// were.
+    pub fn buffer_mut(&mut self) -> &mut Buffer {
+        self.grid_runs = None;
+        self.flowed_runs = None;
+        &mut self.buffer
+    }
+
+    pub fn into_buffer(self) -> Buffer {
+        self.buffer
     }
 
     pub fn measure(
@@ -40,6 +143,8 @@ impl TextGlyphs {
         height_opt: Option<f32>,
         font_system: &mut FontSystem,
     ) -> (f32, f32) {
+        self.grid_runs = None;
+        self.flowed_runs = None;
         self.buffer.set_size(font_system, width_opt, height_opt);
 
         // Compute layout
@@ -57,73 +162,681 @@ impl TextGlyphs {
         (width, height)
     }
 
-    pub fn generate_mesh_glyphs<M: Asset>(
-        &self,
+    /// Moves the glyphs of every line into cells `cell_width` wide, one per cluster, with the
+    /// glyphs centered in their cell. The lines are aligned by whole cells, within `max_width`
+    /// or the widest line, so the cells of all lines line up. Lines keep the breaks of the
+    /// proportional layout. Measuring again undoes it.
+    pub(crate) fn snap_to_grid(
+        &mut self,
+        cell_width: f32,
+        max_width: Option<f32>,
+        alignment: Option<Align>,
+    ) {
+        let mut runs: Vec<_> = self
+            .buffer
+            .layout_runs()
+            .map(|run| {
+                let mut order: Vec<_> = (0..run.glyphs.len()).collect();
+                order.sort_by(|&a, &b| run.glyphs[a].x.total_cmp(&run.glyphs[b].x));
+                let mut glyphs = run.glyphs.to_vec();
+                let mut cells = 0;
+                let mut cluster = None;
+                for i in order {
+                    let glyph = &mut glyphs[i];
+                    if cluster != Some(glyph.start) {
+                        cluster = Some(glyph.start);
+                        cells += 1;
+                    }
+                    glyph.x = (cells - 1) as f32 * cell_width + (cell_width - glyph.w) / 2.0;
+                }
+                let end = if run.rtl { 0.0 } else { 1.0 };
+                let factor = match alignment {
+                    Some(Align::Left | Align::Justified) => 0.0,
+                    Some(Align::Center) => 0.5,
+                    Some(Align::Right) => 1.0,
+                    Some(Align::End) => end,
+                    None => 1.0 - end,
+                };
+                let run = GridRun {
+                    glyphs,
+                    width: cells as f32 * cell_width,
+                };
+                (run, factor)
+            })
+            .collect();
+
+        let block_width = max_width.unwrap_or_else(|| {
+            runs.iter()
+                .fold(0.0, |width: f32, (run, _)| width.max(run.width))
+        });
+        for (run, factor) in &mut runs {
+            let offset = ((block_width - run.width) * *factor / cell_width).floor() * cell_width;
+            for glyph in &mut run.glyphs {
+                glyph.x += offset.max(0.0);
+            }
+        }
+        self.grid_runs = Some(runs.into_iter().map(|(run, _)| run).collect());
+    }
+
+    /// Breaks the laid out lines again so no glyph lands in `zones`, filling the stretches of
+    /// each line between them up to `max_width` with whole words, left aligned. Paragraphs
+    /// still start on a new line, and a word too wide for any line is put where it starts one.
+    /// Words that would start a line below the height of the buffer, or after
+    /// [`MAX_BLANK_LINES`] lines in a row without room for them, are dropped.
+    /// Measuring again undoes it.
+    pub(crate) fn flow_around(&mut self, zones: &[ExclusionZone], max_width: Option<f32>) {
+        self.flow(zones, max_width, None);
+    }
+
+    /// Sets the first cluster of the text as a drop cap, shaped again large enough to reach
+    /// from the height of the capitals of the first line to the baseline of the line
+    /// `drop_cap.lines` down, and flows the lines around it and around `zones` like
+    /// [`flow_around`](Self::flow_around).
+    pub(crate) fn set_drop_cap(
+        &mut self,
+        drop_cap: DropCapParameters,
+        zones: &[ExclusionZone],
+        max_width: Option<f32>,
         font_system: &mut FontSystem,
-        extrusion_depth: f32,
-        meshes: &mut ResMut<Assets<Mesh>>,
-        materials: &[Handle<M>],
-    ) -> Vec<MeshGlyph<M>> {
-        let mut mesh_map: HashMap<u16, (Handle<Mesh>, f32, f32)> = HashMap::new();
-        let mut processed_glyphs = Vec::new();
-        for run in self.buffer.layout_runs() {
+    ) {
+        let line_height = self.buffer.metrics().line_height;
+        let cap = self.buffer.layout_runs().next().and_then(|run| {
+            let first = run.glyphs.iter().min_by_key(|glyph| glyph.start)?;
+            let text = &run.text[first.start..first.end];
+            if text.trim().is_empty() {
+                return None;
+            }
+            let font = font_system.get_font(first.font_id)?;
+            let face = Face::parse(font.data(), 0).ok()?;
+            // The letter grows from its height above the baseline to that of the capitals,
+            // plus the lines it drops
+            let cap_height = FontMetricsInfo::from_face(&face, first.font_size).cap_height;
+            let top = face
+                .glyph_bounding_box(GlyphId(first.glyph_id))
+                .map_or(cap_height, |bounds| {
+                    bounds.y_max as f32 * first.font_size / face.units_per_em() as f32
+                });
+            if top <= 0.0 {
+                return None;
+            }
+            let drop = (drop_cap.lines - 1) as f32 * line_height;
+            let font_size = (drop + cap_height) * first.font_size / top;
+
+            let attrs = AttrsOwned::new(&self.buffer.lines[run.line_i].attrs_list().get_span(0));
+            let mut buffer = Buffer::new_empty(Metrics::new(font_size, font_size));
+            buffer.set_text(font_system, text, &attrs.as_attrs(), Shaping::Advanced);
+            buffer.shape_until_scroll(font_system, false);
+            let glyphs: Vec<LayoutGlyph> = buffer
+                .layout_runs()
+                .flat_map(|run| run.glyphs.iter())
+                .map(|glyph| LayoutGlyph {
+                    start: glyph.start + first.start,
+                    end: glyph.end + first.start,
+                    y: glyph.y + drop,
+                    ..glyph.clone()
+                })
+                .collect();
+            Some(DropCapGlyphs {
+                line_i: run.line_i,
+                end: first.end,
+                glyphs,
+            })
+        });
+        let Some(cap) = cap else {
+            self.flow(zones, max_width, None);
+            return;
+        };
+
+        let width = cap
+            .glyphs
+            .iter()
+            .fold(0.0, |width: f32, glyph| width.max(glyph.x + glyph.w));
+        let mut zones = zones.to_vec();
+        zones.push(ExclusionZone::Rect(Rect::new(
+            0.0,
+            0.0,
+            width + drop_cap.gap,
+            drop_cap.lines as f32 * line_height,
+        )));
+        self.flow(&zones, max_width, Some(cap));
+    }
+
+    fn flow(
+        &mut self,
+        zones: &[ExclusionZone],
+        max_width: Option<f32>,
+        cap: Option<DropCapGlyphs>,
+    ) {
+        let metrics = self.buffer.metrics();
+        let width = max_width.unwrap_or(f32::INFINITY);
+        let height = self.buffer.size().1.unwrap_or(f32::INFINITY);
+
+        // The words of every paragraph, with the width of the spaces before them
+        let mut paragraphs: Vec<(usize, bool, f32, Vec<FlowWord>)> = Vec::new();
+        for run in self.layout_runs() {
+            if paragraphs
+                .last()
+                .is_none_or(|(line_i, ..)| *line_i != run.line_i)
+            {
+                let ascent = run.line_y - run.line_top;
+                paragraphs.push((run.line_i, run.rtl, ascent, Vec::new()));
+            }
+            let words = &mut paragraphs.last_mut().unwrap().3;
+            let mut gap = 0.0;
+            let mut word: Option<FlowWord> = None;
             for glyph in run.glyphs {
-                let Some((geometry, center_x_layout, center_y_layout)) = mesh_map
-                    .get(&glyph.glyph_id)
-                    .map(|(mesh, center_x_layout, center_y_layout)| {
-                        (mesh.clone(), *center_x_layout, *center_y_layout)
-                    })
-                    .or_else(|| {
-                        font_system
-                            .db()
-                            .with_face_data(glyph.font_id, |file, _| {
-                                let Ok(face) = Face::parse(file, 0) else {
-                                    error!("Failed to parse font");
-                                    return None;
-                                };
-                                let bb = face.glyph_bounding_box(GlyphId(glyph.glyph_id))?;
-                                match tessalate_glyph(glyph, bb, face, extrusion_depth) {
-                                    Ok(n) => Some(n),
-                                    Err(e) => {
-                                        error!("Failed to tessalate glyph {:?}", e);
-                                        None
-                                    }
-                                }
-                            })
-                            .flatten()
-                            .map(|(geometry, center_x_layout, center_y_layout)| {
-                                (meshes.add(geometry), center_x_layout, center_y_layout)
-                            })
-                    })
-                else {
-                    error!("Failed to tessalate glyph {:?}", glyph.glyph_id);
+                if cap
+                    .as_ref()
+                    .is_some_and(|cap| cap.line_i == run.line_i && glyph.start < cap.end)
+                {
                     continue;
+                }
+                if run.text[glyph.start..glyph.end].trim().is_empty() {
+                    words.extend(word.take());
+                    gap += glyph.w;
+                    continue;
+                }
+                let word = word.get_or_insert_with(|| FlowWord {
+                    glyphs: Vec::new(),
+                    width: 0.0,
+                    gap: std::mem::take(&mut gap),
+                });
+                let left = word.glyphs.first().map_or(glyph.x, |first| first.x);
+                word.glyphs.push(LayoutGlyph {
+                    x: glyph.x - left,
+                    ..glyph.clone()
+                });
+                word.width = word.width.max(glyph.x + glyph.w - left);
+            }
+            words.extend(word);
+        }
+
+        let mut runs = Vec::new();
+        let mut top = 0.0;
+        let mut blank_lines = 0;
+        'paragraphs: for (line_i, rtl, ascent, words) in paragraphs {
+            let mut words = words.into_iter().peekable();
+            loop {
+                if top >= height || blank_lines > MAX_BLANK_LINES {
+                    if blank_lines > MAX_BLANK_LINES {
+                        warn!("Exclusion zones leave no room for the rest of the text");
+                    }
+                    break 'paragraphs;
+                }
+                let bottom = top + metrics.line_height;
+                let mut run = FlowedRun {
+                    line_i,
+                    rtl,
+                    glyphs: Vec::new(),
+                    line_y: top + ascent,
+                    line_top: top,
+                    line_height: metrics.line_height,
+                    width: 0.0,
                 };
-                mesh_map
-                    .entry(glyph.glyph_id)
-                    .or_insert_with(|| (geometry.clone(), center_x_layout, center_y_layout));
+                for (start, end) in free_segments(zones, top, bottom, width) {
+                    let mut x = start;
+                    while let Some(word) = words.peek() {
+                        let first = x == start;
+                        let at = if first { x } else { x + word.gap };
+                        let too_wide = word.width > width && run.glyphs.is_empty();
+                        if at + word.width > end && !too_wide {
+                            break;
+                        }
+                        let word = words.next().unwrap();
+                        run.glyphs
+                            .extend(word.glyphs.into_iter().map(|glyph| LayoutGlyph {
+                                x: glyph.x + at,
+                                ..glyph
+                            }));
+                        x = at + word.width;
+                        run.width = run.width.max(x);
+                    }
+                }
+                if run.glyphs.is_empty() && words.peek().is_some() {
+                    blank_lines += 1;
+                } else {
+                    blank_lines = 0;
+                }
+                runs.push(run);
+                top = bottom;
+                if words.peek().is_none() {
+                    break;
+                }
+            }
+        }
+        if let Some((cap, first)) = cap.zip(runs.first_mut()) {
+            for glyph in &cap.glyphs {
+                first.width = first.width.max(glyph.x + glyph.w);
+            }
+            first.glyphs.splice(0..0, cap.glyphs);
+        }
+        self.flowed_runs = Some(runs);
+    }
+
+    /// The laid out lines, with their glyphs on the grid after
+    /// [`snap_to_grid`](Self::snap_to_grid) or flowed around exclusion zones after
+    /// [`flow_around`](Self::flow_around).
+    pub(crate) fn layout_runs(&self) -> impl Iterator<Item = LayoutRun<'_>> {
+        let flowed = self.flowed_runs.iter().flatten().map(|run| LayoutRun {
+            line_i: run.line_i,
+            text: self.buffer.lines[run.line_i].text(),
+            rtl: run.rtl,
+            glyphs: &run.glyphs,
+            line_y: run.line_y,
+            line_top: run.line_top,
+            line_height: run.line_height,
+            line_w: run.width,
+        });
+        let mut grid_runs = self.grid_runs.as_deref().map(<[_]>::iter);
+        let buffer_runs = self
+            .flowed_runs
+            .is_none()
+            .then(|| self.buffer.layout_runs());
+        flowed.chain(buffer_runs.into_iter().flatten().map(move |run| {
+            match grid_runs.as_mut().and_then(Iterator::next) {
+                Some(grid) => LayoutRun {
+                    glyphs: &grid.glyphs,
+                    line_w: grid.width,
+                    ..run
+                },
+                None => run,
+            }
+        }))
+    }
+
+    /// Bounds of the laid out glyphs over the full height of their lines, in layout units and
+    /// Y up like the glyph placement in `generate_meshes`. `None` without glyphs.
+    pub(crate) fn bounds(&self) -> Option<Rect> {
+        self.layout_runs()
+            .flat_map(|run| {
+                run.glyphs.iter().map(move |glyph| {
+                    Rect::new(
+                        glyph.x,
+                        -(run.line_top + run.line_height),
+                        glyph.x + glyph.w,
+                        -run.line_top,
+                    )
+                })
+            })
+            .reduce(|a, b| a.union(b))
+    }
 
-                let material = materials
-                    .get(glyph.metadata)
-                    .unwrap_or_else(|| &materials[0])
-                    .clone();
+    /// Meshes the laid out glyphs one at a time, in layout order. Glyphs come from the cache or
+    /// are tessellated as the iterator advances, so callers can interleave other work, report
+    /// progress against the upper bound of `size_hint`, or stop early by dropping the iterator.
+    pub fn generate_mesh_glyphs<'a, M: Asset>(
+        &'a self,
+        font_system: &'a mut FontSystem,
+        glyph_cache: &'a mut GlyphCache,
+        params: &'a Parameters,
+        meshes: &'a mut Assets<Mesh>,
+        materials: &'a [Handle<M>],
+    ) -> impl Iterator<Item = MeshGlyph<M>> + 'a {
+        self.generate_mesh_glyphs_with_source(font_system, glyph_cache, params, meshes, materials)
+            .map(|(_, glyph)| glyph)
+    }
 
-                processed_glyphs.push(MeshGlyph {
+    /// Like [`generate_mesh_glyphs`](Self::generate_mesh_glyphs), with where each glyph comes
+    /// from in the text.
+    pub(crate) fn generate_mesh_glyphs_with_source<'a, M: Asset>(
+        &'a self,
+        font_system: &'a mut FontSystem,
+        glyph_cache: &'a mut GlyphCache,
+        params: &'a Parameters,
+        meshes: &'a mut Assets<Mesh>,
+        materials: &'a [Handle<M>],
+    ) -> impl Iterator<Item = (GlyphSource, MeshGlyph<M>)> + 'a {
+        self.generate_mesh_glyphs_logged(font_system, glyph_cache, params, meshes, materials)
+            .filter_map(|(source, glyph, _)| Some((source, glyph?)))
+    }
+
+    /// Like [`generate_mesh_glyphs_with_source`](Self::generate_mesh_glyphs_with_source), with
+    /// what meshing each glyph ran into, and the glyphs without a mesh.
+    pub(crate) fn generate_mesh_glyphs_logged<'a, M: Asset>(
+        &'a self,
+        font_system: &'a mut FontSystem,
+        glyph_cache: &'a mut GlyphCache,
+        params: &'a Parameters,
+        meshes: &'a mut Assets<Mesh>,
+        materials: &'a [Handle<M>],
+    ) -> impl Iterator<Item = (GlyphSource, Option<MeshGlyph<M>>, GlyphLog)> + 'a {
+        let options = params.extrusion_options();
+        let width = self
+            .layout_runs()
+            .fold(0.0, |width: f32, run| width.max(run.line_w));
+        let blocks: Vec<_> = self
+            .layout_runs()
+            .map(|run| BlockUv {
+                width,
+                line_top: run.line_top,
+                line_height: run.line_height,
+            })
+            .collect();
+        self.layout_glyphs()
+            .into_iter()
+            .map(move |(source, glyph, line_y)| {
+                let mut log = GlyphLog::default();
+                let glyph = mesh_glyph_logged(
+                    font_system,
+                    glyph_cache,
+                    params,
+                    &options,
+                    meshes,
+                    materials,
+                    glyph,
+                    line_y,
+                    Some(&blocks[source.line]),
+                    &mut log,
+                );
+                (source, glyph, log)
+            })
+    }
+
+    /// Every laid out glyph with where it comes from in the text and the baseline of its line,
+    /// in layout order.
+    pub(crate) fn layout_glyphs(&self) -> Vec<(GlyphSource, &LayoutGlyph, f32)> {
+        self.layout_runs()
+            .enumerate()
+            .flat_map(|(line, run)| {
+                run.glyphs.iter().enumerate().map(move |(column, glyph)| {
+                    let character = run.text[glyph.start..].chars().next().unwrap_or_default();
+                    (line, column, character, glyph, run.line_y)
+                })
+            })
+            .enumerate()
+            .map(|(index, (line, column, character, glyph, line_y))| {
+                let source = GlyphSource {
+                    line,
+                    index,
+                    column,
+                    character,
                     glyph_id: glyph.glyph_id,
-                    font_id: Some(glyph.font_id),
-                    x: glyph.x,
-                    y: glyph.y,
-                    x_offset: glyph.x_offset,
-                    y_offset: glyph.y_offset,
-                    line_y: run.line_y,
-                    glyph_center_x_layout: center_x_layout,
-                    glyph_center_y_layout: center_y_layout,
-                    height: glyph.font_size,
-                    mesh: geometry,
-                    material,
-                });
+                };
+                (source, glyph, line_y)
+            })
+            .collect()
+    }
+}
+
+impl BlockUv {
+    /// Moves the cap UVs of a glyph tessellated around its center to where it sits in the
+    /// text block.
+    pub(crate) fn place_uvs(
+        &self,
+        geometry: &mut ExtrudedGlyphGeometry,
+        glyph: &LayoutGlyph,
+        line_y: f32,
+        center_x_layout: f32,
+        center_y_layout: f32,
+    ) {
+        // Cap UVs are relative to the glyph center, Y down
+        let origin = Vec2::new(
+            glyph.x + glyph.x_offset + center_x_layout,
+            line_y + glyph.y + glyph.y_offset - center_y_layout - self.line_top,
+        );
+        let size = Vec2::new(self.width, self.line_height).max(Vec2::ONE);
+        for cap in &geometry.caps {
+            for uv in &mut geometry.uvs[cap.clone()] {
+                *uv = (origin + *uv) / size;
             }
         }
-        processed_glyphs
+    }
+}
+
+/// A laid out glyph as it is tessellated: at [`CANONICAL_FONT_SIZE`], with options to match,
+/// when its mesh can be shared across font sizes, or as it is otherwise.
+pub(crate) struct BakedGlyph {
+    pub glyph: LayoutGlyph,
+    pub options: ExtrusionOptions,
+    /// Size of the laid out glyph relative to its mesh, in the plane of the text
+    pub scale: f32,
+}
+
+impl BakedGlyph {
+    pub(crate) fn new(glyph: &LayoutGlyph, options: &ExtrusionOptions) -> Self {
+        if !options.size_independent() || glyph.font_size <= 0.0 {
+            return Self {
+                glyph: glyph.clone(),
+                options: *options,
+                scale: 1.0,
+            };
+        }
+        let scale = glyph.font_size / CANONICAL_FONT_SIZE;
+        let mut canonical = glyph.clone();
+        canonical.font_size = CANONICAL_FONT_SIZE;
+        // Placeholders are as wide as the advance
+        canonical.w /= scale;
+        Self {
+            glyph: canonical,
+            options: options.scaled_in_plane(1.0 / scale),
+            scale,
+        }
+    }
+
+    /// Scale from the mesh to the glyph's layout units. The depth is baked as it is.
+    pub(crate) fn mesh_scale(&self) -> Vec3 {
+        Vec3::new(self.scale, self.scale, 1.0)
+    }
+}
+
+/// The cache key of a laid out glyph, or `None` if `policy` skips it.
+pub(crate) fn glyph_cache_key(
+    glyph: &LayoutGlyph,
+    policy: MissingGlyphPolicy,
+    options: &ExtrusionOptions,
+) -> Option<GlyphCacheKey> {
+    // Glyphs missing from every font are shaped as the `.notdef` glyph
+    let missing = glyph.glyph_id == 0;
+    match policy {
+        MissingGlyphPolicy::Skip if missing => None,
+        MissingGlyphPolicy::Placeholder if missing => Some(GlyphCacheKey::placeholder(
+            glyph.font_id,
+            glyph.font_size,
+            options,
+        )),
+        _ => Some(GlyphCacheKey::new(
+            glyph.font_id,
+            glyph.glyph_id,
+            glyph.font_size,
+            options,
+        )),
+    }
+}
+
+/// Looks up or tessellates the mesh of a laid out glyph, following the missing glyph policy
+/// of `params`. `line_y` is the baseline of the glyph's line. With [`UvMode::TextBlock`],
+/// `block` places the UVs and the mesh bypasses the cache.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn mesh_glyph<M: Asset>(
+    font_system: &mut FontSystem,
+    glyph_cache: &mut GlyphCache,
+    params: &Parameters,
+    options: &ExtrusionOptions,
+    meshes: &mut Assets<Mesh>,
+    materials: &[Handle<M>],
+    glyph: &LayoutGlyph,
+    line_y: f32,
+    block: Option<&BlockUv>,
+) -> Option<MeshGlyph<M>> {
+    mesh_glyph_logged(
+        font_system,
+        glyph_cache,
+        params,
+        options,
+        meshes,
+        materials,
+        glyph,
+        line_y,
+        block,
+        &mut GlyphLog::default(),
+    )
+}
+
+/// Like [`mesh_glyph`], noting what meshing the glyph ran into in `log`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn mesh_glyph_logged<M: Asset>(
+    font_system: &mut FontSystem,
+    glyph_cache: &mut GlyphCache,
+    params: &Parameters,
+    options: &ExtrusionOptions,
+    meshes: &mut Assets<Mesh>,
+    materials: &[Handle<M>],
+    glyph: &LayoutGlyph,
+    line_y: f32,
+    block: Option<&BlockUv>,
+    log: &mut GlyphLog,
+) -> Option<MeshGlyph<M>> {
+    let baked = BakedGlyph::new(glyph, options);
+    // Glyphs missing from every font are shaped as the `.notdef` glyph
+    if glyph.glyph_id == 0 {
+        log.issues.push(GlyphIssue::MissingGlyph);
+    }
+    let key = glyph_cache_key(&baked.glyph, params.missing_glyph_policy, &baked.options)?;
+    let block = block.filter(|_| options.uv_mode == UvMode::TextBlock);
+    let cached = match block {
+        Some(_) => None,
+        None => glyph_cache.get_or_load(font_system, &key, meshes),
+    };
+    log.cached = cached.is_some();
+    let Some((geometry, center_x_layout, center_y_layout)) = cached
+        .map(|cached| {
+            (
+                cached.mesh.clone(),
+                cached.center_x_layout,
+                cached.center_y_layout,
+            )
+        })
+        .or_else(|| {
+            let geometry = match tessellate_layout_glyph(
+                font_system,
+                &baked.glyph,
+                params.missing_glyph_policy,
+                &baked.options,
+            ) {
+                Ok(geometry) => geometry,
+                Err(e) => {
+                    error!("Failed to tessalate glyph {:?}", e);
+                    log.issues
+                        .push(GlyphIssue::TessellationFailed(e.to_string()));
+                    None
+                }
+            };
+            geometry.map(|(mut geometry, center_x_layout, center_y_layout)| {
+                log.tessellated = true;
+                log.issues.append(&mut geometry.issues);
+                if let Some(block) = block {
+                    block.place_uvs(
+                        &mut geometry,
+                        glyph,
+                        line_y,
+                        center_x_layout,
+                        center_y_layout,
+                    );
+                    return (meshes.add(geometry), center_x_layout, center_y_layout);
+                }
+                let baked = BakedTextMesh {
+                    options: key.options(),
+                    geometry,
+                    center_x_layout,
+                    center_y_layout,
+                };
+                let mesh = glyph_cache.insert_baked(font_system, key, baked, meshes);
+                (mesh, center_x_layout, center_y_layout)
+            })
+        })
+    else {
+        // Glyphs without an outline, like spaces, have no mesh. Failures are logged above.
+        return None;
+    };
+
+    let material = materials
+        .get(glyph.metadata)
+        .unwrap_or_else(|| &materials[0])
+        .clone();
+
+    Some(MeshGlyph {
+        glyph_id: glyph.glyph_id,
+        font_id: Some(glyph.font_id),
+        x: glyph.x,
+        y: glyph.y,
+        x_offset: glyph.x_offset,
+        y_offset: glyph.y_offset,
+        line_y,
+        glyph_center_x_layout: center_x_layout * baked.scale,
+        glyph_center_y_layout: center_y_layout * baked.scale,
+        height: glyph.font_size,
+        mesh_scale: baked.mesh_scale(),
+        mesh: geometry,
+        material,
+    })
+}
+
+impl<M: Asset> MeshGlyph<M> {
+    /// `mesh`, tessellated from `glyph` at its size in the layout and centered on `center`,
+    /// where `glyph` is laid out on the line with its baseline at `line_y`.
+    pub(crate) fn at_layout_size(
+        glyph: &LayoutGlyph,
+        line_y: f32,
+        center: Vec2,
+        mesh: Handle<Mesh>,
+        material: Handle<M>,
+    ) -> Self {
+        Self {
+            glyph_id: glyph.glyph_id,
+            font_id: Some(glyph.font_id),
+            x: glyph.x,
+            y: glyph.y,
+            x_offset: glyph.x_offset,
+            y_offset: glyph.y_offset,
+            line_y,
+            glyph_center_x_layout: center.x,
+            glyph_center_y_layout: center.y,
+            height: glyph.font_size,
+            mesh_scale: Vec3::ONE,
+            mesh,
+            material,
+        }
+    }
+}
+
+/// Tessellates a laid out glyph without the glyph cache, following `policy` for glyphs missing
+/// from every font. Returns `None` for skipped glyphs and glyphs without an outline.
+pub(crate) fn tessellate_layout_glyph(
+    font_system: &mut FontSystem,
+    glyph: &LayoutGlyph,
+    policy: MissingGlyphPolicy,
+    options: &ExtrusionOptions,
+) -> Result<Option<(ExtrudedGlyphGeometry, f32, f32)>, MeshTextError> {
+    let font = font_system.get_font(glyph.font_id);
+    tessellate_font_glyph(font.as_deref().map(Font::data), glyph, policy, options)
+}
+
+/// Like [`tessellate_layout_glyph`], but reads the outline from the data of the glyph's font so
+/// it can run off the main thread.
+pub(crate) fn tessellate_font_glyph(
+    font_data: Option<&[u8]>,
+    glyph: &LayoutGlyph,
+    policy: MissingGlyphPolicy,
+    options: &ExtrusionOptions,
+) -> Result<Option<(ExtrudedGlyphGeometry, f32, f32)>, MeshTextError> {
+    let missing = glyph.glyph_id == 0;
+    if missing && policy == MissingGlyphPolicy::Skip {
+        return Ok(None);
+    }
+    let outline = if missing && policy == MissingGlyphPolicy::Placeholder {
+        None
+    } else {
+        font_data.and_then(|file| {
+            let face = Face::parse(file, 0).ok()?;
+            let bb = face.glyph_bounding_box(GlyphId(glyph.glyph_id))?;
+            Some(tessalate_glyph(glyph, bb, face, options))
+        })
+    };
+    // A `.notdef` glyph without a usable outline falls back to the placeholder
+    match outline {
+        Some(Ok(outline)) => Ok(Some(outline)),
+        _ if missing => placeholder_glyph(glyph, options).map(Some),
+        Some(Err(e)) => Err(e),
+        None => Ok(None),
     }
 }
@@ -0,0 +1,104 @@
+//! Boxes of the laid out lines of generated text, e.g. for selection highlights, line
+//! backgrounds or line-by-line reveals.
+
+use std::ops::Range;
+
+use bevy::prelude::*;
+
+use crate::mesh_text_plugin::{add_back_side, layout_text, staggered_glyph_entry};
+use crate::{InputText, MeshTextEntry, MeshTextError, Parameters, Settings};
+
+/// A laid out line of generated text, in the plane of the text and in the space of the glyph
+/// transforms, Y up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    /// Index of the line in the layout, counting wrapped lines
+    pub index: usize,
+    /// Index of the line of the input text it was wrapped from
+    pub paragraph: usize,
+    /// Y of the baseline
+    pub baseline_y: f32,
+    /// The line box: across the glyphs of the line and over the full line height. Lines
+    /// without glyphs have no width.
+    pub bounds: Rect,
+    /// The entries of the glyphs on the line. Glyphs without an outline, like spaces, have no
+    /// entry.
+    pub glyphs: Range<usize>,
+}
+
+/// Generates the glyph meshes of `text` like [`crate::generate_meshes`], along with the box of
+/// every laid out line.
+///
+/// For [`Parameters::double_sided`] text, the ranges cover the front glyphs; the turned copy
+/// of each glyph follows them in the same order.
+pub fn generate_meshes_with_lines<M: Asset>(
+    text: InputText<M>,
+    fonts: &mut Settings,
+    mut params: Parameters,
+    meshes: &mut Assets<Mesh>,
+) -> Result<(Vec<MeshTextEntry<M>>, Vec<TextLine>), MeshTextError> {
+    let (tx, materials) = layout_text(text, &mut fonts.font_system, &mut params)?;
+    let Settings {
+        font_system,
+        text_scale_factor,
+        glyph_cache,
+    } = fonts;
+    let scale = params.world_scale(*text_scale_factor);
+    let front_z = params.front_z(scale);
+
+    let mut lines: Vec<TextLine> = tx
+        .layout_runs()
+        .enumerate()
+        .map(|(index, run)| {
+            // Same Y-down to Y-up conversion as the glyph placement in `generate_meshes`
+            let (left, right) = run
+                .glyphs
+                .iter()
+                .map(|glyph| (glyph.x, glyph.x + glyph.w))
+                .reduce(|(left, right), (start, end)| (left.min(start), right.max(end)))
+                .unwrap_or_default();
+            let scale = scale.truncate();
+            TextLine {
+                index,
+                paragraph: run.line_i,
+                baseline_y: -run.line_y * scale.y,
+                bounds: Rect::from_corners(
+                    Vec2::new(left, -(run.line_top + run.line_height)) * scale,
+                    Vec2::new(right, -run.line_top) * scale,
+                ),
+                glyphs: 0..0,
+            }
+        })
+        .collect();
+
+    let mut entries = Vec::new();
+    let mut last_line = None;
+    for (source, glyph) in
+        tx.generate_mesh_glyphs_with_source(font_system, glyph_cache, &params, meshes, &materials)
+    {
+        let line = source.line;
+        let start = entries.len();
+        entries.push(staggered_glyph_entry(
+            &source, glyph, scale, front_z, &params,
+        ));
+        let glyphs = &mut lines[line].glyphs;
+        if last_line != Some(line) {
+            *glyphs = start..start;
+            last_line = Some(line);
+        }
+        glyphs.end = start + 1;
+    }
+    // Lines without entries sit where the entries of the next line start
+    let mut next = entries.len();
+    for line in lines.iter_mut().rev() {
+        if line.glyphs.is_empty() {
+            line.glyphs = next..next;
+        }
+        next = line.glyphs.start;
+    }
+
+    if params.double_sided {
+        add_back_side(&mut entries, params.layout_depth() * scale.z);
+    }
+    Ok((entries, lines))
+}
@@ -0,0 +1,254 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use cosmic_text::Attrs;
+
+use crate::bounds::{GlyphBoundingSphere, glyph_bounds};
+use crate::mesh_text_plugin::layout_text;
+use crate::{InputText, MeshTextError, Parameters, Settings, generate_meshes};
+
+/// Longest timer layout, `HH:MM:SS`.
+const MAX_SLOTS: usize = 8;
+
+/// A pre-generated glyph used by timers.
+#[derive(Debug, Clone)]
+struct TimerGlyph {
+    mesh: Handle<Mesh>,
+    /// Offset of the glyph mesh from the start of its slot
+    offset: Vec3,
+    /// Width of the glyph's slot in world units
+    advance: f32,
+    bounds: Option<(Aabb, GlyphBoundingSphere)>,
+}
+
+/// The meshes of the digits and the colon, generated once and shared by timers.
+#[derive(Debug, Clone)]
+pub struct TimerGlyphs {
+    digits: [TimerGlyph; 10],
+    colon: TimerGlyph,
+}
+
+impl TimerGlyphs {
+    /// Generates the digit and colon meshes with `params`. The glyphs are generated one by
+    /// one, so a [`Parameters::fit_box`] is ignored.
+    pub fn new(
+        fonts: &mut Settings,
+        attrs: &Attrs,
+        params: &Parameters,
+        meshes: &mut Assets<Mesh>,
+    ) -> Result<Self, MeshTextError> {
+        let mut params = Parameters {
+            fit_box: None,
+            ..params.clone()
+        };
+        let mut glyph = |c: char| -> Result<TimerGlyph, MeshTextError> {
+            let input = || InputText::Simple {
+                text: c.to_string(),
+                material: Handle::<StandardMaterial>::default(),
+                attrs: attrs.clone(),
+            };
+            let (tx, _) = layout_text(input(), &mut fonts.font_system, &mut params)?;
+            let advance = tx
+                .layout_runs()
+                .flat_map(|run| run.glyphs.iter())
+                .map(|glyph| glyph.w)
+                .sum::<f32>()
+                * params.world_scale(fonts.text_scale_factor).x;
+
+            let entry = generate_meshes(input(), fonts, params.clone(), meshes)?
+                .into_iter()
+                .next()
+                .ok_or(MeshTextError::InvalidInput)?;
+            Ok(TimerGlyph {
+                bounds: meshes.get(&entry.mesh).and_then(glyph_bounds),
+                mesh: entry.mesh,
+                offset: entry.transform.translation,
+                advance,
+            })
+        };
+
+        let digits = [
+            glyph('0')?,
+            glyph('1')?,
+            glyph('2')?,
+            glyph('3')?,
+            glyph('4')?,
+            glyph('5')?,
+            glyph('6')?,
+            glyph('7')?,
+            glyph('8')?,
+            glyph('9')?,
+        ];
+        let colon = glyph(':')?;
+        Ok(Self { digits, colon })
+    }
+
+    fn glyph(&self, c: u8) -> &TimerGlyph {
+        match c {
+            b'0'..=b'9' => &self.digits[(c - b'0') as usize],
+            _ => &self.colon,
+        }
+    }
+
+    /// Widest digit, so the timer doesn't jitter when digits change.
+    fn digit_advance(&self) -> f32 {
+        self.digits
+            .iter()
+            .map(|digit| digit.advance)
+            .fold(0.0, f32::max)
+    }
+}
+
+/// Which way a [`TimerText`] counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimerDirection {
+    /// Count elapsed time up
+    #[default]
+    Up,
+    /// Count remaining time down to zero
+    Down,
+    /// Keep showing the current time
+    Paused,
+}
+
+/// Shows a time as `MM:SS` or `HH:MM:SS` by swapping the meshes of pre-spawned glyph
+/// entities, without generating geometry or strings. Spawn it with [`spawn_timer_text`].
+#[derive(Component, Debug, Clone)]
+pub struct TimerText {
+    pub time: Duration,
+    pub direction: TimerDirection,
+    glyphs: TimerGlyphs,
+    show_hours: bool,
+    shown: [u8; MAX_SLOTS],
+}
+
+impl TimerText {
+    pub fn show_hours(&self) -> bool {
+        self.show_hours
+    }
+
+    fn slots(&self) -> usize {
+        if self.show_hours { 8 } else { 5 }
+    }
+
+    /// Formats the time into ASCII digits and colons, rounding remaining time up so the timer
+    /// only shows zero once it ran out.
+    fn format(&self) -> [u8; MAX_SLOTS] {
+        let seconds = match self.direction {
+            TimerDirection::Down => self.time.as_secs() + u64::from(self.time.subsec_nanos() > 0),
+            _ => self.time.as_secs(),
+        };
+        let (hours, minutes) = if self.show_hours {
+            ((seconds / 3600) % 100, (seconds / 60) % 60)
+        } else {
+            (0, (seconds / 60).min(99))
+        };
+        let two = |n: u64| [b'0' + (n / 10) as u8, b'0' + (n % 10) as u8];
+        let [m1, m2] = two(minutes);
+        let [s1, s2] = two(seconds % 60);
+        if self.show_hours {
+            let [h1, h2] = two(hours);
+            [h1, h2, b':', m1, m2, b':', s1, s2]
+        } else {
+            [m1, m2, b':', s1, s2, 0, 0, 0]
+        }
+    }
+}
+
+/// A glyph entity of a [`TimerText`].
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TimerSlot {
+    index: usize,
+    /// Start of the slot relative to the timer
+    origin: Vec3,
+}
+
+/// Spawns a timer showing `time`, with one child entity per character.
+pub fn spawn_timer_text<M: Material>(
+    commands: &mut Commands,
+    glyphs: TimerGlyphs,
+    time: Duration,
+    direction: TimerDirection,
+    show_hours: bool,
+    material: Handle<M>,
+) -> Entity {
+    let mut timer = TimerText {
+        time,
+        direction,
+        glyphs,
+        show_hours,
+        shown: [0; MAX_SLOTS],
+    };
+    let text = timer.format();
+    timer.shown = text;
+
+    let digit_advance = timer.glyphs.digit_advance();
+    let mut children = Vec::with_capacity(timer.slots());
+    let mut x = 0.0;
+    for (index, &c) in text[..timer.slots()].iter().enumerate() {
+        let glyph = timer.glyphs.glyph(c);
+        let origin = Vec3::new(x, 0.0, 0.0);
+        x += if c == b':' {
+            glyph.advance
+        } else {
+            digit_advance
+        };
+        let mut slot = commands.spawn((
+            TimerSlot { index, origin },
+            Mesh3d(glyph.mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(origin + glyph.offset),
+        ));
+        if let Some(bounds) = glyph.bounds {
+            slot.insert(bounds);
+        }
+        children.push(slot.id());
+    }
+
+    commands
+        .spawn((timer, Transform::default(), Visibility::default()))
+        .add_children(&children)
+        .id()
+}
+
+/// Advances running timers.
+pub fn tick_timer_text(time: Res<Time>, mut timers: Query<&mut TimerText>) {
+    for mut timer in &mut timers {
+        match timer.direction {
+            TimerDirection::Up => timer.time += time.delta(),
+            TimerDirection::Down => timer.time = timer.time.saturating_sub(time.delta()),
+            TimerDirection::Paused => {}
+        }
+    }
+}
+
+/// Swaps the glyph meshes and bounds of timers whose shown text changed.
+pub fn update_timer_text(
+    mut commands: Commands,
+    mut timers: Query<(&mut TimerText, &Children), Changed<TimerText>>,
+    mut slots: Query<(&TimerSlot, &mut Mesh3d, &mut Transform)>,
+) {
+    for (mut timer, children) in &mut timers {
+        let text = timer.format();
+        if text == timer.shown {
+            continue;
+        }
+        for &child in children {
+            let Ok((slot, mut mesh, mut transform)) = slots.get_mut(child) else {
+                continue;
+            };
+            let c = text[slot.index];
+            if c == timer.shown[slot.index] {
+                continue;
+            }
+            let glyph = timer.glyphs.glyph(c);
+            mesh.0 = glyph.mesh.clone();
+            transform.translation = slot.origin + glyph.offset;
+            if let Some(bounds) = glyph.bounds {
+                commands.entity(child).insert(bounds);
+            }
+        }
+        timer.bypass_change_detection().shown = text;
+    }
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, TaskPool};
+use bevy_mesh_text_3d::async_generation::finish_text_generation;
+use bevy_mesh_text_3d::{
+    DepthAnchor, InputText, MeshTextEntry, MeshTextError, Parameters, Settings,
+    TextGenerationHandle, generate_meshes_async,
+};
+use cosmic_text::Attrs;
+
+fn start(world: &mut World, text: &str, params: Parameters) -> TextGenerationHandle {
+    AsyncComputeTaskPool::get_or_init(TaskPool::default);
+    let text = text.to_string();
+    world
+        .run_system_once(move |mut settings: ResMut<Settings>| {
+            generate_meshes_async(
+                InputText::Simple {
+                    text: text.clone(),
+                    material: Handle::<StandardMaterial>::default(),
+                    attrs: Attrs::new(),
+                },
+                &mut settings,
+                params.clone(),
+            )
+            .unwrap()
+        })
+        .unwrap()
+}
+
+/// Polls `handle` until the generation is done.
+fn finish(
+    world: &mut World,
+    handle: &mut TextGenerationHandle,
+) -> Result<Vec<MeshTextEntry<StandardMaterial>>, MeshTextError> {
+    for _ in 0..2000 {
+        let result = world.resource_scope(|world, mut settings: Mut<Settings>| {
+            handle.poll(&mut settings, &mut world.resource_mut::<Assets<Mesh>>())
+        });
+        if let Some(result) = result {
+            return result;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("the generation never finished");
+}
+
+#[test]
+fn async_generation_matches_generate_meshes() {
+    let mut world = common::world();
+    let mut handle = start(&mut world, "Hello", common::params(14.0, 2.0));
+    let entries = finish(&mut world, &mut handle).unwrap();
+    assert_eq!(handle.progress(), 1.0);
+    assert!(handle.is_finished());
+
+    // The meshes went into the cache, so generating again reuses them
+    let cached = world.resource::<Settings>().glyph_cache.len();
+    assert_eq!(cached, 4, "both 'l's share a mesh");
+    let expected = common::generate(&mut world, "Hello", common::params(14.0, 2.0)).unwrap();
+    assert_eq!(entries.len(), expected.len());
+    for (entry, expected) in entries.iter().zip(&expected) {
+        assert_eq!(entry.mesh, expected.mesh);
+        assert_eq!(entry.transform, expected.transform);
+    }
+}
+
+#[test]
+fn async_generation_keeps_the_depth_anchor() {
+    let mut world = common::world();
+    let params = Parameters {
+        depth_anchor: DepthAnchor::Center,
+        ..common::params(14.0, 2.0)
+    };
+    let mut handle = start(&mut world, "Hi", params.clone());
+    let entries = finish(&mut world, &mut handle).unwrap();
+    let expected = common::generate(&mut world, "Hi", params).unwrap();
+    for (entry, expected) in entries.iter().zip(&expected) {
+        assert_eq!(entry.transform, expected.transform);
+        assert!(entry.transform.translation.z < 0.0);
+    }
+}
+
+#[test]
+fn cancelled_generation_adds_no_meshes() {
+    let mut world = common::world();
+    let text: String = ('!'..='~').collect();
+    let mut handle = start(&mut world, &text, common::params(14.0, 2.0));
+    handle.cancel();
+
+    assert!(matches!(
+        finish(&mut world, &mut handle),
+        Err(MeshTextError::Cancelled)
+    ));
+    assert!(world.resource::<Settings>().glyph_cache.is_empty());
+    assert!(world.resource::<Assets<Mesh>>().is_empty());
+}
+
+#[test]
+fn finished_texts_spawn_their_glyphs() {
+    let mut world = common::world();
+    let handle = start(&mut world, "Hi", common::params(14.0, 2.0));
+    let text = world.spawn(handle).id();
+
+    for _ in 0..2000 {
+        world
+            .run_system_once(finish_text_generation::<StandardMaterial>)
+            .unwrap();
+        if !world.entity(text).contains::<TextGenerationHandle>() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    let children = world
+        .get::<Children>(text)
+        .expect("the glyphs were spawned");
+    assert_eq!(children.len(), 2);
+}
@@ -0,0 +1,69 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::render::primitives::Aabb;
+use bevy_mesh_text_3d::{GlyphBoundingSphere, Settings, TextSpawnDesc, spawn_text_3d};
+
+mod common;
+
+#[test]
+fn spawned_glyphs_have_bounds_enclosing_their_mesh() {
+    let mut world = common::world();
+    world.init_resource::<Assets<StandardMaterial>>();
+    let root = world
+        .run_system_once(
+            |mut commands: Commands,
+             mut settings: ResMut<Settings>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<StandardMaterial>>| {
+                spawn_text_3d(
+                    &mut commands,
+                    &mut settings,
+                    &mut meshes,
+                    &mut materials,
+                    TextSpawnDesc::new("Bounds"),
+                )
+            },
+        )
+        .unwrap()
+        .unwrap();
+
+    let children = world.get::<Children>(root).unwrap().to_vec();
+    assert_eq!(children.len(), 6);
+    for child in children {
+        let aabb = *world.get::<Aabb>(child).unwrap();
+        let sphere = *world.get::<GlyphBoundingSphere>(child).unwrap();
+        assert_eq!(sphere.center, Vec3::from(aabb.center));
+        assert!(sphere.radius > 0.0);
+
+        let mesh = world.get::<Mesh3d>(child).unwrap();
+        let meshes = world.resource::<Assets<Mesh>>();
+        let Some(VertexAttributeValues::Float32x3(positions)) = meshes
+            .get(mesh)
+            .unwrap()
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("glyph mesh without positions");
+        };
+        let (min, max) = (aabb.min(), aabb.max());
+        for &p in positions {
+            let p = Vec3::from(p);
+            assert!(p.cmpge(Vec3::from(min) - 1e-4).all() && p.cmple(Vec3::from(max) + 1e-4).all());
+            assert!(p.distance(sphere.center) <= sphere.radius + 1e-4);
+        }
+    }
+}
+
+#[test]
+fn world_spheres_follow_the_transform() {
+    let sphere = GlyphBoundingSphere {
+        center: Vec3::new(1.0, 0.0, 0.0),
+        radius: 2.0,
+    };
+    let transform = GlobalTransform::from(
+        Transform::from_xyz(0.0, 5.0, 0.0).with_scale(Vec3::new(1.0, 3.0, 0.5)),
+    );
+    let (center, radius) = sphere.to_world(&transform);
+    assert!(center.distance(Vec3::new(1.0, 5.0, 0.0)) < 1e-5);
+    assert!((radius - 6.0).abs() < 1e-5);
+}
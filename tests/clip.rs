@@ -0,0 +1,114 @@
+mod common;
+
+use std::f32::consts::TAU;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::contour::{Contour, clip_contours};
+use bevy_mesh_text_3d::{
+    Attrs, ClipOperation, InputText, MeshTextEntry, Settings, generate_clipped_meshes,
+};
+
+fn polygon(points: impl IntoIterator<Item = Vec2>) -> Contour {
+    Contour {
+        points: points.into_iter().collect(),
+        closed: true,
+    }
+}
+
+fn square(min: Vec2, size: f32) -> Contour {
+    polygon([
+        min,
+        min + Vec2::X * size,
+        min + Vec2::splat(size),
+        min + Vec2::Y * size,
+    ])
+}
+
+fn circle(center: Vec2, radius: f32) -> Contour {
+    polygon((0..64).map(|i| center + radius * Vec2::from_angle(i as f32 / 64.0 * TAU)))
+}
+
+fn area(contours: &[Contour]) -> f32 {
+    contours.iter().map(Contour::signed_area).sum()
+}
+
+fn clipped(
+    world: &mut World,
+    mask: Contour,
+    operation: ClipOperation,
+) -> Vec<MeshTextEntry<StandardMaterial>> {
+    world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                generate_clipped_meshes(
+                    InputText::Simple {
+                        text: "oo".to_string(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    common::params(14.0, 2.0),
+                    std::slice::from_ref(&mask),
+                    operation,
+                    &mut meshes,
+                )
+                .unwrap()
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn contours_are_clipped_through_their_crossings() {
+    let subject = [square(Vec2::ZERO, 2.0)];
+    let mask = [square(Vec2::ONE, 2.0)];
+    let inside = clip_contours(&subject, &mask, ClipOperation::Intersection).unwrap();
+    let outside = clip_contours(&subject, &mask, ClipOperation::Difference).unwrap();
+    assert_eq!(inside.len(), 1);
+    assert!((area(&inside) - 1.0).abs() < 1e-5);
+    assert!((area(&outside) - 3.0).abs() < 1e-5);
+
+    // A mask inside the subject cuts a hole, wound the other way
+    let hole = clip_contours(
+        &subject,
+        &[square(Vec2::splat(0.5), 1.0)],
+        ClipOperation::Difference,
+    )
+    .unwrap();
+    assert_eq!(hole.len(), 2);
+    assert!((area(&hole) - 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn masks_clip_the_glyphs_they_touch() {
+    let mut world = common::world();
+    let entries = common::generate(&mut world, "oo", common::params(14.0, 2.0)).unwrap();
+    let first = entries[0].transform.translation.truncate();
+    // Covers the first "o" and misses the second one
+    let gap = (entries[1].transform.translation.x - first.x) / 2.0;
+    let cover = circle(first, gap * 0.9);
+
+    let inside = clipped(&mut world, cover.clone(), ClipOperation::Intersection);
+    assert_eq!(inside.len(), 1);
+    assert!(
+        inside[0]
+            .transform
+            .translation
+            .truncate()
+            .abs_diff_eq(first, 1e-3)
+    );
+
+    // The untouched glyph keeps its shared mesh
+    let outside = clipped(&mut world, cover, ClipOperation::Difference);
+    assert_eq!(outside.len(), 1);
+    assert_eq!(outside[0].mesh, entries[1].mesh);
+    assert_eq!(outside[0].transform, entries[1].transform);
+
+    // Cutting off the left half of the first glyph moves its center to the right
+    let left = square(first - Vec2::new(gap * 2.0, gap), gap * 2.0);
+    let cut = clipped(&mut world, left, ClipOperation::Difference);
+    assert_eq!(cut.len(), 2);
+    assert_ne!(cut[0].mesh, entries[0].mesh);
+    assert!(cut[0].transform.translation.x > first.x);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::{Attrs, ColorMaterials, InputText, Settings, generate_meshes};
+
+fn colored_text(base: &Handle<StandardMaterial>) -> InputText<'static, StandardMaterial> {
+    let red = cosmic_text::Color::rgb(255, 0, 0);
+    let blue = cosmic_text::Color::rgb(0, 0, 255);
+    InputText::Rich {
+        words: ["a", "b", "c", "d"].map(String::from).to_vec(),
+        materials: vec![base.clone(); 4],
+        attrs: vec![
+            Attrs::new().color(red),
+            Attrs::new().color(red),
+            Attrs::new().color(blue),
+            Attrs::new(),
+        ],
+    }
+}
+
+#[test]
+fn span_colors_become_shared_materials() {
+    let mut materials = Assets::<StandardMaterial>::default();
+    let base = materials.add(StandardMaterial {
+        perceptual_roughness: 0.2,
+        ..default()
+    });
+    let mut colors = ColorMaterials::default();
+
+    let InputText::Rich {
+        materials: spans, ..
+    } = colors.apply(colored_text(&base), &mut materials)
+    else {
+        unreachable!()
+    };
+    assert_eq!(spans[0], spans[1]);
+    assert_ne!(spans[0], spans[2]);
+    assert_eq!(spans[3], base);
+    let red = materials.get(&spans[0]).unwrap();
+    assert_eq!(red.base_color, Color::srgb_u8(255, 0, 0));
+    assert_eq!(red.perceptual_roughness, 0.2);
+    assert_eq!(colors.len(), 2);
+
+    // Applying the colors again reuses the copies
+    let InputText::Rich {
+        materials: again, ..
+    } = colors.apply(colored_text(&base), &mut materials)
+    else {
+        unreachable!()
+    };
+    assert_eq!(again, spans);
+    assert_eq!(materials.len(), 3);
+}
+
+#[test]
+fn colored_glyphs_use_their_span_material() {
+    let mut world = common::world();
+    world.init_resource::<Assets<StandardMaterial>>();
+    world.init_resource::<ColorMaterials>();
+    let entries = world
+        .run_system_once(
+            |mut settings: ResMut<Settings>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<StandardMaterial>>,
+             mut colors: ResMut<ColorMaterials>| {
+                let base = materials.add(StandardMaterial::default());
+                let text = colors.apply(colored_text(&base), &mut materials);
+                generate_meshes(text, &mut settings, common::params(14.0, 2.0), &mut meshes)
+            },
+        )
+        .unwrap()
+        .unwrap();
+    let materials = world.resource::<Assets<StandardMaterial>>();
+    let color = |i: usize| materials.get(&entries[i].material).unwrap().base_color;
+    assert_eq!(color(0), Color::srgb_u8(255, 0, 0));
+    assert_eq!(color(2), Color::srgb_u8(0, 0, 255));
+    assert_eq!(color(3), StandardMaterial::default().base_color);
+}
@@ -0,0 +1,147 @@
+//! Golden mesh fingerprints: generated geometry reduced to counts, bounds and areas, compared
+//! with tolerance against files under `tests/golden`. Run with `UPDATE_GOLDEN=1` to rewrite
+//! the files after an intended change to the geometry, and review the diff.
+
+use std::fmt::Write;
+
+use bevy::prelude::*;
+use bevy_mesh_text_3d::MeshTextEntry;
+
+/// Relative and absolute tolerance of the measured values
+const TOLERANCE: f32 = 1e-3;
+
+/// What a glyph mesh is compared by.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fingerprint {
+    pub vertices: usize,
+    pub indices: usize,
+    /// Bounds of the positions, then the translation of the glyph, then the area of its
+    /// triangles, 0 for lines and points
+    pub measures: Vec<f32>,
+}
+
+impl Fingerprint {
+    pub fn of(entry: &MeshTextEntry<StandardMaterial>, meshes: &Assets<Mesh>) -> Self {
+        let mesh = meshes.get(&entry.mesh).unwrap();
+        let positions: Vec<Vec3> = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap()
+            .iter()
+            .map(|&p| Vec3::from(p))
+            .collect();
+        let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+        let (min, max) = positions.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), &p| (min.min(p), max.max(p)),
+        );
+        let area = match mesh.primitive_topology() {
+            bevy::render::mesh::PrimitiveTopology::TriangleList => indices
+                .chunks_exact(3)
+                .map(|t| {
+                    let [a, b, c] = [positions[t[0]], positions[t[1]], positions[t[2]]];
+                    (b - a).cross(c - a).length() / 2.0
+                })
+                .sum(),
+            _ => 0.0,
+        };
+        let translation = entry.transform.translation;
+        let mut measures = min.to_array().to_vec();
+        measures.extend(max.to_array());
+        measures.extend(translation.to_array());
+        measures.push(area);
+        Self {
+            vertices: positions.len(),
+            indices: indices.len(),
+            measures,
+        }
+    }
+
+    fn line(&self, name: &str) -> String {
+        let mut line = format!("{name} {} {}", self.vertices, self.indices);
+        for measure in &self.measures {
+            write!(line, " {measure:.4}").unwrap();
+        }
+        line
+    }
+
+    fn parse(line: &str) -> Option<(&str, Self)> {
+        let mut fields = line.split_whitespace();
+        let name = fields.next()?;
+        let vertices = fields.next()?.parse().ok()?;
+        let indices = fields.next()?.parse().ok()?;
+        let measures = fields
+            .map(|field| field.parse().ok())
+            .collect::<Option<_>>()?;
+        Some((
+            name,
+            Self {
+                vertices,
+                indices,
+                measures,
+            },
+        ))
+    }
+
+    /// Whether the counts match exactly and every measure within the tolerance.
+    fn matches(&self, golden: &Self) -> bool {
+        self.vertices == golden.vertices
+            && self.indices == golden.indices
+            && self.measures.len() == golden.measures.len()
+            && self
+                .measures
+                .iter()
+                .zip(&golden.measures)
+                .all(|(a, b)| (a - b).abs() <= TOLERANCE * (1.0 + a.abs().max(b.abs())))
+    }
+}
+
+/// Compares named fingerprints against `tests/golden/{file}`, or rewrites it with
+/// `UPDATE_GOLDEN` set. Panics listing every mismatch.
+pub fn check(file: &str, fingerprints: &[(String, Fingerprint)]) {
+    let path = format!("{}/tests/golden/{file}", env!("CARGO_MANIFEST_DIR"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let mut text = String::from("# name vertices indices min max translation area\n");
+        for (name, fingerprint) in fingerprints {
+            text.push_str(&fingerprint.line(name));
+            text.push('\n');
+        }
+        std::fs::create_dir_all(std::path::Path::new(&path).parent().unwrap()).unwrap();
+        std::fs::write(&path, text).unwrap();
+        return;
+    }
+
+    let text = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("{path}: {e}, run with UPDATE_GOLDEN=1 to create it"));
+    let golden: Vec<_> = text
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .map(|line| Fingerprint::parse(line).unwrap_or_else(|| panic!("bad golden line {line}")))
+        .collect();
+
+    let mut mismatches = Vec::new();
+    for (name, fingerprint) in fingerprints {
+        match golden.iter().find(|(golden_name, _)| golden_name == name) {
+            Some((_, expected)) if fingerprint.matches(expected) => {}
+            Some((_, expected)) => mismatches.push(format!(
+                "{name}\n  expected {}\n  got      {}",
+                expected.line(name),
+                fingerprint.line(name)
+            )),
+            None => mismatches.push(format!("{name} has no golden fingerprint")),
+        }
+    }
+    if golden.len() != fingerprints.len() {
+        mismatches.push(format!(
+            "{} golden fingerprints for {} meshes",
+            golden.len(),
+            fingerprints.len()
+        ));
+    }
+    assert!(
+        mismatches.is_empty(),
+        "Meshes differ from {path}, run with UPDATE_GOLDEN=1 if the change is intended:\n{}",
+        mismatches.join("\n")
+    );
+}
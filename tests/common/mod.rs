@@ -0,0 +1,85 @@
+#![allow(dead_code)]
+
+pub mod golden;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::extrude_glyph::{ExtrudedGlyphGeometry, ExtrusionOptions, tessalate_glyph};
+use bevy_mesh_text_3d::{
+    GlyphCache, InputText, MeshTextEntry, MeshTextError, Parameters, Settings, embedded_font_system,
+};
+use cosmic_text::ttf_parser::{Face, GlyphId};
+use cosmic_text::{Attrs, Buffer, FontSystem, LayoutGlyph, Metrics, Shaping};
+
+pub fn params(font_size: f32, extrusion_depth: f32) -> Parameters {
+    Parameters {
+        extrusion_depth,
+        font_size,
+        line_height: font_size * 1.2,
+        ..Default::default()
+    }
+}
+
+/// Shapes `text` on a single line with the embedded font.
+pub fn shape(font_system: &mut FontSystem, text: &str, font_size: f32) -> Vec<LayoutGlyph> {
+    let mut buffer = Buffer::new(font_system, Metrics::new(font_size, font_size));
+    buffer.set_text(font_system, text, &Attrs::new(), Shaping::Advanced);
+    buffer.shape_until_scroll(font_system, false);
+    buffer
+        .layout_runs()
+        .flat_map(|run| run.glyphs.iter().cloned())
+        .collect()
+}
+
+/// Tessellates a single glyph, returning `None` for glyphs without an outline.
+pub fn tessellate(
+    font_system: &mut FontSystem,
+    glyph: &LayoutGlyph,
+    extrusion_depth: f32,
+) -> Option<(ExtrudedGlyphGeometry, f32, f32)> {
+    font_system
+        .db()
+        .with_face_data(glyph.font_id, |file, _| {
+            let face = Face::parse(file, 0).unwrap();
+            let bb = face.glyph_bounding_box(GlyphId(glyph.glyph_id))?;
+            Some(tessalate_glyph(glyph, bb, face, &ExtrusionOptions::new(extrusion_depth)).unwrap())
+        })
+        .flatten()
+}
+
+pub fn world() -> World {
+    let mut world = World::new();
+    world.init_resource::<Assets<Mesh>>();
+    world.insert_resource(Settings {
+        font_system: embedded_font_system(),
+        text_scale_factor: 1.0,
+        glyph_cache: GlyphCache::default(),
+    });
+    world
+}
+
+/// Runs `generate_meshes` against the resources of `world`.
+pub fn generate(
+    world: &mut World,
+    text: &str,
+    params: Parameters,
+) -> Result<Vec<MeshTextEntry<StandardMaterial>>, MeshTextError> {
+    let text = text.to_string();
+    let mut params = Some(params);
+    world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                bevy_mesh_text_3d::generate_meshes(
+                    InputText::Simple {
+                        text: text.clone(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    params.take().unwrap(),
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::mesh::MeshAabb;
+use bevy_mesh_text_3d::contour::Contour;
+use bevy_mesh_text_3d::{
+    Attrs, CompositePart, DepthAnchor, InlineIcon, MeshTextEntry, Parameters, Settings,
+    generate_composite_meshes,
+};
+
+/// A play triangle, 10 units wide and 20 high.
+fn play_icon(height: f32) -> InlineIcon {
+    InlineIcon {
+        margin: 1.0,
+        ..InlineIcon::new(
+            vec![Contour {
+                points: vec![Vec2::ZERO, Vec2::new(10.0, 10.0), Vec2::new(0.0, 20.0)],
+                closed: true,
+            }],
+            height,
+        )
+    }
+}
+
+fn generate(
+    world: &mut World,
+    icon: Option<InlineIcon>,
+    params: Parameters,
+) -> Vec<MeshTextEntry<StandardMaterial>> {
+    world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                let mut parts = Vec::new();
+                if let Some(icon) = icon.clone() {
+                    parts.push(CompositePart::Icon {
+                        icon,
+                        material: Handle::default(),
+                    });
+                }
+                parts.push(CompositePart::Text {
+                    text: "PLAY".to_string(),
+                    material: Handle::default(),
+                    attrs: Attrs::new(),
+                });
+                generate_composite_meshes(parts, &mut settings, params.clone(), &mut meshes)
+                    .unwrap()
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn icons_take_their_advance_in_the_line() {
+    let mut world = common::world();
+    let icon = play_icon(10.0);
+    assert_eq!(icon.advance(), 7.0);
+
+    let text = generate(&mut world, None, common::params(14.0, 2.0));
+    let composite = generate(&mut world, Some(icon), common::params(14.0, 2.0));
+    assert_eq!(text.len(), 4);
+    assert_eq!(composite.len(), 5);
+
+    // The text moves over by the advance of the icon and keeps its meshes
+    for (plain, shifted) in text.iter().zip(&composite[1..]) {
+        assert_eq!(plain.mesh, shifted.mesh);
+        let offset = shifted.transform.translation - plain.transform.translation;
+        assert!(offset.abs_diff_eq(Vec3::X * 7.0, 1e-3), "{offset}");
+    }
+
+    // The icon is centered in its advance and stands on the baseline
+    let icon = &composite[0];
+    assert!(
+        icon.transform
+            .translation
+            .abs_diff_eq(Vec3::new(3.5, -14.0 + 5.0, 0.0), 0.5),
+        "{}",
+        icon.transform.translation
+    );
+    let meshes = world.resource::<Assets<Mesh>>();
+    let aabb = meshes.get(&icon.mesh).unwrap().compute_aabb().unwrap();
+    assert!(Vec3::from(aabb.half_extents).abs_diff_eq(Vec3::new(2.5, 5.0, 1.0), 1e-3));
+}
+
+#[test]
+fn double_sided_composites_are_anchored_like_text() {
+    let mut world = common::world();
+    let params = Parameters {
+        depth_anchor: DepthAnchor::Center,
+        double_sided: true,
+        ..common::params(14.0, 2.0)
+    };
+    let scale = world.resource::<Settings>().text_scale_factor;
+
+    // Centered on the whole depth of both sides, like `generate_meshes` places them
+    let composite = generate(&mut world, Some(play_icon(10.0)), params.clone());
+    let expected = common::generate(&mut world, "PLAY", params).unwrap();
+    assert_eq!(expected[0].transform.translation.z, -2.0 * scale);
+    for entry in &composite {
+        assert_eq!(entry.transform.translation.z, -2.0 * scale);
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::debug_text::draw_debug_text;
+use bevy_mesh_text_3d::{DebugText3d, GlyphEntityPool};
+
+fn world() -> World {
+    let mut world = common::world();
+    world.init_resource::<Assets<StandardMaterial>>();
+    world.init_resource::<Time>();
+    world.init_resource::<GlyphEntityPool>();
+    world.init_resource::<DebugText3d>();
+    world
+}
+
+fn frame(world: &mut World, delta: Duration) {
+    world.resource_mut::<Time>().advance_by(delta);
+    world.run_system_once(draw_debug_text).unwrap();
+}
+
+#[test]
+fn printed_text_lasts_one_frame() {
+    let mut world = world();
+    world
+        .resource_mut::<DebugText3d>()
+        .print(Vec3::new(1.0, 2.0, 3.0), "hp 10");
+    frame(&mut world, Duration::ZERO);
+    assert_eq!(world.resource::<DebugText3d>().len(), 1);
+
+    let mut roots = world.query_filtered::<(Entity, &Transform, &Children), Without<Mesh3d>>();
+    let (root, transform, children) = roots.single(&world).unwrap();
+    assert_eq!(transform.translation, Vec3::new(1.0, 2.0, 3.0));
+    let glyphs = children.len();
+    assert!(glyphs >= 3);
+
+    frame(&mut world, Duration::from_millis(16));
+    assert!(world.resource::<DebugText3d>().is_empty());
+    assert_eq!(world.get::<Visibility>(root), Some(&Visibility::Hidden));
+    assert_eq!(world.resource::<GlyphEntityPool>().len(), glyphs);
+
+    // The next label reuses the hidden root and the pooled glyphs
+    world
+        .resource_mut::<DebugText3d>()
+        .print(Vec3::ZERO, "hp 9");
+    frame(&mut world, Duration::from_millis(16));
+    let entities = world.query::<Entity>().iter(&world).count();
+    assert_eq!(entities, glyphs + 1);
+    assert_eq!(world.get::<Visibility>(root), Some(&Visibility::Visible));
+}
+
+#[test]
+fn text_printed_for_a_duration_expires() {
+    let mut world = world();
+    world
+        .resource_mut::<DebugText3d>()
+        .print_for(Vec3::ZERO, "spawn", Duration::from_secs(1));
+    frame(&mut world, Duration::ZERO);
+    frame(&mut world, Duration::from_millis(600));
+    assert_eq!(world.resource::<DebugText3d>().len(), 1);
+    frame(&mut world, Duration::from_millis(600));
+    assert!(world.resource::<DebugText3d>().is_empty());
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::camera::CameraProjection;
+use bevy::render::primitives::{Aabb, Frustum};
+use bevy::tasks::{AsyncComputeTaskPool, TaskPool};
+use bevy_mesh_text_3d::deferred_text::update_deferred_text;
+use bevy_mesh_text_3d::{Attrs, DeferredText, InputText, TextGenerationHandle};
+
+/// A camera at `z` looking down -Z.
+fn camera_at(z: f32) -> (Camera, Frustum) {
+    let view = Transform::from_xyz(0.0, 0.0, z);
+    let projection = PerspectiveProjection::default();
+    let clip_from_world = projection.get_clip_from_view() * view.compute_matrix().inverse();
+    let frustum = Frustum::from_clip_from_world_custom_far(
+        &clip_from_world,
+        &view.translation,
+        &view.back(),
+        projection.far,
+    );
+    (Camera::default(), frustum)
+}
+
+fn spawn_text(world: &mut World, z: f32) -> Entity {
+    let transform = Transform::from_xyz(0.0, 0.0, z);
+    let text = InputText::Simple {
+        text: "Sign".to_string(),
+        material: Handle::<StandardMaterial>::default(),
+        attrs: Attrs::new(),
+    };
+    world
+        .spawn((
+            DeferredText::new(text, common::params(14.0, 2.0)),
+            transform,
+            GlobalTransform::from(transform),
+        ))
+        .id()
+}
+
+#[test]
+fn text_is_generated_once_it_comes_into_view() {
+    AsyncComputeTaskPool::get_or_init(TaskPool::default);
+    let mut world = common::world();
+    let camera = world.spawn(camera_at(50.0)).id();
+    let seen = spawn_text(&mut world, 0.0);
+    // Behind the camera
+    let unseen = spawn_text(&mut world, 100.0);
+
+    let update = |world: &mut World| {
+        world
+            .run_system_once(update_deferred_text::<StandardMaterial>)
+            .unwrap();
+    };
+    update(&mut world);
+    assert!(world.get::<TextGenerationHandle>(seen).is_some());
+    assert!(world.get::<DeferredText>(seen).is_none());
+    assert!(world.get::<TextGenerationHandle>(unseen).is_none());
+
+    // Both got placeholder bounds around the laid out line right away
+    let bounds = *world.get::<Aabb>(unseen).unwrap();
+    assert!(bounds.half_extents.x > 10.0);
+    assert_eq!(bounds.min().z, 0.0);
+    assert_eq!(bounds.max().z, 2.0);
+
+    // Nothing changes while the text stays out of view
+    update(&mut world);
+    assert!(world.get::<DeferredText>(unseen).is_some());
+
+    let (_, frustum) = camera_at(150.0);
+    world.entity_mut(camera).insert(frustum);
+    update(&mut world);
+    assert!(world.get::<TextGenerationHandle>(unseen).is_some());
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::editor::update_mesh_text_editors;
+use bevy_mesh_text_3d::{GlyphEntityPool, MeshTextEditor, Settings};
+use cosmic_text::{Attrs, Cursor, Edit};
+
+fn spawn(world: &mut World, text: &str) -> Entity {
+    world.init_resource::<GlyphEntityPool>();
+    let editor = MeshTextEditor::<StandardMaterial>::new(
+        &mut world.resource_mut::<Settings>().font_system,
+        text,
+        &Attrs::new(),
+        common::params(14.0, 2.0),
+        Handle::default(),
+    );
+    world.spawn(editor).id()
+}
+
+fn update(world: &mut World) {
+    world
+        .run_system_once(update_mesh_text_editors::<StandardMaterial>)
+        .unwrap();
+}
+
+fn lines(world: &World, entity: Entity) -> Vec<Entity> {
+    world
+        .get::<MeshTextEditor>(entity)
+        .unwrap()
+        .lines()
+        .collect()
+}
+
+fn glyphs(world: &World, line: Entity) -> Vec<Entity> {
+    world.get::<Children>(line).unwrap().to_vec()
+}
+
+#[test]
+fn only_edited_lines_are_regenerated() {
+    let mut world = common::world();
+    let entity = spawn(&mut world, "one\ntwo\nthree");
+    update(&mut world);
+
+    let before = lines(&world, entity);
+    assert_eq!(before.len(), 3);
+    let three = glyphs(&world, before[2]);
+    assert_eq!(three.len(), 5);
+
+    let mut editor = world.get_mut::<MeshTextEditor>(entity).unwrap();
+    editor.editor_mut().set_cursor(Cursor::new(0, 3));
+    editor.insert_str("\nnew");
+    update(&mut world);
+
+    assert_eq!(
+        world.get::<MeshTextEditor>(entity).unwrap().text(),
+        "one\nnew\ntwo\nthree"
+    );
+    let after = lines(&world, entity);
+    assert_eq!(after.len(), 4);
+    assert_eq!(after[0], before[0]);
+    assert_eq!(after[2], before[1]);
+    assert_eq!(after[3], before[2]);
+
+    // The last line kept its glyphs and moved down by a line
+    assert_eq!(glyphs(&world, after[3]), three);
+    let moved = world.get::<Transform>(after[3]).unwrap().translation.y;
+    assert!((moved - -3.0 * 14.0 * 1.2).abs() < 1e-3, "{moved}");
+    assert_eq!(glyphs(&world, after[1]).len(), 3);
+}
+
+#[test]
+fn caret_follows_the_cursor() {
+    let mut world = common::world();
+    let entity = spawn(&mut world, "ab");
+    update(&mut world);
+
+    let caret = world
+        .get::<MeshTextEditor>(entity)
+        .unwrap()
+        .caret()
+        .unwrap();
+    assert_eq!(world.get::<Transform>(caret).unwrap().translation.x, 0.0);
+
+    world
+        .get_mut::<MeshTextEditor>(entity)
+        .unwrap()
+        .editor_mut()
+        .set_cursor(Cursor::new(0, 2));
+    update(&mut world);
+    assert!(world.get::<Transform>(caret).unwrap().translation.x > 0.0);
+
+    world.get_mut::<MeshTextEditor>(entity).unwrap().show_caret = false;
+    update(&mut world);
+    assert_eq!(world.get::<Visibility>(caret), Some(&Visibility::Hidden));
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::entity_pool::update_pooled_text;
+use bevy_mesh_text_3d::{GlyphEntityPool, PooledText};
+
+fn show(world: &mut World, root: Entity, text: &str) {
+    let entries = common::generate(world, text, common::params(14.0, 2.0)).unwrap();
+    let mut entries = Some(entries);
+    world
+        .run_system_once(
+            move |mut commands: Commands,
+                  mut pool: ResMut<GlyphEntityPool>,
+                  meshes: Res<Assets<Mesh>>,
+                  mut texts: Query<&mut PooledText>| {
+                let mut pooled = texts.get_mut(root).unwrap();
+                update_pooled_text(
+                    &mut commands,
+                    &mut pool,
+                    root,
+                    &mut pooled,
+                    entries.take().unwrap(),
+                    &meshes,
+                );
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn glyph_entities_are_reused() {
+    let mut world = common::world();
+    world.insert_resource(GlyphEntityPool::new(2));
+    let root = world.spawn(PooledText::default()).id();
+
+    show(&mut world, root, "12345");
+    let first = world.get::<PooledText>(root).unwrap().glyphs().to_vec();
+    assert_eq!(first.len(), 5);
+    assert_eq!(world.get::<Children>(root).unwrap().len(), 5);
+
+    // Shrinking keeps the first glyphs and pools two of the three leftovers
+    show(&mut world, root, "67");
+    let second = world.get::<PooledText>(root).unwrap().glyphs().to_vec();
+    assert_eq!(second, first[..2]);
+    assert_eq!(world.resource::<GlyphEntityPool>().len(), 2);
+    assert_eq!(world.get::<Children>(root).unwrap().len(), 2);
+    assert!(world.get_entity(first[4]).is_err());
+    assert_eq!(world.get::<Visibility>(first[2]), Some(&Visibility::Hidden));
+
+    // Growing again takes the pooled entities before spawning new ones
+    show(&mut world, root, "8901");
+    let third = world.get::<PooledText>(root).unwrap().glyphs().to_vec();
+    assert_eq!(third.len(), 4);
+    assert!(third.contains(&first[2]) && third.contains(&first[3]));
+    assert!(world.resource::<GlyphEntityPool>().is_empty());
+    assert_eq!(
+        world.get::<Visibility>(first[2]),
+        Some(&Visibility::Inherited)
+    );
+}
@@ -0,0 +1,168 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_mesh_text_3d::{DropCapParameters, ExclusionZone, MeshTextError, Parameters};
+
+#[test]
+fn lines_flow_around_exclusion_zones() {
+    let mut world = common::world();
+    let text = "flowing words ".repeat(30);
+    let plain = Parameters {
+        max_width: Some(200.0),
+        ..common::params(14.0, 2.0)
+    };
+    let line_height = plain.line_height;
+    // A picture in the top left corner, three lines high
+    let picture = Rect::new(0.0, 0.0, 100.0, 3.0 * line_height);
+    let flowed = Parameters {
+        exclusion_zones: vec![ExclusionZone::Rect(picture)],
+        ..plain.clone()
+    };
+    let scale = plain.world_scale(1.0);
+
+    let unflowed = common::generate(&mut world, &text, plain).unwrap();
+    let entries = common::generate(&mut world, &text, flowed).unwrap();
+    assert_eq!(entries.len(), unflowed.len());
+
+    // Glyph centers in layout units, Y down
+    let centers: Vec<Vec2> = entries
+        .iter()
+        .map(|entry| {
+            let translation = entry.transform.translation;
+            Vec2::new(translation.x / scale.x, -translation.y / scale.y)
+        })
+        .collect();
+    assert!(centers.iter().all(|center| !picture.contains(*center)));
+    assert!(centers.iter().all(|center| center.x < 200.0));
+    // Next to the picture lines start right of it, and below it back at the left
+    assert!(
+        centers
+            .iter()
+            .any(|center| center.y < picture.max.y && center.x < 120.0)
+    );
+    assert!(
+        centers
+            .iter()
+            .any(|center| center.y > picture.max.y && center.x < 20.0)
+    );
+    // Lines beside the picture hold fewer words, so the text runs longer
+    let bottom = |heights: Vec<f32>| heights.into_iter().fold(f32::MIN, f32::max);
+    let unflowed_bottom = bottom(
+        unflowed
+            .iter()
+            .map(|entry| -entry.transform.translation.y / scale.y)
+            .collect(),
+    );
+    assert!(bottom(centers.iter().map(|center| center.y).collect()) > unflowed_bottom);
+}
+
+#[test]
+fn polygon_zones_span_their_width_across_a_line() {
+    let triangle = ExclusionZone::Polygon(vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(100.0, 100.0),
+        Vec2::new(0.0, 100.0),
+    ]);
+    let (left, right) = triangle.span(40.0, 60.0).unwrap();
+    assert_eq!(left, 0.0);
+    assert!((right - 60.0).abs() < 1e-4);
+    assert_eq!(triangle.span(120.0, 140.0), None);
+}
+
+#[test]
+fn drop_caps_span_their_lines_with_the_text_beside_them() {
+    let mut world = common::world();
+    let text = "Once upon a time there were words flowing on and on ".repeat(6);
+    let plain = Parameters {
+        max_width: Some(300.0),
+        ..common::params(14.0, 2.0)
+    };
+    let line_height = plain.line_height;
+    let capped = Parameters {
+        drop_cap: Some(DropCapParameters { lines: 3, gap: 4.0 }),
+        ..plain.clone()
+    };
+    let scale = plain.world_scale(1.0);
+
+    let unflowed = common::generate(&mut world, &text, plain).unwrap();
+    let entries = common::generate(&mut world, &text, capped).unwrap();
+    assert_eq!(entries.len(), unflowed.len());
+
+    // The cap comes first and grows from the height of the capitals by the two lines it
+    // drops, 1.2 times the font size each
+    let growth = entries[0].transform.scale.y / unflowed[0].transform.scale.y;
+    assert!(growth > 3.5 && growth < 5.0, "{growth}");
+    let cap_center = entries[0].transform.translation.x / scale.x;
+
+    // Layout positions of the other glyphs, Y down
+    let centers: Vec<Vec2> = entries[1..]
+        .iter()
+        .map(|entry| {
+            let translation = entry.transform.translation;
+            Vec2::new(translation.x / scale.x, -translation.y / scale.y)
+        })
+        .collect();
+    let beside = 3.0 * line_height;
+    assert!(
+        centers
+            .iter()
+            .filter(|center| center.y < beside)
+            .all(|center| center.x > cap_center)
+    );
+    assert!(
+        centers
+            .iter()
+            .any(|center| center.y > beside && center.x < cap_center)
+    );
+}
+
+#[test]
+fn zones_without_end_do_not_grow_the_layout_forever() {
+    let mut world = common::world();
+    let text = "flowing words ".repeat(30);
+    let unbounded = Parameters {
+        max_width: Some(200.0),
+        exclusion_zones: vec![ExclusionZone::Rect(Rect::new(
+            0.0,
+            50.0,
+            200.0,
+            f32::INFINITY,
+        ))],
+        ..common::params(14.0, 2.0)
+    };
+    assert!(matches!(
+        common::generate(&mut world, &text, unbounded.clone()),
+        Err(MeshTextError::InvalidExclusionZone(_))
+    ));
+
+    // A zone across the whole width down to the end of the numbers stops the text above it
+    let huge = Parameters {
+        exclusion_zones: vec![ExclusionZone::Polygon(vec![
+            Vec2::new(0.0, 50.0),
+            Vec2::new(200.0, 50.0),
+            Vec2::new(200.0, f32::MAX),
+            Vec2::new(0.0, f32::MAX),
+        ])],
+        ..unbounded.clone()
+    };
+    let entries = common::generate(&mut world, &text, huge).unwrap();
+    assert!(!entries.is_empty());
+    let scale = unbounded.world_scale(1.0);
+    assert!(
+        entries
+            .iter()
+            .all(|entry| -entry.transform.translation.y / scale.y < 50.0)
+    );
+
+    // The same goes for the maximum height
+    let short = Parameters {
+        exclusion_zones: vec![ExclusionZone::Rect(Rect::new(0.0, 50.0, 200.0, 1e30))],
+        max_height: Some(100.0),
+        ..unbounded
+    };
+    assert!(
+        !common::generate(&mut world, &text, short)
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,196 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::mesh::morph::MeshMorphWeights;
+use bevy::tasks::{AsyncComputeTaskPool, TaskPool};
+use bevy_mesh_text_3d::extrusion_animator::animate_extrusion;
+use bevy_mesh_text_3d::{ExtrusionAnimator, ExtrusionKeyframe, GlyphEntityPool};
+use cosmic_text::Attrs;
+
+fn world() -> World {
+    AsyncComputeTaskPool::get_or_init(TaskPool::default);
+    let mut world = common::world();
+    world.init_resource::<Assets<Image>>();
+    world.init_resource::<Time>();
+    world.init_resource::<GlyphEntityPool>();
+    world
+}
+
+fn frame(world: &mut World, delta: Duration) {
+    world.resource_mut::<Time>().advance_by(delta);
+    world
+        .run_system_once(animate_extrusion::<StandardMaterial>)
+        .unwrap();
+}
+
+/// Runs frames without advancing time until the animator shows a step.
+fn wait_for_step(world: &mut World, entity: Entity) -> Vec<Entity> {
+    for _ in 0..500 {
+        frame(world, Duration::ZERO);
+        let glyphs = world.get::<ExtrusionAnimator>(entity).unwrap().glyphs();
+        if !glyphs.is_empty() {
+            return glyphs.to_vec();
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("no step was generated");
+}
+
+fn weight(world: &World, glyph: Entity) -> f32 {
+    world.get::<MeshMorphWeights>(glyph).unwrap().weights()[0]
+}
+
+#[test]
+fn steps_blend_between_keyframes() {
+    let mut world = world();
+    let keyframe = |extrusion_depth| ExtrusionKeyframe {
+        extrusion_depth,
+        front_inset: None,
+    };
+    let animator = ExtrusionAnimator::<StandardMaterial>::new(
+        "Hi",
+        &Attrs::new(),
+        common::params(14.0, 1.0),
+        Handle::default(),
+        keyframe(0.5),
+        keyframe(3.0),
+        Duration::from_secs(1),
+    );
+    let entity = world.spawn(animator).id();
+
+    let glyphs = wait_for_step(&mut world, entity);
+    assert_eq!(glyphs.len(), 2);
+    assert_eq!(weight(&world, glyphs[0]), 0.0);
+    let mesh = world.get::<Mesh3d>(glyphs[0]).unwrap().0.clone();
+    assert!(
+        world
+            .resource::<Assets<Mesh>>()
+            .get(&mesh)
+            .unwrap()
+            .has_morph_targets()
+    );
+
+    // Halfway through the 100 ms step
+    frame(&mut world, Duration::from_millis(50));
+    assert!((weight(&world, glyphs[0]) - 0.5).abs() < 1e-3);
+
+    for _ in 0..2000 {
+        frame(&mut world, Duration::from_millis(10));
+        if world
+            .get::<ExtrusionAnimator>(entity)
+            .unwrap()
+            .is_finished()
+        {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    let animator = world.get::<ExtrusionAnimator>(entity).unwrap();
+    assert!(animator.is_finished());
+    assert_eq!(animator.glyphs(), glyphs);
+    assert_eq!(weight(&world, glyphs[0]), 1.0);
+}
+
+#[test]
+fn invalid_keyframes_stop_the_animation() {
+    let mut world = world();
+    let keyframe = |extrusion_depth| ExtrusionKeyframe {
+        extrusion_depth,
+        front_inset: None,
+    };
+    let animator = ExtrusionAnimator::<StandardMaterial>::new(
+        "Hi",
+        &Attrs::new(),
+        common::params(14.0, 1.0),
+        Handle::default(),
+        keyframe(-1.0),
+        keyframe(3.0),
+        Duration::from_secs(1),
+    );
+    let entity = world.spawn(animator).id();
+
+    for _ in 0..20 {
+        frame(&mut world, Duration::from_millis(100));
+    }
+    let animator = world.get::<ExtrusionAnimator>(entity).unwrap();
+    assert!(animator.glyphs().is_empty());
+    assert!(!animator.is_finished());
+
+    // Fixing the keyframes and starting over resumes it
+    let mut animator = world.get_mut::<ExtrusionAnimator>(entity).unwrap();
+    animator.from = keyframe(0.5);
+    animator.restart();
+    let glyphs = wait_for_step(&mut world, entity);
+    assert_eq!(glyphs.len(), 2);
+}
+
+#[test]
+fn keyframes_interpolate_insets() {
+    use bevy_mesh_text_3d::FrontInset;
+    let inset = |width| Some(FrontInset { width, depth: 0.5 });
+    let a = ExtrusionKeyframe {
+        extrusion_depth: 1.0,
+        front_inset: inset(0.0),
+    };
+    let b = ExtrusionKeyframe {
+        extrusion_depth: 3.0,
+        front_inset: inset(1.0),
+    };
+    let mid = a.lerp(&b, 0.5);
+    assert_eq!(mid.extrusion_depth, 2.0);
+    assert_eq!(mid.front_inset, inset(0.5));
+
+    let none = ExtrusionKeyframe {
+        front_inset: None,
+        ..a
+    };
+    assert_eq!(none.lerp(&b, 0.5).front_inset, None);
+    assert_eq!(none.lerp(&b, 1.0).front_inset, b.front_inset);
+}
+
+#[test]
+fn keyframe_insets_take_the_place_of_bevel_widths() {
+    use bevy::render::mesh::VertexAttributeValues;
+    use bevy_mesh_text_3d::{BevelWidth, FrontInset, Parameters};
+    let mut world = world();
+    let keyframe = |width| ExtrusionKeyframe {
+        extrusion_depth: 2.0,
+        front_inset: Some(FrontInset { width, depth: 0.5 }),
+    };
+    let params = Parameters {
+        bevel_width: Some(BevelWidth::Em(0.05)),
+        ..common::params(14.0, 2.0)
+    };
+    let animator = ExtrusionAnimator::<StandardMaterial>::new(
+        "H",
+        &Attrs::new(),
+        params,
+        Handle::default(),
+        keyframe(0.3),
+        keyframe(1.0),
+        Duration::from_secs(1),
+    );
+    let entity = world.spawn(animator).id();
+    let glyphs = wait_for_step(&mut world, entity);
+
+    let meshes = world.resource::<Assets<Mesh>>();
+    let mesh = &world.get::<Mesh3d>(glyphs[0]).unwrap().0;
+    let Some(VertexAttributeValues::Float32x3(positions)) = meshes
+        .get(mesh)
+        .unwrap()
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("missing positions");
+    };
+    // The first step starts with the lip as wide as the inset of the first keyframe
+    let left = |z: f32| {
+        positions
+            .iter()
+            .filter(|p| p[2] == z)
+            .fold(f32::MAX, |left, p| left.min(p[0]))
+    };
+    assert!((left(0.5) - left(0.0) - 0.3).abs() < 1e-4);
+}
@@ -0,0 +1,43 @@
+use bevy_mesh_text_3d::{MeshTextError, embedded_font_system, font_metrics};
+use cosmic_text::ttf_parser::{Face, GlyphId};
+use cosmic_text::{Attrs, Metrics};
+
+#[test]
+fn font_metrics_are_in_layout_units_above_the_baseline() {
+    let mut font_system = embedded_font_system();
+    let small = font_metrics(&mut font_system, &Attrs::new(), 14.0).unwrap();
+    let large = font_metrics(&mut font_system, &Attrs::new(), 28.0).unwrap();
+
+    assert!(small.ascender > small.cap_height);
+    assert!(small.cap_height > small.x_height && small.x_height > 0.0);
+    assert!(small.descender < 0.0);
+    assert!(small.underline_position < 0.0 && small.underline_thickness > 0.0);
+    assert!(small.strikeout_position > 0.0 && small.strikeout_position < small.cap_height);
+    assert!((large.cap_height - 2.0 * small.cap_height).abs() < 1e-4);
+    assert!((large.underline_position - 2.0 * small.underline_position).abs() < 1e-4);
+
+    // The capitals are as high as a flat "H"
+    let font = font_system.db().faces().next().unwrap().id;
+    let h = font_system
+        .db()
+        .with_face_data(font, |data, index| {
+            let face = Face::parse(data, index).unwrap();
+            let glyph = face.glyph_index('H').unwrap();
+            let top = face.glyph_bounding_box(GlyphId(glyph.0)).unwrap().y_max;
+            top as f32 * 14.0 / face.units_per_em() as f32
+        })
+        .unwrap();
+    assert!(
+        (small.cap_height - h).abs() < 1e-4,
+        "cap height {} should equal the top of \"H\" at {h}",
+        small.cap_height
+    );
+
+    // Sizes set on the attributes win
+    let sized = Attrs::new().metrics(Metrics::new(28.0, 30.0));
+    assert_eq!(font_metrics(&mut font_system, &sized, 14.0).unwrap(), large);
+    assert!(matches!(
+        font_metrics(&mut font_system, &Attrs::new(), 0.0),
+        Err(MeshTextError::InvalidFontSize(_))
+    ));
+}
@@ -0,0 +1,62 @@
+use bevy_mesh_text_3d::{MeshTextError, subset_font};
+use cosmic_text::ttf_parser::{Face, GlyphId};
+
+mod common;
+
+const FONT: &[u8] = include_bytes!("../assets/centurygothic_bold.ttf");
+
+fn has_outline(face: &Face, c: char) -> bool {
+    let id = face.glyph_index(c).unwrap();
+    face.glyph_bounding_box(id).is_some()
+}
+
+#[test]
+fn subset_keeps_only_the_used_glyphs() {
+    let subset = subset_font(FONT, &["Hello", "42"]).unwrap();
+    assert!(subset.len() < FONT.len() / 2, "{} bytes", subset.len());
+
+    let original = Face::parse(FONT, 0).unwrap();
+    let face = Face::parse(&subset, 0).unwrap();
+    assert_eq!(face.number_of_glyphs(), original.number_of_glyphs());
+    for c in "Helo42".chars() {
+        assert!(has_outline(&face, c), "{c} lost its outline");
+        let id = face.glyph_index(c).unwrap();
+        assert_eq!(face.glyph_bounding_box(id), original.glyph_bounding_box(id));
+        assert_eq!(face.glyph_hor_advance(id), original.glyph_hor_advance(id));
+    }
+    for c in "xyzW7".chars() {
+        assert!(!has_outline(&face, c), "{c} kept its outline");
+    }
+    assert_eq!(
+        face.glyph_bounding_box(GlyphId(0)),
+        original.glyph_bounding_box(GlyphId(0))
+    );
+}
+
+#[test]
+fn subset_font_tessellates_like_the_original() {
+    let subset = subset_font(FONT, &["Hello"]).unwrap();
+    let mut original = bevy_mesh_text_3d::embedded_font_system();
+    let mut db = cosmic_text::fontdb::Database::new();
+    db.load_font_data(subset);
+    let mut subset = cosmic_text::FontSystem::new_with_locale_and_db("en-US".into(), db);
+
+    let glyphs = common::shape(&mut original, "Hello", 24.0);
+    let subset_glyphs = common::shape(&mut subset, "Hello", 24.0);
+    assert_eq!(glyphs.len(), subset_glyphs.len());
+    for (glyph, subset_glyph) in glyphs.iter().zip(&subset_glyphs) {
+        assert_eq!(glyph.glyph_id, subset_glyph.glyph_id);
+        assert_eq!(glyph.x, subset_glyph.x);
+        let (geometry, ..) = common::tessellate(&mut original, glyph, 4.0).unwrap();
+        let (subset_geometry, ..) = common::tessellate(&mut subset, subset_glyph, 4.0).unwrap();
+        assert_eq!(geometry.vertices, subset_geometry.vertices);
+    }
+}
+
+#[test]
+fn fonts_without_truetype_outlines_are_rejected() {
+    assert!(matches!(
+        subset_font(b"not a font", &["Hello"]),
+        Err(MeshTextError::SubsetFailed(_))
+    ));
+}
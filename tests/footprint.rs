@@ -0,0 +1,102 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy_mesh_text_3d::footprint::{
+    FootprintGrouping, TextFootprint, convex_hull, spawn_footprints, text_footprints,
+};
+use bevy_mesh_text_3d::{Attrs, InputText, Settings};
+
+fn footprints(world: &mut World, grouping: FootprintGrouping) -> Vec<Vec<Vec2>> {
+    world
+        .run_system_once(move |mut settings: ResMut<Settings>| {
+            text_footprints(
+                InputText::Simple {
+                    text: "ab cd".to_string(),
+                    material: Handle::<StandardMaterial>::default(),
+                    attrs: Attrs::new(),
+                },
+                &mut settings,
+                common::params(14.0, 2.0),
+                grouping,
+            )
+            .unwrap()
+        })
+        .unwrap()
+}
+
+/// Whether `point` lies inside the counter-clockwise convex `polygon`, or within `margin`.
+fn inside(polygon: &[Vec2], point: Vec2, margin: f32) -> bool {
+    (0..polygon.len()).all(|i| {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        (b - a).normalize().perp_dot(point - a) >= -margin
+    })
+}
+
+#[test]
+fn convex_hull_drops_inner_and_collinear_points() {
+    let hull = convex_hull(vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(1.0, 0.0),
+        Vec2::new(2.0, 0.0),
+        Vec2::new(1.0, 1.0),
+        Vec2::new(2.0, 2.0),
+        Vec2::new(0.0, 2.0),
+        Vec2::new(2.0, 0.0),
+    ]);
+    assert_eq!(
+        hull,
+        [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ]
+    );
+}
+
+#[test]
+fn footprints_cover_the_glyphs_or_words() {
+    let mut world = common::world();
+    let glyphs = footprints(&mut world, FootprintGrouping::Glyph);
+    let words = footprints(&mut world, FootprintGrouping::Word);
+    assert_eq!(glyphs.len(), 4);
+    assert_eq!(words.len(), 2);
+
+    // Every glyph lies within the footprint of its word
+    let entries = common::generate(&mut world, "ab cd", common::params(14.0, 2.0)).unwrap();
+    let meshes = world.resource::<Assets<Mesh>>();
+    for (i, entry) in entries.iter().enumerate() {
+        let mesh = meshes.get(&entry.mesh).unwrap();
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("no positions");
+        };
+        for &p in positions {
+            let p = entry.transform.transform_point(p.into()).truncate();
+            assert!(inside(&glyphs[i], p, 1e-3));
+            assert!(inside(&words[i / 2], p, 1e-3));
+            assert!(!inside(&words[1 - i / 2], p, 0.0));
+        }
+    }
+}
+
+#[test]
+fn footprints_spawn_as_children_of_the_text() {
+    let mut world = common::world();
+    let polygons = footprints(&mut world, FootprintGrouping::Word);
+    let text = world.spawn(Transform::default()).id();
+    world
+        .run_system_once(move |mut commands: Commands| {
+            spawn_footprints(&mut commands, text, polygons.clone());
+        })
+        .unwrap();
+
+    let children = world.get::<Children>(text).unwrap().to_vec();
+    assert_eq!(children.len(), 2);
+    for child in children {
+        assert!(world.get::<TextFootprint>(child).unwrap().polygon.len() >= 3);
+    }
+}
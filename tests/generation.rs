@@ -0,0 +1,2973 @@
+mod common;
+
+use std::collections::HashMap;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::mesh::skinning::{SkinnedMesh, SkinnedMeshInverseBindposes};
+use bevy::render::mesh::{MeshAabb, PrimitiveTopology, VertexAttributeValues};
+use bevy_mesh_text_3d::contour::{contours_from_path, normalize_orientation};
+use bevy_mesh_text_3d::extrude_glyph::{
+    ATTRIBUTE_SURFACE, ExtrudedGlyphGeometry, ExtrusionOptions, HairlineContours, OpenContours,
+    TextSurface, extrude_path, tessalate_glyph,
+};
+use bevy_mesh_text_3d::fracture::{
+    FractureOptions, GlyphFragment, fracture_glyph, generate_fractured_meshes,
+};
+use bevy_mesh_text_3d::morph::morph_target;
+use bevy_mesh_text_3d::skeleton::spawn_skinned_text;
+use bevy_mesh_text_3d::{
+    BevelEdges, BevelWidth, CapTessellator, Depth, DepthAnchor, FillRule, FontContexts, FrontInset,
+    FrontRim, GlyphIssue, InflateParameters, InputText, LayoutGrid, MAX_DEPTH_SEGMENTS,
+    MeshSharingReport, MeshTextEntry, MeshTextError, MissingGlyphPolicy, OutputTopology,
+    PanelParameters, Parameters, RoundedBack, RuleParameters, Settings, Stagger, SurfaceNoise,
+    SyntheticStyle, TessellationOptions, TextGlyphs, UvMode, WallSeam, embedded_font_system,
+    generate_meshes_from_glyphs, generate_meshes_with_font_system, generate_meshes_with_panel,
+    generate_meshes_with_report, generate_rules, generate_text_morph_meshes,
+};
+use cosmic_text::ttf_parser::{Face, GlyphId};
+use cosmic_text::{Align, Attrs, Buffer, FontSystem, LayoutGlyph, Metrics, Shaping};
+use lyon::geom::point;
+use lyon::path::Path;
+
+/// Scale of the shared glyph meshes in 14px text
+const MESH_SCALE_14: Vec3 = Vec3::new(0.14, 0.14, 1.0);
+
+fn with_policy(missing_glyph_policy: MissingGlyphPolicy) -> Parameters {
+    Parameters {
+        missing_glyph_policy,
+        ..common::params(14.0, 2.0)
+    }
+}
+
+#[test]
+fn missing_glyph_policy() {
+    let mut world = common::world();
+    // U+4E2D is not covered by the embedded font
+    let text = "a\u{4e2d}b";
+
+    let skipped =
+        common::generate(&mut world, text, with_policy(MissingGlyphPolicy::Skip)).unwrap();
+    let notdef =
+        common::generate(&mut world, text, with_policy(MissingGlyphPolicy::Notdef)).unwrap();
+    let placeholder = common::generate(
+        &mut world,
+        text,
+        with_policy(MissingGlyphPolicy::Placeholder),
+    )
+    .unwrap();
+
+    assert_eq!(skipped.len(), 2);
+    assert_eq!(notdef.len(), 3);
+    assert_eq!(placeholder.len(), 3);
+    assert_ne!(notdef[1].mesh, placeholder[1].mesh);
+}
+
+#[test]
+fn generation_reports_glyph_issues_and_cache_use() {
+    let mut settings = Settings::new(embedded_font_system(), 1.0);
+    let mut meshes = Assets::<Mesh>::default();
+    let mut generate = |settings: &mut Settings| {
+        generate_meshes_with_report(
+            InputText::Simple {
+                text: "a\u{4e2d}ba".to_string(),
+                material: Handle::<StandardMaterial>::default(),
+                attrs: Attrs::new(),
+            },
+            settings,
+            with_policy(MissingGlyphPolicy::Skip),
+            &mut meshes,
+        )
+        .unwrap()
+    };
+
+    let (entries, report) = generate(&mut settings);
+    assert_eq!(entries.len(), 3);
+    assert!(!report.is_clean());
+    assert_eq!(report.warnings.len(), 1);
+    let warning = &report.warnings[0];
+    assert_eq!(
+        (warning.index, warning.character, warning.glyph_id),
+        (1, '\u{4e2d}', 0)
+    );
+    assert_eq!(warning.issue, GlyphIssue::MissingGlyph);
+    // The second "a" shares the mesh of the first
+    assert_eq!((report.tessellated, report.cache_hits), (2, 1));
+    assert_eq!(report.cache, settings.glyph_cache.stats());
+    assert_eq!(report.cache.mesh_count, 2);
+
+    let (_, again) = generate(&mut settings);
+    assert_eq!((again.tessellated, again.cache_hits), (0, 3));
+    assert_eq!(again.warnings, report.warnings);
+}
+
+#[test]
+fn glyphs_without_an_outline_are_not_counted_as_tessellated() {
+    let mut settings = Settings::new(embedded_font_system(), 1.0);
+    let mut meshes = Assets::<Mesh>::default();
+    let mut generate = |settings: &mut Settings| {
+        generate_meshes_with_report(
+            InputText::Simple {
+                text: "a a".to_string(),
+                material: Handle::<StandardMaterial>::default(),
+                attrs: Attrs::new(),
+            },
+            settings,
+            common::params(14.0, 2.0),
+            &mut meshes,
+        )
+        .unwrap()
+        .1
+    };
+
+    let report = generate(&mut settings);
+    assert_eq!((report.tessellated, report.cache_hits), (1, 1));
+    // The space never gets a mesh to find in the cache, nor is it tessellated again
+    let again = generate(&mut settings);
+    assert_eq!(again.tessellated, 0);
+    assert_eq!(again.cache_hits, 2);
+}
+
+/// Rules under `text` generated with `params`.
+fn rules(
+    world: &mut World,
+    text: &str,
+    params: Parameters,
+) -> Vec<MeshTextEntry<StandardMaterial>> {
+    let text = text.to_string();
+    world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                generate_rules(
+                    InputText::Simple {
+                        text: text.clone(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    params.clone(),
+                    RuleParameters::default(),
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn one_rule_per_line() {
+    let mut world = common::world();
+    let rules = rules(
+        &mut world,
+        "First line\nSecond\n\nFourth",
+        common::params(14.0, 2.0),
+    );
+
+    // The empty line has no glyphs to underline
+    assert_eq!(rules.len(), 3);
+    assert!(rules[0].transform.translation.y > rules[1].transform.translation.y);
+}
+
+#[test]
+fn rules_follow_the_placed_glyphs() {
+    let mut world = common::world();
+    let params = Parameters {
+        stagger: Some(Stagger {
+            per_line: 1.0,
+            per_glyph: 0.0,
+        }),
+        double_sided: true,
+        ..common::params(14.0, 2.0)
+    };
+    let glyphs = common::generate(&mut world, "First\nSecond", params.clone()).unwrap();
+    let rules = rules(&mut world, "First\nSecond", params);
+
+    // A rule per line on the front, then their mirrored copies on the back
+    assert_eq!(rules.len(), 4);
+    let front: Vec<f32> = glyphs[..glyphs.len() / 2]
+        .iter()
+        .map(|glyph| glyph.transform.translation.z)
+        .collect();
+    assert_eq!(rules[0].transform.translation.z, front[0]);
+    assert_eq!(rules[1].transform.translation.z, *front.last().unwrap());
+    assert_eq!(
+        rules[1].transform.translation.z,
+        rules[0].transform.translation.z + 1.0
+    );
+    for (rule, back) in rules[..2].iter().zip(&rules[2..]) {
+        assert_eq!(
+            back.transform.translation.z,
+            rule.transform.translation.z + 4.0
+        );
+        assert!(back.transform.translation.x != rule.transform.translation.x);
+    }
+
+    // The back rules span the back glyphs of their line
+    let back_glyphs = &glyphs[glyphs.len() / 2..];
+    let (min_x, max_x) = back_glyphs[..5]
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(min, max), glyph| {
+            (
+                min.min(glyph.transform.translation.x),
+                max.max(glyph.transform.translation.x),
+            )
+        });
+    let center = rules[2].transform.translation.x;
+    assert!(min_x < center && center < max_x);
+}
+
+#[test]
+fn rules_are_extruded_like_the_text() {
+    let mut world = common::world();
+    let params = Parameters {
+        front_inset: Some(FrontInset {
+            width: 0.2,
+            depth: 0.5,
+        }),
+        ..common::params(14.0, 2.0)
+    };
+    let rules = rules(&mut world, "Rule", params);
+    assert_eq!(rules.len(), 1);
+
+    // The front face is recessed behind a lip like the glyphs'
+    let meshes = world.resource::<Assets<Mesh>>();
+    let mesh = meshes.get(&rules[0].mesh).unwrap();
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("missing positions");
+    };
+    let depths: Vec<f32> = positions.iter().map(|p| p[2]).collect();
+    assert!(depths.contains(&0.0));
+    assert!(depths.contains(&0.5));
+    assert!(depths.contains(&2.0));
+}
+
+#[test]
+fn generation_without_the_plugin() {
+    let params = common::params(14.0, 2.0);
+    let mut world = common::world();
+    let expected = common::generate(&mut world, "Hello", params.clone()).unwrap();
+
+    let mut font_system = embedded_font_system();
+    let mut meshes = Assets::<Mesh>::default();
+    let entries = generate_meshes_with_font_system(
+        InputText::Simple {
+            text: "Hello".to_string(),
+            material: Handle::<StandardMaterial>::default(),
+            attrs: Attrs::new(),
+        },
+        &mut font_system,
+        params,
+        &mut meshes,
+    )
+    .unwrap();
+
+    assert_eq!(entries.len(), expected.len());
+    for (entry, expected) in entries.iter().zip(&expected) {
+        assert_eq!(entry.transform, expected.transform);
+    }
+    // The two "l"s share their mesh
+    assert_eq!(entries[2].mesh, entries[3].mesh);
+    assert_eq!(meshes.len(), 4);
+}
+
+#[test]
+fn invalid_parameters_are_rejected() {
+    let mut world = common::world();
+    let base = common::params(14.0, 2.0);
+    let rejected = |params: Parameters| common::generate(&mut common::world(), "Hi", params);
+
+    assert!(matches!(
+        rejected(Parameters {
+            extrusion_depth: -1.0,
+            ..base.clone()
+        }),
+        Err(MeshTextError::NegativeExtrusionDepth(-1.0))
+    ));
+    assert!(matches!(
+        rejected(Parameters {
+            font_size: 0.0,
+            ..base.clone()
+        }),
+        Err(MeshTextError::InvalidFontSize(0.0))
+    ));
+    assert!(matches!(
+        rejected(Parameters {
+            front_inset: Some(FrontInset {
+                width: 7.0,
+                depth: 1.0,
+            }),
+            ..base.clone()
+        }),
+        Err(MeshTextError::EdgeTooWide { width: 7.0, .. })
+    ));
+    assert!(matches!(
+        rejected(Parameters {
+            front_inset: Some(FrontInset {
+                width: 1.0,
+                depth: 1.0,
+            }),
+            hole_bevel_width: Some(BevelWidth::Em(0.5)),
+            ..base.clone()
+        }),
+        Err(MeshTextError::EdgeTooWide {
+            feature: "Hole bevel width",
+            ..
+        })
+    ));
+    assert!(matches!(
+        rejected(Parameters {
+            rounded_back: Some(RoundedBack {
+                radius: 1.0,
+                depth: 1.0,
+                segments: 0,
+                profile_power: 1.0,
+            }),
+            ..base.clone()
+        }),
+        Err(MeshTextError::ZeroSegments(_))
+    ));
+    assert!(matches!(
+        rejected(Parameters {
+            rounded_back: Some(RoundedBack {
+                radius: 1.0,
+                depth: 1.0,
+                segments: 2,
+                profile_power: 0.0,
+            }),
+            ..base.clone()
+        }),
+        Err(MeshTextError::InvalidProfilePower(0.0))
+    ));
+    assert!(matches!(
+        rejected(Parameters {
+            depth_segments: MAX_DEPTH_SEGMENTS + 1,
+            ..base.clone()
+        }),
+        Err(MeshTextError::TooManySegments("Depth", ..))
+    ));
+    assert!(matches!(
+        rejected(Parameters {
+            max_width: Some(10.0),
+            ..base.clone()
+        }),
+        Err(MeshTextError::MaxWidthTooSmall { .. })
+    ));
+    assert!(matches!(
+        rejected(Parameters {
+            lightmap_uvs: true,
+            depth_attribute: true,
+            ..base.clone()
+        }),
+        Err(MeshTextError::LightmapUvsWithAttribute("depth attribute"))
+    ));
+    assert!(matches!(
+        rejected(Parameters {
+            lightmap_uvs: true,
+            surface_attribute: true,
+            ..base.clone()
+        }),
+        Err(MeshTextError::LightmapUvsWithAttribute("surface attribute"))
+    ));
+
+    // Flat text and a narrow inset are fine
+    let flat = Parameters {
+        extrusion_depth: 0.0,
+        front_inset: Some(FrontInset {
+            width: 1.0,
+            depth: 0.0,
+        }),
+        max_width: Some(14.0),
+        ..base
+    };
+    assert!(flat.validate().is_ok());
+    assert!(common::generate(&mut world, "Hi", flat).is_ok());
+}
+
+#[test]
+fn panel_surrounds_the_text() {
+    let mut world = common::world();
+    let scale = world.resource::<Settings>().text_scale_factor;
+    let panel = PanelParameters::default();
+    let entries = world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                generate_meshes_with_panel(
+                    InputText::Simple {
+                        text: "Name\nplate".to_string(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    common::params(14.0, 2.0),
+                    panel,
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(entries.len(), 10);
+
+    let (glyphs, [panel_entry]) = entries.split_at(9) else {
+        unreachable!()
+    };
+    let meshes = world.resource::<Assets<Mesh>>();
+    let aabb = meshes
+        .get(&panel_entry.mesh)
+        .unwrap()
+        .compute_aabb()
+        .unwrap();
+    let to_world = |p: Vec3| panel_entry.transform.transform_point(p);
+    let (min, max) = (to_world(aabb.min().into()), to_world(aabb.max().into()));
+    // Right behind the glyphs, as deep as the panel
+    assert!((min.z - 2.0 * scale).abs() < 1e-4);
+    assert!((max.z - min.z - panel.depth * 14.0 * scale).abs() < 1e-4);
+    // Padded around every glyph
+    let padding = panel.padding * 14.0 * scale;
+    for glyph in glyphs {
+        let center = glyph.transform.translation.truncate();
+        assert!(center.cmpgt(min.truncate() + padding).all());
+        assert!(center.cmplt(max.truncate() - padding).all());
+    }
+}
+
+/// Tessellates a glyph with `options`.
+fn tessellate_with(
+    font_system: &mut FontSystem,
+    glyph: &LayoutGlyph,
+    options: ExtrusionOptions,
+) -> ExtrudedGlyphGeometry {
+    font_system
+        .db()
+        .with_face_data(glyph.font_id, |file, _| {
+            let face = Face::parse(file, 0).unwrap();
+            let bb = face.glyph_bounding_box(GlyphId(glyph.glyph_id)).unwrap();
+            tessalate_glyph(glyph, bb, face, &options).unwrap().0
+        })
+        .unwrap()
+}
+
+/// Area of the front-facing triangles, per Z plane.
+fn front_area(geometry: &ExtrudedGlyphGeometry) -> Vec<(f32, f32)> {
+    let mut areas: Vec<(f32, f32)> = Vec::new();
+    for tri in geometry.indices.chunks_exact(3) {
+        if tri
+            .iter()
+            .any(|&i| geometry.normals[i as usize] != Vec3::NEG_Z)
+        {
+            continue;
+        }
+        let [a, b, c] = [0, 1, 2].map(|k| geometry.vertices[tri[k] as usize]);
+        let area = (b - a).truncate().perp_dot((c - a).truncate()).abs() / 2.0;
+        match areas.iter_mut().find(|(z, _)| *z == a.z) {
+            Some((_, total)) => *total += area,
+            None => areas.push((a.z, area)),
+        }
+    }
+    areas
+}
+
+#[test]
+fn front_inset_splits_the_front_face() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let inset = FrontInset {
+        width: 2.0,
+        depth: 1.5,
+    };
+
+    let (plain, ..) = common::tessellate(&mut font_system, &glyph, 5.0).unwrap();
+    let inset_geometry = tessellate_with(
+        &mut font_system,
+        &glyph,
+        ExtrusionOptions {
+            front_inset: Some(inset),
+            ..ExtrusionOptions::new(5.0)
+        },
+    );
+
+    let plain_area = front_area(&plain);
+    let inset_area = front_area(&inset_geometry);
+    assert_eq!(plain_area.len(), 1);
+    assert_eq!(inset_area.len(), 2);
+    assert_eq!(inset_area[1].0, inset.depth);
+
+    // The lip and the recessed face together cover the original front face
+    let total: f32 = inset_area.iter().map(|(_, area)| area).sum();
+    assert!((total - plain_area[0].1).abs() / plain_area[0].1 < 0.01);
+    assert!(inset_area[0].1 < inset_area[1].1);
+}
+
+#[test]
+fn bevel_widths_convert_to_layout_units() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let units_per_em = font_system
+        .db()
+        .with_face_data(glyph.font_id, |file, _| {
+            Face::parse(file, 0).unwrap().units_per_em()
+        })
+        .unwrap() as f32;
+    let with = |front_inset_width, bevel_width| ExtrusionOptions {
+        front_inset: Some(FrontInset {
+            width: front_inset_width,
+            depth: 1.5,
+        }),
+        bevel_width,
+        ..ExtrusionOptions::new(5.0)
+    };
+
+    let layout = tessellate_with(&mut font_system, &glyph, with(2.0, None));
+    for width in [
+        BevelWidth::Em(0.02),
+        BevelWidth::FontUnits(0.02 * units_per_em),
+        BevelWidth::WorldUnits(2.0),
+    ] {
+        let bevel = tessellate_with(&mut font_system, &glyph, with(0.0, Some(width)));
+        assert_eq!(bevel.vertices.len(), layout.vertices.len(), "{width:?}");
+        for (a, b) in bevel.vertices.iter().zip(&layout.vertices) {
+            assert!(a.abs_diff_eq(*b, 1e-3), "{width:?}");
+        }
+    }
+}
+
+#[test]
+fn em_bevels_share_meshes_across_font_sizes() {
+    let mut world = common::world();
+    let params = |font_size, bevel_width| Parameters {
+        front_inset: Some(FrontInset {
+            width: 1.0,
+            depth: 0.5,
+        }),
+        bevel_width,
+        ..common::params(font_size, 2.0)
+    };
+    let em = Some(BevelWidth::Em(0.05));
+    let small = common::generate(&mut world, "o", params(14.0, em)).unwrap();
+    let large = common::generate(&mut world, "o", params(28.0, em)).unwrap();
+    assert_eq!(small[0].mesh, large[0].mesh);
+
+    // A width in layout units is a different fraction of each size
+    let small = common::generate(&mut world, "o", params(14.0, None)).unwrap();
+    let large = common::generate(&mut world, "o", params(28.0, None)).unwrap();
+    assert_ne!(small[0].mesh, large[0].mesh);
+
+    // World units follow the world scale
+    let world_units = Some(BevelWidth::WorldUnits(0.1));
+    assert!(matches!(
+        common::generate(&mut world, "o", params(14.0, world_units)),
+        Err(MeshTextError::WorldUnitsNeedScale(_))
+    ));
+    let scaled = |font_size| params(font_size, world_units).with_scale(0.1);
+    let small = common::generate(&mut world, "o", scaled(14.0)).unwrap();
+    let em = common::generate(
+        &mut world,
+        "o",
+        params(14.0, Some(BevelWidth::Em(1.0 / 14.0))),
+    )
+    .unwrap();
+    assert_eq!(small[0].mesh, em[0].mesh);
+}
+
+#[test]
+fn front_rim_surrounds_the_front_face() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let rim = FrontRim {
+        width: 1.0,
+        color: [0.1, 0.0, 0.0],
+    };
+
+    let (plain, ..) = common::tessellate(&mut font_system, &glyph, 5.0).unwrap();
+    let rimmed = tessellate_with(
+        &mut font_system,
+        &glyph,
+        ExtrusionOptions {
+            front_rim: Some(rim),
+            ..ExtrusionOptions::new(5.0)
+        },
+    );
+    assert!(plain.vertex_colors.is_none());
+
+    // The ring sits just behind the front face
+    let areas = front_area(&rimmed);
+    assert_eq!(areas.len(), 2);
+    assert_eq!(areas[0], front_area(&plain)[0]);
+    assert!(areas[1].0 > 0.0 && areas[1].0 < rim.width);
+    assert!(areas[1].1 > 0.0);
+
+    // It reaches the rim width past the outline, and only it is tinted
+    let max_x = |geometry: &ExtrudedGlyphGeometry| {
+        geometry
+            .vertices
+            .iter()
+            .map(|v| v.x)
+            .fold(f32::MIN, f32::max)
+    };
+    assert!((max_x(&rimmed) - max_x(&plain) - rim.width).abs() < 0.05);
+    let colors = rimmed.vertex_colors.as_ref().unwrap();
+    for (vertex, color) in rimmed.vertices.iter().zip(colors) {
+        assert_eq!(*color == rim.color, vertex.z == areas[1].0);
+    }
+    assert!(rimmed.winding_matches_normals());
+}
+
+#[test]
+fn glyph_bounds_uvs_span_each_glyph() {
+    let mut font_system = embedded_font_system();
+    for glyph in common::shape(&mut font_system, "iO", 100.0) {
+        let geometry = tessellate_with(
+            &mut font_system,
+            &glyph,
+            ExtrusionOptions {
+                uv_mode: UvMode::GlyphBounds,
+                ..ExtrusionOptions::new(5.0)
+            },
+        );
+        let caps: Vec<(Vec3, Vec2)> = geometry
+            .vertices
+            .iter()
+            .zip(&geometry.uvs)
+            .zip(&geometry.normals)
+            .filter(|(_, normal)| normal.z != 0.0)
+            .map(|((&vertex, &uv), _)| (vertex, uv))
+            .collect();
+
+        let min = caps.iter().fold(Vec2::MAX, |min, (_, uv)| min.min(*uv));
+        let max = caps.iter().fold(Vec2::MIN, |max, (_, uv)| max.max(*uv));
+        assert!(min.abs_diff_eq(Vec2::ZERO, 1e-4), "{min}");
+        assert!(max.abs_diff_eq(Vec2::ONE, 1e-4), "{max}");
+
+        // V runs down from the top of the glyph
+        let (_, top) = caps
+            .iter()
+            .max_by(|(a, _), (b, _)| a.y.total_cmp(&b.y))
+            .unwrap();
+        assert!(top.y.abs() < 1e-4);
+    }
+}
+
+#[test]
+fn edge_mask_separates_walls_from_caps() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+
+    let (plain, ..) = common::tessellate(&mut font_system, &glyph, 5.0).unwrap();
+    assert!(plain.edge_mask.is_none());
+
+    let masked = tessellate_with(
+        &mut font_system,
+        &glyph,
+        ExtrusionOptions {
+            front_inset: Some(FrontInset {
+                width: 2.0,
+                depth: 1.5,
+            }),
+            edge_mask: true,
+            ..ExtrusionOptions::new(5.0)
+        },
+    );
+    let mask = masked.edge_mask.as_ref().unwrap();
+    assert_eq!(mask.len(), masked.vertices.len());
+    for (i, &m) in mask.iter().enumerate() {
+        let v = masked.vertices[i];
+        let n = masked.normals[i];
+        if n.z == 0.0 && v.z == 5.0 {
+            // Outer side walls reach the back face
+            assert_eq!(m, 0.0);
+        } else if n == Vec3::NEG_Z && v.z == 1.5 {
+            // Recessed cap
+            assert_eq!(m, 1.0);
+        } else {
+            assert!((0.0..=1.0).contains(&m));
+        }
+    }
+    // The lip ramps from the outline to the inset contour
+    assert!(mask.contains(&0.0));
+
+    let mesh = Mesh::from(masked);
+    assert!(mesh.attribute(Mesh::ATTRIBUTE_COLOR).is_some());
+}
+
+#[test]
+fn morph_target_between_depths() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "B", 40.0).remove(0);
+
+    let (flat, ..) = common::tessellate(&mut font_system, &glyph, 0.5).unwrap();
+    let (deep, ..) = common::tessellate(&mut font_system, &glyph, 4.0).unwrap();
+    let attributes = morph_target(&flat, &deep).unwrap();
+    assert_eq!(attributes.len(), flat.vertices.len());
+    for ((attribute, from), to) in attributes.iter().zip(&flat.vertices).zip(&deep.vertices) {
+        assert_eq!(from + attribute.position, *to);
+    }
+
+    let other = common::shape(&mut font_system, "C", 40.0).remove(0);
+    let (other, ..) = common::tessellate(&mut font_system, &other, 0.5).unwrap();
+    assert!(morph_target(&flat, &other).is_err());
+}
+
+/// Runs `generate_text_morph_meshes` against the resources of `world`.
+fn text_morph(
+    world: &mut World,
+    from: &'static str,
+    to: &'static str,
+    params: Parameters,
+) -> Vec<MeshTextEntry<StandardMaterial>> {
+    let input = |text: &str| InputText::Simple {
+        text: text.to_string(),
+        material: Handle::<StandardMaterial>::default(),
+        attrs: Attrs::new(),
+    };
+    world.init_resource::<Assets<Image>>();
+    world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>,
+                  mut meshes: ResMut<Assets<Mesh>>,
+                  mut images: ResMut<Assets<Image>>| {
+                generate_text_morph_meshes(
+                    input(from),
+                    input(to),
+                    &mut settings,
+                    params.clone(),
+                    &mut meshes,
+                    &mut images,
+                )
+            },
+        )
+        .unwrap()
+        .unwrap()
+}
+
+fn vertex_count(world: &World, entry: &MeshTextEntry<StandardMaterial>) -> usize {
+    let meshes = world.resource::<Assets<Mesh>>();
+    let mesh = meshes.get(&entry.mesh).unwrap();
+    assert!(mesh.morph_targets().is_some());
+    mesh.count_vertices()
+}
+
+#[test]
+fn morph_between_strings_matches_glyphs_by_place() {
+    let mut world = common::world();
+    let plain = |world: &mut World, text| common::generate(world, text, common::params(14.0, 2.0));
+    let from = plain(&mut world, "AB").unwrap();
+    let to = plain(&mut world, "ACD").unwrap();
+    let count = |world: &World, entries: &[MeshTextEntry<StandardMaterial>]| -> Vec<usize> {
+        let meshes = world.resource::<Assets<Mesh>>();
+        entries
+            .iter()
+            .map(|entry| meshes.get(&entry.mesh).unwrap().count_vertices())
+            .collect()
+    };
+    let (from_counts, to_counts) = (count(&world, &from), count(&world, &to));
+
+    let morph = text_morph(&mut world, "AB", "ACD", common::params(14.0, 2.0));
+    assert_eq!(morph.len(), 3);
+    // "A" slides in place, "B" turns into "C" and "D" grows where it sits in "ACD"
+    assert_eq!(vertex_count(&world, &morph[0]), from_counts[0]);
+    assert_eq!(
+        vertex_count(&world, &morph[1]),
+        from_counts[1] + to_counts[1]
+    );
+    assert_eq!(vertex_count(&world, &morph[2]), to_counts[2]);
+    assert_eq!(
+        morph[0].transform.translation,
+        from[0].transform.translation
+    );
+    assert_eq!(
+        morph[1].transform.translation,
+        from[1].transform.translation
+    );
+    assert_eq!(morph[2].transform.translation, to[2].transform.translation);
+
+    // Glyphs only the longer text has shrink away
+    let morph = text_morph(&mut world, "ACD", "A", common::params(14.0, 2.0));
+    assert_eq!(morph.len(), 3);
+    assert_eq!(vertex_count(&world, &morph[2]), to_counts[2]);
+    assert_eq!(morph[2].transform.translation, to[2].transform.translation);
+}
+
+#[test]
+fn skinned_text_gets_one_joint_per_glyph() {
+    let mut world = common::world();
+    world.init_resource::<Assets<SkinnedMeshInverseBindposes>>();
+    let params = Parameters {
+        skinned: true,
+        ..common::params(14.0, 2.0)
+    };
+    let entries = common::generate(&mut world, "abc", params).unwrap();
+    let meshes = world.resource::<Assets<Mesh>>();
+    for entry in &entries {
+        let mesh = meshes.get(&entry.mesh).unwrap();
+        assert!(mesh.attribute(Mesh::ATTRIBUTE_JOINT_INDEX).is_some());
+        assert!(mesh.attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT).is_some());
+    }
+    let transforms: Vec<_> = entries.iter().map(|entry| entry.transform).collect();
+
+    let mut entries = Some(entries);
+    let root = world
+        .run_system_once(
+            move |mut commands: Commands,
+                  mut bindposes: ResMut<Assets<SkinnedMeshInverseBindposes>>| {
+                spawn_skinned_text(&mut commands, entries.take().unwrap(), &mut bindposes)
+            },
+        )
+        .unwrap();
+
+    let children = world.get::<Children>(root).unwrap().to_vec();
+    let skinned: Vec<_> = children
+        .iter()
+        .filter_map(|&child| world.get::<SkinnedMesh>(child))
+        .collect();
+    assert_eq!(skinned.len(), 3);
+    for (skin, transform) in skinned.iter().zip(&transforms) {
+        assert_eq!(skin.joints.len(), 1);
+        assert_eq!(world.get::<Transform>(skin.joints[0]), Some(transform));
+    }
+}
+
+#[test]
+fn fractured_pieces_cover_the_glyph() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let (geometry, ..) = common::tessellate(&mut font_system, &glyph, 5.0).unwrap();
+
+    let options = FractureOptions { pieces: 6, seed: 7 };
+    let fragments = fracture_glyph(&geometry, 5.0, &options);
+    // Cells around the counter of "O" break into more than one piece
+    assert!(fragments.len() > 6);
+
+    let mut total = 0.0;
+    for fragment in &fragments {
+        let piece = &fragment.geometry;
+        assert!(
+            piece
+                .indices
+                .iter()
+                .all(|&i| (i as usize) < piece.vertices.len())
+        );
+        assert!(piece.vertices.iter().all(|v| v.z == 0.0 || v.z == 5.0));
+        // Every piece is convex: all of it lies behind each of its walls
+        let walls = piece.vertices.iter().zip(&piece.normals);
+        for (p, n) in walls.filter(|(_, n)| n.z == 0.0) {
+            assert!(piece.vertices.iter().all(|v| (v - p).dot(*n) < 1e-3));
+        }
+        total += front_area(piece)[0].1;
+    }
+    let original = front_area(&geometry)[0].1;
+    assert!((total - original).abs() / original < 0.01);
+
+    // The same seed always breaks the glyph the same way
+    let again = fracture_glyph(&geometry, 5.0, &options);
+    let offsets = |fragments: &[GlyphFragment]| -> Vec<Vec2> {
+        fragments.iter().map(|fragment| fragment.offset).collect()
+    };
+    assert_eq!(offsets(&fragments), offsets(&again));
+}
+
+#[test]
+fn fractured_and_morphed_glyphs_are_always_triangles() {
+    let mut world = common::world();
+    let params = Parameters {
+        topology: OutputTopology::Lines,
+        ..common::params(14.0, 2.0)
+    };
+    let morph = text_morph(&mut world, "AB", "AC", params.clone());
+
+    let pieces = world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                generate_fractured_meshes(
+                    InputText::Simple {
+                        text: "AB".to_string(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    params.clone(),
+                    FractureOptions { pieces: 3, seed: 7 },
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+        .unwrap();
+
+    let meshes = world.resource::<Assets<Mesh>>();
+    assert_eq!(pieces.len(), 2);
+    for entry in morph.iter().chain(pieces.iter().flatten()) {
+        let mesh = meshes.get(&entry.mesh).unwrap();
+        assert_eq!(mesh.primitive_topology(), PrimitiveTopology::TriangleList);
+    }
+}
+
+#[test]
+fn depth_fraction_runs_from_front_to_back() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "H", 40.0).remove(0);
+    let geometry = tessellate_with(
+        &mut font_system,
+        &glyph,
+        ExtrusionOptions {
+            depth_attribute: true,
+            ..ExtrusionOptions::new(4.0)
+        },
+    );
+    let depth = geometry.depth_fraction.as_ref().unwrap();
+    for (v, d) in geometry.vertices.iter().zip(depth) {
+        assert_eq!(*d, v.z / 4.0);
+    }
+    assert!(depth.contains(&0.0) && depth.contains(&1.0));
+}
+
+#[test]
+fn estimated_byte_size_counts_the_optional_attributes() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "H", 40.0).remove(0);
+    let plain = tessellate_with(&mut font_system, &glyph, ExtrusionOptions::new(4.0));
+    let with_depth = tessellate_with(
+        &mut font_system,
+        &glyph,
+        ExtrusionOptions {
+            depth_attribute: true,
+            ..ExtrusionOptions::new(4.0)
+        },
+    );
+    assert_eq!(
+        with_depth.estimated_byte_size(),
+        plain.estimated_byte_size() + plain.vertices.len() * 4
+    );
+    let with_surface = tessellate_with(
+        &mut font_system,
+        &glyph,
+        ExtrusionOptions {
+            surface_attribute: true,
+            ..ExtrusionOptions::new(4.0)
+        },
+    );
+    assert_eq!(
+        with_surface.estimated_byte_size(),
+        plain.estimated_byte_size() + plain.vertices.len() * 4
+    );
+}
+
+#[test]
+fn surface_ids_follow_the_faces() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let geometry = tessellate_with(
+        &mut font_system,
+        &glyph,
+        ExtrusionOptions {
+            front_inset: Some(FrontInset {
+                width: 2.0,
+                depth: 1.5,
+            }),
+            surface_attribute: true,
+            ..ExtrusionOptions::new(5.0)
+        },
+    );
+    let surface = geometry.surface.as_ref().unwrap();
+    for ((v, n), &id) in geometry.vertices.iter().zip(&geometry.normals).zip(surface) {
+        let expected = if *n == Vec3::Z {
+            TextSurface::Back
+        } else if *n == Vec3::NEG_Z && v.z == 1.5 {
+            TextSurface::Front
+        } else if *n == Vec3::NEG_Z || v.z == 1.5 {
+            TextSurface::Edge
+        } else if v.z == 5.0 {
+            TextSurface::Side
+        } else {
+            // Wall vertices on the front face belong to the inner or the outer walls
+            assert!(id == TextSurface::Edge as u32 as f32 || id == TextSurface::Side as u32 as f32);
+            continue;
+        };
+        assert_eq!(id, expected as u32 as f32);
+    }
+}
+
+#[test]
+fn fill_rule_decides_overlapping_contours() {
+    // Two overlapping squares winding the same way, as in many variable fonts
+    let mut builder = Path::builder();
+    for x in [0.0, 50.0] {
+        builder.begin(point(x, 0.0));
+        builder.line_to(point(x + 100.0, 0.0));
+        builder.line_to(point(x + 100.0, 100.0));
+        builder.line_to(point(x, 100.0));
+        builder.end(true);
+    }
+    let path = builder.build();
+
+    let area = |fill_rule| {
+        let options = ExtrusionOptions {
+            tessellation: TessellationOptions {
+                fill_rule,
+                ..default()
+            },
+            ..ExtrusionOptions::new(1.0)
+        };
+        let (geometry, ..) = extrude_path(&path, Vec2::ZERO, 100.0, 1.0, &options).unwrap();
+        front_area(&geometry)[0].1
+    };
+    assert!((area(FillRule::EvenOdd) - 10_000.0).abs() < 1.0);
+    assert!((area(FillRule::NonZero) - 15_000.0).abs() < 1.0);
+}
+
+#[test]
+fn ear_clipped_caps_match_the_sweep() {
+    let mut font_system = embedded_font_system();
+    let options = |cap_tessellator, front_inset| ExtrusionOptions {
+        tessellation: TessellationOptions {
+            cap_tessellator,
+            ..default()
+        },
+        front_inset,
+        ..ExtrusionOptions::new(5.0)
+    };
+    let inset = Some(FrontInset {
+        width: 2.0,
+        depth: 1.5,
+    });
+    for glyph in common::shape(&mut font_system, "AaBgQR&%8@?", 100.0) {
+        for front_inset in [None, inset] {
+            let sweep = tessellate_with(
+                &mut font_system,
+                &glyph,
+                options(CapTessellator::Sweep, front_inset),
+            );
+            let clipped = tessellate_with(
+                &mut font_system,
+                &glyph,
+                options(CapTessellator::EarClipping, front_inset),
+            );
+            assert!(
+                clipped.winding_matches_normals(),
+                "glyph {}",
+                glyph.glyph_id
+            );
+            assert!(open_edges(&clipped).is_empty(), "glyph {}", glyph.glyph_id);
+            let (sweep, clipped) = (front_area(&sweep), front_area(&clipped));
+            assert_eq!(sweep.len(), clipped.len());
+            for ((z, expected), (clipped_z, area)) in sweep.into_iter().zip(clipped) {
+                assert_eq!(z, clipped_z);
+                assert!(
+                    (area - expected).abs() / expected < 1e-3,
+                    "glyph {} at {z}: {area} instead of {expected}",
+                    glyph.glyph_id
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn pre_shaped_buffer_matches_generated_text() {
+    let mut world = common::world();
+    let params = common::params(14.0, 2.0);
+    let expected = common::generate(&mut world, "Hi there", params.clone()).unwrap();
+
+    let entries = world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                let mut buffer =
+                    Buffer::new_empty(Metrics::new(params.font_size, params.line_height));
+                buffer.set_text(
+                    &mut settings.font_system,
+                    "Hi there",
+                    &Attrs::new(),
+                    Shaping::Advanced,
+                );
+                buffer.shape_until_scroll(&mut settings.font_system, false);
+                let glyphs = TextGlyphs::from_buffer(buffer);
+                assert!(glyphs.buffer().layout_runs().count() > 0);
+
+                let materials = [Handle::<StandardMaterial>::default()];
+                generate_meshes_from_glyphs(
+                    &glyphs,
+                    &materials,
+                    &mut settings,
+                    &params,
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(entries.len(), expected.len());
+    for (entry, expected) in entries.iter().zip(&expected) {
+        assert_eq!(entry.mesh, expected.mesh);
+        assert_eq!(entry.transform, expected.transform);
+    }
+}
+
+#[test]
+fn pre_shaped_glyphs_reject_invalid_parameters() {
+    let mut world = common::world();
+    let result = world
+        .run_system_once(
+            |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                let params = Parameters {
+                    depth_segments: 0,
+                    ..common::params(14.0, 2.0)
+                };
+                let glyphs = TextGlyphs::new(
+                    Metrics::new(params.font_size, params.line_height),
+                    [("Hi", Attrs::new())],
+                    &Attrs::new(),
+                    &mut settings.font_system,
+                    None,
+                );
+                let materials = [Handle::<StandardMaterial>::default()];
+                generate_meshes_from_glyphs(
+                    &glyphs,
+                    &materials,
+                    &mut settings,
+                    &params,
+                    &mut meshes,
+                )
+                .map(|entries| entries.len())
+            },
+        )
+        .unwrap();
+    assert!(matches!(result, Err(MeshTextError::ZeroSegments("Depth"))));
+}
+
+/// Generates "AAA" with the middle glyph at twice the size.
+fn generate_mixed_sizes(world: &mut World) -> Vec<Transform> {
+    world
+        .run_system_once(
+            |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                let sizes = [14.0, 28.0, 14.0];
+                let text = InputText::Rich {
+                    words: vec!["A".to_string(); 3],
+                    materials: vec![Handle::<StandardMaterial>::default(); 3],
+                    attrs: sizes
+                        .iter()
+                        .map(|&size| Attrs::new().metrics(Metrics::new(size, size * 1.2)))
+                        .collect(),
+                };
+                bevy_mesh_text_3d::generate_meshes(
+                    text,
+                    &mut settings,
+                    common::params(14.0, 2.0),
+                    &mut meshes,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.transform)
+                .collect()
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn mixed_sizes_keep_their_own_glyph_centers() {
+    let mut cold = common::world();
+    let expected = generate_mixed_sizes(&mut cold);
+    assert_eq!(expected.len(), 3);
+
+    // Warm the cache with the large glyph only, then with the small one
+    let mut warm = common::world();
+    common::generate(&mut warm, "A", common::params(28.0, 2.0)).unwrap();
+    common::generate(&mut warm, "A", common::params(14.0, 2.0)).unwrap();
+    assert_eq!(
+        warm.resource::<Settings>().glyph_cache.len(),
+        1,
+        "sizes share one mesh"
+    );
+    assert_eq!(generate_mixed_sizes(&mut warm), expected);
+
+    // Cached placement matches the first, uncached one
+    assert_eq!(generate_mixed_sizes(&mut cold), expected);
+    assert!(expected[1].translation.y > expected[0].translation.y);
+}
+
+#[test]
+fn mixed_sizes_share_the_baseline() {
+    let mut world = common::world();
+    let bottoms = world
+        .run_system_once(
+            |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                // The bottom of "x" is flat on the baseline at any size
+                let sizes = [14.0, 28.0, 14.0, 40.0];
+                let text = InputText::Rich {
+                    words: vec!["x".to_string(); sizes.len()],
+                    materials: vec![Handle::<StandardMaterial>::default(); sizes.len()],
+                    attrs: sizes
+                        .iter()
+                        .map(|&size| Attrs::new().metrics(Metrics::new(size, size * 1.2)))
+                        .collect(),
+                };
+                let entries = bevy_mesh_text_3d::generate_meshes(
+                    text,
+                    &mut settings,
+                    common::params(14.0, 2.0),
+                    &mut meshes,
+                )
+                .unwrap();
+                entries
+                    .iter()
+                    .map(|entry| {
+                        let mesh = meshes.get(&entry.mesh).unwrap();
+                        let Some(VertexAttributeValues::Float32x3(positions)) =
+                            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+                        else {
+                            panic!("no positions");
+                        };
+                        positions
+                            .iter()
+                            .map(|&p| entry.transform.transform_point(p.into()).y)
+                            .fold(f32::MAX, f32::min)
+                    })
+                    .collect::<Vec<f32>>()
+            },
+        )
+        .unwrap();
+    assert_eq!(bottoms.len(), 4);
+    for bottom in &bottoms {
+        assert!((bottom - bottoms[0]).abs() < 1e-3, "{bottoms:?}");
+    }
+}
+
+#[test]
+fn coplanar_walls_are_merged() {
+    // A blocky "L" whose straight sides are split into many collinear segments
+    let corners = [
+        (0.0, 0.0),
+        (60.0, 0.0),
+        (60.0, 20.0),
+        (20.0, 20.0),
+        (20.0, 100.0),
+        (0.0, 100.0),
+    ];
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    for (i, &(x, y)) in corners.iter().enumerate() {
+        let (next_x, next_y) = corners[(i + 1) % corners.len()];
+        for step in 1..=10 {
+            let t = step as f32 / 10.0;
+            builder.line_to(point(x + (next_x - x) * t, y + (next_y - y) * t));
+        }
+    }
+    builder.end(true);
+    let path = builder.build();
+
+    let extrude = |merge_coplanar_walls| {
+        let options = ExtrusionOptions {
+            tessellation: TessellationOptions {
+                merge_coplanar_walls,
+                ..default()
+            },
+            ..ExtrusionOptions::new(1.0)
+        };
+        extrude_path(&path, Vec2::ZERO, 100.0, 1.0, &options)
+            .unwrap()
+            .0
+    };
+    let split = extrude(false);
+    let merged = extrude(true);
+
+    let walls = |geometry: &ExtrudedGlyphGeometry| {
+        geometry
+            .indices
+            .chunks_exact(3)
+            .filter(|tri| geometry.normals[tri[0] as usize].z == 0.0)
+            .count()
+    };
+    assert_eq!(walls(&merged), 2 * corners.len());
+    assert!(walls(&split) >= 10 * walls(&merged) - 2);
+    assert!(merged.indices.len() < split.indices.len() / 2);
+    assert!(merged.winding_matches_normals());
+    assert!((front_area(&merged)[0].1 - front_area(&split)[0].1).abs() < 1e-2);
+}
+
+#[test]
+fn inset_splits_where_the_outline_narrows() {
+    // An "H" whose bar is too thin for the inset, so the inset leaves two separate legs
+    let outline = [
+        (0.0, 0.0),
+        (20.0, 0.0),
+        (20.0, 45.0),
+        (40.0, 45.0),
+        (40.0, 0.0),
+        (60.0, 0.0),
+        (60.0, 100.0),
+        (40.0, 100.0),
+        (40.0, 55.0),
+        (20.0, 55.0),
+        (20.0, 100.0),
+        (0.0, 100.0),
+    ];
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    for &(x, y) in &outline[1..] {
+        builder.line_to(point(x, y));
+    }
+    builder.end(true);
+    let path = builder.build();
+
+    let options = ExtrusionOptions {
+        front_inset: Some(FrontInset {
+            width: 6.0,
+            depth: 0.5,
+        }),
+        ..ExtrusionOptions::new(1.0)
+    };
+    let (geometry, ..) = extrude_path(&path, Vec2::ZERO, 100.0, 1.0, &options).unwrap();
+    assert!(geometry.winding_matches_normals());
+
+    let areas = front_area(&geometry);
+    let area_at = |z: f32| areas.iter().find(|(at, _)| *at == z).unwrap().1;
+    // Two 8 x 88 legs, and the lip covers the rest of the 4200 unit outline
+    assert!((area_at(0.5) - 2.0 * 8.0 * 88.0).abs() < 1.0, "{areas:?}");
+    assert!((area_at(0.0) - (4200.0 - 1408.0)).abs() < 1.0, "{areas:?}");
+}
+
+#[test]
+fn zero_width_inset_matches_the_plain_extrusion() {
+    let mut font_system = embedded_font_system();
+    for glyph in common::shape(&mut font_system, "Bag", 40.0) {
+        let plain = tessellate_with(&mut font_system, &glyph, ExtrusionOptions::new(3.0));
+        let inset = tessellate_with(
+            &mut font_system,
+            &glyph,
+            ExtrusionOptions {
+                front_inset: Some(FrontInset {
+                    width: 0.0,
+                    depth: 1.0,
+                }),
+                ..ExtrusionOptions::new(3.0)
+            },
+        );
+        assert_eq!(plain.vertices, inset.vertices);
+        assert_eq!(plain.indices, inset.indices);
+
+        // Walls follow the deduplicated contours, without zero-length quads
+        assert!(plain.normals.iter().all(|normal| *normal != Vec3::ZERO));
+    }
+}
+
+#[test]
+fn repair_closes_broken_outlines() {
+    let mut builder = Path::builder();
+    // A square that stops just short of its start, without a close command
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.line_to(point(100.0, 100.0));
+    builder.line_to(point(0.0, 100.0));
+    builder.line_to(point(0.0, 0.05));
+    builder.end(false);
+    // A stray contour without an area
+    builder.begin(point(10.0, 10.0));
+    builder.line_to(point(20.0, 20.0));
+    builder.line_to(point(30.0, 30.0));
+    builder.end(true);
+    let path = builder.build();
+
+    let extrude = |repair_outlines| {
+        let options = ExtrusionOptions {
+            tessellation: TessellationOptions {
+                repair_outlines,
+                ..default()
+            },
+            ..ExtrusionOptions::new(1.0)
+        };
+        extrude_path(&path, Vec2::ZERO, 1000.0, 1.0, &options)
+            .unwrap()
+            .0
+    };
+    let walls = |geometry: &ExtrudedGlyphGeometry| {
+        geometry
+            .indices
+            .chunks_exact(3)
+            .filter(|tri| geometry.normals[tri[0] as usize].z == 0.0)
+            .count()
+    };
+
+    let broken = extrude(false);
+    let repaired = extrude(true);
+    assert_ne!(walls(&broken), 8);
+    assert_eq!(walls(&repaired), 8, "one quad per side of the square");
+    assert!(repaired.winding_matches_normals());
+    assert!((front_area(&repaired)[0].1 - 10_000.0).abs() < 1.0);
+}
+
+#[test]
+fn scale_per_axis_overrides_the_plugin_scale() {
+    let mut world = common::world();
+    let uniform = common::generate(&mut world, "ab\ncd", common::params(14.0, 2.0)).unwrap();
+
+    let squashed = Vec3::new(2.0, 3.0, 0.25);
+    let params = Parameters {
+        scale: Some(squashed),
+        ..common::params(14.0, 2.0)
+    };
+    let scaled = common::generate(&mut world, "ab\ncd", params).unwrap();
+
+    assert_eq!(scaled.len(), uniform.len());
+    for (scaled, uniform) in scaled.iter().zip(&uniform) {
+        // The meshes are shared; only the placement changes
+        assert_eq!(scaled.mesh, uniform.mesh);
+        assert_eq!(scaled.transform.scale, squashed * MESH_SCALE_14);
+        assert_eq!(
+            scaled.transform.translation,
+            uniform.transform.translation * squashed
+        );
+    }
+}
+
+#[test]
+fn texts_use_their_own_scale() {
+    let mut world = common::world();
+    world.resource_mut::<Settings>().text_scale_factor = 2.0;
+    let plugin_scale = common::generate(&mut world, "a", common::params(14.0, 2.0)).unwrap();
+    let own_scale =
+        common::generate(&mut world, "a", common::params(14.0, 2.0).with_scale(0.5)).unwrap();
+
+    assert_eq!(
+        plugin_scale[0].transform.scale,
+        Vec3::splat(2.0) * MESH_SCALE_14
+    );
+    assert_eq!(
+        own_scale[0].transform.scale,
+        Vec3::splat(0.5) * MESH_SCALE_14
+    );
+    assert_eq!(
+        own_scale[0].transform.translation * 4.0,
+        plugin_scale[0].transform.translation
+    );
+}
+
+#[test]
+fn font_contexts_keep_their_own_glyph_cache() {
+    let mut world = common::world();
+    let mut contexts = FontContexts::default();
+    contexts.insert("decorative", Settings::new(embedded_font_system(), 3.0));
+    world.insert_resource(contexts);
+
+    let entries = world
+        .run_system_once(
+            |mut contexts: ResMut<FontContexts>, mut meshes: ResMut<Assets<Mesh>>| {
+                let fonts = contexts.get_mut("decorative").unwrap();
+                bevy_mesh_text_3d::generate_meshes(
+                    InputText::Simple {
+                        text: "ab".to_string(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    fonts,
+                    common::params(14.0, 2.0),
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].transform.scale, Vec3::splat(3.0) * MESH_SCALE_14);
+    let contexts = world.resource::<FontContexts>();
+    assert_eq!(
+        contexts
+            .get("decorative")
+            .unwrap()
+            .glyph_cache
+            .stats()
+            .mesh_count,
+        2
+    );
+    assert_eq!(
+        world.resource::<Settings>().glyph_cache.stats().mesh_count,
+        0
+    );
+}
+
+#[test]
+fn shared_meshes_match_size_specific_ones() {
+    let mut world = common::world();
+    let params = |size_specific| Parameters {
+        front_inset: Some(FrontInset {
+            width: 1.0,
+            depth: 0.5,
+        }),
+        tessellation: TessellationOptions {
+            size_specific,
+            ..default()
+        },
+        ..common::params(28.0, 2.0)
+    };
+    let shared = common::generate(&mut world, "e", params(false)).unwrap();
+    let baked = common::generate(&mut world, "e", params(true)).unwrap();
+    assert_ne!(shared[0].mesh, baked[0].mesh);
+    assert_eq!(baked[0].transform.scale, Vec3::ONE);
+    assert!(
+        shared[0]
+            .transform
+            .translation
+            .abs_diff_eq(baked[0].transform.translation, 1e-3)
+    );
+
+    // The shared mesh, placed with its transform, is the mesh baked at the size
+    let meshes = world.resource::<Assets<Mesh>>();
+    let positions = |entry: &MeshTextEntry<StandardMaterial>| -> Vec<Vec3> {
+        let mesh = meshes.get(&entry.mesh).unwrap();
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("no positions");
+        };
+        positions
+            .iter()
+            .map(|&p| entry.transform.transform_point(p.into()))
+            .collect()
+    };
+    let (shared, baked) = (positions(&shared[0]), positions(&baked[0]));
+    assert_eq!(shared.len(), baked.len());
+    for shared in &shared {
+        assert!(
+            baked.iter().any(|baked| shared.abs_diff_eq(*baked, 1e-3)),
+            "{shared}"
+        );
+    }
+}
+
+#[test]
+fn glyphs_share_meshes_across_materials() {
+    let mut world = common::world();
+    world.init_resource::<Assets<StandardMaterial>>();
+    let entries = world
+        .run_system_once(
+            |mut settings: ResMut<Settings>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<StandardMaterial>>| {
+                let sizes = [14.0, 14.0, 28.0];
+                let text = InputText::Rich {
+                    words: vec!["ab".to_string(); 3],
+                    materials: (0..3)
+                        .map(|_| materials.add(StandardMaterial::default()))
+                        .collect(),
+                    attrs: sizes
+                        .iter()
+                        .map(|&size| Attrs::new().metrics(Metrics::new(size, size * 1.2)))
+                        .collect(),
+                };
+                bevy_mesh_text_3d::generate_meshes(
+                    text,
+                    &mut settings,
+                    common::params(14.0, 2.0),
+                    &mut meshes,
+                )
+                .unwrap()
+            },
+        )
+        .unwrap();
+    assert_eq!(entries.len(), 6);
+    assert_ne!(entries[0].material, entries[2].material);
+    assert_eq!(entries[0].mesh, entries[2].mesh);
+    assert_eq!(entries[0].mesh, entries[4].mesh, "sizes share meshes too");
+
+    let report = world
+        .resource::<Settings>()
+        .glyph_cache
+        .sharing_report(entries.iter().map(|entry| &entry.mesh));
+    assert_eq!(
+        report,
+        MeshSharingReport {
+            glyphs: 6,
+            unique_meshes: 2,
+            cached_meshes: 2,
+        }
+    );
+    assert_eq!(report.glyphs_per_mesh(), 3.0);
+}
+
+#[test]
+fn text_block_uvs_flow_across_the_glyphs() {
+    let mut world = common::world();
+    let params = Parameters {
+        uv_mode: UvMode::TextBlock,
+        ..common::params(14.0, 2.0)
+    };
+    let entries = common::generate(&mut world, "ab\nab", params).unwrap();
+    assert_eq!(entries.len(), 4);
+    assert!(
+        world.resource::<Settings>().glyph_cache.is_empty(),
+        "placed glyphs are not shared"
+    );
+    assert_ne!(entries[0].mesh, entries[2].mesh);
+
+    // Range of the cap UVs of each glyph
+    let meshes = world.resource::<Assets<Mesh>>();
+    let ranges: Vec<(Vec2, Vec2)> = entries
+        .iter()
+        .map(|entry| {
+            let mesh = meshes.get(&entry.mesh).unwrap();
+            let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0)
+            else {
+                panic!("missing UVs");
+            };
+            let Some(VertexAttributeValues::Float32x3(normals)) =
+                mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+            else {
+                panic!("missing normals");
+            };
+            uvs.iter()
+                .zip(normals)
+                .filter(|(_, normal)| normal[2] != 0.0)
+                .map(|(uv, _)| Vec2::from(*uv))
+                .fold((Vec2::MAX, Vec2::MIN), |(min, max), uv| {
+                    (min.min(uv), max.max(uv))
+                })
+        })
+        .collect();
+
+    for (min, max) in &ranges {
+        assert!(min.cmpge(Vec2::ZERO).all() && max.cmple(Vec2::ONE).all());
+    }
+    // U continues from one glyph to the next, V starts over on every line
+    assert!(ranges[0].1.x <= ranges[1].0.x + 0.05);
+    assert!(ranges[1].1.x > 0.8);
+    for (first, second) in ranges[..2].iter().zip(&ranges[2..]) {
+        assert!(first.0.abs_diff_eq(second.0, 1e-4));
+        assert!(first.1.abs_diff_eq(second.1, 1e-4));
+    }
+}
+
+/// UVs of the walls of "o" generated with `params`, once with text block UVs and once with
+/// UVs per glyph.
+fn wall_uvs_by_uv_mode(params: Parameters) -> [Vec<Vec2>; 2] {
+    [UvMode::TextBlock, UvMode::Em].map(|uv_mode| {
+        let mut world = common::world();
+        let params = Parameters {
+            uv_mode,
+            surface_attribute: true,
+            ..params.clone()
+        };
+        let entries = common::generate(&mut world, "o", params).unwrap();
+        let meshes = world.resource::<Assets<Mesh>>();
+        let mesh = meshes.get(&entries[0].mesh).unwrap();
+        let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("missing UVs");
+        };
+        let Some(VertexAttributeValues::Float32(surface)) = mesh.attribute(ATTRIBUTE_SURFACE)
+        else {
+            panic!("missing surface");
+        };
+        uvs.iter()
+            .zip(surface)
+            .filter(|&(_, &id)| id == TextSurface::Side as u32 as f32)
+            .map(|(uv, _)| Vec2::from(*uv))
+            .collect()
+    })
+}
+
+#[test]
+fn text_block_uvs_leave_the_rounded_back_alone() {
+    let [block, em] = wall_uvs_by_uv_mode(Parameters {
+        rounded_back: Some(RoundedBack {
+            radius: 1.0,
+            depth: 2.0,
+            segments: 3,
+            profile_power: 1.0,
+        }),
+        ..common::params(14.0, 4.0)
+    });
+    assert!(!block.is_empty());
+    assert_eq!(block, em);
+}
+
+#[test]
+fn text_block_uvs_leave_noisy_walls_alone() {
+    let [block, em] = wall_uvs_by_uv_mode(Parameters {
+        surface_noise: Some(SurfaceNoise {
+            seed: 7,
+            frequency: 0.5,
+            amplitude: 0.3,
+            axis_mask: [1.0; 3],
+        }),
+        ..common::params(14.0, 4.0)
+    });
+    assert!(!block.is_empty());
+    assert_eq!(block, em);
+}
+
+#[test]
+fn lightmap_uvs_pack_without_overlap() {
+    let mut font_system = embedded_font_system();
+    for glyph in common::shape(&mut font_system, "OE", 100.0) {
+        let geometry = tessellate_with(
+            &mut font_system,
+            &glyph,
+            ExtrusionOptions {
+                lightmap_uvs: true,
+                front_inset: Some(FrontInset {
+                    width: 2.0,
+                    depth: 1.0,
+                }),
+                ..ExtrusionOptions::new(5.0)
+            },
+        );
+        let uv1 = geometry.uv1.as_ref().unwrap();
+        assert_eq!(uv1.len(), geometry.vertices.len());
+        assert!(
+            uv1.iter()
+                .all(|uv| uv.cmpge(Vec2::ZERO).all() && uv.cmple(Vec2::ONE).all())
+        );
+
+        let triangles: Vec<([Vec2; 3], f32)> = geometry
+            .indices
+            .chunks_exact(3)
+            .map(|tri| {
+                let uv = [0, 1, 2].map(|k| uv1[tri[k] as usize]);
+                let [a, b, c] = [0, 1, 2].map(|k| geometry.vertices[tri[k] as usize]);
+                (uv, (b - a).cross(c - a).length() / 2.0)
+            })
+            .filter(|(_, area)| *area > 1e-3)
+            .collect();
+
+        // Every surface gets the same texel density
+        let uv_area = |[a, b, c]: [Vec2; 3]| (b - a).perp_dot(c - a).abs() / 2.0;
+        let density = uv_area(triangles[0].0) / triangles[0].1;
+        for (uv, area) in &triangles {
+            assert!((uv_area(*uv) / area / density - 1.0).abs() < 0.01);
+        }
+
+        // No point of the lightmap is covered twice
+        let inside = |p: Vec2, [a, b, c]: [Vec2; 3]| {
+            let d = [
+                (b - a).perp_dot(p - a),
+                (c - b).perp_dot(p - b),
+                (a - c).perp_dot(p - c),
+            ];
+            d.iter().all(|&d| d > 1e-6) || d.iter().all(|&d| d < -1e-6)
+        };
+        for i in 0..64 {
+            for j in 0..64 {
+                let p = Vec2::new(i as f32 + 0.5, j as f32 + 0.5) / 64.0;
+                let covered = triangles.iter().filter(|(uv, _)| inside(p, *uv)).count();
+                assert!(covered <= 1, "{p} is covered {covered} times");
+            }
+        }
+    }
+}
+
+#[test]
+fn wall_smoothing_keeps_corners_hard() {
+    let mut font_system = embedded_font_system();
+    let glyphs = common::shape(&mut font_system, "OE", 100.0);
+    let smoothed = |font_system: &mut FontSystem, glyph| {
+        tessellate_with(
+            font_system,
+            glyph,
+            ExtrusionOptions {
+                wall_smoothing_angle: Some(30f32.to_radians()),
+                ..ExtrusionOptions::new(5.0)
+            },
+        )
+    };
+
+    // Around the curves of the O, walls meeting at a vertex share one normal
+    let round = smoothed(&mut font_system, &glyphs[0]);
+    let walls: Vec<(Vec3, Vec3)> = round
+        .vertices
+        .iter()
+        .zip(&round.normals)
+        .filter(|(_, normal)| normal.z == 0.0)
+        .map(|(&vertex, &normal)| (vertex, normal))
+        .collect();
+    let mut shared = 0;
+    for (i, (a, normal_a)) in walls.iter().enumerate() {
+        for (b, normal_b) in &walls[i + 1..] {
+            if a.distance(*b) < 1e-4 {
+                assert!(normal_a.abs_diff_eq(*normal_b, 1e-4));
+                shared += 1;
+            }
+        }
+    }
+    assert!(shared > 0);
+    assert!(round.winding_matches_normals());
+
+    // The square corners of the E stay hard
+    let (flat, ..) = common::tessellate(&mut font_system, &glyphs[1], 5.0).unwrap();
+    assert_eq!(smoothed(&mut font_system, &glyphs[1]).normals, flat.normals);
+}
+
+#[test]
+fn contours_are_oriented_whatever_the_font_convention() {
+    // A clockwise square with a hole wound the same way, as some broken fonts have it
+    let square = |builder: &mut lyon::path::Builder, min: f32, max: f32| {
+        builder.begin(point(min, min));
+        builder.line_to(point(min, max));
+        builder.line_to(point(max, max));
+        builder.line_to(point(max, min));
+        builder.end(true);
+    };
+    let mut builder = Path::builder();
+    square(&mut builder, 0.0, 100.0);
+    square(&mut builder, 40.0, 60.0);
+    let path = builder.build();
+
+    let contours = normalize_orientation(contours_from_path(&path), FillRule::EvenOdd);
+    assert!(
+        contours[0].signed_area() > 0.0,
+        "outer contours wind counter-clockwise"
+    );
+    assert!(contours[1].signed_area() < 0.0, "holes wind clockwise");
+
+    let (geometry, ..) = extrude_path(
+        &path,
+        Vec2::splat(50.0),
+        1000.0,
+        1.0,
+        &ExtrusionOptions::new(1.0),
+    )
+    .unwrap();
+    assert!(geometry.winding_matches_normals());
+    for (vertex, normal) in geometry.vertices.iter().zip(&geometry.normals) {
+        if normal.z != 0.0 {
+            continue;
+        }
+        // Outer walls face away from the center, the walls of the hole face into it
+        let outward = normal.dot(vertex.with_z(0.0)) > 0.0;
+        assert_eq!(outward, vertex.x.abs().max(vertex.y.abs()) > 25.0);
+    }
+
+    // Filling by winding keeps the relative orientation of the contours
+    let contours = normalize_orientation(contours_from_path(&path), FillRule::NonZero);
+    assert!(contours.iter().all(|contour| contour.signed_area() > 0.0));
+}
+
+#[test]
+fn prewarmed_glyphs_come_from_the_cache() {
+    let mut world = common::world();
+    let warmed = world
+        .run_system_once(
+            |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                bevy_mesh_text_3d::prewarm_glyphs(
+                    "0123456789".chars(),
+                    &Attrs::new(),
+                    &mut settings,
+                    &common::params(14.0, 2.0),
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(warmed, 10);
+    let meshes = world.resource::<Assets<Mesh>>().len();
+
+    let entries = common::generate(&mut world, "2024", common::params(14.0, 2.0)).unwrap();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(entries[0].mesh, entries[2].mesh);
+    assert_eq!(world.resource::<Settings>().glyph_cache.len(), 10);
+    assert_eq!(
+        world.resource::<Assets<Mesh>>().len(),
+        meshes,
+        "no glyph is tessellated again"
+    );
+}
+
+#[test]
+fn prewarming_rejects_invalid_parameters() {
+    let mut world = common::world();
+    let warmed = world
+        .run_system_once(
+            |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                bevy_mesh_text_3d::prewarm_glyphs(
+                    "0123456789".chars(),
+                    &Attrs::new(),
+                    &mut settings,
+                    &Parameters {
+                        extrusion_depth: -1.0,
+                        ..common::params(14.0, 2.0)
+                    },
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap();
+    assert!(matches!(
+        warmed,
+        Err(MeshTextError::NegativeExtrusionDepth(-1.0))
+    ));
+    assert_eq!(world.resource::<Settings>().glyph_cache.len(), 0);
+}
+
+/// Directed edges of `geometry` that no triangle runs along the other way, i.e. the rim of
+/// a gap in the surface.
+fn open_edges(geometry: &ExtrudedGlyphGeometry) -> Vec<(Vec3, Vec3)> {
+    let key = |p: Vec3| (p * 1024.0).round().as_ivec3().to_array();
+    let edges: Vec<(Vec3, Vec3)> = geometry
+        .indices
+        .chunks_exact(3)
+        .flat_map(|tri| {
+            let [a, b, c] = [0, 1, 2].map(|k| geometry.vertices[tri[k] as usize]);
+            [(a, b), (b, c), (c, a)]
+        })
+        .collect();
+    let directed: std::collections::HashSet<_> =
+        edges.iter().map(|(a, b)| (key(*a), key(*b))).collect();
+    edges
+        .into_iter()
+        .filter(|(a, b)| !directed.contains(&(key(*b), key(*a))))
+        .collect()
+}
+
+#[test]
+fn front_inset_leaves_no_gaps() {
+    let mut font_system = embedded_font_system();
+    // Wide enough for the inset to collapse in the thin parts of some glyphs
+    for width in [2.0, 6.0] {
+        for glyph in common::shape(&mut font_system, "OBe8&", 100.0) {
+            let geometry = tessellate_with(
+                &mut font_system,
+                &glyph,
+                ExtrusionOptions {
+                    front_inset: Some(FrontInset { width, depth: 1.5 }),
+                    ..ExtrusionOptions::new(5.0)
+                },
+            );
+            let open = open_edges(&geometry);
+            assert!(
+                open.is_empty(),
+                "glyph {} with an inset of {width} has {} open edges",
+                glyph.glyph_id,
+                open.len()
+            );
+        }
+    }
+}
+
+#[test]
+fn bevel_on_selected_edges_leaves_no_gaps() {
+    let mut font_system = embedded_font_system();
+    let options = |width, bevel_edges| ExtrusionOptions {
+        front_inset: Some(FrontInset { width, depth: 1.5 }),
+        bevel_edges,
+        ..ExtrusionOptions::new(5.0)
+    };
+    for width in [2.0, 6.0] {
+        for glyph in common::shape(&mut font_system, "OBe8&", 100.0) {
+            for bevel_edges in [BevelEdges::Outer, BevelEdges::Holes] {
+                let geometry =
+                    tessellate_with(&mut font_system, &glyph, options(width, bevel_edges));
+                let open = open_edges(&geometry);
+                assert!(
+                    open.is_empty(),
+                    "glyph {} with an inset of {width} on {bevel_edges:?} has {} open edges",
+                    glyph.glyph_id,
+                    open.len()
+                );
+            }
+        }
+    }
+
+    // The lips along the outer contour and along the hole of an O make up the lip all around
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let mut lip = |bevel_edges| {
+        front_area(&tessellate_with(
+            &mut font_system,
+            &glyph,
+            options(2.0, bevel_edges),
+        ))[0]
+    };
+    let (all, outer, holes) = (
+        lip(BevelEdges::All),
+        lip(BevelEdges::Outer),
+        lip(BevelEdges::Holes),
+    );
+    assert_eq!(all.0, 0.0);
+    assert!(outer.1 < all.1 && holes.1 < all.1);
+    assert!((outer.1 + holes.1 - all.1).abs() / all.1 < 0.01);
+}
+
+#[test]
+fn walls_split_by_the_bevel_stay_flat() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let geometry = tessellate_with(
+        &mut font_system,
+        &glyph,
+        ExtrusionOptions {
+            front_inset: Some(FrontInset {
+                width: 2.0,
+                depth: 1.5,
+            }),
+            bevel_edges: BevelEdges::Outer,
+            wall_smoothing_angle: Some(30f32.to_radians()),
+            ..ExtrusionOptions::new(5.0)
+        },
+    );
+
+    let (min, max) = geometry.vertices.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), &v| (min.min(v), max.max(v)),
+    );
+    let center = (min + max) / 2.0;
+    // Walls of the hole face the center, and are split where the recessed cap meets them. The
+    // inner walls of the lip face the center too, but don't reach behind the recessed cap.
+    let (mut hole, mut smoothed) = (0, 0);
+    for tri in geometry.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| tri[k] as usize);
+        let normals = [a, b, c].map(|i| geometry.normals[i]);
+        if normals.iter().any(|normal| normal.z != 0.0) {
+            continue;
+        }
+        let [pa, pb, pc] = [a, b, c].map(|i| geometry.vertices[i]);
+        let face = (pb - pa).cross(pc - pa);
+        let flat = normals.iter().all(|&normal| normal == normals[0]);
+        if face.dot(pa - center) > 0.0 {
+            smoothed += usize::from(!flat);
+        } else if [pa, pb, pc].iter().any(|p| p.z > 1.5 + 1e-4) {
+            hole += 1;
+            assert!(flat, "hole wall normals {normals:?} are smoothed");
+        }
+    }
+    assert!(hole > 0);
+    // The outer walls are still smoothed
+    assert!(smoothed > 0);
+    assert!(geometry.winding_matches_normals());
+}
+
+#[test]
+fn holes_take_their_own_bevel_width() {
+    let mut font_system = embedded_font_system();
+    let options = |width, bevel_edges, hole_bevel_width| ExtrusionOptions {
+        front_inset: Some(FrontInset { width, depth: 1.5 }),
+        bevel_edges,
+        hole_bevel_width,
+        ..ExtrusionOptions::new(5.0)
+    };
+    for glyph in common::shape(&mut font_system, "OBe8&", 100.0) {
+        for hole_width in [0.0, 1.0] {
+            let hole_width = Some(BevelWidth::WorldUnits(hole_width));
+            let geometry = tessellate_with(
+                &mut font_system,
+                &glyph,
+                options(6.0, BevelEdges::All, hole_width),
+            );
+            assert!(open_edges(&geometry).is_empty(), "glyph {}", glyph.glyph_id);
+        }
+    }
+
+    // A narrow lip in the hole of an O and the usual one around it
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let mut lip = |width, bevel_edges, hole_bevel_width| {
+        front_area(&tessellate_with(
+            &mut font_system,
+            &glyph,
+            options(width, bevel_edges, hole_bevel_width),
+        ))[0]
+            .1
+    };
+    let narrow_holes = lip(3.0, BevelEdges::All, Some(BevelWidth::WorldUnits(1.0)));
+    let outer = lip(3.0, BevelEdges::Outer, None);
+    let holes = lip(1.0, BevelEdges::Holes, None);
+    assert!((outer + holes - narrow_holes).abs() / narrow_holes < 0.01);
+    // Without a width of its own a hole takes the lip width
+    assert_eq!(
+        lip(3.0, BevelEdges::All, None),
+        lip(3.0, BevelEdges::All, Some(BevelWidth::WorldUnits(3.0)))
+    );
+}
+
+#[test]
+fn rounded_back_closes_in_on_a_smaller_back_face() {
+    let mut font_system = embedded_font_system();
+    for glyph in common::shape(&mut font_system, "OBe", 100.0) {
+        let flat = tessellate_with(&mut font_system, &glyph, ExtrusionOptions::new(6.0));
+        let rounded = tessellate_with(
+            &mut font_system,
+            &glyph,
+            ExtrusionOptions {
+                rounded_back: Some(RoundedBack {
+                    radius: 3.0,
+                    depth: 3.0,
+                    segments: 4,
+                    profile_power: 1.0,
+                }),
+                ..ExtrusionOptions::new(6.0)
+            },
+        );
+        assert!(rounded.winding_matches_normals());
+        assert!(open_edges(&rounded).is_empty(), "glyph {}", glyph.glyph_id);
+
+        // The back face shrinks, less so on the thin tail of the "e", and the front face stays
+        let width = |geometry: &ExtrudedGlyphGeometry, normal: Vec3| {
+            let xs = geometry
+                .vertices
+                .iter()
+                .zip(&geometry.normals)
+                .filter(|(_, n)| **n == normal)
+                .map(|(v, _)| v.x);
+            let (min, max) = xs.fold((f32::MAX, f32::MIN), |(min, max), x| {
+                (min.min(x), max.max(x))
+            });
+            max - min
+        };
+        assert_eq!(width(&rounded, Vec3::NEG_Z), width(&flat, Vec3::NEG_Z));
+        let shrunk = width(&flat, Vec3::Z) - width(&rounded, Vec3::Z);
+        assert!(shrunk > 0.0 && shrunk <= 6.0 + 1e-3, "{shrunk}");
+        let depth = rounded
+            .vertices
+            .iter()
+            .fold(0.0f32, |depth, v| depth.max(v.z));
+        assert!((depth - 6.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn profile_power_clusters_rings_near_the_walls() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let mut ring_depths = |profile_power| {
+        let geometry = tessellate_with(
+            &mut font_system,
+            &glyph,
+            ExtrusionOptions {
+                rounded_back: Some(RoundedBack {
+                    radius: 3.0,
+                    depth: 3.0,
+                    segments: 4,
+                    profile_power,
+                }),
+                ..ExtrusionOptions::new(6.0)
+            },
+        );
+        assert!(geometry.winding_matches_normals());
+        assert!(open_edges(&geometry).is_empty());
+        let mut depths: Vec<f32> = geometry
+            .vertices
+            .iter()
+            .map(|v| v.z)
+            .filter(|&z| z >= 3.0 - 1e-4)
+            .collect();
+        depths.sort_by(f32::total_cmp);
+        depths.dedup_by(|a, b| (*a - *b).abs() < 1e-4);
+        depths
+    };
+
+    let even = ring_depths(1.0);
+    let clustered = ring_depths(3.0);
+    // The walls, three rings between them and the back face, and the back face
+    assert_eq!(even.len(), 5);
+    assert_eq!(clustered.len(), 5);
+    assert_eq!(even[0], clustered[0]);
+    assert_eq!(even[4], clustered[4]);
+    for ring in 1..4 {
+        assert!(clustered[ring] < even[ring], "ring {ring}");
+    }
+}
+
+#[test]
+fn rounded_back_rings_share_their_vertices() {
+    let mut font_system = embedded_font_system();
+    for glyph in common::shape(&mut font_system, "OBe", 100.0) {
+        let geometry = tessellate_with(
+            &mut font_system,
+            &glyph,
+            ExtrusionOptions {
+                rounded_back: Some(RoundedBack {
+                    radius: 3.0,
+                    depth: 3.0,
+                    segments: 4,
+                    profile_power: 1.0,
+                }),
+                wall_smoothing_angle: Some(0.5),
+                ..ExtrusionOptions::new(6.0)
+            },
+        );
+        assert!(open_edges(&geometry).is_empty(), "glyph {}", glyph.glyph_id);
+
+        // Between the walls and the back face every vertex is one of the rings', each only
+        // once for the quads around it
+        let key = |i: usize| {
+            [
+                geometry.vertices[i],
+                geometry.normals[i],
+                geometry.uvs[i].extend(0.0),
+            ]
+            .map(|v| v.to_array().map(f32::to_bits))
+        };
+        let rounding: Vec<usize> = (0..geometry.vertices.len())
+            .filter(|&i| geometry.vertices[i].z > 3.0 + 1e-4 && geometry.vertices[i].z < 6.0 - 1e-4)
+            .collect();
+        let unique: std::collections::HashSet<_> = rounding.iter().map(|&i| key(i)).collect();
+        assert!(!rounding.is_empty());
+        assert_eq!(unique.len(), rounding.len(), "glyph {}", glyph.glyph_id);
+    }
+}
+
+#[test]
+fn inflated_caps_bulge_from_their_edges() {
+    let mut font_system = embedded_font_system();
+    for glyph in common::shape(&mut font_system, "Oi", 50.0) {
+        let flat = tessellate_with(&mut font_system, &glyph, ExtrusionOptions::new(6.0));
+        let inflated = tessellate_with(
+            &mut font_system,
+            &glyph,
+            ExtrusionOptions {
+                inflate: Some(InflateParameters {
+                    amount: 3.0,
+                    falloff: 4.0,
+                }),
+                ..ExtrusionOptions::new(6.0)
+            },
+        );
+        assert!(inflated.winding_matches_normals());
+
+        let (front, back) = inflated
+            .vertices
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), v| {
+                (min.min(v.z), max.max(v.z))
+            });
+        assert!((front + 3.0).abs() < 0.1, "{front}");
+        assert!((back - 9.0).abs() < 0.1, "{back}");
+
+        // The edges of the caps stay on the walls
+        let key = |v: &Vec3| (*v * 1024.0).round().as_ivec3().to_array();
+        let kept: std::collections::HashSet<_> = inflated.vertices.iter().map(key).collect();
+        let edges = flat
+            .vertices
+            .iter()
+            .zip(&flat.normals)
+            .filter(|(_, n)| n.z != 0.0);
+        for (v, _) in edges {
+            assert!(kept.contains(&key(v)), "{v} moved");
+        }
+    }
+}
+
+#[test]
+fn surface_noise_roughens_the_walls_only() {
+    let mut font_system = embedded_font_system();
+    let glyph = &common::shape(&mut font_system, "O", 50.0)[0];
+    let noisy = |font_system: &mut FontSystem, seed, amplitude| {
+        tessellate_with(
+            font_system,
+            glyph,
+            ExtrusionOptions {
+                surface_noise: Some(SurfaceNoise {
+                    seed,
+                    frequency: 0.5,
+                    amplitude,
+                    axis_mask: [1.0, 1.0, 0.0],
+                }),
+                ..ExtrusionOptions::new(5.0)
+            },
+        )
+    };
+    let flat = tessellate_with(&mut font_system, glyph, ExtrusionOptions::new(5.0));
+    let rough = noisy(&mut font_system, 7, 0.5);
+    assert!(rough.winding_matches_normals());
+    assert_eq!(noisy(&mut font_system, 7, 0.5).vertices, rough.vertices);
+    assert_ne!(noisy(&mut font_system, 8, 0.5).vertices, rough.vertices);
+
+    // The walls move within the plane of the text, while the caps and their edges stay
+    let still = noisy(&mut font_system, 7, 1e-6);
+    assert_eq!(still.vertices.len(), rough.vertices.len());
+    let moved = rough
+        .vertices
+        .iter()
+        .zip(&still.vertices)
+        .map(|(rough, still)| {
+            assert_eq!(rough.z, still.z);
+            rough.distance(*still)
+        })
+        .fold(0.0, f32::max);
+    assert!(moved > 0.05 && moved <= 0.5 * 2f32.sqrt(), "{moved}");
+
+    let key = |v: &Vec3| (*v * 1024.0).round().as_ivec3().to_array();
+    let kept: std::collections::HashSet<_> = rough.vertices.iter().map(key).collect();
+    let caps = flat
+        .vertices
+        .iter()
+        .zip(&flat.normals)
+        .filter(|(_, n)| n.z != 0.0);
+    for (v, _) in caps {
+        assert!(kept.contains(&key(v)), "{v} moved");
+    }
+}
+
+#[test]
+fn wall_uvs_wrap_once_around_each_contour() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    for wall_seam in [WallSeam::ContourStart, WallSeam::Lowest] {
+        let geometry = tessellate_with(
+            &mut font_system,
+            &glyph,
+            ExtrusionOptions {
+                wall_seam,
+                ..ExtrusionOptions::new(5.0)
+            },
+        );
+        // The walls come last, one quad per edge from the front to the back
+        let walls: Vec<_> = geometry
+            .normals
+            .iter()
+            .position(|n| n.z == 0.0)
+            .map(|start| (start..geometry.vertices.len()).step_by(4).collect())
+            .unwrap();
+        let u = |i: usize| geometry.uvs[i].x;
+        for &quad in &walls {
+            assert!(u(quad) < u(quad + 1));
+            assert_eq!((u(quad), u(quad + 1)), (u(quad + 2), u(quad + 3)));
+        }
+        // An "O" has two contours, each wrapped once
+        let wrapped: f32 = walls.iter().map(|&quad| u(quad + 1) - u(quad)).sum();
+        assert!((wrapped - 2.0).abs() < 1e-3, "{wrapped}");
+
+        if wall_seam == WallSeam::Lowest {
+            let lowest = walls
+                .iter()
+                .min_by(|&&a, &&b| geometry.vertices[a].y.total_cmp(&geometry.vertices[b].y))
+                .unwrap();
+            assert_eq!(u(*lowest), 0.0);
+        }
+    }
+}
+
+#[test]
+fn wall_uvs_span_the_depth_or_follow_the_scale() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    for wall_uv_scale in [None, Some(0.5)] {
+        let geometry = tessellate_with(
+            &mut font_system,
+            &glyph,
+            ExtrusionOptions {
+                wall_uv_scale,
+                ..ExtrusionOptions::new(5.0)
+            },
+        );
+        let start = geometry.normals.iter().position(|n| n.z == 0.0).unwrap();
+        for quad in (start..geometry.vertices.len()).step_by(4) {
+            let uv = |i: usize| geometry.uvs[quad + i];
+            let v = [0, 1, 2, 3].map(|i| uv(i).y);
+            match wall_uv_scale {
+                None => assert_eq!(v, [0.0, 0.0, 1.0, 1.0]),
+                Some(scale) => {
+                    assert_eq!(v, [0.0, 0.0, 5.0 * scale, 5.0 * scale]);
+                    // Square texels: U advances by the width of the wall times the scale
+                    let width = geometry.vertices[quad].distance(geometry.vertices[quad + 1]);
+                    assert!((uv(1).x - uv(0).x - width * scale).abs() < 1e-3);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn open_contours_follow_their_policy() {
+    // A stroke shaped like a "U", as drawn by some decorative fonts
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 100.0));
+    builder.line_to(point(0.0, 0.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.line_to(point(100.0, 100.0));
+    builder.end(false);
+    let path = builder.build();
+
+    let extrude = |open_contours| {
+        let options = ExtrusionOptions {
+            tessellation: TessellationOptions {
+                open_contours,
+                ..default()
+            },
+            ..ExtrusionOptions::new(1.0)
+        };
+        extrude_path(&path, Vec2::ZERO, 1000.0, 1.0, &options)
+            .unwrap()
+            .0
+    };
+
+    let closed = extrude(OpenContours::Close);
+    assert!(open_edges(&closed).is_empty());
+    assert_eq!(closed.issues, [GlyphIssue::OpenContours(1)]);
+    assert!((front_area(&closed)[0].1 - 10_000.0).abs() < 1.0);
+
+    let ribbon = extrude(OpenContours::Ribbon { width: 10.0 });
+    assert!(open_edges(&ribbon).is_empty());
+    assert!(ribbon.winding_matches_normals());
+    // Three strokes of 100 by 10, overlapping at the two corners
+    assert!((front_area(&ribbon)[0].1 - 3_000.0).abs() < 1.0);
+}
+
+#[test]
+fn hairline_contours_follow_their_policy() {
+    // A square next to a stroke drawn out and back, without an area
+    let mut builder = Path::builder();
+    builder.begin(point(0.0, 0.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.line_to(point(100.0, 100.0));
+    builder.line_to(point(0.0, 100.0));
+    builder.end(true);
+    builder.begin(point(150.0, 50.0));
+    builder.line_to(point(250.0, 50.0));
+    builder.end(true);
+    let path = builder.build();
+
+    let extrude = |hairlines| {
+        let options = ExtrusionOptions {
+            tessellation: TessellationOptions {
+                hairlines,
+                ..default()
+            },
+            ..ExtrusionOptions::new(1.0)
+        };
+        extrude_path(&path, Vec2::ZERO, 1000.0, 1.0, &options)
+            .unwrap()
+            .0
+    };
+
+    let dropped = extrude(HairlineContours::Drop { thickness: 1.0 });
+    assert!(open_edges(&dropped).is_empty());
+    assert!((front_area(&dropped)[0].1 - 10_000.0).abs() < 1.0);
+
+    let thickened = extrude(HairlineContours::Thicken { thickness: 10.0 });
+    assert!(open_edges(&thickened).is_empty());
+    assert_eq!(thickened.issues, [GlyphIssue::HairlineContours(1)]);
+    assert!(thickened.winding_matches_normals());
+    // A stroke of 100 by 10 with square ends
+    assert!((front_area(&thickened)[0].1 - 11_100.0).abs() < 1.0);
+
+    // The square is thicker than the threshold and kept
+    let kept = extrude(HairlineContours::Thicken { thickness: 40.0 });
+    assert!((front_area(&kept)[0].1 - 10_000.0 - 140.0 * 40.0).abs() < 1.0);
+}
+
+#[test]
+fn glyphs_are_meshed_as_the_iterator_advances() {
+    let mut world = common::world();
+    let (first, cached) = world
+        .run_system_once(
+            |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                let params = common::params(14.0, 2.0);
+                let Settings {
+                    font_system,
+                    glyph_cache,
+                    ..
+                } = &mut *settings;
+                let mut glyphs = TextGlyphs::new(
+                    Metrics::new(params.font_size, params.line_height),
+                    [("abcdef", Attrs::new())],
+                    &Attrs::new(),
+                    font_system,
+                    None,
+                );
+                glyphs.measure(None, None, font_system);
+
+                let materials = [Handle::<StandardMaterial>::default()];
+                let mut iter = glyphs.generate_mesh_glyphs(
+                    font_system,
+                    glyph_cache,
+                    &params,
+                    &mut meshes,
+                    &materials,
+                );
+                assert_eq!(iter.size_hint().1, Some(6));
+                let first: Vec<_> = iter.by_ref().take(2).collect();
+                drop(iter);
+                (first.len(), glyph_cache.len())
+            },
+        )
+        .unwrap();
+    assert_eq!(first, 2);
+    assert_eq!(cached, 2, "the rest of the text is never tessellated");
+}
+
+#[test]
+fn double_sided_text_reads_from_behind() {
+    let mut world = common::world();
+    let params = Parameters {
+        double_sided: true,
+        ..common::params(14.0, 2.0)
+    };
+    let entries = common::generate(&mut world, "ab", params).unwrap();
+    assert_eq!(entries.len(), 4);
+    let (front, back) = entries.split_at(2);
+
+    for (front, back) in front.iter().zip(back) {
+        assert_eq!(front.mesh, back.mesh);
+        // The front face of the copy looks backwards, from behind the front glyph
+        let normal = back.transform.rotation * Vec3::NEG_Z;
+        assert!(normal.abs_diff_eq(Vec3::Z, 1e-6), "{normal}");
+        assert!((back.transform.translation.z - 4.0).abs() < 1e-4);
+    }
+    // Seen from behind, "a" is still on the left
+    let (a, b) = (back[0].transform.translation, back[1].transform.translation);
+    assert!((a.x - front[1].transform.translation.x).abs() < 1e-4);
+    assert!((b.x - front[0].transform.translation.x).abs() < 1e-4);
+}
+
+/// Z of the glyphs of "ab" placed by `depth_anchor`, relative to the world scale.
+fn anchored_z(world: &mut World, depth_anchor: DepthAnchor, double_sided: bool) -> Vec<f32> {
+    let scale = world.resource::<Settings>().text_scale_factor;
+    let params = Parameters {
+        depth_anchor,
+        double_sided,
+        ..common::params(14.0, 2.0)
+    };
+    common::generate(world, "ab", params)
+        .unwrap()
+        .iter()
+        .map(|entry| entry.transform.translation.z / scale)
+        .collect()
+}
+
+#[test]
+fn depth_anchor_places_the_text_along_z() {
+    let mut world = common::world();
+    assert_eq!(
+        anchored_z(&mut world, DepthAnchor::Front, false),
+        [0.0, 0.0]
+    );
+    assert_eq!(
+        anchored_z(&mut world, DepthAnchor::Center, false),
+        [-1.0, -1.0]
+    );
+    assert_eq!(
+        anchored_z(&mut world, DepthAnchor::Back, false),
+        [-2.0, -2.0]
+    );
+    // Double-sided text is centered as a whole, with the back side behind the plane
+    assert_eq!(
+        anchored_z(&mut world, DepthAnchor::Center, true),
+        [-2.0, -2.0, 2.0, 2.0]
+    );
+}
+
+#[test]
+fn relative_depths_follow_the_font_size() {
+    let mut world = common::world();
+    let mut world_depth = |params: Parameters| {
+        let entries = common::generate(&mut world, "H", params)?;
+        let meshes = world.resource::<Assets<Mesh>>();
+        let aabb = meshes
+            .get(&entries[0].mesh)
+            .unwrap()
+            .compute_aabb()
+            .unwrap();
+        Ok::<_, MeshTextError>(2.0 * aabb.half_extents.z * entries[0].transform.scale.z)
+    };
+    let with_depth = |font_size, depth| Parameters {
+        depth: Some(depth),
+        ..common::params(font_size, 1.0)
+    };
+
+    // Em depths keep the proportions of the text
+    let small = world_depth(with_depth(14.0, Depth::Em(0.2))).unwrap();
+    let large = world_depth(with_depth(28.0, Depth::Em(0.2))).unwrap();
+    assert!((small - 2.8).abs() < 1e-4, "{small}");
+    assert!((large - 5.6).abs() < 1e-4, "{large}");
+
+    // World depths stay the same at every size and scale
+    for (font_size, scale) in [(14.0, 0.5), (28.0, 0.25)] {
+        let params = with_depth(font_size, Depth::World(1.0)).with_scale(scale);
+        let depth = world_depth(params).unwrap();
+        assert!((depth - 1.0).abs() < 1e-4, "{depth}");
+    }
+    assert!(matches!(
+        world_depth(with_depth(14.0, Depth::World(1.0))),
+        Err(MeshTextError::WorldUnitsNeedScale(_))
+    ));
+}
+
+#[test]
+fn depth_segments_split_the_walls_into_rows() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let mut wall_rows = |options: ExtrusionOptions| {
+        let geometry = tessellate_with(&mut font_system, &glyph, options);
+        let mut zs: Vec<f32> = geometry
+            .vertices
+            .iter()
+            .zip(&geometry.normals)
+            .filter(|(_, normal)| normal.z == 0.0)
+            .map(|(vertex, _)| vertex.z)
+            .collect();
+        zs.sort_by(f32::total_cmp);
+        zs.dedup_by(|a, b| (*a - *b).abs() < 1e-4);
+        (zs, geometry.vertices.len())
+    };
+
+    let (single, single_count) = wall_rows(ExtrusionOptions::new(4.0));
+    assert_eq!(single, [0.0, 4.0]);
+    let (split, split_count) = wall_rows(ExtrusionOptions {
+        depth_segments: 4,
+        ..ExtrusionOptions::new(4.0)
+    });
+    assert_eq!(split, [0.0, 1.0, 2.0, 3.0, 4.0]);
+    assert!(split_count > single_count);
+
+    // The inner walls of the lip are split too
+    let inset = FrontInset {
+        width: 2.0,
+        depth: 2.0,
+    };
+    let (split, _) = wall_rows(ExtrusionOptions {
+        front_inset: Some(inset),
+        depth_segments: 2,
+        ..ExtrusionOptions::new(4.0)
+    });
+    assert_eq!(split, [0.0, 1.0, 2.0, 4.0]);
+}
+
+#[test]
+fn glyphs_past_the_last_u16_index_fail() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let options = ExtrusionOptions {
+        depth_segments: 2_000,
+        ..ExtrusionOptions::new(4.0)
+    };
+    let result = font_system
+        .db()
+        .with_face_data(glyph.font_id, |file, _| {
+            let face = Face::parse(file, 0).unwrap();
+            let bb = face.glyph_bounding_box(GlyphId(glyph.glyph_id)).unwrap();
+            tessalate_glyph(&glyph, bb, face, &options).map(|_| ())
+        })
+        .unwrap();
+    assert!(matches!(result, Err(MeshTextError::TessellationFailed)));
+}
+
+#[test]
+fn wall_smoothing_covers_every_row() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "O", 100.0).remove(0);
+    let geometry = tessellate_with(
+        &mut font_system,
+        &glyph,
+        ExtrusionOptions {
+            wall_smoothing_angle: Some(30f32.to_radians()),
+            depth_segments: 4,
+            ..ExtrusionOptions::new(4.0)
+        },
+    );
+
+    // Every row of a column of wall vertices is shaded the same
+    let key = |v: Vec3| ((v.x * 1024.0).round() as i32, (v.y * 1024.0).round() as i32);
+    let mut columns: HashMap<(i32, i32), Vec<(Vec3, Vec3)>> = HashMap::new();
+    for (&vertex, &normal) in geometry.vertices.iter().zip(&geometry.normals) {
+        if normal.z == 0.0 {
+            columns
+                .entry(key(vertex))
+                .or_default()
+                .push((vertex, normal));
+        }
+    }
+    let mut smoothed = 0;
+    for column in columns.values() {
+        let row = |z: f32| -> Vec<Vec3> {
+            let mut normals: Vec<Vec3> = column
+                .iter()
+                .filter(|(vertex, _)| (vertex.z - z).abs() < 1e-4)
+                .map(|&(_, normal)| normal)
+                .collect();
+            normals.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+            normals.dedup_by(|a, b| a.abs_diff_eq(*b, 1e-4));
+            normals
+        };
+        let front = row(0.0);
+        for z in [1.0, 2.0, 3.0, 4.0] {
+            let normals = row(z);
+            assert_eq!(normals.len(), front.len());
+            assert!(
+                normals
+                    .iter()
+                    .zip(&front)
+                    .all(|(a, b)| a.abs_diff_eq(*b, 1e-4))
+            );
+        }
+        smoothed += usize::from(front.len() == 1);
+    }
+    assert!(smoothed > 0);
+    assert!(geometry.winding_matches_normals());
+}
+
+#[test]
+fn wireframes_keep_the_folded_edges() {
+    let mut font_system = embedded_font_system();
+    let glyph = common::shape(&mut font_system, "I", 100.0).remove(0);
+    let mut generate = |topology: OutputTopology, depth_segments: u32| {
+        tessellate_with(
+            &mut font_system,
+            &glyph,
+            ExtrusionOptions {
+                topology,
+                depth_segments,
+                ..ExtrusionOptions::new(4.0)
+            },
+        )
+    };
+
+    // A box: four edges around the front, four around the back and four along the depth
+    let lines = generate(OutputTopology::Lines, 1);
+    assert_eq!(lines.indices.len(), 2 * 12);
+    // Rows split the edges along the depth, but don't fold the walls
+    let rows = generate(OutputTopology::Lines, 4);
+    assert_eq!(rows.indices.len(), 2 * (8 + 4 * 4));
+    for line in rows.indices.chunks_exact(2) {
+        let [a, b] = [line[0], line[1]].map(|i| rows.vertices[i as usize]);
+        assert!(a.z != b.z || a.z == 0.0 || a.z == 4.0);
+    }
+    let points = generate(OutputTopology::Points, 1);
+    assert_eq!(points.indices.len(), 8);
+
+    assert_eq!(
+        Mesh::from(lines).primitive_topology(),
+        PrimitiveTopology::LineList
+    );
+    assert_eq!(
+        Mesh::from(points).primitive_topology(),
+        PrimitiveTopology::PointList
+    );
+    assert_eq!(
+        Mesh::from(generate(OutputTopology::Triangles, 1)).primitive_topology(),
+        PrimitiveTopology::TriangleList
+    );
+}
+
+/// X of the glyphs of `text` laid out on `grid`, per line and in layout units.
+fn grid_columns(world: &mut World, text: &str, params: Parameters) -> Vec<Vec<f32>> {
+    let scale = world.resource::<Settings>().text_scale_factor;
+    let mut lines: Vec<Vec<(f32, f32)>> = Vec::new();
+    for entry in common::generate(world, text, params).unwrap() {
+        let translation = entry.transform.translation / scale;
+        match lines.last_mut() {
+            Some(line) if (line[0].1 - translation.y).abs() < 1.0 => {
+                line.push((translation.x, translation.y));
+            }
+            _ => lines.push(vec![(translation.x, translation.y)]),
+        }
+    }
+    lines
+        .into_iter()
+        .map(|line| line.into_iter().map(|(x, _)| x).collect())
+        .collect()
+}
+
+#[test]
+fn grid_puts_every_glyph_in_a_cell() {
+    let mut world = common::world();
+    let grid = LayoutGrid {
+        cell_width: 12.0,
+        snap: None,
+    };
+    let params = Parameters {
+        grid: Some(grid),
+        ..common::params(14.0, 2.0)
+    };
+    let columns = grid_columns(&mut world, "iWl\nWiW", params.clone());
+    assert_eq!(columns.len(), 2);
+    for line in &columns {
+        assert_eq!(line.len(), 3);
+        for (column, x) in line.iter().enumerate() {
+            // The glyphs are centered in their cells, give or take their side bearings
+            let cell_center = (column as f32 + 0.5) * grid.cell_width;
+            assert!((x - cell_center).abs() < 1.0, "{x} in cell {column}");
+        }
+    }
+
+    // Centered lines move by whole cells, so the columns still line up
+    let centered = grid_columns(
+        &mut world,
+        "ll\nllll",
+        Parameters {
+            alignment: Some(Align::Center),
+            ..params.clone()
+        },
+    );
+    let offset = (centered[0][0] - centered[1][0]) / grid.cell_width;
+    assert!((offset - 1.0).abs() < 1e-4, "{offset}");
+
+    // Snapped transforms land on the world grid
+    let snap = 0.25;
+    let entries = common::generate(
+        &mut world,
+        "iWl",
+        Parameters {
+            grid: Some(LayoutGrid {
+                snap: Some(snap),
+                ..grid
+            }),
+            ..params.clone()
+        },
+    )
+    .unwrap();
+    for entry in entries {
+        let cells = entry.transform.translation.truncate() / snap;
+        assert!(cells.abs_diff_eq(cells.round(), 1e-4), "{cells}");
+    }
+
+    let invalid = Parameters {
+        grid: Some(LayoutGrid {
+            cell_width: 0.0,
+            snap: None,
+        }),
+        ..params
+    };
+    assert!(matches!(
+        invalid.validate(),
+        Err(MeshTextError::InvalidGrid("cell width", 0.0))
+    ));
+}
+
+/// Width of the ink of `entries` and the scale of their first glyph, in world units.
+fn ink_width(world: &World, entries: &[MeshTextEntry<StandardMaterial>]) -> (f32, f32) {
+    let meshes = world.resource::<Assets<Mesh>>();
+    let (min, max) = entries
+        .iter()
+        .map(|entry| {
+            let aabb = meshes.get(&entry.mesh).unwrap().compute_aabb().unwrap();
+            let min = entry.transform.transform_point(aabb.min().into()).x;
+            let max = entry.transform.transform_point(aabb.max().into()).x;
+            (min, max)
+        })
+        .fold((f32::MAX, f32::MIN), |(a, b), (min, max)| {
+            (a.min(min), b.max(max))
+        });
+    (max - min, entries[0].transform.scale.x)
+}
+
+#[test]
+fn fit_box_sizes_the_text_to_the_box() {
+    let mut world = common::world();
+    let fitted = |world: &mut World, fit_box: Vec2| {
+        let params = Parameters {
+            fit_box: Some(fit_box),
+            ..common::params(14.0, 2.0).with_scale(0.1)
+        };
+        let entries = common::generate(world, "Sign", params).unwrap();
+        ink_width(world, &entries)
+    };
+
+    // The text grows to the width of a wide box, whatever the font size it asks for
+    let (width, scale) = fitted(&mut world, Vec2::new(4.0, 10.0));
+    assert!(width <= 4.0 && width > 3.5, "{width}");
+    let (wider, wider_scale) = fitted(&mut world, Vec2::new(8.0, 10.0));
+    assert!((wider / width - 2.0).abs() < 0.01, "{wider} {width}");
+    assert!((wider_scale / scale - 2.0).abs() < 0.01);
+    // A flat box limits the height instead
+    let (_, flat_scale) = fitted(&mut world, Vec2::new(8.0, 1.0));
+    assert!(flat_scale < scale);
+
+    let rejected = |fit_box, scale| Parameters {
+        fit_box: Some(fit_box),
+        scale,
+        ..common::params(14.0, 2.0)
+    };
+    assert!(matches!(
+        rejected(Vec2::ONE, None).validate(),
+        Err(MeshTextError::WorldUnitsNeedScale("Fit box"))
+    ));
+    assert!(matches!(
+        rejected(Vec2::new(1.0, 0.0), Some(Vec3::ONE)).validate(),
+        Err(MeshTextError::InvalidFitBox(_))
+    ));
+}
+
+#[test]
+fn stagger_steps_the_glyphs_along_z() {
+    let mut world = common::world();
+    let params = Parameters {
+        stagger: Some(Stagger {
+            per_line: -1.0,
+            per_glyph: 0.25,
+        }),
+        ..common::params(14.0, 2.0)
+    };
+    let z: Vec<_> = common::generate(&mut world, "ab c\nde", params)
+        .unwrap()
+        .iter()
+        .map(|entry| entry.transform.translation.z)
+        .collect();
+    // The space takes a step without a glyph of its own
+    assert_eq!(z, [0.0, 0.25, 0.75, -1.0, -0.75]);
+}
+
+/// World space vertices of the single glyph of `text`.
+fn glyph_vertices(world: &mut World, text: &str, params: Parameters) -> Vec<Vec3> {
+    let entries = common::generate(world, text, params).unwrap();
+    let mesh = world
+        .resource::<Assets<Mesh>>()
+        .get(&entries[0].mesh)
+        .unwrap();
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("positions");
+    };
+    positions
+        .iter()
+        .map(|&p| entries[0].transform.transform_point(p.into()))
+        .collect()
+}
+
+#[test]
+fn synthetic_styles_slant_and_embolden_the_outlines() {
+    let mut world = common::world();
+    let styled = |style| Parameters {
+        synthetic_style: Some(style),
+        ..common::params(14.0, 2.0)
+    };
+    let extent = |vertices: &[Vec3]| {
+        let min = vertices.iter().fold(Vec3::MAX, |a, &b| a.min(b));
+        let max = vertices.iter().fold(Vec3::MIN, |a, &b| a.max(b));
+        (min, max)
+    };
+    let plain = glyph_vertices(&mut world, "I", common::params(14.0, 2.0));
+    let (min, max) = extent(&plain);
+
+    // The stem of the I leans right, by its height times the tangent of the slant
+    let skew = 0.2;
+    let slanted = glyph_vertices(
+        &mut world,
+        "I",
+        styled(SyntheticStyle {
+            skew,
+            embolden: 0.0,
+        }),
+    );
+    let left_at = |y: f32| {
+        slanted
+            .iter()
+            .filter(|p| (p.y - y).abs() < 1e-3)
+            .fold(f32::MAX, |left, p| left.min(p.x))
+    };
+    let (bottom, top) = (extent(&slanted).0.y, extent(&slanted).1.y);
+    let lean = left_at(top) - left_at(bottom);
+    assert!(
+        ((lean / (top - bottom)) - skew.tan()).abs() < 1e-3,
+        "{lean}"
+    );
+    assert!((bottom - min.y).abs() < 1e-3 && (top - max.y).abs() < 1e-3);
+
+    // Fake bold grows the outline on every side, thinning shrinks it
+    for embolden in [0.02, -0.02] {
+        let (bold_min, bold_max) = extent(&glyph_vertices(
+            &mut world,
+            "I",
+            styled(SyntheticStyle {
+                skew: 0.0,
+                embolden,
+            }),
+        ));
+        let grown = embolden * 14.0;
+        assert!((min.x - bold_min.x - grown).abs() < 1e-3, "{bold_min}");
+        assert!((bold_max.x - max.x - grown).abs() < 1e-3, "{bold_max}");
+        assert!((bold_max.y - max.y - grown).abs() < 1e-3);
+    }
+}
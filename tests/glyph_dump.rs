@@ -0,0 +1,53 @@
+mod common;
+
+use bevy_mesh_text_3d::{Attrs, MeshTextError, debug_dump_glyph, embedded_font_system};
+
+fn count_lines(text: &str, prefix: &str) -> usize {
+    text.lines().filter(|line| line.starts_with(prefix)).count()
+}
+
+#[test]
+fn glyphs_are_dumped_as_obj_and_ply() {
+    let dir = std::env::temp_dir().join(format!("mesh_text_glyph_dump_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut font_system = embedded_font_system();
+    let params = common::params(20.0, 3.0);
+    let glyph = &common::shape(&mut font_system, "A", 20.0)[0];
+    let (geometry, _, _) = common::tessellate(&mut font_system, glyph, 3.0).unwrap();
+
+    let obj = dir.join("a.obj");
+    debug_dump_glyph('A', &Attrs::new(), &mut font_system, &params, &obj).unwrap();
+    let obj = std::fs::read_to_string(obj).unwrap();
+    assert_eq!(count_lines(&obj, "v "), geometry.vertices.len());
+    assert_eq!(count_lines(&obj, "vn "), geometry.normals.len());
+    assert_eq!(count_lines(&obj, "f "), geometry.indices.len() / 3);
+    // OBJ counts vertices from 1
+    assert!(!obj.contains(" 0/"));
+
+    let ply = dir.join("a.ply");
+    debug_dump_glyph('A', &Attrs::new(), &mut font_system, &params, &ply).unwrap();
+    let ply = std::fs::read_to_string(ply).unwrap();
+    let vertex_header = format!("element vertex {}", geometry.vertices.len());
+    assert!(ply.lines().any(|line| line == vertex_header));
+    let body = ply.split_once("end_header\n").unwrap().1;
+    assert_eq!(
+        body.lines().count(),
+        geometry.vertices.len() + geometry.indices.len() / 3
+    );
+
+    // A space has no outline and is written without geometry
+    let space = dir.join("space.obj");
+    debug_dump_glyph(' ', &Attrs::new(), &mut font_system, &params, &space).unwrap();
+    assert_eq!(std::fs::read_to_string(space).unwrap(), "");
+
+    let unknown = debug_dump_glyph(
+        'A',
+        &Attrs::new(),
+        &mut font_system,
+        &params,
+        dir.join("a.stl"),
+    );
+    assert!(matches!(unknown, Err(MeshTextError::InvalidInput)));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,189 @@
+mod common;
+
+use std::path::PathBuf;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::extrude_glyph::ExtrusionOptions;
+use bevy_mesh_text_3d::incremental_text::update_incremental_text;
+use bevy_mesh_text_3d::{
+    Attrs, BakedTextMesh, GlyphStore, IncrementalGlyphs, IncrementalText, InputText, Settings,
+    embedded_font_system,
+};
+
+#[test]
+fn baked_meshes_round_trip_through_bytes() {
+    let mut font_system = embedded_font_system();
+    let glyph = &common::shape(&mut font_system, "g", 20.0)[0];
+    let (geometry, center_x, center_y) = common::tessellate(&mut font_system, glyph, 3.0).unwrap();
+    let baked = BakedTextMesh {
+        options: ExtrusionOptions::new(3.0).cache_key(),
+        geometry,
+        center_x_layout: center_x,
+        center_y_layout: center_y,
+    };
+
+    let bytes = baked.to_bytes();
+    let read = BakedTextMesh::from_bytes(&bytes).unwrap();
+    assert_eq!(read.geometry.vertices, baked.geometry.vertices);
+    assert_eq!(read.geometry.normals, baked.geometry.normals);
+    assert_eq!(read.geometry.uvs, baked.geometry.uvs);
+    assert_eq!(read.geometry.indices, baked.geometry.indices);
+    assert_eq!(read.geometry.surface, baked.geometry.surface);
+    assert_eq!(read.center_x_layout, center_x);
+    assert_eq!(read.center_y_layout, center_y);
+    assert_eq!(read.options, baked.options);
+
+    // Truncated or foreign data is a miss, not a broken mesh
+    assert!(BakedTextMesh::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+    assert!(BakedTextMesh::from_bytes(b"not a glyph").is_none());
+}
+
+/// An empty directory for a store, unique to the test.
+fn store_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "mesh_text_glyph_store_{name}_{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn world_with_store(store: GlyphStore) -> World {
+    let mut world = common::world();
+    let mut settings = world.resource_mut::<Settings>();
+    settings.glyph_cache.set_store(Some(store));
+    world
+}
+
+fn spawn_text(world: &mut World, text: &str) -> Entity {
+    let text = InputText::Simple {
+        text: text.to_string(),
+        material: Handle::<StandardMaterial>::default(),
+        attrs: Attrs::new(),
+    };
+    world
+        .spawn(IncrementalText::new(text, common::params(14.0, 2.0)))
+        .id()
+}
+
+fn update(world: &mut World) {
+    world
+        .run_system_once(update_incremental_text::<StandardMaterial>)
+        .unwrap();
+}
+
+#[test]
+fn incremental_text_caps_tessellation_and_reuses_the_store() {
+    let dir = store_dir("incremental");
+
+    let mut world = world_with_store(GlyphStore::new(&dir));
+    world.insert_resource(IncrementalGlyphs {
+        glyphs_per_frame: 2,
+    });
+    // Six glyphs, five of them unique
+    let text = spawn_text(&mut world, "ABCDEA");
+
+    update(&mut world);
+    assert_eq!(world.resource::<Settings>().glyph_cache.len(), 2);
+    assert_eq!(
+        world.get::<IncrementalText>(text).unwrap().remaining(),
+        Some(3)
+    );
+    assert!(world.get::<Children>(text).is_none());
+
+    update(&mut world);
+    update(&mut world);
+    assert!(world.get::<IncrementalText>(text).is_none());
+    assert_eq!(world.get::<Children>(text).unwrap().len(), 6);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 5);
+
+    // A later run loads every glyph from the store without tessellating
+    let mut world = world_with_store(GlyphStore::new(&dir));
+    world.insert_resource(IncrementalGlyphs {
+        glyphs_per_frame: 0,
+    });
+    let text = spawn_text(&mut world, "BADCE");
+    update(&mut world);
+    assert!(world.get::<IncrementalText>(text).is_none());
+    assert_eq!(world.get::<Children>(text).unwrap().len(), 5);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn generated_glyphs_come_from_an_opened_store() {
+    let dir = store_dir("generate");
+    let mut world = world_with_store(GlyphStore::new(&dir));
+    common::generate(&mut world, "I", common::params(14.0, 2.0)).unwrap();
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+
+    // Doubles the stored mesh, so a glyph read from the store tells itself apart
+    let mut baked = BakedTextMesh::from_bytes(&std::fs::read(&files[0]).unwrap()).unwrap();
+    let original = baked.geometry.vertices.clone();
+    for vertex in &mut baked.geometry.vertices {
+        *vertex *= 2.0;
+    }
+    std::fs::write(&files[0], baked.to_bytes()).unwrap();
+
+    let mut world = world_with_store(GlyphStore::open(&dir).unwrap());
+    let entries = common::generate(&mut world, "I", common::params(14.0, 2.0)).unwrap();
+    let meshes = world.resource::<Assets<Mesh>>();
+    let positions = meshes
+        .get(&entries[0].mesh)
+        .unwrap()
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    assert_eq!(positions.len(), original.len());
+    assert_eq!(Vec3::from(positions[0]), original[0] * 2.0);
+
+    // Opening a missing directory gives an empty store
+    assert!(GlyphStore::open(dir.join("missing")).is_ok());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn stored_meshes_of_other_options_are_not_loaded() {
+    let dir = store_dir("collision");
+    let mut world = world_with_store(GlyphStore::new(&dir));
+    common::generate(&mut world, "I", common::params(14.0, 2.0)).unwrap();
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+
+    // As if other options hashed to the same file
+    let mut baked = BakedTextMesh::from_bytes(&std::fs::read(&files[0]).unwrap()).unwrap();
+    baked.options = ExtrusionOptions::new(7.0).cache_key();
+    for vertex in &mut baked.geometry.vertices {
+        *vertex *= 2.0;
+    }
+    std::fs::write(&files[0], baked.to_bytes()).unwrap();
+
+    for store in [GlyphStore::new(&dir), GlyphStore::open(&dir).unwrap()] {
+        let mut world = world_with_store(store);
+        let entries = common::generate(&mut world, "I", common::params(14.0, 2.0)).unwrap();
+        let meshes = world.resource::<Assets<Mesh>>();
+        let positions = meshes
+            .get(&entries[0].mesh)
+            .unwrap()
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        // Tessellated again instead
+        assert!(
+            positions
+                .iter()
+                .all(|&position| !baked.geometry.vertices.contains(&Vec3::from(position)))
+        );
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_mesh_text_3d::{
+    Attrs, FrontInset, InflateParameters, InputText, OutputTopology, Parameters, RoundedBack,
+    SyntheticStyle, embedded_font_system, generate_meshes_with_font_system,
+};
+use common::golden::{Fingerprint, check};
+
+/// Glyphs with straight and curved strokes, holes, overlaps and thin details
+const CORPUS: &str = "AaBgQR&%8@?";
+
+/// A curved and a straight glyph, for extrusions too slow to run the whole corpus through in
+/// debug builds
+const SHORT_CORPUS: &str = "Oi";
+
+/// Extrusions run through the golden corpus, by name, with the glyphs they generate.
+fn variants() -> Vec<(&'static str, Parameters, &'static str)> {
+    let plain = common::params(20.0, 3.0);
+    vec![
+        ("plain", plain.clone(), CORPUS),
+        (
+            "inset",
+            Parameters {
+                front_inset: Some(FrontInset {
+                    width: 0.6,
+                    depth: 0.5,
+                }),
+                ..plain.clone()
+            },
+            CORPUS,
+        ),
+        (
+            "rounded",
+            Parameters {
+                rounded_back: Some(RoundedBack {
+                    radius: 0.4,
+                    depth: 1.0,
+                    segments: 3,
+                    profile_power: 1.0,
+                }),
+                ..plain.clone()
+            },
+            CORPUS,
+        ),
+        (
+            "inflated",
+            Parameters {
+                inflate: Some(InflateParameters {
+                    amount: 1.0,
+                    falloff: 2.0,
+                }),
+                ..plain.clone()
+            },
+            SHORT_CORPUS,
+        ),
+        (
+            "synthetic",
+            Parameters {
+                synthetic_style: Some(SyntheticStyle {
+                    skew: 0.2,
+                    embolden: 0.02,
+                }),
+                ..plain.clone()
+            },
+            CORPUS,
+        ),
+        (
+            "wireframe",
+            Parameters {
+                topology: OutputTopology::Lines,
+                ..plain
+            },
+            CORPUS,
+        ),
+    ]
+}
+
+#[test]
+fn generated_meshes_match_the_golden_fingerprints() {
+    let mut font_system = embedded_font_system();
+    let mut meshes = Assets::<Mesh>::default();
+    let mut fingerprints = Vec::new();
+    for (variant, params, corpus) in variants() {
+        let text = InputText::Simple {
+            text: corpus.to_string(),
+            material: Handle::<StandardMaterial>::default(),
+            attrs: Attrs::new(),
+        };
+        let entries =
+            generate_meshes_with_font_system(text, &mut font_system, params, &mut meshes).unwrap();
+        assert_eq!(entries.len(), corpus.chars().count(), "{variant}");
+        for (entry, c) in entries.iter().zip(corpus.chars()) {
+            let name = format!("{variant}/U+{:04X}", c as u32);
+            fingerprints.push((name, Fingerprint::of(entry, &meshes)));
+        }
+    }
+    check("meshes.txt", &fingerprints);
+}
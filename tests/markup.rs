@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use bevy_mesh_text_3d::{Attrs, InputText, MeshTextError, parse_markup};
+
+fn parse(
+    source: &str,
+    materials: &mut Assets<StandardMaterial>,
+    base: &Handle<StandardMaterial>,
+) -> Result<(Vec<String>, Vec<Handle<StandardMaterial>>), MeshTextError> {
+    match parse_markup(source, Attrs::new(), base, materials)? {
+        InputText::Rich {
+            words, materials, ..
+        } => Ok((words, materials)),
+        InputText::Simple { .. } => unreachable!(),
+    }
+}
+
+#[test]
+fn tags_style_their_spans() {
+    let mut materials = Assets::<StandardMaterial>::default();
+    let base = materials.add(StandardMaterial {
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    let (words, spans) = parse(
+        "Press [emissive=#00ffff]jump[/emissive] or [[ [metallic=0.8]fly \
+         [emissive=#00ffff]now[/emissive][/metallic] [emissive=#00ffff]up",
+        &mut materials,
+        &base,
+    )
+    .unwrap();
+    assert_eq!(
+        words,
+        ["Press ", "jump", " or [ ", "fly ", "now", " ", "up"]
+    );
+
+    assert_eq!(spans[0], base);
+    assert_eq!(spans[2], base);
+    // Spans with the same properties share a material
+    assert_eq!(spans[1], spans[6]);
+    assert_ne!(spans[1], spans[4]);
+
+    let glowing = materials.get(&spans[1]).unwrap();
+    assert_eq!(
+        glowing.emissive,
+        LinearRgba::from(Srgba::rgb(0.0, 1.0, 1.0))
+    );
+    assert_eq!(glowing.perceptual_roughness, 0.3);
+    let both = materials.get(&spans[4]).unwrap();
+    assert_eq!(both.metallic, 0.8);
+    assert_eq!(both.emissive, glowing.emissive);
+    assert_eq!(materials.len(), 4);
+}
+
+#[test]
+fn malformed_tags_are_rejected() {
+    let mut materials = Assets::<StandardMaterial>::default();
+    let base = Handle::default();
+    for source in [
+        "[emissive=cyan]a",
+        "[metallic=shiny]a",
+        "a[/metallic]",
+        "[bold]a",
+        "a [emissive=#fff",
+    ] {
+        assert!(matches!(
+            parse(source, &mut materials, &base),
+            Err(MeshTextError::InvalidMarkup(_))
+        ));
+    }
+}
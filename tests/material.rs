@@ -0,0 +1,189 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{MeshVertexBufferLayoutRef, MeshVertexBufferLayouts, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{
+    FragmentState, RenderPipelineDescriptor, ShaderDefVal, VertexState,
+};
+use bevy_mesh_text_3d::extrude_glyph::{ATTRIBUTE_DEPTH_FRACTION, ATTRIBUTE_SURFACE};
+use bevy_mesh_text_3d::material::{bind_to_uv_b, discard_in_prepass};
+use bevy_mesh_text_3d::{ATTRIBUTE_TEXT_PROGRESS, Parameters};
+
+/// The layout of a glyph mesh generated with `params`.
+fn glyph_layout(params: Parameters) -> MeshVertexBufferLayoutRef {
+    let mut world = common::world();
+    let entries = common::generate(&mut world, "A", params).unwrap();
+    let meshes = world.resource::<Assets<Mesh>>();
+    let mesh = meshes.get(&entries[0].mesh).unwrap();
+    mesh.get_mesh_vertex_buffer_layout(&mut MeshVertexBufferLayouts::default())
+}
+
+fn depth_layout() -> MeshVertexBufferLayoutRef {
+    glyph_layout(Parameters {
+        depth_attribute: true,
+        ..common::params(10.0, 2.0)
+    })
+}
+
+/// A prepass descriptor the way Bevy specializes it for an opaque mesh with UV_0.
+fn prepass_descriptor(
+    layout: &MeshVertexBufferLayoutRef,
+    shader_defs: &[&str],
+    fragment: bool,
+) -> RenderPipelineDescriptor {
+    let shader_defs: Vec<ShaderDefVal> = shader_defs.iter().map(|&def| def.into()).collect();
+    let attributes = [
+        Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+        Mesh::ATTRIBUTE_UV_0.at_shader_location(1),
+    ];
+    RenderPipelineDescriptor {
+        label: Some("prepass_pipeline".into()),
+        layout: Vec::new(),
+        push_constant_ranges: Vec::new(),
+        vertex: VertexState {
+            shader: Handle::default(),
+            shader_defs: shader_defs.clone(),
+            entry_point: "vertex".into(),
+            buffers: vec![layout.0.get_layout(&attributes).unwrap()],
+        },
+        primitive: default(),
+        depth_stencil: None,
+        multisample: default(),
+        fragment: fragment.then(|| FragmentState {
+            shader: Handle::default(),
+            shader_defs,
+            entry_point: "fragment".into(),
+            targets: Vec::new(),
+        }),
+        zero_initialize_workgroup_memory: false,
+    }
+}
+
+fn has_define(defs: &[ShaderDefVal], define: &str) -> bool {
+    defs.contains(&define.into())
+}
+
+#[test]
+fn depth_prepass_binds_the_attribute_and_discards() {
+    let layout = depth_layout();
+    let mut descriptor = prepass_descriptor(
+        &layout,
+        &["PREPASS_PIPELINE", "VERTEX_UVS", "VERTEX_UVS_A"],
+        false,
+    );
+    let expected = layout
+        .0
+        .get_layout(&[ATTRIBUTE_DEPTH_FRACTION.at_shader_location(2)])
+        .unwrap()
+        .attributes[0];
+
+    bind_to_uv_b(&mut descriptor, &layout, ATTRIBUTE_DEPTH_FRACTION).unwrap();
+    let shader = Handle::<Shader>::default();
+    discard_in_prepass(&mut descriptor, Some(&shader));
+
+    let attributes = &descriptor.vertex.buffers[0].attributes;
+    assert_eq!(attributes.len(), 3);
+    assert!(attributes.contains(&expected));
+    assert!(
+        attributes
+            .iter()
+            .any(|attribute| attribute.shader_location == 1)
+    );
+    assert!(has_define(&descriptor.vertex.shader_defs, "VERTEX_UVS_B"));
+
+    // The depth-only prepass gets a fragment stage to discard the hidden part in
+    let fragment = descriptor.fragment.unwrap();
+    assert_eq!(fragment.shader, shader);
+    assert!(fragment.targets.is_empty());
+    assert!(has_define(&fragment.shader_defs, "PREPASS_PIPELINE"));
+    assert!(has_define(&fragment.shader_defs, "VERTEX_UVS_B"));
+}
+
+#[test]
+fn deferred_prepass_binds_the_attribute_for_its_fragment_shader() {
+    let layout = depth_layout();
+    let mut descriptor = prepass_descriptor(
+        &layout,
+        &["PREPASS_PIPELINE", "DEFERRED_PREPASS", "PREPASS_FRAGMENT"],
+        true,
+    );
+
+    bind_to_uv_b(&mut descriptor, &layout, ATTRIBUTE_DEPTH_FRACTION).unwrap();
+    discard_in_prepass(&mut descriptor, Some(&Handle::default()));
+
+    let attributes = &descriptor.vertex.buffers[0].attributes;
+    assert!(
+        attributes
+            .iter()
+            .any(|attribute| attribute.shader_location == 2)
+    );
+    let fragment = descriptor.fragment.unwrap();
+    assert!(has_define(&fragment.shader_defs, "VERTEX_UVS_B"));
+    assert!(has_define(&fragment.shader_defs, "DEFERRED_PREPASS"));
+}
+
+#[test]
+fn deferred_prepass_binds_the_surface() {
+    let layout = glyph_layout(Parameters {
+        surface_attribute: true,
+        ..common::params(10.0, 2.0)
+    });
+    let mut descriptor = prepass_descriptor(
+        &layout,
+        &["PREPASS_PIPELINE", "DEFERRED_PREPASS", "PREPASS_FRAGMENT"],
+        true,
+    );
+    let expected = layout
+        .0
+        .get_layout(&[ATTRIBUTE_SURFACE.at_shader_location(2)])
+        .unwrap()
+        .attributes[0];
+
+    bind_to_uv_b(&mut descriptor, &layout, ATTRIBUTE_SURFACE).unwrap();
+
+    assert!(descriptor.vertex.buffers[0].attributes.contains(&expected));
+    let fragment = descriptor.fragment.unwrap();
+    assert!(has_define(&fragment.shader_defs, "VERTEX_UVS_B"));
+}
+
+#[test]
+fn shadow_pass_discards_the_dissolved_text() {
+    let mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 3])
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0f32, 0.0, 1.0]; 3])
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0f32; 2]; 3])
+    .with_inserted_attribute(ATTRIBUTE_TEXT_PROGRESS, vec![0.0f32, 0.5, 1.0]);
+    let layout = mesh.get_mesh_vertex_buffer_layout(&mut MeshVertexBufferLayouts::default());
+    let mut descriptor = prepass_descriptor(&layout, &["PREPASS_PIPELINE"], false);
+
+    bind_to_uv_b(&mut descriptor, &layout, ATTRIBUTE_TEXT_PROGRESS).unwrap();
+    discard_in_prepass(&mut descriptor, Some(&Handle::default()));
+
+    let attributes = &descriptor.vertex.buffers[0].attributes;
+    assert!(
+        attributes
+            .iter()
+            .any(|attribute| attribute.shader_location == 2)
+    );
+    let fragment = descriptor.fragment.unwrap();
+    assert!(has_define(&fragment.shader_defs, "VERTEX_UVS_B"));
+}
+
+#[test]
+fn other_pipelines_are_left_alone() {
+    let layout = depth_layout();
+    let mut descriptor = prepass_descriptor(&layout, &[], false);
+    descriptor.label = Some("wireframe_pipeline".into());
+    let attributes = descriptor.vertex.buffers[0].attributes.clone();
+
+    bind_to_uv_b(&mut descriptor, &layout, ATTRIBUTE_DEPTH_FRACTION).unwrap();
+    discard_in_prepass(&mut descriptor, Some(&Handle::default()));
+
+    assert_eq!(descriptor.vertex.buffers[0].attributes, attributes);
+    assert!(descriptor.vertex.shader_defs.is_empty());
+    assert!(descriptor.fragment.is_none());
+}
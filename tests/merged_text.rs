@@ -0,0 +1,142 @@
+use std::collections::BTreeSet;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy_mesh_text_3d::{
+    ATTRIBUTE_GLYPH, ATTRIBUTE_TEXT_PROGRESS, Attrs, InputText, MeshTextEntry, Parameters,
+    Settings, generate_merged_meshes,
+};
+
+mod common;
+
+fn merged(
+    world: &mut World,
+    text: &str,
+    params: Parameters,
+) -> Vec<MeshTextEntry<StandardMaterial>> {
+    let text = text.to_string();
+    let mut params = Some(params);
+    world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                generate_merged_meshes(
+                    InputText::Simple {
+                        text: text.clone(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    params.take().unwrap(),
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn merged_text_tags_every_vertex_with_its_glyph() {
+    let mut world = common::world();
+    let params = common::params(14.0, 2.0);
+    let glyphs = common::generate(&mut world, "Hi yo", params.clone()).unwrap();
+    let entries = merged(&mut world, "Hi yo", params);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].transform, Transform::IDENTITY);
+
+    let meshes = world.resource::<Assets<Mesh>>();
+    let mesh = meshes.get(&entries[0].mesh).unwrap();
+    let glyph_vertices: usize = glyphs
+        .iter()
+        .map(|glyph| meshes.get(&glyph.mesh).unwrap().count_vertices())
+        .sum();
+    assert_eq!(mesh.count_vertices(), glyph_vertices);
+
+    let Some(VertexAttributeValues::Float32x2(tags)) = mesh.attribute(ATTRIBUTE_GLYPH) else {
+        panic!("merged mesh without glyph attribute");
+    };
+    let tags: BTreeSet<(u32, char)> = tags
+        .iter()
+        .map(|&[index, code]| (index as u32, char::from_u32(code as u32).unwrap()))
+        .collect();
+    // The space has no mesh but still counts
+    assert_eq!(
+        tags,
+        BTreeSet::from([(0, 'H'), (1, 'i'), (3, 'y'), (4, 'o')])
+    );
+
+    // The vertices of the first glyph sit where its entity would place them
+    let first = meshes.get(&glyphs[0].mesh).unwrap();
+    let (Some(expected), Some(actual)) = (
+        first
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(VertexAttributeValues::as_float3),
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(VertexAttributeValues::as_float3),
+    ) else {
+        panic!("meshes without positions");
+    };
+    for (&local, &merged) in expected.iter().zip(actual) {
+        let placed = glyphs[0].transform.transform_point(Vec3::from(local));
+        assert!(placed.distance(Vec3::from(merged)) < 1e-4);
+    }
+}
+
+#[test]
+fn double_sided_text_tags_the_back_glyphs_too() {
+    let mut world = common::world();
+    let params = Parameters {
+        double_sided: true,
+        ..common::params(14.0, 2.0)
+    };
+    let entries = merged(&mut world, "ab", params);
+    let meshes = world.resource::<Assets<Mesh>>();
+    let Some(VertexAttributeValues::Float32x2(tags)) = meshes
+        .get(&entries[0].mesh)
+        .unwrap()
+        .attribute(ATTRIBUTE_GLYPH)
+    else {
+        panic!("merged mesh without glyph attribute");
+    };
+    let half = tags.len() / 2;
+    assert_eq!(
+        tags[..half]
+            .iter()
+            .filter(|tag| tag[1] == 'a' as u32 as f32)
+            .count(),
+        tags[half..]
+            .iter()
+            .filter(|tag| tag[1] == 'a' as u32 as f32)
+            .count()
+    );
+}
+
+#[test]
+fn progress_runs_along_the_text() {
+    let mut world = common::world();
+    let entries = merged(&mut world, "Hi yo", common::params(14.0, 2.0));
+    let mesh = world
+        .resource::<Assets<Mesh>>()
+        .get(&entries[0].mesh)
+        .unwrap();
+    let (
+        Some(VertexAttributeValues::Float32(progress)),
+        Some(VertexAttributeValues::Float32x2(tags)),
+    ) = (
+        mesh.attribute(ATTRIBUTE_TEXT_PROGRESS),
+        mesh.attribute(ATTRIBUTE_GLYPH),
+    )
+    else {
+        panic!("merged mesh without progress");
+    };
+    assert_eq!(progress.len(), mesh.count_vertices());
+    let min = progress.iter().copied().fold(f32::MAX, f32::min);
+    let max = progress.iter().copied().fold(f32::MIN, f32::max);
+    assert!(min.abs() < 1e-5 && (max - 1.0).abs() < 1e-5);
+
+    // Each of the five glyphs covers a fifth of the range, in order
+    for (&value, &[index, _]) in progress.iter().zip(tags) {
+        assert!(value >= index / 5.0 - 1e-5 && value <= (index + 1.0) / 5.0 + 1e-5);
+    }
+}
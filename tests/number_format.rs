@@ -0,0 +1,35 @@
+use bevy_mesh_text_3d::{NumberLocale, format_number};
+
+#[test]
+fn groups_thousands() {
+    assert_eq!(format_number(0.0, 0, NumberLocale::EN_US), "0");
+    assert_eq!(format_number(999.0, 0, NumberLocale::EN_US), "999");
+    assert_eq!(format_number(1000.0, 0, NumberLocale::EN_US), "1,000");
+    assert_eq!(
+        format_number(1234567.891, 2, NumberLocale::EN_US),
+        "1,234,567.89"
+    );
+    assert_eq!(
+        format_number(1234567.891, 2, NumberLocale::DE_DE),
+        "1.234.567,89"
+    );
+    assert_eq!(
+        format_number(1234567.0, 1, NumberLocale::FR_FR),
+        "1\u{202F}234\u{202F}567,0"
+    );
+}
+
+#[test]
+fn signs_and_rounding() {
+    assert_eq!(format_number(-1234.5, 0, NumberLocale::EN_US), "-1,234");
+    assert_eq!(format_number(-0.004, 2, NumberLocale::EN_US), "0.00");
+    assert_eq!(format_number(999.999, 2, NumberLocale::EN_US), "1,000.00");
+}
+
+#[test]
+fn locale_from_tag() {
+    assert_eq!(NumberLocale::from_tag("de-AT"), NumberLocale::DE_DE);
+    assert_eq!(NumberLocale::from_tag("de-CH"), NumberLocale::DE_CH);
+    assert_eq!(NumberLocale::from_tag("fr"), NumberLocale::FR_FR);
+    assert_eq!(NumberLocale::from_tag("ja-JP"), NumberLocale::EN_US);
+}
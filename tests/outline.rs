@@ -0,0 +1,94 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::mesh::{PrimitiveTopology, VertexAttributeValues};
+use bevy_mesh_text_3d::{
+    Attrs, InputText, MeshTextEntry, OutlineOptions, Parameters, Settings, generate_outline_meshes,
+};
+
+mod common;
+
+fn outlines(
+    world: &mut World,
+    text: &str,
+    params: Parameters,
+    options: OutlineOptions,
+) -> Vec<MeshTextEntry<StandardMaterial>> {
+    let text = text.to_string();
+    let mut params = Some(params);
+    world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                generate_outline_meshes(
+                    InputText::Simple {
+                        text: text.clone(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    params.take().unwrap(),
+                    &options,
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+        .unwrap()
+}
+
+fn positions(world: &World, entry: &MeshTextEntry<StandardMaterial>) -> Vec<Vec3> {
+    let mesh = world.resource::<Assets<Mesh>>().get(&entry.mesh).unwrap();
+    assert_eq!(mesh.primitive_topology(), PrimitiveTopology::LineList);
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("outline without positions");
+    };
+    positions.iter().copied().map(Vec3::from).collect()
+}
+
+#[test]
+fn outlines_trace_the_solid_glyphs() {
+    let mut world = common::world();
+    let params = common::params(14.0, 2.0);
+    let solid = common::generate(&mut world, "I I", params.clone()).unwrap();
+    let lines = outlines(&mut world, "I I", params, OutlineOptions::default());
+    assert_eq!(lines.len(), solid.len());
+    for (line, glyph) in lines.iter().zip(&solid) {
+        let offset = line.transform.translation - glyph.transform.translation;
+        assert!(offset.length() < 1e-3, "outline is off by {offset}");
+    }
+
+    // The box of an 'I', closed by its last edge
+    let mesh = world
+        .resource::<Assets<Mesh>>()
+        .get(&lines[0].mesh)
+        .unwrap();
+    assert_eq!(mesh.count_vertices(), 4);
+    assert_eq!(mesh.indices().unwrap().len(), 2 * 4);
+    assert!(positions(&world, &lines[0]).iter().all(|p| p.z == 0.0));
+}
+
+#[test]
+fn offset_rings_surround_the_outline() {
+    let mut world = common::world();
+    let lines = outlines(
+        &mut world,
+        "I",
+        common::params(14.0, 2.0),
+        OutlineOptions {
+            z: 3.0,
+            offsets: vec![0.5],
+        },
+    );
+    let points = positions(&world, &lines[0]);
+    assert_eq!(points.len(), 8);
+    assert!(points.iter().all(|p| p.z == 3.0));
+
+    let extent = |points: &[Vec3]| {
+        points
+            .iter()
+            .fold(Vec2::ZERO, |max, p| max.max(p.truncate().abs()))
+    };
+    let (outline, ring) = (extent(&points[..4]), extent(&points[4..]));
+    assert!((ring - outline - Vec2::splat(0.5)).abs().max_element() < 1e-4);
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_mesh_text_3d::projection::{
+    Heightfield, MeshSurface, ProjectionMode, ProjectionTarget, SurfaceProjection,
+    project_onto_surface,
+};
+
+/// A plane rising along X: y = 1 + x / 2.
+fn slope() -> Heightfield {
+    let size = UVec2::new(101, 101);
+    let heights = (0..size.y)
+        .flat_map(|_| (0..size.x).map(|x| 1.0 + (x as f32 - 50.0) / 2.0))
+        .collect();
+    Heightfield {
+        origin: Vec2::splat(-50.0),
+        cell_size: 1.0,
+        size,
+        heights,
+    }
+}
+
+#[test]
+fn heightfield_interpolates_between_samples() {
+    let field = slope();
+    assert_eq!(field.height_at(Vec2::new(0.0, 3.0)), 1.0);
+    assert_eq!(field.height_at(Vec2::new(2.5, -7.25)), 2.25);
+    // Clamped outside the grid
+    assert_eq!(field.height_at(Vec2::new(100.0, 0.0)), 26.0);
+
+    let t = field.cast(Vec3::new(4.0, 10.0, 0.0), Vec3::NEG_Y).unwrap();
+    assert!((t - 7.0).abs() < 1e-4);
+}
+
+#[test]
+fn mesh_surface_hits_on_both_sides() {
+    let plane = Plane3d::default().mesh().size(10.0, 10.0).build();
+    let surface = MeshSurface::new(&plane, &GlobalTransform::from_xyz(0.0, 2.0, 0.0)).unwrap();
+    assert_eq!(
+        surface.cast(Vec3::new(1.0, 5.0, 1.0), Vec3::NEG_Y),
+        Some(3.0)
+    );
+    assert_eq!(
+        surface.cast(Vec3::new(1.0, -1.0, 1.0), Vec3::NEG_Y),
+        Some(-3.0)
+    );
+    assert_eq!(surface.cast(Vec3::new(20.0, 5.0, 0.0), Vec3::NEG_Y), None);
+}
+
+#[test]
+fn glyphs_rest_on_the_surface() {
+    let mut world = common::world();
+    let field = slope();
+    let projection = SurfaceProjection {
+        // Lay the text flat on the ground
+        text_transform: Transform::from_rotation(Quat::from_rotation_x(
+            -std::f32::consts::FRAC_PI_2,
+        )),
+        hover: 0.25,
+        ..default()
+    };
+
+    let mut entries = common::generate(&mut world, "ab", common::params(14.0, 2.0)).unwrap();
+    let mut meshes = world.resource_mut::<Assets<Mesh>>();
+    project_onto_surface(&mut entries, &mut meshes, &field, &projection);
+    for entry in &entries {
+        let p = entry.transform.translation;
+        assert!((p.y - (field.height_at(p.xz()) + 0.25)).abs() < 1e-3);
+    }
+
+    let mut bent = common::generate(&mut world, "ab", common::params(14.0, 2.0)).unwrap();
+    let original = bent[0].mesh.clone();
+    let mut meshes = world.resource_mut::<Assets<Mesh>>();
+    project_onto_surface(
+        &mut bent,
+        &mut meshes,
+        &field,
+        &SurfaceProjection {
+            mode: ProjectionMode::PerVertex,
+            ..projection
+        },
+    );
+    // Cached glyph meshes stay untouched
+    assert_ne!(bent[0].mesh, original);
+}
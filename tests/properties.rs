@@ -0,0 +1,121 @@
+//! Property-based checks of the tessellation output across random glyphs, sizes and depths.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_mesh_text_3d::{MeshTextError, embedded_font_system};
+use proptest::prelude::*;
+
+/// Front cap triangles projected onto the glyph plane.
+fn front_cap(vertices: &[Vec3], indices: &[u16], normals: &[Vec3]) -> Vec<[Vec2; 3]> {
+    indices
+        .chunks_exact(3)
+        .filter(|tri| tri.iter().all(|&i| normals[i as usize] == Vec3::NEG_Z))
+        .map(|tri| [0, 1, 2].map(|k| vertices[tri[k] as usize].truncate()))
+        .collect()
+}
+
+fn covered(cap: &[[Vec2; 3]], p: Vec2) -> bool {
+    cap.iter().any(|[a, b, c]| {
+        let d1 = (p - *a).perp_dot(*b - *a);
+        let d2 = (p - *b).perp_dot(*c - *b);
+        let d3 = (p - *c).perp_dot(*a - *c);
+        (d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0) || (d1 <= 0.0 && d2 <= 0.0 && d3 <= 0.0)
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn tessellated_glyphs_are_well_formed(
+        c in proptest::char::range('!', '~'),
+        font_size in 1.0f32..200.0,
+        extrusion_depth in 0.01f32..50.0,
+    ) {
+        let mut font_system = embedded_font_system();
+        for glyph in common::shape(&mut font_system, &c.to_string(), font_size) {
+            let Some((geometry, center_x, center_y)) =
+                common::tessellate(&mut font_system, &glyph, extrusion_depth)
+            else {
+                continue;
+            };
+
+            prop_assert!(center_x.is_finite() && center_y.is_finite());
+            prop_assert_eq!(geometry.vertices.len(), geometry.normals.len());
+            prop_assert_eq!(geometry.vertices.len(), geometry.uvs.len());
+            prop_assert_eq!(geometry.indices.len() % 3, 0);
+            prop_assert!(geometry.indices.iter().all(|&i| (i as usize) < geometry.vertices.len()));
+            prop_assert!(geometry.vertices.iter().all(|v| v.is_finite()));
+            prop_assert!(geometry.normals.iter().all(|n| n.is_finite() && n.length() <= 1.0 + 1e-4));
+            prop_assert!(geometry.uvs.iter().all(|uv| uv.is_finite()));
+            prop_assert!(geometry.winding_matches_normals());
+            prop_assert!(
+                geometry
+                    .vertices
+                    .iter()
+                    .all(|v| v.z >= 0.0 && v.z <= extrusion_depth)
+            );
+        }
+    }
+
+    #[test]
+    fn side_walls_face_away_from_the_glyph(
+        c in proptest::char::range('!', '~'),
+        font_size in 1.0f32..200.0,
+    ) {
+        let mut font_system = embedded_font_system();
+        for glyph in common::shape(&mut font_system, &c.to_string(), font_size) {
+            let Some((geometry, _, _)) = common::tessellate(&mut font_system, &glyph, 1.0) else {
+                continue;
+            };
+            let cap = front_cap(&geometry.vertices, &geometry.indices, &geometry.normals);
+            let probe = font_size * 0.002;
+
+            // Every side quad has to face away from the filled area, including the walls of
+            // holes, or back-face culling hides it from the outside.
+            let mut inward = 0;
+            let side_start = geometry
+                .indices
+                .iter()
+                .position(|&i| geometry.normals[i as usize].z == 0.0)
+                .unwrap_or(geometry.indices.len());
+            for quad in geometry.indices[side_start..].chunks_exact(6) {
+                let normal = geometry.normals[quad[0] as usize].truncate();
+                if normal == Vec2::ZERO {
+                    continue;
+                }
+                let mid = (geometry.vertices[quad[0] as usize] + geometry.vertices[quad[1] as usize])
+                    .truncate()
+                    / 2.0;
+                if covered(&cap, mid + normal * probe) && !covered(&cap, mid - normal * probe) {
+                    inward += 1;
+                }
+            }
+            prop_assert_eq!(inward, 0, "side quads face into the glyph");
+        }
+    }
+
+    #[test]
+    fn generated_transforms_are_finite(
+        text in "[ -~]{1,24}",
+        font_size in 1.0f32..100.0,
+        max_width in proptest::option::of(10.0f32..400.0),
+    ) {
+        let mut world = common::world();
+        let mut params = common::params(font_size, 1.0);
+        params.max_width = max_width;
+
+        let entries = common::generate(&mut world, &text, params);
+        if max_width.is_some_and(|max_width| max_width < font_size) {
+            let too_narrow = matches!(entries, Err(MeshTextError::MaxWidthTooSmall { .. }));
+            prop_assert!(too_narrow);
+            return Ok(());
+        }
+        prop_assert!(entries.is_ok());
+        for entry in entries.unwrap() {
+            prop_assert!(entry.transform.translation.is_finite());
+            prop_assert!(entry.transform.scale.is_finite());
+        }
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "raycast")]
+
+mod common;
+
+use bevy::prelude::*;
+use bevy::render::mesh::MeshAabb;
+use bevy_mesh_text_3d::raycast::{GlyphBvhs, TriangleBvh, raycast, raycast_text};
+
+#[test]
+fn bvh_finds_the_closest_triangle() {
+    // A grid of quads, two layers deep
+    let mut triangles = Vec::new();
+    for z in [0.0, 1.0] {
+        for x in 0..10 {
+            for y in 0..10 {
+                let p = Vec3::new(x as f32, y as f32, z);
+                triangles.push([p, p + Vec3::X, p + Vec3::Y]);
+                triangles.push([p + Vec3::X, p + Vec3::ONE.with_z(0.0), p + Vec3::Y]);
+            }
+        }
+    }
+    let bvh = TriangleBvh::new(triangles);
+
+    let ray = Ray3d::new(Vec3::new(3.25, 4.5, -5.0), Dir3::Z);
+    let hit = bvh.cast(ray).unwrap();
+    assert!((hit.distance - 5.0).abs() < 1e-5);
+    assert_eq!(hit.normal, Vec3::NEG_Z);
+    assert_eq!(hit.triangle, 2 * (3 * 10 + 4));
+
+    // From behind, the back layer comes first
+    let back = bvh.cast(Ray3d::new(Vec3::new(3.25, 4.5, 5.0), Dir3::NEG_Z));
+    assert!((back.unwrap().distance - 4.0).abs() < 1e-5);
+
+    let beside = Ray3d::new(Vec3::new(-1.0, 4.5, -5.0), Dir3::Z);
+    assert_eq!(bvh.cast(beside), None);
+}
+
+#[test]
+fn rays_pick_glyphs_by_their_triangles() {
+    let mut world = common::world();
+    let params = common::params(14.0, 2.0).with_scale(2.0);
+    let entries = common::generate(&mut world, "oo", params).unwrap();
+    let meshes = world.resource::<Assets<Mesh>>();
+    let mut bvhs = GlyphBvhs::default();
+
+    // The second glyph, hit through its stroke from the front
+    let second = &entries[1];
+    let aabb = meshes.get(&second.mesh).unwrap().compute_aabb().unwrap();
+    let stroke = second
+        .transform
+        .transform_point(Vec3::new(aabb.min().x + 0.5, 0.0, 0.0));
+    let ray = Ray3d::new(stroke.with_z(-10.0), Dir3::Z);
+    let (index, hit) = raycast_text(&entries, ray, meshes, &mut bvhs).unwrap();
+    assert_eq!(index, 1);
+    assert!((hit.distance - 10.0).abs() < 1e-3, "{}", hit.distance);
+    assert!(hit.normal.abs_diff_eq(Vec3::NEG_Z, 1e-4));
+    assert!(hit.point.abs_diff_eq(stroke, 1e-3));
+
+    // Through the counter of the "o" the ray misses, unlike its bounding box
+    let counter = second.transform.translation.with_z(-10.0);
+    assert_eq!(
+        raycast(second, Ray3d::new(counter, Dir3::Z), meshes, &mut bvhs),
+        None
+    );
+}
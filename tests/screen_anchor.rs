@@ -0,0 +1,107 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy::render::camera::CameraProjection;
+use bevy_mesh_text_3d::screen_anchor::{orthographic_text_scale, screen_anchor_transform};
+use bevy_mesh_text_3d::{Parameters, ScreenAnchor};
+
+#[test]
+fn anchored_text_stays_in_its_corner() {
+    // Looking along -X, with 90 degrees of vertical field of view
+    let camera = GlobalTransform::from(Transform::from_rotation(Quat::from_rotation_y(FRAC_PI_2)));
+    let projection = Projection::Perspective(PerspectiveProjection {
+        fov: FRAC_PI_2,
+        ..default()
+    });
+    let anchor = ScreenAnchor::top_right(Entity::PLACEHOLDER, 10.0).with_size(Vec2::new(4.0, 2.0));
+
+    let transform = screen_anchor_transform(
+        &anchor,
+        &camera,
+        &projection,
+        Vec2::new(200.0, 100.0),
+        Vec3::ONE,
+    )
+    .unwrap();
+    // The viewport is 40 by 20 units wide at that depth, and the text ends at its right edge
+    assert!(
+        transform
+            .translation
+            .abs_diff_eq(Vec3::new(-10.0, 10.0, -16.0), 1e-4)
+    );
+    assert!(
+        transform
+            .rotation
+            .abs_diff_eq(Quat::from_rotation_y(FRAC_PI_2), 1e-6)
+    );
+
+    // Twice the text scale moves the origin twice as far from the corner
+    let scaled = screen_anchor_transform(
+        &anchor,
+        &camera,
+        &projection,
+        Vec2::new(200.0, 100.0),
+        Vec3::splat(2.0),
+    )
+    .unwrap();
+    assert!(
+        scaled
+            .translation
+            .abs_diff_eq(Vec3::new(-10.0, 10.0, -12.0), 1e-4)
+    );
+}
+
+#[test]
+fn orthographic_anchors_follow_the_viewport() {
+    let camera = GlobalTransform::from_xyz(0.0, 0.0, 450.0);
+    let mut orthographic = OrthographicProjection::default_3d();
+    orthographic.update(200.0, 100.0);
+    let projection = Projection::Orthographic(orthographic);
+    let anchor = ScreenAnchor::bottom_left(Entity::PLACEHOLDER, 10.0)
+        .with_size(Vec2::new(30.0, 8.0))
+        .with_offset(Vec2::new(10.0, -10.0));
+
+    let transform = screen_anchor_transform(
+        &anchor,
+        &camera,
+        &projection,
+        Vec2::new(200.0, 100.0),
+        Vec3::ONE,
+    )
+    .unwrap();
+    // One unit per pixel; the bottom of the text sits 10 pixels above the bottom edge
+    assert!(
+        transform
+            .translation
+            .abs_diff_eq(Vec3::new(-90.0, -32.0, 440.0), 1e-4)
+    );
+
+    assert_eq!(
+        screen_anchor_transform(&anchor, &camera, &projection, Vec2::ZERO, Vec3::ONE),
+        None
+    );
+}
+
+#[test]
+fn orthographic_scale_matches_the_pixel_size() {
+    let mut orthographic = OrthographicProjection {
+        scale: 0.5,
+        ..OrthographicProjection::default_3d()
+    };
+    orthographic.update(200.0, 100.0);
+
+    // Half a unit per pixel, so 20 pixels are 10 units, the size of the font
+    assert_eq!(
+        orthographic_text_scale(&orthographic, 100.0, 10.0, 20.0),
+        1.0
+    );
+    assert_eq!(
+        orthographic_text_scale(&orthographic, 100.0, 10.0, 40.0),
+        2.0
+    );
+    assert_eq!(orthographic_text_scale(&orthographic, 0.0, 10.0, 20.0), 0.0);
+
+    let params =
+        Parameters::default().with_scale(orthographic_text_scale(&orthographic, 100.0, 14.0, 14.0));
+    assert_eq!(params.scale, Some(Vec3::splat(0.5)));
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::entity_pool::PooledText;
+use bevy_mesh_text_3d::scrolling_text::update_scrolling_text;
+use bevy_mesh_text_3d::{Attrs, GlyphEntityPool, InputText, ScrollingText};
+
+/// Heights of the glyphs of the scrolling text on `entity`, top to bottom.
+fn glyph_heights(world: &mut World, entity: Entity) -> Vec<f32> {
+    world
+        .run_system_once(update_scrolling_text::<StandardMaterial>)
+        .unwrap();
+    let glyphs = world.get::<PooledText>(entity).unwrap().glyphs().to_vec();
+    let mut heights: Vec<f32> = glyphs
+        .iter()
+        .map(|&glyph| world.get::<Transform>(glyph).unwrap().translation.y)
+        .collect();
+    heights.sort_by(|a, b| b.total_cmp(a));
+    heights
+}
+
+#[test]
+fn only_the_lines_in_the_window_are_meshed() {
+    let mut world = common::world();
+    world.init_resource::<GlyphEntityPool>();
+    let params = common::params(14.0, 2.0);
+    let line_height = params.line_height;
+    let credits: String = (0..500).map(|line| format!("Line {line:03}\n")).collect();
+    let text = InputText::Simple {
+        text: credits,
+        material: Handle::<StandardMaterial>::default(),
+        attrs: Attrs::new(),
+    };
+    let entity = world
+        .spawn(ScrollingText::new(text, params, 3.0 * line_height))
+        .id();
+
+    // Three lines of seven glyphs, the space has no mesh
+    let top = glyph_heights(&mut world, entity);
+    assert_eq!(top.len(), 21);
+
+    // Lines far down the text sit on the lines of the window like the first ones, up to the
+    // centers of their digits
+    world.get_mut::<ScrollingText>(entity).unwrap().scroll = 200.0 * line_height;
+    let middle = glyph_heights(&mut world, entity);
+    assert_eq!(middle.len(), top.len());
+    for (a, b) in top.iter().zip(&middle) {
+        assert!((a - b).abs() < line_height / 4.0, "{a} {b}");
+    }
+    // Glyph entities are reused as the window moves
+    assert!(world.resource::<GlyphEntityPool>().is_empty());
+
+    // The window stops at the end of the text
+    world.get_mut::<ScrollingText>(entity).unwrap().scroll = 10_000.0 * line_height;
+    let end = glyph_heights(&mut world, entity);
+    assert!(!end.is_empty() && end.len() <= top.len());
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::extrude_glyph::{ExtrudedGlyphGeometry, ExtrusionOptions, tessalate_glyph};
+use bevy_mesh_text_3d::shadow::shadow_caster_options;
+use bevy_mesh_text_3d::{Attrs, InputText, Settings, embedded_font_system, generate_shadow_meshes};
+use cosmic_text::ttf_parser::{Face, GlyphId};
+
+/// Area of the front-facing triangles.
+fn front_area(geometry: &ExtrudedGlyphGeometry) -> f32 {
+    geometry
+        .indices
+        .chunks_exact(3)
+        .filter(|tri| {
+            tri.iter()
+                .all(|&i| geometry.normals[i as usize] == Vec3::NEG_Z)
+        })
+        .map(|tri| {
+            let [a, b, c] = [0, 1, 2].map(|k| geometry.vertices[tri[k] as usize]);
+            (b - a).truncate().perp_dot((c - a).truncate()).abs() / 2.0
+        })
+        .sum()
+}
+
+#[test]
+fn shadow_casters_are_filled_and_coarse() {
+    let mut font_system = embedded_font_system();
+    let options = ExtrusionOptions::new(10.0);
+    let shadow = shadow_caster_options(&options);
+    for glyph in common::shape(&mut font_system, "oe", 100.0) {
+        let (detailed, caster) = font_system
+            .db()
+            .with_face_data(glyph.font_id, |file, _| {
+                let face = Face::parse(file, 0).unwrap();
+                let bb = face.glyph_bounding_box(GlyphId(glyph.glyph_id)).unwrap();
+                let detailed = tessalate_glyph(&glyph, bb, face.clone(), &options).unwrap();
+                let caster = tessalate_glyph(&glyph, bb, face, &shadow).unwrap();
+                (detailed.0, caster.0)
+            })
+            .unwrap();
+        assert!(caster.indices.len() < detailed.indices.len() / 2);
+
+        // The counters are filled, so the front face covers more
+        assert!(front_area(&caster) > front_area(&detailed) * 1.05);
+    }
+}
+
+#[test]
+fn shadow_casters_follow_the_glyphs() {
+    let mut world = common::world();
+    let params = common::params(14.0, 2.0);
+    let entries = common::generate(&mut world, "oe", params.clone()).unwrap();
+    let casters = world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                generate_shadow_meshes(
+                    InputText::Simple {
+                        text: "oe".to_string(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    params.clone(),
+                    &mut meshes,
+                )
+                .unwrap()
+            },
+        )
+        .unwrap();
+
+    assert_eq!(casters.len(), entries.len());
+    for (caster, entry) in casters.iter().zip(&entries) {
+        assert_ne!(caster.mesh, entry.mesh);
+        assert_eq!(caster.transform, entry.transform);
+    }
+}
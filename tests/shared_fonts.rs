@@ -0,0 +1,52 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_mesh_text_3d::footprint::{FootprintGrouping, text_footprints};
+use bevy_mesh_text_3d::{Attrs, InputText, Settings, SharedFonts, embedded_font_system};
+
+fn footprints(fonts: &mut Settings) -> Vec<Vec<Vec2>> {
+    text_footprints(
+        InputText::Simple {
+            text: "ab cd".to_string(),
+            material: Handle::<StandardMaterial>::default(),
+            attrs: Attrs::new(),
+        },
+        fonts,
+        common::params(14.0, 2.0),
+        FootprintGrouping::Word,
+    )
+    .unwrap()
+}
+
+#[test]
+fn every_context_has_one_user_at_a_time() {
+    let fonts = SharedFonts::with_copies(&embedded_font_system(), 1.0, 2);
+    assert_eq!(fonts.contexts(), 2);
+
+    let first = fonts.lock();
+    let second = fonts.try_lock().expect("a free context");
+    assert!(fonts.try_lock().is_none());
+    drop(first);
+    assert!(fonts.try_lock().is_some());
+    drop(second);
+}
+
+#[test]
+fn threads_lay_out_text_side_by_side() {
+    let expected = footprints(&mut Settings::new(embedded_font_system(), 1.0));
+    let fonts = SharedFonts::with_copies(&embedded_font_system(), 1.0, 2);
+
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let fonts = fonts.clone();
+                scope.spawn(move || footprints(&mut fonts.lock()))
+            })
+            .collect();
+        threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect()
+    });
+    assert!(results.iter().all(|result| *result == expected));
+}
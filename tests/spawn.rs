@@ -0,0 +1,72 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::{Settings, TextSpawnDesc, spawn_text_3d};
+
+mod common;
+
+#[test]
+fn spawns_the_glyphs_under_one_root() {
+    let mut world = common::world();
+    world.init_resource::<Assets<StandardMaterial>>();
+    let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+    let root = world
+        .run_system_once(
+            move |mut commands: Commands,
+                  mut settings: ResMut<Settings>,
+                  mut meshes: ResMut<Assets<Mesh>>,
+                  mut materials: ResMut<Assets<StandardMaterial>>| {
+                spawn_text_3d(
+                    &mut commands,
+                    &mut settings,
+                    &mut meshes,
+                    &mut materials,
+                    TextSpawnDesc {
+                        color: Color::BLACK,
+                        transform,
+                        ..TextSpawnDesc::new("Hi you")
+                    },
+                )
+            },
+        )
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(world.get::<Transform>(root), Some(&transform));
+    assert_eq!(world.get::<Name>(root).unwrap().as_str(), "Hi you");
+    let children = world.get::<Children>(root).unwrap().to_vec();
+    assert_eq!(children.len(), 5);
+
+    let materials = world.resource::<Assets<StandardMaterial>>();
+    assert_eq!(materials.len(), 1);
+    for child in children {
+        let material = world
+            .get::<MeshMaterial3d<StandardMaterial>>(child)
+            .unwrap();
+        assert_eq!(materials.get(&material.0).unwrap().base_color, Color::BLACK);
+        assert!(world.get::<Mesh3d>(child).is_some());
+    }
+}
+
+#[test]
+fn invalid_text_spawns_nothing() {
+    let mut world = common::world();
+    world.init_resource::<Assets<StandardMaterial>>();
+    let result = world
+        .run_system_once(
+            |mut commands: Commands,
+             mut settings: ResMut<Settings>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<StandardMaterial>>| {
+                spawn_text_3d(
+                    &mut commands,
+                    &mut settings,
+                    &mut meshes,
+                    &mut materials,
+                    TextSpawnDesc::new(""),
+                )
+            },
+        )
+        .unwrap();
+    assert!(result.is_err());
+    assert_eq!(world.entities().len(), 0);
+}
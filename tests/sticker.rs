@@ -0,0 +1,115 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::mesh::MeshAabb;
+use bevy_mesh_text_3d::{
+    Attrs, FrontInset, InputText, MeshTextEntry, MeshTextError, Parameters, Settings, StickerEntry,
+    StickerParameters, generate_sticker_meshes,
+};
+
+mod common;
+
+fn sticker(
+    world: &mut World,
+    text: &str,
+    params: Parameters,
+    sticker: StickerParameters,
+    shell_material: Handle<StandardMaterial>,
+) -> Result<Vec<StickerEntry<StandardMaterial>>, MeshTextError> {
+    let text = text.to_string();
+    let mut params = Some(params);
+    world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                generate_sticker_meshes(
+                    InputText::Simple {
+                        text: text.clone(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    params.take().unwrap(),
+                    sticker,
+                    shell_material.clone(),
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+}
+
+/// World space corners of the mesh of `entry`.
+fn world_bounds(world: &World, entry: &MeshTextEntry<StandardMaterial>) -> (Vec3, Vec3) {
+    let aabb = world
+        .resource::<Assets<Mesh>>()
+        .get(&entry.mesh)
+        .unwrap()
+        .compute_aabb()
+        .unwrap();
+    let to_world = |p: Vec3| entry.transform.transform_point(p);
+    (to_world(aabb.min().into()), to_world(aabb.max().into()))
+}
+
+#[test]
+fn shells_surround_the_beveled_glyphs() {
+    let mut world = common::world();
+    let params = Parameters {
+        front_inset: Some(FrontInset {
+            width: 0.3,
+            depth: 0.3,
+        }),
+        ..common::params(14.0, 2.0)
+    };
+    let shell_material = Assets::<StandardMaterial>::default().add(StandardMaterial::default());
+    let settings = StickerParameters {
+        width: 1.0,
+        depth: 1.0,
+        setback: 0.5,
+    };
+    let entries = sticker(
+        &mut world,
+        "H I",
+        params.clone(),
+        settings,
+        shell_material.clone(),
+    )
+    .unwrap();
+    let solid = common::generate(&mut world, "H I", params).unwrap();
+    assert_eq!(entries.len(), 2);
+
+    for (entry, glyph) in entries.iter().zip(&solid) {
+        // The glyphs are the ones generate_meshes makes
+        assert_eq!(entry.glyph.mesh, glyph.mesh);
+        assert_eq!(entry.glyph.transform, glyph.transform);
+        assert_eq!(entry.shell.material, shell_material);
+
+        let (glyph_min, glyph_max) = world_bounds(&world, &entry.glyph);
+        let (shell_min, shell_max) = world_bounds(&world, &entry.shell);
+        let grown = (glyph_min.truncate() - shell_min.truncate())
+            .min(shell_max.truncate() - glyph_max.truncate());
+        assert!(
+            (grown - Vec2::ONE).abs().max_element() < 1e-3,
+            "grown by {grown}"
+        );
+        assert!((shell_min.z - glyph_min.z - 0.5).abs() < 1e-4);
+        assert!((shell_max.z - shell_min.z - 1.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn negative_shell_depth_is_rejected() {
+    let mut world = common::world();
+    let result = sticker(
+        &mut world,
+        "A",
+        common::params(14.0, 2.0),
+        StickerParameters {
+            depth: -1.0,
+            ..default()
+        },
+        Handle::default(),
+    );
+    assert!(matches!(
+        result,
+        Err(MeshTextError::NegativeExtrusionDepth(-1.0))
+    ));
+}
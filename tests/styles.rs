@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use bevy_mesh_text_3d::{InputText, TextStyle, TextStyles};
+
+#[test]
+fn styles_produce_matching_input_and_parameters() {
+    let mut styles = TextStyles::default();
+    styles.insert(
+        "heading",
+        TextStyle {
+            font_size: 32.0,
+            line_height: 40.0,
+            extrusion_depth: 6.0,
+            weight: 700,
+            ..default()
+        },
+    );
+    let mut materials = Assets::<StandardMaterial>::default();
+
+    assert!(styles.text("missing", "Hi", &mut materials).is_none());
+    let (input, params) = styles.text("heading", "Hi", &mut materials).unwrap();
+    let InputText::Simple { text, material, .. } = input else {
+        panic!("styled text is a simple text");
+    };
+    assert_eq!(text, "Hi");
+    assert!(materials.get(&material).is_some());
+    assert_eq!(params.font_size, 32.0);
+    assert_eq!(params.line_height, 40.0);
+    assert_eq!(params.extrusion_depth, 6.0);
+}
+
+#[cfg(feature = "ron")]
+#[test]
+fn styles_load_from_ron() {
+    use bevy_mesh_text_3d::FrontInset;
+
+    let styles = TextStyles::from_ron(
+        r#"{
+            "damage_number": (
+                font_size: 24.0,
+                extrusion_depth: 3.0,
+                front_inset: Some((width: 0.5, depth: 1.0)),
+                color: (1.0, 0.2, 0.1, 1.0),
+            ),
+        }"#,
+    )
+    .unwrap();
+    let style = styles.get("damage_number").unwrap();
+    assert_eq!(style.font_size, 24.0);
+    assert_eq!(style.line_height, TextStyle::default().line_height);
+    assert_eq!(
+        style.front_inset,
+        Some(FrontInset {
+            width: 0.5,
+            depth: 1.0
+        })
+    );
+    assert_eq!(style.color, [1.0, 0.2, 0.1, 1.0]);
+}
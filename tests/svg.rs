@@ -0,0 +1,75 @@
+#![cfg(feature = "svg")]
+
+use std::f32::consts::PI;
+
+use bevy::math::{Vec2, Vec3};
+use bevy_mesh_text_3d::extrude_glyph::{ExtrusionOptions, extrude_contours};
+use bevy_mesh_text_3d::svg::parse_svg_path;
+
+#[test]
+fn parses_lines_with_flipped_y() {
+    let absolute = parse_svg_path("M0 0 H10 V10 H0 Z").unwrap();
+    let relative = parse_svg_path("m0,0 10,0 0,10 -10,0z").unwrap();
+    assert_eq!(absolute, relative);
+    assert_eq!(absolute.len(), 1);
+    assert!(absolute[0].closed);
+    assert_eq!(
+        absolute[0].points,
+        [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, -10.0),
+            Vec2::new(0.0, -10.0)
+        ]
+    );
+}
+
+#[test]
+fn parses_compact_numbers() {
+    let contours = parse_svg_path("M1.5.5L1e1-2l-.5.5z").unwrap();
+    assert_eq!(
+        contours[0].points,
+        [
+            Vec2::new(1.5, -0.5),
+            Vec2::new(10.0, 2.0),
+            Vec2::new(9.5, 1.5)
+        ]
+    );
+}
+
+#[test]
+fn rejects_malformed_data() {
+    assert!(parse_svg_path("M 0").is_err());
+    assert!(parse_svg_path("10 10").is_err());
+    assert!(parse_svg_path("M0 0 L10 10 Z 5 5").is_err());
+    assert!(parse_svg_path("M0 0 X 1 1").is_err());
+}
+
+#[test]
+fn extrudes_arcs_with_holes() {
+    let ring = "M0 5 A5 5 0 1 0 10 5 A5 5 0 1 0 0 5 Z \
+                M3 5 a2 2 0 1 1 4 0 a2 2 0 1 1 -4 0 z";
+    let contours = parse_svg_path(ring).unwrap();
+    assert_eq!(contours.len(), 2);
+    let areas: Vec<f32> = contours.iter().map(|c| c.signed_area().abs()).collect();
+    // Flattening cuts a little off the curves
+    assert!((areas[0] / (25.0 * PI) - 1.0).abs() < 0.02, "{areas:?}");
+    assert!((areas[1] / (4.0 * PI) - 1.0).abs() < 0.03, "{areas:?}");
+
+    let geometry = extrude_contours(&contours, &ExtrusionOptions::new(1.0)).unwrap();
+    assert!(geometry.winding_matches_normals());
+    let front: f32 = geometry
+        .indices
+        .chunks_exact(3)
+        .filter(|tri| geometry.normals[tri[0] as usize] == Vec3::NEG_Z)
+        .map(|tri| {
+            let [a, b, c] = [0, 1, 2].map(|k| geometry.vertices[tri[k] as usize].truncate());
+            (b - a).perp_dot(c - a).abs() / 2.0
+        })
+        .sum();
+    assert!((front / (21.0 * PI) - 1.0).abs() < 0.02, "{front}");
+
+    // Centered on the bounding box
+    let max = geometry.vertices.iter().fold(Vec3::MIN, |m, v| m.max(*v));
+    assert!((max.x - 5.0).abs() < 1e-3 && (max.y - 5.0).abs() < 1e-3);
+}
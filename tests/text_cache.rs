@@ -0,0 +1,146 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::{
+    Attrs, DropCapParameters, ExclusionZone, InputText, MeshTextEntry, Parameters, Settings,
+    TextCache,
+};
+
+mod common;
+
+fn cached(
+    world: &mut World,
+    text: &str,
+    material: Handle<StandardMaterial>,
+    params: Parameters,
+) -> Vec<MeshTextEntry<StandardMaterial>> {
+    let text = text.to_string();
+    let mut params = Some(params);
+    world
+        .run_system_once(
+            move |mut cache: ResMut<TextCache<StandardMaterial>>,
+                  mut settings: ResMut<Settings>,
+                  mut meshes: ResMut<Assets<Mesh>>| {
+                cache.generate(
+                    InputText::Simple {
+                        text: text.clone(),
+                        material: material.clone(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    params.take().unwrap(),
+                    &mut meshes,
+                )
+            },
+        )
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn identical_texts_are_generated_once() {
+    let mut world = common::world();
+    world.init_resource::<TextCache<StandardMaterial>>();
+    let material = Handle::<StandardMaterial>::default();
+    let params = common::params(14.0, 2.0);
+
+    let first = cached(&mut world, "Loading...", material.clone(), params.clone());
+    let mesh_count = world.resource::<Assets<Mesh>>().len();
+    let second = cached(&mut world, "Loading...", material.clone(), params.clone());
+    assert_eq!(world.resource::<Assets<Mesh>>().len(), mesh_count);
+    assert_eq!(first.len(), second.len());
+    for (a, b) in first.iter().zip(&second) {
+        assert_eq!(a.mesh, b.mesh);
+        assert_eq!(a.transform, b.transform);
+    }
+    let cache = world.resource::<TextCache<StandardMaterial>>();
+    assert_eq!((cache.len(), cache.hits()), (1, 1));
+}
+
+#[test]
+fn texts_differing_in_anything_are_cached_apart() {
+    let mut world = common::world();
+    world.init_resource::<TextCache<StandardMaterial>>();
+    let material = Handle::<StandardMaterial>::default();
+    let params = common::params(14.0, 2.0);
+
+    cached(&mut world, "Sword", material.clone(), params.clone());
+    cached(&mut world, "Shield", material.clone(), params.clone());
+    let larger = cached(
+        &mut world,
+        "Sword",
+        material.clone(),
+        common::params(28.0, 2.0),
+    );
+    let deeper = cached(
+        &mut world,
+        "Sword",
+        material.clone(),
+        common::params(14.0, 3.0),
+    );
+    let other_material = Handle::<StandardMaterial>::Weak(AssetId::invalid());
+    let recolored = cached(&mut world, "Sword", other_material.clone(), params);
+    assert_ne!(larger[0].transform, deeper[0].transform);
+    assert_eq!(recolored[0].material, other_material);
+
+    let mut cache = world.resource_mut::<TextCache<StandardMaterial>>();
+    assert_eq!((cache.len(), cache.hits()), (5, 0));
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn drop_caps_are_cached_apart() {
+    let mut world = common::world();
+    world.init_resource::<TextCache<StandardMaterial>>();
+    let material = Handle::<StandardMaterial>::default();
+    let params = Parameters {
+        max_width: Some(200.0),
+        ..common::params(14.0, 2.0)
+    };
+    let text = "Once upon a time there was a drop cap beside a few lines of text";
+
+    let plain = cached(&mut world, text, material.clone(), params.clone());
+    let capped = cached(
+        &mut world,
+        text,
+        material.clone(),
+        Parameters {
+            drop_cap: Some(DropCapParameters { lines: 2, gap: 2.0 }),
+            ..params.clone()
+        },
+    );
+    assert_ne!(plain[0].transform, capped[0].transform);
+
+    let cache = world.resource::<TextCache<StandardMaterial>>();
+    assert_eq!((cache.len(), cache.hits()), (2, 0));
+}
+
+#[test]
+fn exclusion_zones_are_cached_apart() {
+    let mut world = common::world();
+    world.init_resource::<TextCache<StandardMaterial>>();
+    let material = Handle::<StandardMaterial>::default();
+    let text = "Lines of text flowing around a picture set into the page";
+    let around = |zone: Rect| Parameters {
+        max_width: Some(200.0),
+        exclusion_zones: vec![ExclusionZone::Rect(zone)],
+        ..common::params(14.0, 2.0)
+    };
+
+    let left = cached(
+        &mut world,
+        text,
+        material.clone(),
+        around(Rect::new(0.0, 0.0, 60.0, 40.0)),
+    );
+    let right = cached(
+        &mut world,
+        text,
+        material.clone(),
+        around(Rect::new(140.0, 0.0, 200.0, 40.0)),
+    );
+    assert_ne!(left[0].transform, right[0].transform);
+
+    let cache = world.resource::<TextCache<StandardMaterial>>();
+    assert_eq!((cache.len(), cache.hits()), (2, 0));
+}
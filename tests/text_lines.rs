@@ -0,0 +1,79 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::{
+    Attrs, InputText, MeshTextEntry, Parameters, Settings, TextLine, generate_meshes_with_lines,
+};
+
+fn generate(
+    world: &mut World,
+    text: &str,
+    params: Parameters,
+) -> (Vec<MeshTextEntry<StandardMaterial>>, Vec<TextLine>) {
+    let text = text.to_string();
+    world
+        .run_system_once(
+            move |mut settings: ResMut<Settings>, mut meshes: ResMut<Assets<Mesh>>| {
+                generate_meshes_with_lines(
+                    InputText::Simple {
+                        text: text.clone(),
+                        material: Handle::<StandardMaterial>::default(),
+                        attrs: Attrs::new(),
+                    },
+                    &mut settings,
+                    params.clone(),
+                    &mut meshes,
+                )
+                .unwrap()
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn lines_box_their_glyphs() {
+    let mut world = common::world();
+    let scale = world.resource::<Settings>().text_scale_factor;
+    let params = common::params(14.0, 2.0);
+    let (entries, lines) = generate(&mut world, "ab c\n\nde", params.clone());
+    assert_eq!(entries.len(), 5);
+    assert_eq!(lines.len(), 3);
+
+    let ranges: Vec<_> = lines.iter().map(|line| line.glyphs.clone()).collect();
+    assert_eq!(ranges, [0..3, 3..3, 3..5]);
+    for (i, line) in lines.iter().enumerate() {
+        assert_eq!((line.index, line.paragraph), (i, i));
+        assert!((line.bounds.height() - params.line_height * scale).abs() < 1e-4);
+        assert!(line.bounds.min.y < line.baseline_y && line.baseline_y < line.bounds.max.y);
+        for entry in &entries[line.glyphs.clone()] {
+            assert!(line.bounds.contains(entry.transform.translation.truncate()));
+        }
+    }
+    assert_eq!(lines[1].bounds.width(), 0.0);
+    assert!(lines[2].baseline_y < lines[0].baseline_y);
+
+    // The same meshes and placement as `generate_meshes`
+    let expected = common::generate(&mut world, "ab c\n\nde", params).unwrap();
+    for (entry, expected) in entries.iter().zip(&expected) {
+        assert_eq!(entry.mesh, expected.mesh);
+        assert_eq!(entry.transform, expected.transform);
+    }
+}
+
+#[test]
+fn wrapped_lines_share_their_paragraph() {
+    let mut world = common::world();
+    let params = Parameters {
+        max_width: Some(40.0),
+        ..common::params(14.0, 2.0)
+    };
+    let (_, lines) = generate(&mut world, "one two three", params);
+    assert!(lines.len() > 1);
+    assert!(lines.iter().all(|line| line.paragraph == 0));
+    assert!(
+        lines
+            .windows(2)
+            .all(|pair| pair[0].glyphs.end == pair[1].glyphs.start)
+    );
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_mesh_text_3d::timer_text::{
+    TimerDirection, TimerGlyphs, TimerText, spawn_timer_text, update_timer_text,
+};
+use bevy_mesh_text_3d::{Attrs, Settings};
+
+fn spawn(world: &mut World, time: Duration, show_hours: bool) -> Entity {
+    world
+        .run_system_once(
+            move |mut commands: Commands,
+                  mut settings: ResMut<Settings>,
+                  mut meshes: ResMut<Assets<Mesh>>| {
+                let glyphs = TimerGlyphs::new(
+                    &mut settings,
+                    &Attrs::new(),
+                    &common::params(14.0, 2.0),
+                    &mut meshes,
+                )
+                .unwrap();
+                spawn_timer_text(
+                    &mut commands,
+                    glyphs,
+                    time,
+                    TimerDirection::Down,
+                    show_hours,
+                    Handle::<StandardMaterial>::default(),
+                )
+            },
+        )
+        .unwrap()
+}
+
+fn slot_meshes(world: &World, timer: Entity) -> Vec<Handle<Mesh>> {
+    world
+        .get::<Children>(timer)
+        .unwrap()
+        .iter()
+        .map(|child| world.get::<Mesh3d>(child).unwrap().0.clone())
+        .collect()
+}
+
+#[test]
+fn timer_swaps_meshes_of_changed_digits() {
+    let mut world = common::world();
+    let timer = spawn(&mut world, Duration::from_millis(70_500), false);
+    let before = slot_meshes(&world, timer);
+    // Remaining time is rounded up: 01:11
+    assert_eq!(before.len(), 5);
+    assert_eq!(before[1], before[3]);
+    assert_eq!(before[1], before[4]);
+
+    world.get_mut::<TimerText>(timer).unwrap().time = Duration::from_secs(69);
+    world.run_system_once(update_timer_text).unwrap();
+    let after = slot_meshes(&world, timer);
+    // 01:09
+    assert_eq!(after[..3], before[..3]);
+    assert_eq!(after[3], after[0]);
+    assert_ne!(after[4], before[4]);
+
+    let glyph_count = world.resource::<Settings>().glyph_cache.len();
+    world.get_mut::<TimerText>(timer).unwrap().time = Duration::from_secs(5);
+    world.run_system_once(update_timer_text).unwrap();
+    assert_eq!(world.resource::<Settings>().glyph_cache.len(), glyph_count);
+}
+
+#[test]
+fn timer_shows_hours() {
+    let mut world = common::world();
+    let timer = spawn(&mut world, Duration::from_secs(3 * 3600 + 25), true);
+    let meshes = slot_meshes(&world, timer);
+    // 03:00:25
+    assert_eq!(meshes.len(), 8);
+    assert_eq!(meshes[2], meshes[5]);
+    assert_eq!(meshes[0], meshes[3]);
+    assert_eq!(meshes[0], meshes[4]);
+}